@@ -2,6 +2,7 @@ package io.affex.tts
 
 import android.app.Activity
 import android.media.AudioAttributes
+import android.media.AudioDeviceInfo
 import android.media.AudioFocusRequest
 import android.media.AudioManager
 import android.os.Build
@@ -17,7 +18,11 @@ import app.tauri.plugin.JSArray
 import app.tauri.plugin.JSObject
 import app.tauri.plugin.Plugin
 import app.tauri.plugin.Invoke
+import java.io.File
+import java.util.Collections
 import java.util.Locale
+import java.util.UUID
+import java.util.concurrent.ConcurrentHashMap
 import java.util.concurrent.ConcurrentLinkedQueue
 
 @InvokeArg
@@ -29,6 +34,46 @@ class SpeakArgs {
     var pitch: Float = 1.0f
     var volume: Float = 1.0f
     var queueMode: String = "flush"
+    var pauseAfterMs: Long? = null
+    var ssml: Boolean = false
+    var wordOffsets: Array<WordOffset> = emptyArray()
+    var sentenceOffsets: Array<SentenceOffset> = emptyArray()
+}
+
+/** Where a word of the spoken text came from in the caller's text, in UTF-16 offsets */
+@InvokeArg
+class WordOffset {
+    var spokenStart: Int = 0
+    var spokenEnd: Int = 0
+    var textStart: Int = 0
+    var textEnd: Int = 0
+}
+
+/** Where a sentence of the spoken text came from in the caller's text, in UTF-16 offsets */
+@InvokeArg
+class SentenceOffset {
+    var index: Int = 0
+    var spokenStart: Int = 0
+    var spokenEnd: Int = 0
+    var textStart: Int = 0
+    var textEnd: Int = 0
+}
+
+/** Sentence offsets of an utterance and the index of the next one to report */
+private class SentenceProgress(val offsets: Array<SentenceOffset>) {
+    var next = 0
+}
+
+@InvokeArg
+class SpeakToFileArgs {
+    var text: String = ""
+    var language: String? = null
+    var voiceId: String? = null
+    var rate: Float = 1.0f
+    var pitch: Float = 1.0f
+    var volume: Float = 1.0f
+    var outputPath: String = ""
+    var ssml: Boolean = false
 }
 
 @InvokeArg
@@ -36,10 +81,18 @@ class GetVoicesArgs {
     var language: String? = null
 }
 
+@InvokeArg
+class WaitForUtteranceArgs {
+    var utteranceId: String = ""
+}
+
 @InvokeArg
 class PreviewVoiceArgs {
     var voiceId: String = ""
     var text: String? = null
+    var rate: Float = 1.0f
+    var pitch: Float = 1.0f
+    var volume: Float = 1.0f
     
     fun sampleText(): String = text ?: "Hello! This is a sample of how this voice sounds."
 }
@@ -59,9 +112,74 @@ private const val MAX_PENDING_REQUESTS = 50
 /** Timeout for pending requests in milliseconds */
 private const val PENDING_TIMEOUT_MS = 30_000L
 
+/** How many ended utterances waitForUtterance still knows the outcome of */
+private const val ENDED_UTTERANCES_KEPT = 64
+
 /** Allowed pattern for voice ID (alphanumeric, dots, underscores, hyphens) */
 private val VOICE_ID_PATTERN = Regex("^[a-zA-Z0-9._-]+$")
 
+/** Engines that read SSML passed as the utterance text; others speak the tags aloud */
+private val SSML_ENGINES = setOf("com.google.android.tts")
+
+private const val SSML_PLAIN_TEXT_WARNING = "SSML is not supported by this engine, speaking the text without markup"
+private const val NO_VOICES_WARNING = "No text-to-speech voices available on android, speech may be silent"
+private const val REPLACE_PENDING_WARNING = "Pending speech can't be dropped without stopping on this engine, flushed instead"
+private const val URGENT_WARNING = "Interrupted speech can't be resumed on this engine, flushed instead"
+
+/** Separates an utterance id from the index of one of its chunks in the id the engine sees */
+private const val CHUNK_SEPARATOR = "_chunk"
+
+/** One engine utterance of a text too long to be spoken in one */
+private data class UtteranceChunk(
+    /** Id of the whole utterance, reported in events */
+    val utteranceId: String,
+    /** Where the chunk starts in the utterance text */
+    val start: Int,
+    val first: Boolean,
+    val last: Boolean,
+)
+
+/**
+ * Split [text] into pieces of at most [maxLength] UTF-16 units, each paired with
+ * where it starts. Pieces end after a sentence where one fits, then at a space,
+ * and only as a last resort mid-word
+ */
+private fun splitForEngine(text: String, maxLength: Int): List<Pair<Int, String>> {
+    val pieces = mutableListOf<Pair<Int, String>>()
+    var start = 0
+    while (text.length - start > maxLength) {
+        val window = text.substring(start, start + maxLength)
+        val sentenceEnd = Regex("[.!?…]\\s|\\n").findAll(window).lastOrNull()?.let { it.range.last + 1 }
+        val spaceEnd = window.lastIndexOf(' ').takeIf { it > 0 }?.let { it + 1 }
+        var end = sentenceEnd ?: spaceEnd ?: maxLength
+        // Never cut a surrogate pair in half
+        if (end == maxLength && Character.isHighSurrogate(window[end - 1])) end--
+        pieces.add(start to window.substring(0, end))
+        start += end
+    }
+    pieces.add(start to text.substring(start))
+    return pieces
+}
+
+/** The words of an SSML document, already checked to be well-formed by the Rust side */
+private fun ssmlToPlainText(markup: String): String =
+    markup
+        .replace(Regex("<!--.*?-->", RegexOption.DOT_MATCHES_ALL), "")
+        // Keep the words on either side of a break, sentence or paragraph apart
+        .replace(Regex("</?(speak|break|p|s)\\b[^>]*>"), " ")
+        .replace(Regex("<[^>]*>"), "")
+        .replace(Regex("&#(x?)([0-9a-fA-F]+);")) { match ->
+            val radix = if (match.groupValues[1].isEmpty()) 10 else 16
+            String(Character.toChars(match.groupValues[2].toInt(radix)))
+        }
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+        .replace(Regex("\\s+"), " ")
+        .trim()
+
 
 private object InputValidator {
     fun validateText(text: String): String? {
@@ -88,6 +206,13 @@ data class PendingSpeak(
     val timestamp: Long = System.currentTimeMillis()
 )
 
+/** A speakToFile call waiting for the engine to finish writing its file */
+data class PendingFileWrite(
+    val invoke: Invoke,
+    val path: String,
+    val warning: String?
+)
+
 @TauriPlugin
 class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech.OnInitListener {
     private var tts: TextToSpeech? = null
@@ -100,8 +225,34 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
     private var cachedVoices: Set<Voice>? = null
     private var lastVoiceId: String? = null
     private var wasPlayingBeforeInterruption = false
+    // Set by the requestAudioFocus command: keep focus between utterances until abandoned
+    private var holdsAudioFocus = false
     private var lastUtteranceId: String? = null
     private var callbackReceived = false 
+    // speakToFile calls by utterance id; their callbacks resolve the call instead of emitting events
+    private val fileWrites = ConcurrentHashMap<String, PendingFileWrite>()
+    // Utterances handed to the engine that haven't started yet, in order, with the
+    // voice requested for each, for getQueueLength and getQueue
+    private val queuedUtterances = Collections.synchronizedMap(LinkedHashMap<String, String?>())
+    // Word offsets by utterance id, translating engine word boundaries for word-boundary events
+    private val wordOffsets = ConcurrentHashMap<String, Array<WordOffset>>()
+    // Sentence offsets by utterance id, a word boundary entering a new sentence emits sentence-start
+    private val sentenceOffsets = ConcurrentHashMap<String, SentenceProgress>()
+    // Text of each utterance handed to the engine, and the one it is speaking, for isSpeaking
+    private val utteranceTexts = ConcurrentHashMap<String, String>()
+    // Chunks of utterances too long for the engine, by the id the engine sees. Not
+    // cleared on flush: the engine stops every flushed chunk, which settles it
+    private val utteranceChunks = ConcurrentHashMap<String, UtteranceChunk>()
+    // Chunked utterances already reported as stopped or failed, so later chunks stay quiet
+    private val settledUtterances = ConcurrentHashMap.newKeySet<String>()
+    @Volatile private var currentUtteranceId: String? = null
+    // waitForUtterance calls by utterance id, resolved with how the utterance ended
+    private val utteranceWaiters = HashMap<String, MutableList<Invoke>>()
+    // How recent utterances ended, for waitForUtterance calls arriving after the end
+    private val endedUtterances = object : LinkedHashMap<String, JSObject>() {
+        override fun removeEldestEntry(eldest: MutableMap.MutableEntry<String, JSObject>?) =
+            size > ENDED_UTTERANCES_KEPT
+    }
 
 
     private val audioFocusChangeListener = AudioManager.OnAudioFocusChangeListener { focusChange ->
@@ -109,8 +260,14 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
             AudioManager.AUDIOFOCUS_LOSS -> {
                 // Permanent loss - another app took focus
                 Log.d(TAG, "Audio focus LOST permanently")
+                holdsAudioFocus = false
+                triggerFocusLost(transient = false, canDuck = false)
                 wasPlayingBeforeInterruption = tts?.isSpeaking == true
                 tts?.stop()
+                queuedUtterances.clear()
+                wordOffsets.clear()
+                sentenceOffsets.clear()
+                utteranceTexts.clear()
                 val event = JSObject()
                 event.put("reason", "audio_focus_lost")
                 trigger("speech:interrupted", event)
@@ -118,6 +275,7 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
             AudioManager.AUDIOFOCUS_LOSS_TRANSIENT -> {
                 // Temporary loss - e.g., phone call
                 Log.d(TAG, "Audio focus LOST transiently (phone call, notification, etc.)")
+                triggerFocusLost(transient = true, canDuck = false)
                 wasPlayingBeforeInterruption = tts?.isSpeaking == true
                 if (wasPlayingBeforeInterruption) {
                     pauseSpeakingInternal()
@@ -129,6 +287,7 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
             AudioManager.AUDIOFOCUS_LOSS_TRANSIENT_CAN_DUCK -> {
                 // We could lower volume, but for TTS it's better to pause
                 Log.d(TAG, "Audio focus LOSS_TRANSIENT_CAN_DUCK - pausing speech")
+                triggerFocusLost(transient = true, canDuck = true)
                 wasPlayingBeforeInterruption = tts?.isSpeaking == true
                 if (wasPlayingBeforeInterruption) {
                     pauseSpeakingInternal()
@@ -153,6 +312,8 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
 
     companion object {
         private const val TAG = "TtsPlugin"
+        // Silent utterances queued for pauseAfterMs; they must not surface as speech events
+        private const val PAUSE_UTTERANCE_SUFFIX = "_pause"
     }
 
     init {
@@ -215,18 +376,74 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         tts?.setOnUtteranceProgressListener(object : UtteranceProgressListener() {
             override fun onStart(utteranceId: String?) {
                 Log.d(TAG, "✓ UtteranceProgressListener.onStart() CALLED: $utteranceId")
+                if (utteranceId?.endsWith(PAUSE_UTTERANCE_SUFFIX) == true) return
+                if (utteranceId != null && fileWrites.containsKey(utteranceId)) return
+                val chunk = utteranceId?.let { utteranceChunks[it] }
+                // A long text starts once, with its first chunk
+                if (chunk?.first == false) return
+                val id = chunk?.utteranceId ?: utteranceId
+                id?.let { queuedUtterances.remove(it) }
+                currentUtteranceId = id
                 callbackReceived = true
                 val event = JSObject()
-                event.put("id", utteranceId ?: "")
+                event.put("id", id ?: "")
                 trigger("speech:start", event)
+                trigger("utterance-start", event)
+            }
+            
+            // API 26+; offsets are UTF-16 indices into the text the engine was given
+            override fun onRangeStart(utteranceId: String?, start: Int, end: Int, frame: Int) {
+                val chunk = utteranceId?.let { utteranceChunks[it] }
+                val id = chunk?.utteranceId ?: utteranceId
+                if (id == null) return
+                // Offsets are into the whole text, the engine's into the chunk
+                val spokenStart = start + (chunk?.start ?: 0)
+                sentenceOffsets[id]?.let { progress ->
+                    val sentence = progress.offsets.firstOrNull {
+                        spokenStart >= it.spokenStart && spokenStart < it.spokenEnd
+                    }
+                    if (sentence != null && sentence.index >= progress.next) {
+                        progress.next = sentence.index + 1
+                        val event = JSObject()
+                        event.put("utteranceId", id)
+                        event.put("index", sentence.index)
+                        event.put("charStart", sentence.textStart)
+                        event.put("charEnd", sentence.textEnd)
+                        trigger("sentence-start", event)
+                    }
+                }
+                val offsets = wordOffsets[id] ?: return
+                // Words added by preprocessing, like emoji names, have no offsets and no event
+                val word = offsets.firstOrNull { spokenStart >= it.spokenStart && spokenStart < it.spokenEnd } ?: return
+                val event = JSObject()
+                event.put("utteranceId", id)
+                event.put("charStart", word.textStart)
+                event.put("charEnd", word.textEnd)
+                event.put("charLength", word.textEnd - word.textStart)
+                trigger("word-boundary", event)
             }
             
             override fun onDone(utteranceId: String?) {
                 Log.d(TAG, "✓ UtteranceProgressListener.onDone() CALLED: $utteranceId")
+                if (utteranceId?.endsWith(PAUSE_UTTERANCE_SUFFIX) == true) return
+                if (finishFileWrite(utteranceId, null)) return
+                val chunk = utteranceId?.let { utteranceChunks.remove(it) }
+                // A long text finishes once, with its last chunk, unless it already failed
+                if (chunk != null && (!chunk.last || settledUtterances.remove(chunk.utteranceId))) return
+                val id = chunk?.utteranceId ?: utteranceId
+                id?.let {
+                    queuedUtterances.remove(it)
+                    wordOffsets.remove(it)
+                    sentenceOffsets.remove(it)
+                    utteranceTexts.remove(it)
+                    if (currentUtteranceId == it) currentUtteranceId = null
+                }
                 callbackReceived = true
+                endUtterance(id, "completed")
                 val event = JSObject()
-                event.put("id", utteranceId ?: "")
+                event.put("id", id ?: "")
                 trigger("speech:finish", event)
+                trigger("utterance-end", event)
                 
                 releaseAudioFocus()
             }
@@ -234,32 +451,68 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
             @Deprecated("Deprecated in API level 21")
             override fun onError(utteranceId: String?) {
                 Log.e(TAG, "✗ UtteranceProgressListener.onError() CALLED: $utteranceId")
+                if (finishFileWrite(utteranceId, "Speech synthesis error")) return
+                if (settledChunk(utteranceId)) return
+                val id = utteranceId?.let { utteranceChunks.remove(it)?.utteranceId } ?: utteranceId
+                id?.let {
+                    queuedUtterances.remove(it)
+                    wordOffsets.remove(it)
+                    sentenceOffsets.remove(it)
+                    utteranceTexts.remove(it)
+                    if (currentUtteranceId == it) currentUtteranceId = null
+                }
                 callbackReceived = true
                 val event = JSObject()
-                event.put("id", utteranceId ?: "")
+                event.put("id", id ?: "")
                 event.put("error", "Speech synthesis error")
                 trigger("speech:error", event)
+                triggerError(id, "Speech synthesis error")
+                endUtterance(id, "error", "Speech synthesis error")
                 
                 releaseAudioFocus()
             }
             
             override fun onError(utteranceId: String?, errorCode: Int) {
                 Log.e(TAG, "✗ UtteranceProgressListener.onError() CALLED: $utteranceId, code: $errorCode")
+                if (finishFileWrite(utteranceId, getErrorMessage(errorCode))) return
+                if (settledChunk(utteranceId)) return
+                val id = utteranceId?.let { utteranceChunks.remove(it)?.utteranceId } ?: utteranceId
+                id?.let {
+                    queuedUtterances.remove(it)
+                    wordOffsets.remove(it)
+                    sentenceOffsets.remove(it)
+                    utteranceTexts.remove(it)
+                    if (currentUtteranceId == it) currentUtteranceId = null
+                }
                 val event = JSObject()
-                event.put("id", utteranceId ?: "")
+                event.put("id", id ?: "")
                 event.put("error", getErrorMessage(errorCode))
                 event.put("code", errorCode)
                 trigger("speech:error", event)
+                triggerError(id, getErrorMessage(errorCode))
+                endUtterance(id, "error", getErrorMessage(errorCode))
                 
                 releaseAudioFocus()
             }
             
             override fun onStop(utteranceId: String?, interrupted: Boolean) {
                 Log.d(TAG, "✓ UtteranceProgressListener.onStop() CALLED: $utteranceId, interrupted: $interrupted")
+                if (finishFileWrite(utteranceId, "Writing speech was stopped before it finished")) return
+                if (settledChunk(utteranceId)) return
+                val id = utteranceId?.let { utteranceChunks.remove(it)?.utteranceId } ?: utteranceId
+                id?.let {
+                    queuedUtterances.remove(it)
+                    wordOffsets.remove(it)
+                    sentenceOffsets.remove(it)
+                    utteranceTexts.remove(it)
+                    if (currentUtteranceId == it) currentUtteranceId = null
+                }
                 val event = JSObject()
-                event.put("id", utteranceId ?: "")
+                event.put("id", id ?: "")
                 event.put("interrupted", interrupted)
                 trigger("speech:cancel", event)
+                trigger("utterance-cancelled", event)
+                endUtterance(id, "stopped")
                 
                 releaseAudioFocus()
             }
@@ -267,7 +520,52 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         Log.d(TAG, "  ✓ UtteranceProgressListener registered successfully")
     }
     
+    /**
+     * Whether [utteranceId] is a chunk of a long text that was already reported as
+     * stopped or failed, so that only the first chunk to stop or fail reports it
+     */
+    private fun settledChunk(utteranceId: String?): Boolean {
+        val chunk = utteranceId?.let { utteranceChunks[it] } ?: return false
+        val settled = if (chunk.last) {
+            settledUtterances.remove(chunk.utteranceId)
+        } else {
+            !settledUtterances.add(chunk.utteranceId)
+        }
+        if (settled) utteranceChunks.remove(utteranceId)
+        return settled
+    }
+
+    /** Settle the speakToFile call behind [utteranceId], if any. Returns whether there was one */
+    /** Remember how an utterance ended and answer the waitForUtterance calls waiting on it */
+    private fun endUtterance(utteranceId: String?, outcome: String, error: String? = null) {
+        if (utteranceId == null) return
+        val ret = JSObject()
+        ret.put("outcome", outcome)
+        error?.let { ret.put("error", it) }
+        val waiters = synchronized(utteranceWaiters) {
+            endedUtterances[utteranceId] = ret
+            utteranceWaiters.remove(utteranceId)
+        }
+        waiters?.forEach { it.resolve(ret) }
+    }
+
+    private fun finishFileWrite(utteranceId: String?, error: String?): Boolean {
+        val pending = utteranceId?.let { fileWrites.remove(it) } ?: return false
+        if (error != null) {
+            pending.invoke.reject("Failed to write speech to ${pending.path}: $error")
+            return true
+        }
+        Log.d(TAG, "  Speech written to ${pending.path}")
+        val ret = JSObject()
+        ret.put("path", pending.path)
+        pending.warning?.let { ret.put("warning", it) }
+        pending.invoke.resolve(ret)
+        return true
+    }
+
     private fun requestAudioFocus(): Boolean {
+        // Re-requesting would replace the held request, which then could never be abandoned
+        if (holdsAudioFocus) return true
         return if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
             val focusRequest = AudioFocusRequest.Builder(AudioManager.AUDIOFOCUS_GAIN_TRANSIENT)
                 .setAudioAttributes(
@@ -291,6 +589,8 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
     }
     
     private fun releaseAudioFocus() {
+        // Focus requested explicitly stays until abandonAudioFocus
+        if (holdsAudioFocus) return
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
             audioFocusRequest?.let { audioManager?.abandonAudioFocusRequest(it) }
         } else {
@@ -299,6 +599,59 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         }
     }
     
+    private fun triggerFocusLost(transient: Boolean, canDuck: Boolean) {
+        val event = JSObject()
+        event.put("transient", transient)
+        event.put("canDuck", canDuck)
+        trigger("audio:focusLost", event)
+    }
+    
+    // Same shape as the errors commands reject with, for the tts://error channel
+    private fun triggerError(utteranceId: String?, message: String) {
+        val event = JSObject()
+        event.put("code", "TTS_ENGINE_ERROR")
+        event.put("message", "TTS error: $message")
+        utteranceId?.let { event.put("id", it) }
+        trigger("error", event)
+    }
+    
+    /**
+     * Hand [text] to the engine in sentence-aligned chunks of at most [maxLength], the
+     * first with [queueMode] and the rest queued behind it. Returns the result of
+     * speaking the first chunk; when a later one is refused, the text ends early
+     */
+    private fun speakChunks(
+        engine: TextToSpeech,
+        utteranceId: String,
+        text: String,
+        maxLength: Int,
+        queueMode: Int,
+        params: HashMap<String, String>,
+    ): Int {
+        val pieces = splitForEngine(text, maxLength)
+        var previousId: String? = null
+        for ((index, piece) in pieces.withIndex()) {
+            val (start, chunkText) = piece
+            val chunkId = "$utteranceId$CHUNK_SEPARATOR$index"
+            utteranceChunks[chunkId] = UtteranceChunk(utteranceId, start, index == 0, index == pieces.lastIndex)
+            val chunkParams = HashMap(params)
+            chunkParams[TextToSpeech.Engine.KEY_PARAM_UTTERANCE_ID] = chunkId
+            val mode = if (index == 0) queueMode else TextToSpeech.QUEUE_ADD
+            @Suppress("DEPRECATION")
+            val result = engine.speak(chunkText, mode, chunkParams)
+            if (result != TextToSpeech.SUCCESS) {
+                utteranceChunks.remove(chunkId)
+                val previous = previousId ?: return result
+                Log.e(TAG, "  Engine refused chunk $index of $utteranceId, the text ends early")
+                // The chunk already queued finishes the utterance instead
+                utteranceChunks.computeIfPresent(previous) { _, chunk -> chunk.copy(last = true) }
+                break
+            }
+            previousId = chunkId
+        }
+        return TextToSpeech.SUCCESS
+    }
+
     private fun getErrorMessage(errorCode: Int): String {
         return when (errorCode) {
             TextToSpeech.ERROR -> "Generic error"
@@ -558,7 +911,7 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
 
                 // Android TTS: 1.0 is normal speed, 0.5 is half, 2.0 is double
                 // Match user API directly (no normalization needed)
-                val rate = args.rate.coerceIn(0.1f, 4.0f)
+                val rate = args.rate.coerceIn(0.1f, 3.0f)
                 val pitch = args.pitch.coerceIn(0.1f, 2.0f)
                 val volume = args.volume.coerceIn(0.0f, 1.0f)
                 
@@ -589,7 +942,8 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
 
                 // Create params bundle with utteranceId only
                 // Note: Using deprecated API with HashMap is more stable than Bundle with some engines
-                val utteranceId = "tts_${System.currentTimeMillis()}"
+                // Unique even for segments sent within the same millisecond
+                val utteranceId = "tts_${UUID.randomUUID()}"
                 val params = HashMap<String, String>()
                 params[TextToSpeech.Engine.KEY_PARAM_UTTERANCE_ID] = utteranceId
                 
@@ -597,8 +951,27 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
                 lastUtteranceId = utteranceId
                 callbackReceived = false
                 
+                // Stripped images can ship an engine without voices, which then speaks nothing
+                if (engine.voices.isNullOrEmpty() && cachedVoices.isNullOrEmpty()) {
+                    Log.w(TAG, "  Engine lists no voices")
+                    warning = warning ?: NO_VOICES_WARNING
+                }
+
                 // Determine queue mode: QUEUE_FLUSH (default) or QUEUE_ADD
-                val queueMode = if (args.queueMode.lowercase() == "add") {
+                val mode = args.queueMode.lowercase()
+                // The engine can't drop queued utterances alone, so replacePending only
+                // keeps current speech when nothing is waiting behind it
+                val replaceable = mode == "replacepending" && queuedUtterances.size <= 1
+                if (mode == "replacepending" && !replaceable) {
+                    Log.w(TAG, "  Utterances are waiting, replacePending flushes instead")
+                    warning = warning ?: REPLACE_PENDING_WARNING
+                }
+                // The engine can't put interrupted speech back, so urgent speech flushes
+                if (mode == "urgent" && (engine.isSpeaking || queuedUtterances.isNotEmpty())) {
+                    Log.w(TAG, "  Speech is in progress, urgent flushes it")
+                    warning = warning ?: URGENT_WARNING
+                }
+                val queueMode = if (mode == "add" || replaceable) {
                     Log.d(TAG, "  Queue mode: QUEUE_ADD")
                     TextToSpeech.QUEUE_ADD
                 } else {
@@ -611,9 +984,52 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
                 Log.d(TAG, "    Engine default voice: ${engine.defaultVoice?.name}")
                 Log.d(TAG, "    Engine voices available: ${engine.voices?.size ?: 0}")
                 
-                @Suppress("DEPRECATION")
-                val speakResult = engine.speak(args.text, queueMode, params)
+                // Tracked before speaking, onStart may run before speak() returns
+                if (queueMode == TextToSpeech.QUEUE_FLUSH) {
+                    queuedUtterances.clear()
+                    wordOffsets.clear()
+                    sentenceOffsets.clear()
+                    utteranceTexts.clear()
+                }
+                queuedUtterances[utteranceId] = args.voiceId
+                if (args.wordOffsets.isNotEmpty()) wordOffsets[utteranceId] = args.wordOffsets
+                if (args.sentenceOffsets.isNotEmpty()) {
+                    sentenceOffsets[utteranceId] = SentenceProgress(args.sentenceOffsets)
+                }
+
+                val readsSsml = args.ssml && engine.defaultEngine in SSML_ENGINES
+                val text = if (args.ssml && !readsSsml) {
+                    Log.w(TAG, "  Engine ${engine.defaultEngine} can't read SSML, speaking plain text")
+                    warning = warning ?: SSML_PLAIN_TEXT_WARNING
+                    ssmlToPlainText(args.text)
+                } else {
+                    args.text
+                }
+                utteranceTexts[utteranceId] = text
+
+                // The engine refuses text over its input limit, so longer text is spoken in
+                // chunks that report as this one utterance. Markup can't be cut apart
+                val maxLength = TextToSpeech.getMaxSpeechInputLength()
+                val speakResult = if (text.length > maxLength && !readsSsml) {
+                    Log.d(TAG, "  Text over the engine limit of $maxLength, speaking it in chunks")
+                    speakChunks(engine, utteranceId, text, maxLength, queueMode, params)
+                } else {
+                    @Suppress("DEPRECATION")
+                    engine.speak(text, queueMode, params)
+                }
                 Log.d(TAG, "  speak() result: $speakResult (SUCCESS=${TextToSpeech.SUCCESS}, ERROR=${TextToSpeech.ERROR})")
+                if (speakResult != TextToSpeech.SUCCESS) {
+                    queuedUtterances.remove(utteranceId)
+                    wordOffsets.remove(utteranceId)
+                    sentenceOffsets.remove(utteranceId)
+                    utteranceTexts.remove(utteranceId)
+                }
+                
+                // Queue silence behind the utterance so the next queued one starts after the pause
+                args.pauseAfterMs?.takeIf { it > 0 && speakResult == TextToSpeech.SUCCESS }?.let { pauseMs ->
+                    engine.playSilentUtterance(pauseMs, TextToSpeech.QUEUE_ADD, "$utteranceId$PAUSE_UTTERANCE_SUFFIX")
+                    Log.d(TAG, "  Pause after utterance: ${pauseMs}ms")
+                }
                 
                 // Log final engine state after speak attempt
                 val voiceAfterSpeak = engine.voice
@@ -667,6 +1083,7 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
                             errorEvent.put("id", utteranceId)
                             errorEvent.put("error", "TTS engine timeout - callbacks not received. Voice data may not be installed.")
                             trigger("speech:error", errorEvent)
+                            triggerError(utteranceId, "TTS engine timeout - callbacks not received. Voice data may not be installed.")
                             releaseAudioFocus()
                         }
                     }, 1000)
@@ -686,11 +1103,133 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         }
     }
 
+    @Command
+    fun speakToFile(invoke: Invoke) {
+        Log.i(TAG, "speakToFile() CALLED")
+        val args = invoke.parseArgs(SpeakToFileArgs::class.java)
+
+        InputValidator.validateText(args.text)?.let { error ->
+            invoke.reject(error)
+            return
+        }
+        args.voiceId?.let { voiceId ->
+            InputValidator.validateVoiceId(voiceId)?.let { error ->
+                invoke.reject(error)
+                return
+            }
+        }
+        args.language?.let { language ->
+            InputValidator.validateLanguage(language)?.let { error ->
+                invoke.reject(error)
+                return
+            }
+        }
+
+        val engine = tts
+        if (engine == null || !isInitialized) {
+            invoke.reject("TTS not initialized")
+            return
+        }
+
+        try {
+            var warning: String? = null
+            val voiceId = args.voiceId?.takeIf { it != "default" && it.isNotBlank() }
+            if (voiceId != null) {
+                val voice = engine.voices?.find { it.name == voiceId }
+                if (voice != null) {
+                    engine.voice = voice
+                    lastVoiceId = voiceId
+                } else {
+                    Log.w(TAG, "  Voice not found: $voiceId, using default")
+                    warning = "Voice '$voiceId' not found, using default voice"
+                }
+            } else {
+                args.language?.takeIf { it != "default" && it.isNotBlank() }?.let { lang ->
+                    val result = engine.setLanguage(parseLocale(lang))
+                    if (result == TextToSpeech.LANG_MISSING_DATA || result == TextToSpeech.LANG_NOT_SUPPORTED) {
+                        Log.w(TAG, "  Language not supported: $lang, using default")
+                        warning = "Language '$lang' not supported, using default language"
+                    }
+                }
+            }
+
+            // Same workaround as speak(): leave default values alone
+            if (args.rate != 1.0f) engine.setSpeechRate(args.rate)
+            if (args.pitch != 1.0f) engine.setPitch(args.pitch)
+
+            val text = if (args.ssml && engine.defaultEngine !in SSML_ENGINES) {
+                warning = warning ?: SSML_PLAIN_TEXT_WARNING
+                ssmlToPlainText(args.text)
+            } else {
+                args.text
+            }
+
+            val file = File(args.outputPath)
+            file.parentFile?.mkdirs()
+            val params = Bundle()
+            params.putFloat(TextToSpeech.Engine.KEY_PARAM_VOLUME, args.volume)
+
+            // Resolved by the utterance listener once the engine finished writing
+            val utteranceId = "tts_file_${UUID.randomUUID()}"
+            fileWrites[utteranceId] = PendingFileWrite(invoke, file.absolutePath, warning)
+            val result = engine.synthesizeToFile(text, params, file, utteranceId)
+            Log.d(TAG, "  synthesizeToFile() result: $result, path: ${file.absolutePath}")
+            if (result != TextToSpeech.SUCCESS) {
+                fileWrites.remove(utteranceId)
+                invoke.reject("Failed to start writing speech to ${file.absolutePath}")
+            }
+        } catch (e: Exception) {
+            Log.e(TAG, "Error writing speech to file: ${e.message}")
+            invoke.reject("Failed to write speech to file: ${e.message}")
+        }
+    }
+
+    /** Resolves once the utterance finished, was stopped or failed, for speak_and_wait */
+    @Command
+    fun waitForUtterance(invoke: Invoke) {
+        val args = invoke.parseArgs(WaitForUtteranceArgs::class.java)
+        val ended = synchronized(utteranceWaiters) {
+            endedUtterances[args.utteranceId] ?: run {
+                utteranceWaiters.getOrPut(args.utteranceId) { mutableListOf() }.add(invoke)
+                null
+            }
+        }
+        ended?.let { invoke.resolve(it) }
+    }
+
+    @Command
+    fun getQueueLength(invoke: Invoke) {
+        val ret = JSObject()
+        ret.put("length", queuedUtterances.size)
+        invoke.resolve(ret)
+    }
+
+    @Command
+    fun getQueue(invoke: Invoke) {
+        val queued = synchronized(queuedUtterances) { queuedUtterances.toList() }
+        val utterances = JSArray()
+        for ((id, voiceId) in queued) {
+            val utterance = JSObject()
+            utterance.put("id", id)
+            utterance.put("text", utteranceTexts[id] ?: "")
+            voiceId?.let { utterance.put("voiceId", it) }
+            utterances.put(utterance)
+        }
+        val ret = JSObject()
+        ret.put("utterances", utterances)
+        invoke.resolve(ret)
+    }
+
     @Command
     fun stop(invoke: Invoke) {
         Log.i(TAG, "stop() CALLED")
         try {
             tts?.stop()
+            queuedUtterances.clear()
+            wordOffsets.clear()
+            sentenceOffsets.clear()
+            utteranceTexts.clear()
+            isPaused = false
             Log.d(TAG, "  TTS stopped")
             val ret = JSObject()
             ret.put("success", true)
@@ -802,12 +1341,7 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
                 if (languageFilter == null || voiceLanguage.contains(languageFilter)) {
                     seenIds.add(voice.name)
                     
-                    val voiceObj = JSObject()
-                    voiceObj.put("id", voice.name)
-                    // Create friendly display name from voice identifier
-                    voiceObj.put("name", formatVoiceDisplayName(voice))
-                    voiceObj.put("language", voice.locale.toLanguageTag())
-                    voicesArray.put(voiceObj)
+                    voicesArray.put(voiceJson(voice, engine))
                 }
             }
             
@@ -821,6 +1355,51 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         }
     }
     
+    @Command
+    fun getDefaultVoice(invoke: Invoke) {
+        Log.i(TAG, "getDefaultVoice() CALLED")
+        val engine = tts
+        if (!isInitialized || engine == null) {
+            invoke.reject("TTS not initialized")
+            return
+        }
+        // The voice and language picked in the system's text-to-speech settings
+        val voice = engine.defaultVoice
+        Log.d(TAG, "  Default voice: ${voice?.name ?: "null"}")
+        val ret = JSObject()
+        voice?.let { ret.put("voice", voiceJson(it, engine)) }
+        ret.put("language", (voice?.locale ?: Locale.getDefault()).toLanguageTag())
+        invoke.resolve(ret)
+    }
+    
+    /** A Voice as the plugin's Voice model */
+    private fun voiceJson(voice: Voice, engine: TextToSpeech?): JSObject {
+        val voiceObj = JSObject()
+        voiceObj.put("id", voice.name)
+        // Create friendly display name from voice identifier
+        voiceObj.put("name", formatVoiceDisplayName(voice))
+        voiceObj.put("language", voice.locale.toLanguageTag())
+        // Android voices don't report a gender
+        voiceObj.put("quality", qualityName(voice.quality))
+        voiceObj.put("requiresNetwork", voice.isNetworkConnectionRequired)
+        if (!voice.isNetworkConnectionRequired) {
+            // Engines flag local voices whose data hasn't been downloaded yet
+            val notInstalled = voice.features.orEmpty()
+                .contains(TextToSpeech.Engine.KEY_FEATURE_NOT_INSTALLED)
+            voiceObj.put("installed", !notInstalled)
+        }
+        engine?.defaultEngine?.let { voiceObj.put("engine", it) }
+        return voiceObj
+    }
+    
+    /** Name of the plugin's VoiceQuality tier for a Voice.QUALITY_* value */
+    private fun qualityName(quality: Int): String = when {
+        quality >= Voice.QUALITY_VERY_HIGH -> "premium"
+        quality >= Voice.QUALITY_HIGH -> "enhanced"
+        quality >= Voice.QUALITY_NORMAL -> "normal"
+        else -> "low"
+    }
+    
     private fun formatVoiceDisplayName(voice: Voice): String {
         val locale = voice.locale
         val language = locale.displayLanguage
@@ -848,6 +1427,9 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
             Log.d(TAG, "  Speaking: $speaking")
             val ret = JSObject()
             ret.put("speaking", speaking)
+            val current = currentUtteranceId?.takeIf { speaking }
+            current?.let { ret.put("currentUtteranceId", it) }
+            current?.let { utteranceTexts[it] }?.let { ret.put("currentText", it) }
             invoke.resolve(ret)
         } catch (e: Exception) {
             Log.e(TAG, "Failed to check speaking status: ${e.message}", e)
@@ -855,6 +1437,13 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         }
     }
     
+    @Command
+    fun isPaused(invoke: Invoke) {
+        val ret = JSObject()
+        ret.put("paused", isPaused)
+        invoke.resolve(ret)
+    }
+    
     @Command
     fun isInitialized(invoke: Invoke) {
         Log.d(TAG, "isInitialized() CALLED")
@@ -952,6 +1541,78 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         isPaused = false
     }
     
+    @Command
+    fun getEngineParams(invoke: Invoke) {
+        Log.d(TAG, "getEngineParams() CALLED")
+        // TextToSpeech has no getters for rate, pitch or volume, only the voice
+        val ret = JSObject()
+        ret.put("voiceId", tts?.voice?.name)
+        invoke.resolve(ret)
+    }
+    
+    @Command
+    fun requestAudioFocus(invoke: Invoke) {
+        Log.i(TAG, "requestAudioFocus() CALLED")
+        holdsAudioFocus = false
+        val granted = requestAudioFocus()
+        holdsAudioFocus = granted
+        Log.d(TAG, "  Granted: $granted")
+        val ret = JSObject()
+        ret.put("granted", granted)
+        invoke.resolve(ret)
+    }
+    
+    @Command
+    fun abandonAudioFocus(invoke: Invoke) {
+        Log.i(TAG, "abandonAudioFocus() CALLED")
+        holdsAudioFocus = false
+        if (tts?.isSpeaking != true) {
+            releaseAudioFocus()
+        }
+        val ret = JSObject()
+        ret.put("granted", false)
+        invoke.resolve(ret)
+    }
+    
+    @Command
+    fun getAudioRoute(invoke: Invoke) {
+        Log.i(TAG, "getAudioRoute() CALLED")
+        val route = currentAudioRoute()
+        Log.d(TAG, "  Route: $route")
+        val ret = JSObject()
+        ret.put("route", route)
+        invoke.resolve(ret)
+    }
+    
+    private fun currentAudioRoute(): String {
+        val am = audioManager ?: return "unknown"
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.M) {
+            @Suppress("DEPRECATION")
+            return when {
+                am.isBluetoothA2dpOn || am.isBluetoothScoOn -> "bluetooth"
+                am.isWiredHeadsetOn -> "headphones"
+                else -> "speaker"
+            }
+        }
+        // Media follows the most private connected output, so report by priority
+        val types = am.getDevices(AudioManager.GET_DEVICES_OUTPUTS).map { it.type }.toSet()
+        val bluetooth = mutableListOf(AudioDeviceInfo.TYPE_BLUETOOTH_A2DP, AudioDeviceInfo.TYPE_BLUETOOTH_SCO)
+        val wired = mutableListOf(AudioDeviceInfo.TYPE_WIRED_HEADSET, AudioDeviceInfo.TYPE_WIRED_HEADPHONES)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            wired.add(AudioDeviceInfo.TYPE_USB_HEADSET)
+        }
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) {
+            bluetooth.add(AudioDeviceInfo.TYPE_BLE_HEADSET)
+        }
+        return when {
+            types.any { it in wired } -> "headphones"
+            types.any { it in bluetooth } -> "bluetooth"
+            AudioDeviceInfo.TYPE_BUILTIN_SPEAKER in types -> "speaker"
+            types.isEmpty() -> "unknown"
+            else -> "other"
+        }
+    }
+    
     @Command
     fun previewVoice(invoke: Invoke) {
         Log.i(TAG, "previewVoice() CALLED")
@@ -983,6 +1644,10 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
                 requestAudioFocus()
                 
                 engine.stop()
+                queuedUtterances.clear()
+                wordOffsets.clear()
+                sentenceOffsets.clear()
+                utteranceTexts.clear()
                 Log.d(TAG, "  Stopped current speech")
                 
                 val voices = engine.voices ?: emptySet()
@@ -1002,6 +1667,8 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
                 
                 // WORKAROUND: Don't set rate/pitch to 1.0f (Google TTS bug)
                 // Just use engine defaults instead of explicitly setting to 1.0
+                if (args.rate != 1.0f) engine.setSpeechRate(args.rate)
+                if (args.pitch != 1.0f) engine.setPitch(args.pitch)
                 
                 val utteranceId = "preview_${System.currentTimeMillis()}"
                 val params = HashMap<String, String>()
@@ -1045,6 +1712,9 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         tts?.shutdown()
         tts = null
         isInitialized = false
+        // A shut down engine reports nothing more, so nothing would answer them
+        val waiting = synchronized(utteranceWaiters) { utteranceWaiters.keys.toList() }
+        waiting.forEach { endUtterance(it, "stopped") }
         Log.d(TAG, "  TTS resources released")
     }
     
@@ -1056,6 +1726,11 @@ class TtsPlugin(private val activity: Activity) : Plugin(activity), TextToSpeech
         if (tts?.isSpeaking == true) {
             Log.d(TAG, "  Stopping speech due to activity pause")
             tts?.stop()
+            queuedUtterances.clear()
+            wordOffsets.clear()
+            sentenceOffsets.clear()
+            utteranceTexts.clear()
+            isPaused = false
             releaseAudioFocus()
         }
     }
@@ -1,12 +1,73 @@
 const COMMANDS: &[&str] = &[
     "speak",
+    "speak_and_wait",
     "stop",
+    "stop_if_matches",
+    "stop_gentle",
+    "get_queue_length",
+    "get_queue",
+    "clear_queue",
+    "remove_from_queue",
+    "skip_to_next",
     "get_voices",
+    "refresh_voices",
+    "get_languages",
     "is_speaking",
+    "is_paused",
     "is_initialized",
+    "reinitialize",
+    "get_capabilities",
+    "get_rate_info",
+    "get_features",
+    "get_backends",
+    "get_cache_stats",
+    "clear_tts_cache",
+    "list_piper_voices",
+    "download_piper_voice",
+    "get_audio_route",
+    "request_audio_focus",
+    "abandon_audio_focus",
+    "get_engine_params",
+    "get_current_voice",
+    "get_default_voice",
+    "resolve_voice",
+    "get_best_voice",
+    "speak_to_file",
+    "synthesize",
+    "synthesize_stream",
     "pause_speaking",
     "resume_speaking",
     "preview_voice",
+    "preview_all",
+    "calibration_samples",
+    "get_pronunciation",
+    "count_words",
+    "set_affixes",
+    "add_pronunciation",
+    "remove_pronunciation",
+    "list_pronunciations",
+    "set_voice_gain",
+    "export_profile",
+    "import_profile",
+    "set_voice",
+    "set_default_voice",
+    "set_rate",
+    "set_pitch",
+    "set_volume",
+    "reset_session_usage",
+    "speak_sequence",
+    "speak_segments",
+    "speak_from_offset",
+    "speak_outline",
+    "speak_section",
+    "speak_list_begin",
+    "speak_list_next",
+    "speak_list_reset",
+    "set_enabled",
+    "is_enabled",
+    "speak_words_begin",
+    "speak_word_next",
+    "benchmark_voice",
 ];
 
 fn main() {
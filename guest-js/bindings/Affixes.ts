@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Text framing every utterance, e.g. a brand name announced first
+ */
+export type Affixes = { 
+/**
+ * Text spoken before every utterance
+ */
+prefix: string | null, 
+/**
+ * Text spoken after every utterance
+ */
+suffix: string | null, };
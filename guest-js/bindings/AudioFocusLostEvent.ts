@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://audio:focusLost` event (mobile only)
+ */
+export type AudioFocusLostEvent = { 
+/**
+ * Whether focus is expected back, e.g. after a phone call ends
+ */
+transient: boolean, 
+/**
+ * Whether the other app only asked for ducking (Android)
+ */
+canDuck: boolean, };
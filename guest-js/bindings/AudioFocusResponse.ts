@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AudioFocusResponse = { 
+/**
+ * Whether the app now holds audio focus (always `false` after abandoning)
+ */
+granted: boolean, 
+/**
+ * Why focus wasn't granted, or why the call had no effect
+ */
+note?: string, };
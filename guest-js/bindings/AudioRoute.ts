@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where audio output is currently going
+ */
+export type AudioRoute = "speaker" | "earpiece" | "headphones" | "bluetooth" | "other" | "unknown";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AudioRoute } from "./AudioRoute";
+
+export type AudioRouteResponse = { 
+/**
+ * Current output route
+ */
+route: AudioRoute, 
+/**
+ * Whether the route is a private output (headphones, Bluetooth or earpiece)
+ */
+isPrivate: boolean, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://backend-fallback` event (desktop): the backend speaking
+ * an utterance failed, and the rest of it is spoken with the next one of
+ * `backendFallback`
+ */
+export type BackendFallbackEvent = { 
+/**
+ * The `utteranceId` of the utterance being spoken
+ */
+utteranceId: string, 
+/**
+ * Backend that failed, "system" for the OS engine
+ */
+from: string, 
+/**
+ * Backend speaking the rest of the utterance
+ */
+to: string, 
+/**
+ * Why `from` failed
+ */
+error: string, };
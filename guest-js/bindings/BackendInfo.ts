@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TtsFeatures } from "./TtsFeatures";
+
+/**
+ * A backend requests can name in `backend`, the OS engine being `system`
+ */
+export type BackendInfo = { name: string, 
+/**
+ * What the backend can honor, like `getFeatures` for the OS engine
+ */
+features: TtsFeatures, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Voice } from "./Voice";
+
+export type BenchmarkVoiceResponse = { success: boolean, 
+/**
+ * The benchmarked voice. Left out, like `text` and `durationMs`, when nothing
+ * was spoken because the plugin is disabled
+ */
+voice?: Voice, 
+/**
+ * The standard phrase that was spoken
+ */
+text?: string, 
+/**
+ * Measured time from submitting the phrase until speech ended, in milliseconds
+ */
+durationMs?: number, 
+/**
+ * Anything that makes the measurement less comparable (e.g. fallback phrase),
+ * or `"tts disabled"` when there was none
+ */
+warnings: Array<string>, };
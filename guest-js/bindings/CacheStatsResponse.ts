@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Size and use of the synthesized audio cache
+ */
+export type CacheStatsResponse = { 
+/**
+ * Cached clips, including those of earlier launches
+ */
+entries: number, 
+/**
+ * Bytes the cached clips take up
+ */
+bytes: number, 
+/**
+ * Configured `cacheMaxBytes`, 0 when caching is off
+ */
+maxBytes: number, 
+/**
+ * Clips played or rendered from the cache this session
+ */
+hits: number, 
+/**
+ * Clips looked up but synthesized this session
+ */
+misses: number, 
+/**
+ * Where clips are kept, `null` when the app cache directory can't be resolved
+ */
+directory: string | null, };
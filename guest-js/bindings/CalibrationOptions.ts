@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CalibrationOptions = { 
+/**
+ * Phrase spoken at every rate (uses default if not provided)
+ */
+text: string | null, 
+/**
+ * Rates to sample, in order (default: 0.75, 1.0, 1.25, 1.5)
+ */
+rates: Array<number> | null, 
+/**
+ * Voice to calibrate. Defaults to the active profile
+ */
+voiceId: string | null, 
+/**
+ * Language, used when no voice is given
+ */
+language: string | null, 
+/**
+ * Silence between samples in milliseconds (default: 700)
+ */
+gapMs: number | null, };
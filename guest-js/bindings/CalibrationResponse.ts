@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CalibrationResponse = { success: boolean, 
+/**
+ * Rates queued for playback, in order
+ */
+rates: Array<number>, 
+/**
+ * Set when nothing was queued because the plugin is disabled
+ */
+warning?: string, };
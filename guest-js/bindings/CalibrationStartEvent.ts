@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://calibration:start` event
+ */
+export type CalibrationStartEvent = { 
+/**
+ * Position of this sample in the sequence (0-based)
+ */
+index: number, 
+/**
+ * Total number of samples
+ */
+total: number, 
+/**
+ * Rate of the sample that is starting
+ */
+rate: number, };
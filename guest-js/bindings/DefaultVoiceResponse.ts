@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Voice } from "./Voice";
+
+/**
+ * The system's own voice and speech language, before the app picks any
+ */
+export type DefaultVoiceResponse = { 
+/**
+ * Voice the system speaks with by default, `null` when the engine can't report it
+ */
+voice: Voice | null, 
+/**
+ * Language the system speaks by default (e.g. "en-US"), `null` when unknown
+ */
+language: string | null, };
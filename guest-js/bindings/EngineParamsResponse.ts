@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Parameters as read back from the live engine. `None` means the engine can't report it
+ */
+export type EngineParamsResponse = { 
+/**
+ * Speech rate converted to the plugin's scale (1.0 = normal)
+ */
+rate: number | null, 
+/**
+ * Speech rate in the engine's own units
+ */
+nativeRate: number | null, 
+/**
+ * Pitch (1.0 = normal)
+ */
+pitch: number | null, 
+/**
+ * Volume (0.0 to 1.0)
+ */
+volume: number | null, 
+/**
+ * ID of the voice the engine currently uses
+ */
+voiceId: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendInfo } from "./BackendInfo";
+
+export type GetBackendsResponse = { 
+/**
+ * Backends by name, the OS engine among them as "system"
+ */
+backends: Array<BackendInfo>, };
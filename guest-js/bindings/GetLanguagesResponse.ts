@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Languages the available voices speak
+ */
+export type GetLanguagesResponse = { 
+/**
+ * Language codes of the voices, sorted and without duplicates
+ */
+languages: Array<string>, 
+/**
+ * Number of voices per language code
+ */
+voiceCounts: { [key in string]?: number }, };
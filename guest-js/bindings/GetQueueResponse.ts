@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueuedItem } from "./QueuedItem";
+
+export type GetQueueResponse = { 
+/**
+ * Utterances waiting behind the one being spoken, in the order they'll be spoken
+ */
+items: Array<QueuedItem>, };
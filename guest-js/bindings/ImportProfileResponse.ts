@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VoiceProfile } from "./VoiceProfile";
+
+export type ImportProfileResponse = { 
+/**
+ * The profile as applied, after fallbacks
+ */
+profile: VoiceProfile, 
+/**
+ * Settings that could not be applied as-is (e.g. missing voice)
+ */
+warnings: Array<string>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type IsSpeakingResponse = { 
+/**
+ * Whether speech is in progress or queued
+ */
+speaking: boolean, 
+/**
+ * Id of the utterance being spoken, as in its `speech:*` events
+ */
+currentUtteranceId?: string, 
+/**
+ * Text of that utterance as handed to the engine, after preprocessing
+ */
+currentText?: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Respelling applied to every utterance's text before it is spoken
+ */
+export type LexiconEntry = { 
+/**
+ * Word or phrase as it appears in the text, matched whole-word and case-insensitively
+ */
+word: string, 
+/**
+ * Text spoken in its place, e.g. "sequel" for "SQL"
+ */
+spokenAs: string, };
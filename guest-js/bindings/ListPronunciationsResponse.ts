@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LexiconEntry } from "./LexiconEntry";
+
+export type ListPronunciationsResponse = { 
+/**
+ * Lexicon entries in the order they were added
+ */
+entries: Array<LexiconEntry>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A heading found by `speak_outline`
+ */
+export type OutlineEntry = { 
+/**
+ * Heading text, without markdown `#`
+ */
+title: string, 
+/**
+ * Markdown heading depth (1 for `#`), 1 for headings detected by their casing
+ */
+level: number, 
+/**
+ * Line of the heading in the text (0-based)
+ */
+line: number, };
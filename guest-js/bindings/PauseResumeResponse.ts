@@ -2,6 +2,6 @@
 
 export type PauseResumeResponse = { success: boolean, 
 /**
- * Reason for failure (if success is false)
+ * Reason for failure, or how desktop approximated the pause on success
  */
 reason: string | null, };
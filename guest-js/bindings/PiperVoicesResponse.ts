@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Voice } from "./Voice";
+
+/**
+ * Piper voices installed for the `piper` backend
+ */
+export type PiperVoicesResponse = { 
+/**
+ * Where voices are kept, `null` when the app data directory can't be resolved
+ */
+directory: string | null, voices: Array<Voice>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PreviewAllOptions = { 
+/**
+ * Language whose voices should be previewed (e.g., "en" or "pt-BR")
+ */
+language: string, 
+/**
+ * Optional custom sample text (uses default if not provided)
+ */
+text: string | null, 
+/**
+ * Silence between previews in milliseconds (default: 700)
+ */
+gapMs: number | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Voice } from "./Voice";
+
+export type PreviewAllResponse = { success: boolean, 
+/**
+ * Voices queued for preview, in playback order
+ */
+voices: Array<Voice>, 
+/**
+ * Set when nothing was queued because the plugin is disabled
+ */
+warning?: string, };
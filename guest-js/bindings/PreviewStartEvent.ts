@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Voice } from "./Voice";
+
+/**
+ * Payload of the `tts://preview:start` event
+ */
+export type PreviewStartEvent = { 
+/**
+ * Position of this voice in the preview sequence (0-based)
+ */
+index: number, 
+/**
+ * Total number of voices being previewed
+ */
+total: number, 
+/**
+ * The voice whose preview is starting
+ */
+voice: Voice, };
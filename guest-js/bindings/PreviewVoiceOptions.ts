@@ -8,4 +8,16 @@ voiceId: string,
 /**
  * Optional custom sample text (uses default if not provided)
  */
-text: string | null, };
+text: string | null, 
+/**
+ * Speech rate (0.1 to 3.0, where 1.0 = normal). Defaults to the active profile
+ */
+rate: number | null, 
+/**
+ * Pitch (0.5 to 2.0, where 1.0 = normal). Defaults to the active profile
+ */
+pitch: number | null, 
+/**
+ * Volume (0.0 to 1.0, where 1.0 = full volume). Defaults to the active profile
+ */
+volume: number | null, };
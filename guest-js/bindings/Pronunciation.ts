@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Pronunciation override for one word or phrase
+ */
+export type Pronunciation = { 
+/**
+ * Word as it appears in the text, matched case-insensitively
+ */
+word: string, 
+/**
+ * Pronunciation in the International Phonetic Alphabet, e.g. "təˈmɑːtəʊ"
+ */
+ipa: string, };
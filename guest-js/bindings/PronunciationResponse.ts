@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PronunciationResponse = { 
+/**
+ * Phonetic representation of the text as the engine will pronounce it
+ */
+phonemes: string, 
+/**
+ * Phoneme alphabet used by the engine (e.g., "apple", "ipa")
+ */
+alphabet: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An utterance waiting to be spoken
+ */
+export type QueuedItem = { 
+/**
+ * Id carried by the `speech:*` events of this utterance
+ */
+id: string, 
+/**
+ * Start of its text as handed to the engine, ending in "…" when cut
+ */
+textPreview: string, 
+/**
+ * Voice requested for it, when not the engine default
+ */
+voiceId?: string, 
+/**
+ * Place in the queue, 0 being the next one spoken
+ */
+position: number, };
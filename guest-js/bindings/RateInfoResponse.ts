@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RateInfoResponse = { 
+/**
+ * Estimated words per minute at rate 1.0. The real pace varies with the
+ * voice and the text
+ */
+normalWpm: number, 
+/**
+ * Words per minute at the slowest rate (0.1)
+ */
+minWpm: number, 
+/**
+ * Words per minute at the fastest rate (3.0)
+ */
+maxWpm: number, };
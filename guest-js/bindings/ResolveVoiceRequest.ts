@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A voice saved earlier, possibly on another device or OS version, to find
+ * among the current voices
+ */
+export type ResolveVoiceRequest = { 
+/**
+ * Id of the saved voice
+ */
+id: string, 
+/**
+ * Display name of the saved voice
+ */
+name: string, 
+/**
+ * Language code of the saved voice
+ */
+language: string, 
+/**
+ * Platform the voice was saved on
+ */
+platform: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://segment:start` event
+ */
+export type SegmentStartEvent = { 
+/**
+ * Position of the segment in the request (0-based)
+ */
+index: number, 
+/**
+ * Total number of segments
+ */
+total: number, 
+/**
+ * Utterance id of the segment, as in the response's `utteranceIds`
+ */
+utteranceId: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://sentence-start` event: the sentence about to be spoken,
+ * as UTF-16 offsets into the `text` of the request like [`WordBoundaryEvent`]
+ */
+export type SentenceStartEvent = { 
+/**
+ * The `utteranceId` of the utterance being spoken
+ */
+utteranceId: string, 
+/**
+ * Position of the sentence in the utterance, from 0
+ */
+index: number, 
+/**
+ * Start of the sentence in the request's text
+ */
+charStart: number, 
+/**
+ * End of the sentence in the request's text, exclusive
+ */
+charEnd: number, };
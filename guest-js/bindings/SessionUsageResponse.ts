@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SessionUsageResponse = { 
+/**
+ * Characters spoken so far this session
+ */
+used: number, 
+/**
+ * Configured `sessionCharLimit`, if any
+ */
+limit: number | null, 
+/**
+ * Characters left before `speak` is rejected, if limited
+ */
+remaining: number | null, };
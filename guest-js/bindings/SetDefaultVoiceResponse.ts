@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SetDefaultVoiceResponse = { 
+/**
+ * The voice now saved for `language`
+ */
+voiceId: string | null, 
+/**
+ * Language the voice is saved for, `null` for requests without one
+ */
+language: string | null, 
+/**
+ * Set when the voice isn't available and the saved one was kept
+ */
+warning?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SetVoiceResponse = { 
+/**
+ * The voice `speak()` now defaults to
+ */
+voiceId: string | null, 
+/**
+ * Set when the voice isn't available and the previous one was kept
+ */
+warning?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SkipToNextResponse = { 
+/**
+ * Whether an utterance was being spoken and was cut short
+ */
+skipped: boolean, 
+/**
+ * Id of the utterance spoken next, when one is waiting
+ */
+nextUtteranceId?: string, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SpeechOutcome } from "./SpeechOutcome";
+
+export type SpeakAndWaitResponse = { outcome: SpeechOutcome, 
+/**
+ * What the engine reported, when `outcome` is `error`
+ */
+error?: string, 
+/**
+ * Optional warning message (e.g., voice not found, using fallback)
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, 
+/**
+ * Id carried by the `speech:*` events of this utterance
+ */
+utteranceId?: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakFromOffsetResponse = { success: boolean, 
+/**
+ * Offset reading started at, in grapheme clusters of the full text. Add it to
+ * positions within the spoken remainder to get positions in the full text
+ */
+offset: number, 
+/**
+ * Set when the request was adjusted or dropped (e.g. by the interrupt cooldown)
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, };
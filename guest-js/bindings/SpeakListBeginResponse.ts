@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakListBeginResponse = { 
+/**
+ * Pass to `speakListNext` and `speakListReset`
+ */
+sessionId: string, 
+/**
+ * Number of items in the list
+ */
+total: number, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakListNextResponse = { 
+/**
+ * Whether the item was spoken. Dropped items are retried by the next call
+ */
+success: boolean, 
+/**
+ * Position of the item in the list, starting at 0
+ */
+index: number, 
+/**
+ * Whether items remain. The session is gone once this is false
+ */
+hasMore: boolean, 
+/**
+ * Set when the item was dropped (e.g. by the interrupt cooldown)
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakListResetResponse = { 
+/**
+ * Whether the session existed. Finished sessions are already gone
+ */
+removed: boolean, };
@@ -1,5 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmojiMode } from "./EmojiMode";
+import type { Pronunciation } from "./Pronunciation";
 import type { QueueMode } from "./QueueMode";
+import type { TextFormat } from "./TextFormat";
 
 export type SpeakOptions = { 
 /**
@@ -11,22 +14,93 @@ text: string,
  */
 language: string | null, 
 /**
- * Specific voice ID to use (from getVoices). Takes priority over language
+ * Specific voice ID to use (from getVoices). Takes priority over language.
+ * A display name like "Samantha" or "Microsoft Zira" also works, matched
+ * loosely with a warning when it isn't the whole name
  */
 voiceId: string | null, 
 /**
- * Speech rate (0.1 to 4.0, where 1.0 = normal)
+ * Detect the language of the text and speak it with a voice for that language.
+ * `language` is kept when detection is unsure, and `voiceId` takes priority
+ */
+autoDetectLanguage: boolean | null, 
+/**
+ * Speech rate (0.1 to 3.0, where 1.0 = normal). Defaults to the active profile
  */
 rate: number | null, 
 /**
- * Pitch (0.5 to 2.0, where 1.0 = normal)
+ * Speech rate in words per minute, taking precedence over `rate`. Converted
+ * with the engine's estimated normal pace (see `getRateInfo`)
+ */
+rateWpm: number | null, 
+/**
+ * Pitch (0.5 to 2.0, where 1.0 = normal). Defaults to the active profile
  */
 pitch: number | null, 
 /**
- * Volume (0.0 to 1.0, where 1.0 = full volume)
+ * Volume (0.0 to 1.0, where 1.0 = full volume). Defaults to the active profile
  */
 volume: number | null, 
 /**
- * Queue mode: "flush" (default) or "add"
+ * Queue mode: "flush", "add", "replacePending" or "urgent" (defaults to the configured default queue mode)
+ */
+queueMode: QueueMode | null, 
+/**
+ * Silence to insert after this utterance, in milliseconds
+ */
+pauseAfterMs: number | null, 
+/**
+ * Send the text to the engine verbatim, bypassing all preprocessing.
+ * Overrides every other preprocessing option
+ */
+raw: boolean | null, 
+/**
+ * Whether to add the global prefix/suffix to this utterance (default: true)
+ */
+useAffixes: boolean | null, 
+/**
+ * Pause before and after heading-like lines (markdown `#`, short ALL CAPS or
+ * Title Case lines) when reading multi-line text. Ignored in raw mode
+ */
+structuralPauses: boolean | null, 
+/**
+ * How to handle emoji: "skip", "describe" or "keep" (default). Ignored in raw mode
+ */
+emojiMode: EmojiMode | null, 
+/**
+ * Markup to strip from the text: "plain" (default), "markdown" or "html".
+ * List items are read as sentences and code blocks skipped. Ignored in raw mode
+ */
+format: TextFormat | null, 
+/**
+ * Emit this utterance's events as `<eventScope>://speech:*` instead of
+ * `tts://speech:*` (letters, digits, `-` and `_`). Desktop only
+ */
+eventScope: string | null, 
+/**
+ * Treat the text as an SSML `<speak>` document. Skips preprocessing,
+ * structural pauses and affixes; engines without SSML support speak the
+ * text without its markup
+ */
+ssml: boolean | null, 
+/**
+ * Stop the utterance if it is still playing this many milliseconds after it
+ * started, emitting `tts://utterance-timeout`. Desktop only
+ */
+maxDurationMs: number | null, 
+/**
+ * Words to speak with the given IPA pronunciation, matched whole-word and
+ * case-insensitively. Needs an engine that reads SSML; others speak the
+ * text as written and warn
+ */
+pronunciations: Array<Pronunciation> | null, 
+/**
+ * Speak with this backend instead of the OS engine, one of `getBackends`
+ * (default: "system"). Desktop only
+ */
+backend: string | null, 
+/**
+ * Reject with `VOICE_NOT_FOUND` instead of speaking with a fallback voice when
+ * no voice has `voiceId` or speaks `language` (default: false)
  */
-queueMode: QueueMode | null, };
+strictVoice: boolean | null, };
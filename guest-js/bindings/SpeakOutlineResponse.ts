@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutlineEntry } from "./OutlineEntry";
+
+export type SpeakOutlineResponse = { 
+/**
+ * Headings in document order; nothing is spoken when empty
+ */
+outline: Array<OutlineEntry>, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, };
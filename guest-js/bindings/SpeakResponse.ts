@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakResponse = { 
+/**
+ * Whether speech was successfully initiated
+ */
+success: boolean, 
+/**
+ * Optional warning message (e.g., voice not found, using fallback)
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, 
+/**
+ * Id carried by the `speech:*` events of this utterance. When the text is split
+ * for structural pauses, the id of the last part, whose `speech:finish` ends it
+ */
+utteranceId?: string, 
+/**
+ * Backend the speech starts with, "system" for the OS engine, after skipping
+ * `backendFallback` entries that aren't registered (desktop only). Falling
+ * back while speaking is reported by `tts://backend-fallback`
+ */
+backend?: string, 
+/**
+ * Voice the speech starts with, the engine's initial voice when the requested
+ * one wasn't found. Left out when the engine keeps whichever voice it has
+ * (desktop only)
+ */
+usedVoiceId?: string, };
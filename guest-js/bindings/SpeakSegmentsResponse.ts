@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakSegmentsResponse = { success: boolean, 
+/**
+ * Utterance id of each segment, in order: the `id` of its `speech:*` events.
+ * Empty when nothing was queued
+ */
+utteranceIds: Array<string>, 
+/**
+ * Set when the request was adjusted or dropped (e.g. by the interrupt cooldown)
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, };
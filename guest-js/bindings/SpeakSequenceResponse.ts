@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakSequenceResponse = { success: boolean, 
+/**
+ * Identifies this sequence's utterances in the queue
+ */
+sequenceId: string, 
+/**
+ * Whether the rest of an earlier sequence was dropped
+ */
+interrupted: boolean, 
+/**
+ * Set when nothing was queued because the plugin is disabled
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakToFileResponse = { 
+/**
+ * Path of the written file
+ */
+path: string, 
+/**
+ * Optional warning message (e.g., voice not found, using fallback)
+ */
+warning?: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakWordNextResponse = { 
+/**
+ * Whether the word was spoken. Dropped words are retried by the next call
+ */
+success: boolean, 
+/**
+ * The word, with any punctuation attached to it
+ */
+word: string, 
+/**
+ * Words left to speak, including a dropped one. The session is gone once this is 0
+ */
+remaining: number, 
+/**
+ * Set when the word was dropped (e.g. by the interrupt cooldown)
+ */
+warning?: string, 
+/**
+ * Characters left in the session budget, when `sessionCharLimit` is set
+ */
+remainingChars?: number, };
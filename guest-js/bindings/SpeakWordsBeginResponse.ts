@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakWordsBeginResponse = { 
+/**
+ * Pass to `speakWordNext`. `null` when the text has no words to speak
+ */
+sessionId: string | null, 
+/**
+ * Number of words
+ */
+total: number, };
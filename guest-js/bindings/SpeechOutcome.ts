@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How speech waited on by `speak_and_wait` ended
+ */
+export type SpeechOutcome = "completed" | "skipped" | "stopped" | "error";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type StopGentleResponse = { success: boolean, 
+/**
+ * Whether speech was faded out rather than stopped immediately
+ */
+faded: boolean, 
+/**
+ * Why the fade wasn't applied, if it wasn't
+ */
+note?: string, };
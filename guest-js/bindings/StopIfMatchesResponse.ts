@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type StopIfMatchesResponse = { 
+/**
+ * Whether the current utterance matched and speech was stopped
+ */
+stopped: boolean, 
+/**
+ * Why nothing could be checked, if so
+ */
+note?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SynthesizeStreamResponse = { 
+/**
+ * Number of WAV chunks sent, one per sentence
+ */
+chunks: number, 
+/**
+ * Optional warning message (e.g., voice not found, using fallback)
+ */
+warning?: string, };
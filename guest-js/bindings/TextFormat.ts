@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Markup the text is written in, stripped before speaking
+ */
+export type TextFormat = "plain" | "markdown" | "html";
@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the current engine can honor, e.g. to disable controls it would ignore
+ */
+export type TtsFeatures = { 
+/**
+ * `stop()` interrupts speech
+ */
+stop: boolean, 
+/**
+ * `rate` changes the speaking speed
+ */
+rate: boolean, 
+/**
+ * `pitch` changes the voice pitch
+ */
+pitch: boolean, 
+/**
+ * `volume` changes the loudness
+ */
+volume: boolean, 
+/**
+ * `voiceId` selects a voice
+ */
+voice: boolean, 
+/**
+ * `isSpeaking()` reports the engine state
+ */
+isSpeaking: boolean, 
+/**
+ * The engine reports when utterances start and end, so speech events are exact
+ * rather than detected by polling
+ */
+utteranceCallbacks: boolean, 
+/**
+ * `pauseSpeaking()`/`resumeSpeaking()` work
+ */
+pauseResume: boolean, };
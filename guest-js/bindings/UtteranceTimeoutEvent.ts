@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://utterance-timeout` event
+ */
+export type UtteranceTimeoutEvent = { 
+/**
+ * Id of the utterance that was stopped, as in its `speech:*` events
+ */
+id: string, 
+/**
+ * The `maxDurationMs` it ran into
+ */
+maxDurationMs: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://viseme` event (desktop backends that report visemes):
+ * the mouth shape of the sound about to be heard, e.g. to animate an avatar
+ */
+export type VisemeEvent = { 
+/**
+ * The `utteranceId` of the utterance being spoken
+ */
+utteranceId: string, 
+/**
+ * Viseme in the backend's alphabet, e.g. Amazon Polly's "p", "t" or "@"
+ */
+viseme: string, };
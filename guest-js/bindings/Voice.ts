@@ -1,4 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VoiceGender } from "./VoiceGender";
+import type { VoiceQuality } from "./VoiceQuality";
 
 export type Voice = { 
 /**
@@ -12,4 +14,30 @@ name: string,
 /**
  * Language code (e.g., "en-US")
  */
-language: string, };
+language: string, 
+/**
+ * `null` when the engine doesn't report it
+ */
+gender: VoiceGender | null, 
+/**
+ * `null` when the engine doesn't report it
+ */
+quality: VoiceQuality | null, 
+/**
+ * Whether speaking needs a network connection. `null` when the engine doesn't report it
+ */
+requiresNetwork: boolean | null, 
+/**
+ * Whether the voice's data is on the device, `false` when it still has to be
+ * downloaded. `null` when the engine doesn't report it
+ */
+installed: boolean | null, 
+/**
+ * Engine that speaks the voice, e.g. "com.google.android.tts", "AVSpeechSynthesizer",
+ * "WinRT" or the name of a cloud backend. `null` when unknown
+ */
+engine: string | null, 
+/**
+ * Platform that reported the voice: "windows", "macos", "linux", "ios" or "android"
+ */
+platform: string, };
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Shareable voice setup, applied to every request that doesn't override it
+ */
+export type VoiceProfile = { 
+/**
+ * Profile format version, bumped when the meaning of a field changes
+ */
+version: number, 
+/**
+ * Voice ID (from getVoices)
+ */
+voiceId: string | null, 
+/**
+ * Language/locale code, used when no voice is set
+ */
+language: string | null, 
+/**
+ * Speech rate (0.1 to 3.0, where 1.0 = normal)
+ */
+rate: number, 
+/**
+ * Pitch (0.5 to 2.0, where 1.0 = normal)
+ */
+pitch: number, 
+/**
+ * Volume (0.0 to 1.0, where 1.0 = full volume)
+ */
+volume: number, 
+/**
+ * Bypass all preprocessing by default
+ */
+raw: boolean, };
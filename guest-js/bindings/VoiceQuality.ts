@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Quality tier of a voice, lowest first
+ */
+export type VoiceQuality = "low" | "normal" | "enhanced" | "premium";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Voice } from "./Voice";
+
+/**
+ * Payload of the `tts://voices-changed` event: the voice list differs from the
+ * one seen before, e.g. after a voice was installed in the system settings
+ */
+export type VoicesChangedEvent = { 
+/**
+ * Every voice now available
+ */
+voices: Array<Voice>, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `tts://word-boundary` event (mobile, and desktop backends that
+ * report words): the word about to be spoken, as UTF-16 offsets into the `text` of the request (JavaScript string
+ * indices, so `text.slice(charStart, charEnd)` is the word)
+ */
+export type WordBoundaryEvent = { 
+/**
+ * The `utteranceId` of the utterance being spoken
+ */
+utteranceId: string, 
+/**
+ * Start of the word in the request's text
+ */
+charStart: number, 
+/**
+ * End of the word in the request's text, exclusive
+ */
+charEnd: number, 
+/**
+ * Length of the word, `charEnd - charStart`
+ */
+charLength: number, };
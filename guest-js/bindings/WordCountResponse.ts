@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WordCountResponse = { 
+/**
+ * Number of words that will be spoken
+ */
+words: number, 
+/**
+ * Number of characters that will be spoken
+ */
+characters: number, };
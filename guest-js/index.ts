@@ -1,15 +1,141 @@
-import { invoke } from "@tauri-apps/api/core";
+import { addPluginListener, Channel, invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type { Voice } from "./bindings/Voice";
+import type { VoiceGender } from "./bindings/VoiceGender";
 import type { PauseResumeResponse } from "./bindings/PauseResumeResponse";
 import type { SpeakOptions } from "./bindings/SpeakOptions";
 import type {PreviewVoiceOptions} from "./bindings/PreviewVoiceOptions";
+import type { PreviewAllOptions } from "./bindings/PreviewAllOptions";
+import type { PreviewStartEvent } from "./bindings/PreviewStartEvent";
+import type { CalibrationOptions } from "./bindings/CalibrationOptions";
+import type { CalibrationStartEvent } from "./bindings/CalibrationStartEvent";
+import type { PronunciationResponse } from "./bindings/PronunciationResponse";
+import type { Capabilities } from "./bindings/Capabilities";
+import type { RateInfoResponse } from "./bindings/RateInfoResponse";
+import type { IsSpeakingResponse } from "./bindings/IsSpeakingResponse";
+import type { TtsFeatures } from "./bindings/TtsFeatures";
+import type { GetBackendsResponse } from "./bindings/GetBackendsResponse";
+import type { BackendInfo } from "./bindings/BackendInfo";
+import type { CacheStatsResponse } from "./bindings/CacheStatsResponse";
+import type { ClearCacheResponse } from "./bindings/ClearCacheResponse";
+import type { PiperVoicesResponse } from "./bindings/PiperVoicesResponse";
+import type { AudioRoute } from "./bindings/AudioRoute";
+import type { AudioRouteResponse } from "./bindings/AudioRouteResponse";
+import type { AudioFocusResponse } from "./bindings/AudioFocusResponse";
+import type { AudioFocusLostEvent } from "./bindings/AudioFocusLostEvent";
+import type { VisemeEvent } from "./bindings/VisemeEvent";
+import type { BackendFallbackEvent } from "./bindings/BackendFallbackEvent";
+import type { WordBoundaryEvent } from "./bindings/WordBoundaryEvent";
+import type { SentenceStartEvent } from "./bindings/SentenceStartEvent";
+import type { EngineParamsResponse } from "./bindings/EngineParamsResponse";
+import type { DefaultVoiceResponse } from "./bindings/DefaultVoiceResponse";
+import type { WordCountResponse } from "./bindings/WordCountResponse";
+import type { Affixes } from "./bindings/Affixes";
+import type { VoiceProfile } from "./bindings/VoiceProfile";
+import type { ImportProfileResponse } from "./bindings/ImportProfileResponse";
+import type { SetVoiceResponse } from "./bindings/SetVoiceResponse";
+import type { SetDefaultVoiceResponse } from "./bindings/SetDefaultVoiceResponse";
+import type { SpeakResponse } from "./bindings/SpeakResponse";
+import type { SpeakAndWaitResponse } from "./bindings/SpeakAndWaitResponse";
+import type { SpeakSequenceResponse } from "./bindings/SpeakSequenceResponse";
+import type { SpeakSegmentsResponse } from "./bindings/SpeakSegmentsResponse";
+import type { SegmentStartEvent } from "./bindings/SegmentStartEvent";
+import type { UtteranceTimeoutEvent } from "./bindings/UtteranceTimeoutEvent";
+import type { SpeakFromOffsetResponse } from "./bindings/SpeakFromOffsetResponse";
+import type { OutlineEntry } from "./bindings/OutlineEntry";
+import type { SpeakOutlineResponse } from "./bindings/SpeakOutlineResponse";
+import type { SpeakListBeginResponse } from "./bindings/SpeakListBeginResponse";
+import type { SpeakListNextResponse } from "./bindings/SpeakListNextResponse";
+import type { SpeakListResetResponse } from "./bindings/SpeakListResetResponse";
+import type { SpeakWordsBeginResponse } from "./bindings/SpeakWordsBeginResponse";
+import type { SpeakWordNextResponse } from "./bindings/SpeakWordNextResponse";
+import type { SpeakToFileResponse } from "./bindings/SpeakToFileResponse";
+import type { SynthesizeStreamResponse } from "./bindings/SynthesizeStreamResponse";
+import type { SessionUsageResponse } from "./bindings/SessionUsageResponse";
+import type { BenchmarkVoiceResponse } from "./bindings/BenchmarkVoiceResponse";
+import type { CalibrationResponse } from "./bindings/CalibrationResponse";
+import type { PreviewAllResponse } from "./bindings/PreviewAllResponse";
+import type { StopGentleResponse } from "./bindings/StopGentleResponse";
+import type { StopIfMatchesResponse } from "./bindings/StopIfMatchesResponse";
+import type { ResolveVoiceRequest } from "./bindings/ResolveVoiceRequest";
+import type { GetLanguagesResponse } from "./bindings/GetLanguagesResponse";
+import type { VoicesChangedEvent } from "./bindings/VoicesChangedEvent";
+import type { QueuedItem } from "./bindings/QueuedItem";
+import type { GetQueueResponse } from "./bindings/GetQueueResponse";
+import type { SkipToNextResponse } from "./bindings/SkipToNextResponse";
+import type { LexiconEntry } from "./bindings/LexiconEntry";
+import type { ListPronunciationsResponse } from "./bindings/ListPronunciationsResponse";
 
 export type { QueueMode } from "./bindings/QueueMode";
+export type { EmojiMode } from "./bindings/EmojiMode";
+export type { TextFormat } from "./bindings/TextFormat";
 export type { Voice } from "./bindings/Voice";
+export type { VoiceGender } from "./bindings/VoiceGender";
+export type { VoiceQuality } from "./bindings/VoiceQuality";
 export type { PauseResumeResponse } from "./bindings/PauseResumeResponse";
 export type { SpeakOptions } from "./bindings/SpeakOptions";
+export type { Pronunciation } from "./bindings/Pronunciation";
 export type { PreviewVoiceOptions } from "./bindings/PreviewVoiceOptions";
+export type { PreviewAllOptions } from "./bindings/PreviewAllOptions";
+export type { PreviewStartEvent } from "./bindings/PreviewStartEvent";
+export type { CalibrationOptions } from "./bindings/CalibrationOptions";
+export type { CalibrationStartEvent } from "./bindings/CalibrationStartEvent";
+export type { PronunciationResponse } from "./bindings/PronunciationResponse";
+export type { Capabilities } from "./bindings/Capabilities";
+export type { RateInfoResponse } from "./bindings/RateInfoResponse";
+export type { IsSpeakingResponse } from "./bindings/IsSpeakingResponse";
+export type { TtsFeatures } from "./bindings/TtsFeatures";
+export type { BackendInfo } from "./bindings/BackendInfo";
+export type { CacheStatsResponse } from "./bindings/CacheStatsResponse";
+export type { ClearCacheResponse } from "./bindings/ClearCacheResponse";
+export type { PiperVoicesResponse } from "./bindings/PiperVoicesResponse";
+export type { AudioRoute } from "./bindings/AudioRoute";
+export type { AudioRouteResponse } from "./bindings/AudioRouteResponse";
+export type { AudioFocusResponse } from "./bindings/AudioFocusResponse";
+export type { AudioFocusLostEvent } from "./bindings/AudioFocusLostEvent";
+export type { VisemeEvent } from "./bindings/VisemeEvent";
+export type { BackendFallbackEvent } from "./bindings/BackendFallbackEvent";
+export type { WordBoundaryEvent } from "./bindings/WordBoundaryEvent";
+export type { SentenceStartEvent } from "./bindings/SentenceStartEvent";
+export type { EngineParamsResponse } from "./bindings/EngineParamsResponse";
+export type { DefaultVoiceResponse } from "./bindings/DefaultVoiceResponse";
+export type { WordCountResponse } from "./bindings/WordCountResponse";
+export type { Affixes } from "./bindings/Affixes";
+export type { VoiceProfile } from "./bindings/VoiceProfile";
+export type { ImportProfileResponse } from "./bindings/ImportProfileResponse";
+export type { SetVoiceResponse } from "./bindings/SetVoiceResponse";
+export type { SetDefaultVoiceResponse } from "./bindings/SetDefaultVoiceResponse";
+export type { SpeakResponse } from "./bindings/SpeakResponse";
+export type { SpeechOutcome } from "./bindings/SpeechOutcome";
+export type { SpeakAndWaitResponse } from "./bindings/SpeakAndWaitResponse";
+export type { SpeakSequenceResponse } from "./bindings/SpeakSequenceResponse";
+export type { SpeakSegmentsResponse } from "./bindings/SpeakSegmentsResponse";
+export type { SegmentStartEvent } from "./bindings/SegmentStartEvent";
+export type { UtteranceTimeoutEvent } from "./bindings/UtteranceTimeoutEvent";
+export type { SpeakFromOffsetResponse } from "./bindings/SpeakFromOffsetResponse";
+export type { OutlineEntry } from "./bindings/OutlineEntry";
+export type { SpeakOutlineResponse } from "./bindings/SpeakOutlineResponse";
+export type { SpeakListBeginResponse } from "./bindings/SpeakListBeginResponse";
+export type { SpeakListNextResponse } from "./bindings/SpeakListNextResponse";
+export type { SpeakListResetResponse } from "./bindings/SpeakListResetResponse";
+export type { SpeakWordsBeginResponse } from "./bindings/SpeakWordsBeginResponse";
+export type { SpeakWordNextResponse } from "./bindings/SpeakWordNextResponse";
+export type { SpeakToFileResponse } from "./bindings/SpeakToFileResponse";
+export type { SynthesizeStreamResponse } from "./bindings/SynthesizeStreamResponse";
+export type { SessionUsageResponse } from "./bindings/SessionUsageResponse";
+export type { BenchmarkVoiceResponse } from "./bindings/BenchmarkVoiceResponse";
+export type { CalibrationResponse } from "./bindings/CalibrationResponse";
+export type { PreviewAllResponse } from "./bindings/PreviewAllResponse";
+export type { StopGentleResponse } from "./bindings/StopGentleResponse";
+export type { StopIfMatchesResponse } from "./bindings/StopIfMatchesResponse";
+export type { ResolveVoiceRequest } from "./bindings/ResolveVoiceRequest";
+export type { GetLanguagesResponse } from "./bindings/GetLanguagesResponse";
+export type { VoicesChangedEvent } from "./bindings/VoicesChangedEvent";
+export type { QueuedItem } from "./bindings/QueuedItem";
+export type { GetQueueResponse } from "./bindings/GetQueueResponse";
+export type { SkipToNextResponse } from "./bindings/SkipToNextResponse";
+export type { LexiconEntry } from "./bindings/LexiconEntry";
+export type { ListPronunciationsResponse } from "./bindings/ListPronunciationsResponse";
 
 export type TtsErrorCode =
   | "IO_ERROR"
@@ -20,6 +146,12 @@ export type TtsErrorCode =
   | "NOT_INITIALIZED"
   | "VALIDATION_ERROR"
   | "OPERATION_FAILED"
+  | "SESSION_LIMIT_EXCEEDED"
+  | "SPEAK_LIST_NOT_FOUND"
+  | "SEQUENCE_ACTIVE"
+  | "NO_VOICES"
+  | "BACKEND_NOT_FOUND"
+  | "VOICE_NOT_FOUND"
   | "EMPTY_TEXT"
   | "TEXT_TOO_LONG"
   | "VOICE_ID_TOO_LONG"
@@ -33,6 +165,12 @@ export interface TtsError {
   message: string;
 }
 
+/** Payload of `tts://error`, a failure after the call that caused it returned */
+export interface TtsErrorEvent extends TtsError {
+  /** Id of the utterance that failed, as in its speech events */
+  id?: string;
+}
+
 export function isTtsError(error: unknown): error is TtsError {
   return (
     typeof error === "object" &&
@@ -42,6 +180,15 @@ export function isTtsError(error: unknown): error is TtsError {
   );
 }
 
+export interface VoiceFilter {
+  /** Only voices of this gender; voices of unknown gender are left out */
+  gender?: VoiceGender;
+  /** Only enhanced or premium voices; voices of unknown quality are left out */
+  enhancedOnly?: boolean;
+  /** Voices of this backend from `getBackends()` instead of the OS engine's */
+  backend?: string;
+}
+
 export interface SpeechEvent {
   /** Unique identifier for the utterance (if available) */
   id?: string;
@@ -68,6 +215,8 @@ export type SpeechEventType =
  *
  * @param eventType - The type of speech event to listen for
  * @param callback - Function called when the event occurs
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * (default: events of utterances without one)
  * @returns Promise that resolves to an unlisten function
  *
  * @example
@@ -80,23 +229,154 @@ export type SpeechEventType =
  *
  * // Later, stop listening
  * unlisten();
+ *
+ * // Events of `speak({ text, eventScope: "reader" })` only
+ * await onSpeechEvent("speech:start", onReaderStart, "reader");
  * ```
  */
 export async function onSpeechEvent(
   eventType: SpeechEventType,
-  callback: (event: SpeechEvent) => void
+  callback: (event: SpeechEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<SpeechEvent>(eventType, callback, scope);
+}
+
+/**
+ * Listen for utterances starting to play
+ *
+ * Emitted on every platform along with `speech:start`, once the engine actually
+ * starts the utterance rather than when `speak()` returned.
+ *
+ * @param callback - Function called with the utterance's `id`
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ *
+ * @example
+ * ```typescript
+ * import { onUtteranceEnd, onUtteranceStart, speak } from "tauri-plugin-tts-api";
+ *
+ * await onUtteranceStart(({ id }) => showSpeakingIndicator(id));
+ * await onUtteranceEnd(({ id }) => hideSpeakingIndicator(id));
+ * await speak({ text: "Hello!" });
+ * ```
+ */
+export async function onUtteranceStart(
+  callback: (event: SpeechEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<SpeechEvent>("utterance-start", callback, scope);
+}
+
+/**
+ * Listen for utterances that played to the end, along with `speech:finish`
+ *
+ * @param callback - Function called with the utterance's `id`
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onUtteranceEnd(
+  callback: (event: SpeechEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<SpeechEvent>("utterance-end", callback, scope);
+}
+
+/**
+ * Listen for utterances cut short, e.g. by `stop()` or a flush, along with
+ * `speech:cancel`
+ *
+ * On desktop the `id` may be missing when `stop()` or a flush stops speech.
+ *
+ * @param callback - Function called with the utterance's `id`, if known
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onUtteranceCancelled(
+  callback: (event: SpeechEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<SpeechEvent>("utterance-cancelled", callback, scope);
+}
+
+/**
+ * Listen for utterances stopped because they played longer than their
+ * `maxDurationMs`
+ *
+ * Currently emitted on desktop only. A `speech:cancel` event for the utterance
+ * comes along with it.
+ *
+ * @param callback - Function called with the stopped utterance's id
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onUtteranceTimeout(
+  callback: (event: UtteranceTimeoutEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<UtteranceTimeoutEvent>("utterance-timeout", callback, scope);
+}
+
+/**
+ * Listen for failures that happen after the call that caused them returned,
+ * e.g. the engine failing mid-utterance
+ *
+ * Errors carry the same `code` and `message` as a rejected call, so one
+ * handler can report both. Emitted on desktop and Android; iOS reports no
+ * speech errors.
+ *
+ * @param callback - Function called with the error, and the failed utterance's
+ * id if there is one
+ * @returns Promise that resolves to an unlisten function
+ *
+ * @example
+ * ```typescript
+ * import { isTtsError, onError, speak } from "tauri-plugin-tts-api";
+ *
+ * const report = (error: unknown) => {
+ *   if (isTtsError(error)) showToast(`${error.code}: ${error.message}`);
+ * };
+ * await onError(report);
+ * await speak({ text: "Hello!" }).catch(report);
+ * ```
+ */
+export async function onError(
+  callback: (event: TtsErrorEvent) => void
 ): Promise<UnlistenFn> {
-  return listen<SpeechEvent>(`tts://${eventType}`, (event) => {
+  return listenTts<TtsErrorEvent>("error", callback);
+}
+
+/**
+ * Listen for a plugin event on every platform. Desktop emits Tauri events, while
+ * the iOS and Android plugins trigger them on the plugin's own channel
+ */
+async function listenTts<T>(
+  eventType: string,
+  callback: (payload: T) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  const unlisten = await listen<T>(`${scope}://${eventType}`, (event) => {
     callback(event.payload);
   });
+  if (scope !== "tts") {
+    return unlisten;
+  }
+  // Desktop has no native plugin to register with
+  const native = await addPluginListener<T>("tts", eventType, callback).catch(
+    () => null
+  );
+  return () => {
+    unlisten();
+    void native?.unregister();
+  };
 }
 
 /**
  * Speak the given text using text-to-speech
  *
  * @param options - The speak options including text and optional parameters
- * @returns Promise that resolves when speech has started
- * @throws TtsError if validation fails or TTS operation fails
+ * @returns Whether speech started, plus the remaining `sessionCharLimit` budget if one is set
+ * @throws TtsError if validation fails, the session limit is exceeded or TTS operation fails
  *
  * @example
  * ```typescript
@@ -117,186 +397,1893 @@ export async function onSpeechEvent(
  * // Queue mode - add to queue instead of interrupting
  * await speak({ text: "First sentence" });
  * await speak({ text: "Second sentence", queueMode: "add" });
+ *
+ * // Read emoji in chat messages by name ("😀" → "grinning face")
+ * await speak({ text: "See you soon 👋", emojiMode: "describe" });
+ *
+ * // Read a markdown note without its syntax or code blocks
+ * await speak({ text: "# Todo\n- **Call** Sam\n- Buy milk", format: "markdown" });
+ *
+ * // Raw mode - read code verbatim, skipping all preprocessing
+ * await speak({ text: "let url = \"https://example.com\";", raw: true });
  * ```
  */
-export async function speak(options: SpeakOptions): Promise<void> {
-  await invoke("plugin:tts|speak", {
-    payload: {
-      text: options.text,
-      language: options.language ?? null,
-      voiceId: options.voiceId ?? null,
-      rate: options.rate ?? 1.0,
-      pitch: options.pitch ?? 1.0,
-      volume: options.volume ?? 1.0,
-      queueMode: options.queueMode ?? "flush",
-    },
+export async function speak(options: SpeakOptions): Promise<SpeakResponse> {
+  return invoke<SpeakResponse>("plugin:tts|speak", {
+    payload: speakPayload(options),
   });
 }
 
 /**
- * Stop any ongoing speech
+ * Speak text and wait until it ends, instead of only until it starts
+ *
+ * Takes the same options as `speak()`, queue mode included: with `queueMode: "add"`
+ * the Promise resolves once the speech queued before it and this text have
+ * been spoken.
+ *
+ * @param options - Speech options
+ * @returns How the speech ended: `"completed"`, `"stopped"` (by `stop()` or by
+ * later speech that flushed the queue), `"error"` or `"skipped"` (while the
+ * plugin is disabled), plus the fields of `speak()`
+ *
+ * On iOS and Android the end is detected by polling `isSpeaking()`, and engine
+ * errors are reported as `"completed"`.
  *
  * @example
  * ```typescript
- * import { stop } from "tauri-plugin-tts-api";
- *
- * await stop();
+ * await speakAndWait({ text: "What is 6 times 7?" });
+ * const { outcome } = await speakAndWait({ text: "42", queueMode: "add" });
  * ```
  */
-export async function stop(): Promise<void> {
-  await invoke("plugin:tts|stop");
+export async function speakAndWait(
+  options: SpeakOptions
+): Promise<SpeakAndWaitResponse> {
+  return invoke<SpeakAndWaitResponse>("plugin:tts|speak_and_wait", {
+    payload: speakPayload(options),
+  });
+}
+
+function speakPayload(
+  options: Pick<SpeakOptions, "text"> & Partial<SpeakOptions>
+) {
+  return {
+    text: options.text,
+    language: options.language ?? null,
+    voiceId: options.voiceId ?? null,
+    autoDetectLanguage: options.autoDetectLanguage ?? false,
+    rate: options.rate ?? null,
+    rateWpm: options.rateWpm ?? null,
+    pitch: options.pitch ?? null,
+    volume: options.volume ?? null,
+    queueMode: options.queueMode ?? null,
+    pauseAfterMs: options.pauseAfterMs ?? null,
+    raw: options.raw ?? null,
+    useAffixes: options.useAffixes ?? true,
+    structuralPauses: options.structuralPauses ?? false,
+    emojiMode: options.emojiMode ?? null,
+    format: options.format ?? null,
+    eventScope: options.eventScope ?? null,
+    ssml: options.ssml ?? false,
+    maxDurationMs: options.maxDurationMs ?? null,
+    pronunciations: options.pronunciations ?? [],
+    backend: options.backend ?? null,
+    strictVoice: options.strictVoice ?? false,
+  };
 }
 
 /**
- * Get available voices, optionally filtered by language
+ * Export the active voice settings as a shareable, versioned profile
  *
- * @param language - Optional language code to filter voices
- * @returns Array of available voices
+ * @returns The active profile (voice, language, rate, pitch, volume and
+ * preprocessing options)
  *
  * @example
  * ```typescript
- * import { getVoices } from "tauri-plugin-tts-api";
+ * import { exportProfile } from "tauri-plugin-tts-api";
  *
- * // Get all voices
- * const allVoices = await getVoices();
+ * const json = JSON.stringify(await exportProfile());
+ * ```
+ */
+export async function exportProfile(): Promise<VoiceProfile> {
+  return invoke<VoiceProfile>("plugin:tts|export_profile");
+}
+
+/**
+ * Apply a voice profile, e.g. one exported on another device
  *
- * // Get only English voices
- * const englishVoices = await getVoices("en");
+ * The profile's settings are used by every `speak()` call that doesn't set them
+ * explicitly. If the referenced voice isn't available, the profile falls back to
+ * its language (or the default voice) and a warning is returned.
  *
- * // Get only Brazilian Portuguese voices
- * const ptBrVoices = await getVoices("pt-BR");
+ * @param profile - A profile from `exportProfile()`
+ * @returns The profile as applied, plus any fallback warnings
+ *
+ * @example
+ * ```typescript
+ * import { importProfile } from "tauri-plugin-tts-api";
+ *
+ * const { warnings } = await importProfile(JSON.parse(json));
+ * warnings.forEach((w) => console.warn(w));
  * ```
  */
-export async function getVoices(language?: string): Promise<Voice[]> {
-  const response = await invoke<{ voices: Voice[] }>("plugin:tts|get_voices", {
-    payload: { language: language ?? null },
+export async function importProfile(
+  profile: Partial<VoiceProfile>
+): Promise<ImportProfileResponse> {
+  return invoke<ImportProfileResponse>("plugin:tts|import_profile", {
+    payload: profile,
   });
-  return response.voices;
 }
 
 /**
- * Check if TTS is currently speaking
+ * Set the voice of the active profile, used by every `speak()` call that sets
+ * neither `voiceId` nor `language`
  *
- * @returns True if speech is in progress
+ * If the voice isn't available, the current one is kept and a warning is
+ * returned.
+ *
+ * @param voiceId - Voice ID from `getVoices()`, or `null` to fall back to the
+ * profile's language or the default voice
+ * @returns The voice now in effect, plus a warning if it wasn't changed
  *
  * @example
  * ```typescript
- * import { isSpeaking, stop } from "tauri-plugin-tts-api";
+ * import { setVoice } from "tauri-plugin-tts-api";
  *
- * if (await isSpeaking()) {
- *   await stop();
- * }
+ * const { warning } = await setVoice(selectedVoiceId);
+ * if (warning) console.warn(warning);
  * ```
  */
-export async function isSpeaking(): Promise<boolean> {
-  const response = await invoke<{ speaking: boolean }>(
-    "plugin:tts|is_speaking"
-  );
-  return response.speaking;
+export async function setVoice(
+  voiceId: string | null
+): Promise<SetVoiceResponse> {
+  return invoke<SetVoiceResponse>("plugin:tts|set_voice", {
+    payload: { voiceId },
+  });
 }
 
 /**
- * Check if TTS engine is initialized and ready
+ * Save the voice `speak()` uses when a request names none, kept in the app
+ * data directory across launches
  *
- * On mobile platforms, TTS initialization is asynchronous. Use this
- * to wait for the engine to be ready before calling getVoices().
+ * Without `language` the voice is used for requests that set neither
+ * `voiceId` nor `language`. With one, it is used for requests in that
+ * language or a regional variant of it. The active profile's voice (see
+ * `setVoice()`) still comes first, and requests for a registered backend
+ * never use the saved voices. If the voice isn't available, the saved one is
+ * kept and a warning is returned.
  *
- * @returns Object with initialized status and voice count
+ * @param voiceId - Voice ID from `getVoices()`, or `null` to remove the saved voice
+ * @param language - Only use the voice for this language (e.g. "en" or "pt-BR")
+ * @returns The voice now saved, plus a warning if it wasn't changed
  *
  * @example
  * ```typescript
- * import { isInitialized, getVoices } from "tauri-plugin-tts-api";
+ * import { setDefaultVoice } from "tauri-plugin-tts-api";
  *
- * // Wait for TTS to be ready
- * const waitForTts = async () => {
- *   for (let i = 0; i < 10; i++) {
- *     const status = await isInitialized();
- *     if (status.initialized && status.voiceCount > 0) {
- *       return true;
- *     }
- *     await new Promise(r => setTimeout(r, 500));
- *   }
- *   return false;
- * };
+ * await setDefaultVoice("com.apple.voice.compact.en-US.Samantha");
+ * await setDefaultVoice("com.apple.voice.compact.pt-BR.Luciana", "pt");
+ * ```
+ */
+export async function setDefaultVoice(
+  voiceId: string | null,
+  language?: string
+): Promise<SetDefaultVoiceResponse> {
+  return invoke<SetDefaultVoiceResponse>("plugin:tts|set_default_voice", {
+    payload: { voiceId, language },
+  });
+}
+
+/**
+ * Set the rate of the active profile, used by every `speak()` call that omits it
  *
- * if (await waitForTts()) {
- *   const voices = await getVoices();
- * }
+ * @param rate - Speech rate (0.1 to 3.0, where 1.0 = normal)
+ * @returns The rate now in effect
+ * @throws TtsError with code `VALIDATION_ERROR` when out of range
+ *
+ * @example
+ * ```typescript
+ * import { setRate, speak } from "tauri-plugin-tts-api";
+ *
+ * await setRate(1.25);
+ * await speak({ text: "A little faster" });
  * ```
  */
-export async function isInitialized(): Promise<{
-  initialized: boolean;
-  voiceCount: number;
-}> {
-  return invoke<{ initialized: boolean; voiceCount: number }>(
-    "plugin:tts|is_initialized"
-  );
+export async function setRate(rate: number): Promise<number> {
+  const response = await invoke<{ value: number }>("plugin:tts|set_rate", {
+    payload: { value: rate },
+  });
+  return response.value;
 }
 
 /**
- * Pause the current speech (iOS only - Android/Desktop not supported)
+ * Set the pitch of the active profile, used by every `speak()` call that omits it
  *
- * @returns Promise with success status and optional reason
+ * @param pitch - Voice pitch (0.5 to 2.0, where 1.0 = normal)
+ * @returns The pitch now in effect
+ * @throws TtsError with code `VALIDATION_ERROR` when out of range
+ */
+export async function setPitch(pitch: number): Promise<number> {
+  const response = await invoke<{ value: number }>("plugin:tts|set_pitch", {
+    payload: { value: pitch },
+  });
+  return response.value;
+}
+
+/**
+ * Set the volume of the active profile, used by every `speak()` call that omits it
+ *
+ * @param volume - Volume (0.0 to 1.0, where 1.0 = full)
+ * @returns The volume now in effect
+ * @throws TtsError with code `VALIDATION_ERROR` when out of range
+ */
+export async function setVolume(volume: number): Promise<number> {
+  const response = await invoke<{ value: number }>("plugin:tts|set_volume", {
+    payload: { value: volume },
+  });
+  return response.value;
+}
+
+/**
+ * Speak several utterances back to back
+ *
+ * The first item's `queueMode` decides how the sequence treats other speech.
+ * When an earlier sequence is still playing, the `sequenceOverlap` config
+ * decides instead: `"interrupt"` (default) drops the rest of the earlier
+ * sequence, `"append"` plays the new one after it and `"reject"` fails.
+ *
+ * @param items - Texts or speak options, in order (at most 1000)
+ * @returns The sequence id and whether an earlier sequence was interrupted
+ * @throws TtsError with code `SEQUENCE_ACTIVE` when rejected by `sequenceOverlap`
  *
  * @example
  * ```typescript
- * import { pauseSpeaking, resumeSpeaking } from "tauri-plugin-tts-api";
+ * import { speakSequence } from "tauri-plugin-tts-api";
  *
- * const result = await pauseSpeaking();
- * if (result.success) {
- *   // Speech is paused
- *   await resumeSpeaking();
- * } else {
- *   console.log("Cannot pause:", result.reason);
- * }
+ * await speakSequence(["Step one.", { text: "Step two.", pauseAfterMs: 500 }]);
  * ```
  */
-export async function pauseSpeaking(): Promise<PauseResumeResponse> {
-  return await invoke<PauseResumeResponse>("plugin:tts|pause_speaking");
+export async function speakSequence(
+  items: (string | SpeakOptions)[]
+): Promise<SpeakSequenceResponse> {
+  return invoke<SpeakSequenceResponse>("plugin:tts|speak_sequence", {
+    payload: {
+      items: items.map((item) =>
+        speakPayload(typeof item === "string" ? { text: item } : item)
+      ),
+    },
+  });
 }
 
 /**
- * Resume paused speech (iOS only - Android/Desktop not supported)
+ * Speak segments back to back, each with its own voice, language, rate, pitch
+ * and volume, e.g. a sentence that switches language part-way through
  *
- * @returns Promise with success status and optional reason
+ * The first segment's `queueMode` applies to the whole batch, so a flush
+ * interrupts current speech once instead of cutting off earlier segments.
+ * The batch is validated as a whole and queued in one go, so concurrent
+ * `speak()` calls can't land between its segments.
+ * On desktop, `tts://segment:start` fires as each segment starts playing
+ * (see `onSegmentStart()`).
+ *
+ * @param segments - Speak options for each segment, in order (at most 1000)
+ * @returns The utterance id of each segment, matching its speech events
+ *
+ * @example
+ * ```typescript
+ * import { speakSegments } from "tauri-plugin-tts-api";
+ *
+ * await speakSegments([
+ *   { text: "The French word for cheese is", language: "en-US" },
+ *   { text: "fromage", language: "fr-FR", rate: 0.8 },
+ * ]);
+ * ```
  */
-export async function resumeSpeaking(): Promise<PauseResumeResponse> {
-  return await invoke<PauseResumeResponse>("plugin:tts|resume_speaking");
+export async function speakSegments(
+  segments: SpeakOptions[]
+): Promise<SpeakSegmentsResponse> {
+  return invoke<SpeakSegmentsResponse>("plugin:tts|speak_segments", {
+    payload: { segments: segments.map(speakPayload) },
+  });
 }
 
 /**
- * Preview a voice with sample text
+ * Listen for the start of each segment queued by `speakSegments()`
  *
- * Useful for letting users hear what a voice sounds like before selecting it.
- * Uses default rate, pitch, and volume settings.
+ * Currently emitted on desktop only.
  *
- * @param options - The preview options including voiceId and optional text
- * @returns Promise that resolves when preview has started
+ * @param callback - Function called when a segment starts
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onSegmentStart(
+  callback: (event: SegmentStartEvent) => void
+): Promise<UnlistenFn> {
+  return listen<SegmentStartEvent>("tts://segment:start", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Speak a document starting part-way through, e.g. where the user stopped
+ *
+ * The offset counts user-perceived characters (grapheme clusters), so an emoji
+ * or an accented letter counts once. Reading starts at the beginning of the word
+ * containing the offset.
+ *
+ * @param options - The full document and the same options you would pass to `speak()`
+ * @param offset - Where to start, in grapheme clusters of `options.text`
+ * @returns The offset reading really started at, relative to the full text
+ * @throws TtsError with code `VALIDATION_ERROR` if the offset is past the end
  *
  * @example
  * ```typescript
- * import { getVoices, previewVoice } from "tauri-plugin-tts-api";
+ * import { speakFromOffset } from "tauri-plugin-tts-api";
  *
- * // Get available voices
- * const voices = await getVoices();
+ * const saved = Number(localStorage.getItem("chapter-3") ?? 0);
+ * const { offset } = await speakFromOffset({ text: chapter }, saved);
+ * ```
+ */
+export async function speakFromOffset(
+  options: SpeakOptions,
+  offset: number
+): Promise<SpeakFromOffsetResponse> {
+  return invoke<SpeakFromOffsetResponse>("plugin:tts|speak_from_offset", {
+    payload: { ...speakPayload(options), offset },
+  });
+}
+
+/**
+ * Speak the headings of a document as a numbered list ("Section 1:
+ * Introduction", ...), before reading the body
  *
- * // Preview a specific voice
- * await previewVoice({ voiceId: voices[0].id });
+ * Headings are markdown `#` lines and short ALL CAPS or Title Case lines. The
+ * outline is queued like `speakSequence()`, so `sequenceOverlap` applies. Voice
+ * and prosody options are used for every heading.
  *
- * // Preview with custom text
- * await previewVoice({
- *   voiceId: voices[0].id,
- *   text: "Testing this voice!"
- * });
+ * @param options - The document and the same options you would pass to `speak()`
+ * @param gapMs - Silence between headings in milliseconds (default: 500)
+ * @returns The headings found, in order. Nothing is spoken when there are none
+ *
+ * @example
+ * ```typescript
+ * import { speakOutline, speakSection } from "tauri-plugin-tts-api";
+ *
+ * const { outline } = await speakOutline({ text: article });
+ * // The user picked "Section 3"
+ * await speakSection({ text: article }, 2);
  * ```
  */
-export async function previewVoice(
-  options: PreviewVoiceOptions
-): Promise<void> {
-  await invoke("plugin:tts|preview_voice", {
+export async function speakOutline(
+  options: SpeakOptions,
+  gapMs?: number
+): Promise<SpeakOutlineResponse> {
+  return invoke<SpeakOutlineResponse>("plugin:tts|speak_outline", {
+    payload: { ...speakPayload(options), gapMs: gapMs ?? null },
+  });
+}
+
+/**
+ * Read a document from one of the sections returned by `speakOutline()` to the
+ * end, pausing around later headings
+ *
+ * @param options - The same document and options as given to `speakOutline()`
+ * @param index - Position of the section in the outline (0-based)
+ * @throws TtsError with code `VALIDATION_ERROR` if the section doesn't exist
+ */
+export async function speakSection(
+  options: SpeakOptions,
+  index: number
+): Promise<SpeakResponse> {
+  return invoke<SpeakResponse>("plugin:tts|speak_section", {
+    payload: { ...speakPayload(options), index },
+  });
+}
+
+/**
+ * Start a user-paced list, e.g. flashcard prompts, without speaking anything yet
+ *
+ * Every item is validated up front. Items are spoken one per `speakListNext()`
+ * call, using the voice profile and prefix/suffix active at that time.
+ *
+ * @param items - Texts or speak options, in order (at most 1000)
+ * @returns The session id and number of items
+ *
+ * @example
+ * ```typescript
+ * import { speakListBegin, speakListNext } from "tauri-plugin-tts-api";
+ *
+ * const { sessionId } = await speakListBegin(cards.map((card) => card.prompt));
+ * // Each time the user taps "next"
+ * const { hasMore } = await speakListNext(sessionId);
+ * ```
+ */
+export async function speakListBegin(
+  items: (string | SpeakOptions)[]
+): Promise<SpeakListBeginResponse> {
+  return invoke<SpeakListBeginResponse>("plugin:tts|speak_list_begin", {
     payload: {
-      voiceId: options.voiceId,
-      text: options.text ?? null,
+      items: items.map((item) =>
+        speakPayload(typeof item === "string" ? { text: item } : item)
+      ),
+    },
+  });
+}
+
+/**
+ * Speak the next item of a list started with `speakListBegin()`
+ *
+ * Behaves like `speak()` for that item, including the session character limit.
+ * An item dropped by the interrupt cooldown is retried by the next call. Once
+ * `hasMore` is false the session is gone.
+ *
+ * @param sessionId - Session id returned by `speakListBegin()`
+ * @throws TtsError with code `SPEAK_LIST_NOT_FOUND` for unknown or finished sessions
+ */
+export async function speakListNext(
+  sessionId: string
+): Promise<SpeakListNextResponse> {
+  return invoke<SpeakListNextResponse>("plugin:tts|speak_list_next", {
+    payload: { sessionId },
+  });
+}
+
+/**
+ * Forget a list before it was spoken to the end, e.g. when the user leaves the deck
+ *
+ * Doesn't stop speech that is already playing.
+ *
+ * @param sessionId - Session id returned by `speakListBegin()`
+ * @returns Whether the session still existed
+ */
+export async function speakListReset(
+  sessionId: string
+): Promise<SpeakListResetResponse> {
+  return invoke<SpeakListResetResponse>("plugin:tts|speak_list_reset", {
+    payload: { sessionId },
+  });
+}
+
+/**
+ * Split text into words to be spoken one per `speakWordNext()` call, e.g. when
+ * an AAC user advances with a switch
+ *
+ * Punctuation is attached to its word rather than spoken on its own, and the
+ * global prefix/suffix is not used. Nothing is spoken yet.
+ *
+ * @param options - The text plus the voice and prosody for every word
+ * @returns The session id and number of words. `sessionId` is `null` when the
+ * text has no words, and no session is kept
+ *
+ * @example
+ * ```typescript
+ * import { speakWordsBegin, speakWordNext } from "tauri-plugin-tts-api";
+ *
+ * const { sessionId } = await speakWordsBegin({ text: "I would like water, please." });
+ * // Each time the switch is pressed
+ * const { word, remaining } = await speakWordNext(sessionId!);
+ * ```
+ */
+export async function speakWordsBegin(
+  options: SpeakOptions
+): Promise<SpeakWordsBeginResponse> {
+  return invoke<SpeakWordsBeginResponse>("plugin:tts|speak_words_begin", {
+    payload: speakPayload(options),
+  });
+}
+
+/**
+ * Speak the next word of a session started with `speakWordsBegin()`
+ *
+ * Behaves like `speak()` for that word. A word dropped by the interrupt cooldown
+ * is retried by the next call. Once `remaining` is 0 the session is gone.
+ *
+ * @param sessionId - Session id returned by `speakWordsBegin()`
+ * @throws TtsError with code `SPEAK_LIST_NOT_FOUND` for unknown or finished sessions
+ */
+export async function speakWordNext(
+  sessionId: string
+): Promise<SpeakWordNextResponse> {
+  return invoke<SpeakWordNextResponse>("plugin:tts|speak_word_next", {
+    payload: { sessionId },
+  });
+}
+
+/**
+ * Synthesize speech into an audio file instead of playing it, e.g. to export
+ * narration
+ *
+ * Writes a WAV file and resolves once it is complete. Linux rejects with
+ * `OPERATION_FAILED`. The text is written as one utterance, so
+ * `structuralPauses` and `pauseAfterMs` don't add silence. Writing a file
+ * isn't muted by `setEnabled(false)` and doesn't count against
+ * `sessionCharLimit`.
+ *
+ * @param options - The same options you would pass to `speak()`
+ * @param outputPath - Absolute path of the file to write, replaced if it exists
+ * @returns The path written, plus a warning if the voice or language fell back
+ *
+ * @example
+ * ```typescript
+ * import { speakToFile } from "tauri-plugin-tts-api";
+ * import { appDataDir, join } from "@tauri-apps/api/path";
+ *
+ * const path = await join(await appDataDir(), "chapter-1.wav");
+ * await speakToFile({ text: chapterText, rate: 0.9 }, path);
+ * ```
+ */
+export async function speakToFile(
+  options: SpeakOptions,
+  outputPath: string
+): Promise<SpeakToFileResponse> {
+  return invoke<SpeakToFileResponse>("plugin:tts|speak_to_file", {
+    payload: { ...speakPayload(options), outputPath },
+  });
+}
+
+/**
+ * Synthesize speech into WAV audio without playing it or touching the filesystem,
+ * for playing through WebAudio, visualizing or uploading.
+ *
+ * Renders the same way as `speakToFile()`: Linux rejects with
+ * `OPERATION_FAILED`, the text becomes one utterance, and it isn't muted by
+ * `setEnabled(false)` nor counted against `sessionCharLimit`.
+ *
+ * @param options - The same options you would pass to `speak()`
+ * @returns The bytes of a WAV file
+ *
+ * @example
+ * ```typescript
+ * import { synthesize } from "tauri-plugin-tts-api";
+ *
+ * const wav = await synthesize({ text: "Hello!" });
+ * const context = new AudioContext();
+ * const source = context.createBufferSource();
+ * source.buffer = await context.decodeAudioData(wav);
+ * source.connect(context.destination);
+ * source.start();
+ * ```
+ */
+export async function synthesize(options: SpeakOptions): Promise<ArrayBuffer> {
+  return invoke<ArrayBuffer>("plugin:tts|synthesize", {
+    payload: speakPayload(options),
+  });
+}
+
+/**
+ * Synthesize speech a sentence at a time, calling `onChunk` with each
+ * sentence's WAV audio as soon as it is ready, so playback of long texts can
+ * start before the rest is synthesized.
+ *
+ * Chunks arrive in order and are complete WAV files. Renders like
+ * `synthesize()`, so Linux rejects with `OPERATION_FAILED`. SSML is sent as a
+ * single chunk.
+ *
+ * @param options - The same options you would pass to `speak()`
+ * @param onChunk - Called with the WAV bytes of each sentence
+ * @returns How many chunks were sent, plus a warning if the voice or language fell back
+ *
+ * @example
+ * ```typescript
+ * import { synthesizeStream } from "tauri-plugin-tts-api";
+ *
+ * const context = new AudioContext();
+ * let startAt = context.currentTime;
+ * await synthesizeStream({ text: chapterText }, async (wav) => {
+ *   const source = context.createBufferSource();
+ *   source.buffer = await context.decodeAudioData(wav);
+ *   source.connect(context.destination);
+ *   startAt = Math.max(startAt, context.currentTime);
+ *   source.start(startAt);
+ *   startAt += source.buffer.duration;
+ * });
+ * ```
+ */
+export async function synthesizeStream(
+  options: SpeakOptions,
+  onChunk: (wav: ArrayBuffer) => void
+): Promise<SynthesizeStreamResponse> {
+  const channel = new Channel<ArrayBuffer>();
+  channel.onmessage = onChunk;
+  return invoke<SynthesizeStreamResponse>("plugin:tts|synthesize_stream", {
+    payload: speakPayload(options),
+    onChunk: channel,
+  });
+}
+
+/**
+ * Reset the characters counted against `sessionCharLimit`, e.g. after an upgrade
+ *
+ * @returns Usage after the reset
+ *
+ * @example
+ * ```typescript
+ * import { isTtsError, resetSessionUsage, speak } from "tauri-plugin-tts-api";
+ *
+ * try {
+ *   const { remainingChars } = await speak({ text: article });
+ * } catch (e) {
+ *   if (isTtsError(e) && e.code === "SESSION_LIMIT_EXCEEDED") {
+ *     await showUpgradeDialog();
+ *     await resetSessionUsage();
+ *   }
+ * }
+ * ```
+ */
+export async function resetSessionUsage(): Promise<SessionUsageResponse> {
+  return invoke<SessionUsageResponse>("plugin:tts|reset_session_usage");
+}
+
+/**
+ * Set the global prefix and suffix added to all spoken text
+ *
+ * Affixes are applied after preprocessing. Opt out per utterance with
+ * `useAffixes: false`. Omitted fields are cleared.
+ *
+ * @param affixes - The prefix and/or suffix to use
+ *
+ * @example
+ * ```typescript
+ * import { setAffixes, speak } from "tauri-plugin-tts-api";
+ *
+ * await setAffixes({ prefix: "Acme says:" });
+ * await speak({ text: "Your order has shipped" }); // "Acme says: Your order has shipped"
+ * await speak({ text: "No prefix here", useAffixes: false });
+ * ```
+ */
+export async function setAffixes(affixes: Partial<Affixes>): Promise<void> {
+  await invoke("plugin:tts|set_affixes", {
+    payload: {
+      prefix: affixes.prefix ?? null,
+      suffix: affixes.suffix ?? null,
+    },
+  });
+}
+
+/**
+ * Remove the global prefix and suffix
+ */
+export async function clearAffixes(): Promise<void> {
+  await setAffixes({});
+}
+
+/**
+ * Add a respelling to the pronunciation lexicon, replacing any entry for the
+ * same word
+ *
+ * Every utterance is spoken with the lexicon applied: whole-word matches of
+ * `word`, ignoring case, are spoken as `spokenAs`. Raw text and SSML are left
+ * as written. The lexicon is saved in the app data directory and kept across
+ * launches. Respelled words get no `tts://speech:word` events.
+ *
+ * Unlike the per-request `pronunciations`, which take IPA and need an engine
+ * that reads SSML, respellings work with every engine.
+ *
+ * @param word - Word or phrase as it appears in the text
+ * @param spokenAs - Text spoken in its place
+ *
+ * @example
+ * ```typescript
+ * import { addPronunciation, speak } from "tauri-plugin-tts-api";
+ *
+ * await addPronunciation("SQL", "sequel");
+ * await speak({ text: "Query the SQL database" }); // "Query the sequel database"
+ * ```
+ */
+export async function addPronunciation(
+  word: string,
+  spokenAs: string
+): Promise<void> {
+  await invoke("plugin:tts|add_pronunciation", {
+    payload: { word, spokenAs },
+  });
+}
+
+/**
+ * Remove a word's respelling from the pronunciation lexicon
+ *
+ * @param word - Word of the entry, matched case-insensitively
+ * @returns False when the lexicon had no entry for the word
+ */
+export async function removePronunciation(word: string): Promise<boolean> {
+  const response = await invoke<{ removed: boolean }>(
+    "plugin:tts|remove_pronunciation",
+    {
+      payload: { word },
+    }
+  );
+  return response.removed;
+}
+
+/**
+ * List the pronunciation lexicon's respellings, in the order they were added
+ */
+export async function listPronunciations(): Promise<LexiconEntry[]> {
+  const response = await invoke<ListPronunciationsResponse>(
+    "plugin:tts|list_pronunciations"
+  );
+  return response.entries;
+}
+
+/**
+ * Set the volume multiplier applied whenever a voice is used, to even out
+ * loudness between voices
+ *
+ * The plugin can't measure the system engines' loudness; pick gains by ear,
+ * e.g. with `previewAll()`. Desktop backends can be measured instead with the
+ * `normalizeLoudness` config. Volume is capped at 1.0, so gains above 1 only
+ * make a difference when speaking below full volume.
+ *
+ * @param voiceId - Voice ID (from getVoices)
+ * @param gain - Multiplier from 0.0 to 2.0, or `null` to remove the voice's gain
+ *
+ * @example
+ * ```typescript
+ * import { setVoiceGain } from "tauri-plugin-tts-api";
+ *
+ * await setVoiceGain("com.apple.voice.compact.en-US.Samantha", 0.8);
+ * ```
+ */
+export async function setVoiceGain(
+  voiceId: string,
+  gain: number | null
+): Promise<void> {
+  await invoke("plugin:tts|set_voice_gain", {
+    payload: { voiceId, gain },
+  });
+}
+
+/**
+ * Count the words and characters that would actually be spoken for the given
+ * options, after preprocessing, without speaking
+ *
+ * @param options - The same options you would pass to `speak()`
+ * @returns Word and character counts of the preprocessed text
+ *
+ * @example
+ * ```typescript
+ * import { countWords } from "tauri-plugin-tts-api";
+ *
+ * const { words, characters } = await countWords({ text: article });
+ * ```
+ */
+export async function countWords(
+  options: SpeakOptions
+): Promise<WordCountResponse> {
+  return invoke<WordCountResponse>("plugin:tts|count_words", {
+    payload: speakPayload(options),
+  });
+}
+
+/**
+ * Stop any ongoing speech
+ *
+ * @example
+ * ```typescript
+ * import { stop } from "tauri-plugin-tts-api";
+ *
+ * await stop();
+ * ```
+ */
+export async function stop(): Promise<void> {
+  await invoke("plugin:tts|stop");
+}
+
+/**
+ * Stop speaking, along with everything queued, only if the utterance being
+ * spoken matches a regular expression
+ *
+ * The pattern uses Rust `regex` syntax (e.g. `(?i)` for case-insensitive) and
+ * is tested against the text as sent to the engine, after preprocessing.
+ * On mobile nothing is checked and `stopped` is always false.
+ *
+ * @param pattern - Regular expression to test
+ * @returns Whether speech was stopped
+ * @throws TtsError with code `VALIDATION_ERROR` if the pattern is invalid
+ *
+ * @example
+ * ```typescript
+ * import { stopIfMatches } from "tauri-plugin-tts-api";
+ *
+ * // A moderation check flagged new terms while a message was being read
+ * const { stopped } = await stopIfMatches(`(?i)\\b(${flagged.join("|")})\\b`);
+ * ```
+ */
+export async function stopIfMatches(
+  pattern: string
+): Promise<StopIfMatchesResponse> {
+  return invoke<StopIfMatchesResponse>("plugin:tts|stop_if_matches", {
+    payload: { pattern },
+  });
+}
+
+/**
+ * Stop speech gradually, lowering volume and rate over `durationMs` before
+ * stopping
+ *
+ * Speech from the built-in desktop backends fades out and the Promise resolves
+ * once it has stopped. The system speech engines can't be faded, so their
+ * speech stops immediately and the response explains why in `note`.
+ *
+ * @param durationMs - How long the fade should take, in milliseconds (at most 30 seconds)
+ * @returns Whether speech was faded, plus a note when it wasn't
+ *
+ * @example
+ * ```typescript
+ * import { stopGentle } from "tauri-plugin-tts-api";
+ *
+ * const { faded, note } = await stopGentle(3000);
+ * if (!faded) console.info(note);
+ * ```
+ */
+export async function stopGentle(
+  durationMs: number
+): Promise<StopGentleResponse> {
+  return invoke<StopGentleResponse>("plugin:tts|stop_gentle", {
+    payload: { durationMs },
+  });
+}
+
+/**
+ * Count the utterances waiting behind the one being spoken
+ *
+ * Text split by `structuralPauses` counts as one utterance per part.
+ *
+ * @returns Number of pending utterances
+ */
+export async function getQueueLength(): Promise<number> {
+  const response = await invoke<{ length: number }>(
+    "plugin:tts|get_queue_length"
+  );
+  return response.length;
+}
+
+/**
+ * List the utterances waiting behind the one being spoken, next first
+ *
+ * Text split by `structuralPauses` lists one item per part.
+ *
+ * @returns Pending utterances with their id, a preview of their text, the voice
+ * requested for them and their position
+ *
+ * @example
+ * ```typescript
+ * import { getQueue } from "tauri-plugin-tts-api";
+ *
+ * for (const item of await getQueue()) {
+ *   console.log(`${item.position + 1}. ${item.textPreview}`);
+ * }
+ * ```
+ */
+export async function getQueue(): Promise<QueuedItem[]> {
+  const response = await invoke<GetQueueResponse>("plugin:tts|get_queue");
+  return response.items;
+}
+
+/**
+ * Drop the utterances waiting to be spoken while the current one finishes
+ *
+ * Desktop only: mobile engines can't drop queued speech without stopping the
+ * current utterance, so this rejects with `OPERATION_FAILED` there.
+ *
+ * @returns Number of utterances dropped
+ *
+ * @example
+ * ```typescript
+ * import { clearQueue } from "tauri-plugin-tts-api";
+ *
+ * skipButton.onclick = () => clearQueue();
+ * ```
+ */
+export async function clearQueue(): Promise<number> {
+  const response = await invoke<{ cleared: number }>("plugin:tts|clear_queue");
+  return response.cleared;
+}
+
+/**
+ * Drop one waiting utterance while the rest of the queue plays on
+ *
+ * The utterance being spoken can't be removed, use `stop()` for it. Desktop
+ * only: mobile engines can only flush their queue as a whole, so this rejects
+ * with `OPERATION_FAILED` there.
+ *
+ * @param utteranceId - The `id` from `getQueue()` or the `speak()` response
+ * @returns False when no waiting utterance has that id, e.g. it already started
+ *
+ * @example
+ * ```typescript
+ * import { getQueue, removeFromQueue } from "tauri-plugin-tts-api";
+ *
+ * const [next] = await getQueue();
+ * if (next) {
+ *   await removeFromQueue(next.id);
+ * }
+ * ```
+ */
+export async function removeFromQueue(utteranceId: string): Promise<boolean> {
+  const response = await invoke<{ removed: boolean }>(
+    "plugin:tts|remove_from_queue",
+    {
+      payload: { utteranceId },
+    }
+  );
+  return response.removed;
+}
+
+/**
+ * Cut the current utterance short and go on with the next queued one
+ *
+ * The skipped utterance gets `speech:cancel`, the waiting ones are kept. Desktop
+ * only: mobile engines can't stop one utterance without flushing the rest, so
+ * this rejects with `OPERATION_FAILED` there.
+ *
+ * @returns Whether anything was skipped, and the id of the utterance spoken next
+ *
+ * @example
+ * ```typescript
+ * import { skipToNext } from "tauri-plugin-tts-api";
+ *
+ * nextButton.onclick = async () => {
+ *   const { nextUtteranceId } = await skipToNext();
+ *   highlight(nextUtteranceId);
+ * };
+ * ```
+ */
+export async function skipToNext(): Promise<SkipToNextResponse> {
+  return invoke<SkipToNextResponse>("plugin:tts|skip_to_next");
+}
+
+/**
+ * Get available voices, optionally filtered by language, gender and quality
+ *
+ * @param language - Optional language code to filter voices
+ * @param filter - Optional gender and quality filters
+ * @returns Array of available voices, with `gender` and `quality` set when the
+ * engine reports them
+ *
+ * @example
+ * ```typescript
+ * import { getVoices } from "tauri-plugin-tts-api";
+ *
+ * // Get all voices
+ * const allVoices = await getVoices();
+ *
+ * // Get only English voices
+ * const englishVoices = await getVoices("en");
+ *
+ * // Get only Brazilian Portuguese voices
+ * const ptBrVoices = await getVoices("pt-BR");
+ *
+ * // Get high-quality female voices in any language
+ * const voices = await getVoices(undefined, {
+ *   gender: "female",
+ *   enhancedOnly: true,
+ * });
+ * ```
+ */
+export async function getVoices(
+  language?: string,
+  filter?: VoiceFilter
+): Promise<Voice[]> {
+  const response = await invoke<{ voices: Voice[] }>("plugin:tts|get_voices", {
+    payload: {
+      language: language ?? null,
+      gender: filter?.gender ?? null,
+      enhancedOnly: filter?.enhancedOnly ?? false,
+      backend: filter?.backend ?? null,
+    },
+  });
+  return response.voices;
+}
+
+/**
+ * Enumerate the engine's voices again and return all of them
+ *
+ * Desktop keeps the voice list from the first `getVoices()` call, so call this
+ * after the user installs new system voices. When the list differs from the one
+ * seen before, `onVoicesChanged()` listeners are called too.
+ *
+ * @returns Array of available voices
+ */
+export async function refreshVoices(): Promise<Voice[]> {
+  const response = await invoke<{ voices: Voice[] }>(
+    "plugin:tts|refresh_voices"
+  );
+  return response.voices;
+}
+
+/**
+ * Listen for the available voices changing, e.g. to update a voice picker after
+ * the user installed a voice in the system settings
+ *
+ * iOS 17+ reports changes as they happen. Elsewhere they are found by
+ * `refreshVoices()`, compared with the list of the last unfiltered `getVoices()`
+ * or `refreshVoices()` call, so call it when the app comes back to the foreground.
+ *
+ * @example
+ * ```typescript
+ * import { onVoicesChanged, refreshVoices } from "tauri-plugin-tts-api";
+ *
+ * await onVoicesChanged(({ voices }) => voicePicker.setOptions(voices));
+ * window.addEventListener("focus", () => void refreshVoices());
+ * ```
+ *
+ * @param callback - Function called with every voice now available
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onVoicesChanged(
+  callback: (event: VoicesChangedEvent) => void
+): Promise<UnlistenFn> {
+  return listenTts<VoicesChangedEvent>("voices-changed", callback);
+}
+
+/**
+ * Get the languages of the available voices, e.g. for a language picker
+ *
+ * Derived from the same voice list as `getVoices()`, so repeated calls are cheap
+ * on desktop and iOS.
+ *
+ * @returns Language codes sorted and without duplicates, plus the number of
+ * voices for each
+ *
+ * @example
+ * ```typescript
+ * import { getLanguages } from "tauri-plugin-tts-api";
+ *
+ * const { languages, voiceCounts } = await getLanguages();
+ * const options = languages.map((code) => `${code} (${voiceCounts[code]})`);
+ * ```
+ */
+export async function getLanguages(): Promise<GetLanguagesResponse> {
+  return invoke<GetLanguagesResponse>("plugin:tts|get_languages");
+}
+
+/**
+ * Check if TTS is currently speaking
+ *
+ * @returns True if speech is in progress
+ *
+ * @example
+ * ```typescript
+ * import { isSpeaking, stop } from "tauri-plugin-tts-api";
+ *
+ * if (await isSpeaking()) {
+ *   await stop();
+ * }
+ * ```
+ */
+export async function isSpeaking(): Promise<boolean> {
+  const response = await invoke<{ speaking: boolean }>(
+    "plugin:tts|is_speaking"
+  );
+  return response.speaking;
+}
+
+/**
+ * Check if TTS is speaking, and which utterance
+ *
+ * Lets a page that was reloaded pick up speech that is already playing. The
+ * utterance fields are left out between utterances and when the engine can't
+ * tell.
+ *
+ * @returns Whether speech is in progress, plus the current utterance's id and text
+ *
+ * @example
+ * ```typescript
+ * import { getSpeakingState } from "tauri-plugin-tts-api";
+ *
+ * const { speaking, currentUtteranceId, currentText } = await getSpeakingState();
+ * if (speaking && currentText) {
+ *   showNowReading(currentText, currentUtteranceId);
+ * }
+ * ```
+ */
+export async function getSpeakingState(): Promise<IsSpeakingResponse> {
+  return invoke<IsSpeakingResponse>("plugin:tts|is_speaking");
+}
+
+/**
+ * Check if speech is paused by `pauseSpeaking()`, e.g. to pick between pause and
+ * resume buttons when the app returns to the foreground
+ *
+ * @returns True if speech is paused and not resumed or stopped
+ *
+ * @example
+ * ```typescript
+ * import { isPaused } from "tauri-plugin-tts-api";
+ *
+ * resumeButton.hidden = !(await isPaused());
+ * ```
+ */
+export async function isPaused(): Promise<boolean> {
+  const response = await invoke<{ paused: boolean }>("plugin:tts|is_paused");
+  return response.paused;
+}
+
+/**
+ * Turn all speech on or off, e.g. for a "mute all" toggle
+ *
+ * While disabled, `speak()`, previews, sequences and the calls built on them
+ * resolve with `success: true` and `warning: "tts disabled"` without speaking.
+ * `stop()` keeps working, and speech already playing is not stopped.
+ *
+ * @param enabled - `false` to mute, `true` to speak again
+ *
+ * @example
+ * ```typescript
+ * import { setEnabled, stop } from "tauri-plugin-tts-api";
+ *
+ * await setEnabled(false);
+ * await stop(); // Also silence what is playing right now
+ * ```
+ */
+export async function setEnabled(enabled: boolean): Promise<void> {
+  await invoke("plugin:tts|set_enabled", { payload: { enabled } });
+}
+
+/**
+ * Check whether speech is enabled (see `setEnabled()`)
+ *
+ * @returns True unless speech was disabled
+ */
+export async function isEnabled(): Promise<boolean> {
+  const response = await invoke<{ enabled: boolean }>("plugin:tts|is_enabled");
+  return response.enabled;
+}
+
+/**
+ * Check if TTS engine is initialized and ready
+ *
+ * On mobile platforms, TTS initialization is asynchronous. Use this
+ * to wait for the engine to be ready before calling getVoices().
+ *
+ * @returns Object with initialized status and voice count
+ *
+ * @example
+ * ```typescript
+ * import { isInitialized, getVoices } from "tauri-plugin-tts-api";
+ *
+ * // Wait for TTS to be ready
+ * const waitForTts = async () => {
+ *   for (let i = 0; i < 10; i++) {
+ *     const status = await isInitialized();
+ *     if (status.initialized && status.voiceCount > 0) {
+ *       return true;
+ *     }
+ *     await new Promise(r => setTimeout(r, 500));
+ *   }
+ *   return false;
+ * };
+ *
+ * if (await waitForTts()) {
+ *   const voices = await getVoices();
+ * }
+ * ```
+ */
+export async function isInitialized(): Promise<{
+  initialized: boolean;
+  voiceCount: number;
+}> {
+  return invoke<{ initialized: boolean; voiceCount: number }>(
+    "plugin:tts|is_initialized"
+  );
+}
+
+/**
+ * Get platform capabilities that affect how the frontend should use TTS
+ *
+ * @returns Capabilities of the current platform
+ *
+ * @example
+ * ```typescript
+ * import { getCapabilities, speak } from "tauri-plugin-tts-api";
+ *
+ * const { requiresUserGesture } = await getCapabilities();
+ * if (requiresUserGesture) {
+ *   // Gate the first utterance behind a click
+ *   button.addEventListener("click", () => speak({ text: "Hello!" }), { once: true });
+ * } else {
+ *   await speak({ text: "Hello!" });
+ * }
+ * ```
+ */
+export async function getCapabilities(): Promise<Capabilities> {
+  return invoke<Capabilities>("plugin:tts|get_capabilities");
+}
+
+/**
+ * Get the speech rate range in words per minute
+ *
+ * The numbers are estimates for the platform engine; the real pace depends on
+ * the voice and the text. Use them to label a speed slider or to pick a
+ * `rateWpm` within range.
+ *
+ * @returns Words per minute at the normal, slowest and fastest rate
+ *
+ * @example
+ * ```typescript
+ * import { getRateInfo, speak } from "tauri-plugin-tts-api";
+ *
+ * const { minWpm, maxWpm } = await getRateInfo();
+ * slider.min = String(minWpm);
+ * slider.max = String(maxWpm);
+ * await speak({ text: "Hello!", rateWpm: Number(slider.value) });
+ * ```
+ */
+export async function getRateInfo(): Promise<RateInfoResponse> {
+  return invoke<RateInfoResponse>("plugin:tts|get_rate_info");
+}
+
+/**
+ * Get which speech features the current engine supports, e.g. to disable
+ * controls it would ignore instead of reporting errors afterwards
+ *
+ * On desktop this depends on the backend in use (SAPI, AVSpeechSynthesizer or
+ * speech-dispatcher).
+ *
+ * @example
+ * ```typescript
+ * import { getFeatures } from "tauri-plugin-tts-api";
+ *
+ * const features = await getFeatures();
+ * pitchSlider.disabled = !features.pitch;
+ * pauseButton.hidden = !features.pauseResume;
+ * ```
+ */
+export async function getFeatures(): Promise<TtsFeatures> {
+  return invoke<TtsFeatures>("plugin:tts|get_features");
+}
+
+/**
+ * List the speech backends, with what each supports: the OS engine as `"system"`
+ * and those the app registered. Name one in the `backend` speak option to speak
+ * with it
+ *
+ * Backends only exist on desktop, mobile lists none.
+ *
+ * @example
+ * ```typescript
+ * import { getBackends, speak } from "tauri-plugin-tts-api";
+ *
+ * const backends = await getBackends();
+ * if (backends.some((backend) => backend.name === "piper")) {
+ *   await speak({ text: "Hello!", backend: "piper" });
+ * }
+ * ```
+ */
+export async function getBackends(): Promise<BackendInfo[]> {
+  const response = await invoke<GetBackendsResponse>("plugin:tts|get_backends");
+  return response.backends;
+}
+
+/**
+ * Get how much synthesized audio is cached on disk, and how often this session
+ * it was reused instead of synthesized again (desktop)
+ *
+ * @example
+ * ```typescript
+ * import { getCacheStats } from "tauri-plugin-tts-api";
+ *
+ * const { bytes, maxBytes, hits, misses } = await getCacheStats();
+ * console.log(`${bytes} of ${maxBytes} bytes, ${hits} hits, ${misses} misses`);
+ * ```
+ */
+export async function getCacheStats(): Promise<CacheStatsResponse> {
+  return invoke<CacheStatsResponse>("plugin:tts|get_cache_stats");
+}
+
+/**
+ * Delete all cached synthesized audio, including that of earlier launches
+ * (desktop)
+ *
+ * @example
+ * ```typescript
+ * import { clearTtsCache } from "tauri-plugin-tts-api";
+ *
+ * const { removedEntries, freedBytes } = await clearTtsCache();
+ * ```
+ */
+export async function clearTtsCache(): Promise<ClearCacheResponse> {
+  return invoke<ClearCacheResponse>("plugin:tts|clear_tts_cache");
+}
+
+/**
+ * List the Piper voices installed for the "piper" backend, and the app data
+ * directory they are kept in
+ *
+ * Desktop only, and only with the plugin's `piper` feature enabled.
+ *
+ * @example
+ * ```typescript
+ * import { listPiperVoices } from "tauri-plugin-tts-api";
+ *
+ * const { voices } = await listPiperVoices();
+ * console.log(voices.map((voice) => voice.id));
+ * ```
+ */
+export async function listPiperVoices(): Promise<PiperVoicesResponse> {
+  return invoke<PiperVoicesResponse>("plugin:tts|list_piper_voices");
+}
+
+/**
+ * Download a voice from the Piper voice repository, replacing any installed
+ * copy. Speak with it by passing its ID as `voiceId` with `backend: "piper"`
+ *
+ * Desktop only, and only with the plugin's `piper` feature enabled.
+ *
+ * @param voiceId - Voice ID like "en_US-lessac-medium"
+ *
+ * @example
+ * ```typescript
+ * import { downloadPiperVoice, speak } from "tauri-plugin-tts-api";
+ *
+ * const voice = await downloadPiperVoice("de_DE-thorsten-high");
+ * await speak({ text: "Guten Tag!", backend: "piper", voiceId: voice.id });
+ * ```
+ */
+export async function downloadPiperVoice(voiceId: string): Promise<Voice> {
+  return invoke<Voice>("plugin:tts|download_piper_voice", {
+    payload: { voiceId },
+  });
+}
+
+/**
+ * Get where audio output is currently routed
+ *
+ * Desktop platforms always report `"unknown"`.
+ *
+ * @returns The current route and whether it is a private output
+ *
+ * @example
+ * ```typescript
+ * import { getAudioRoute, speak } from "tauri-plugin-tts-api";
+ *
+ * const { isPrivate } = await getAudioRoute();
+ * if (isPrivate) {
+ *   await speak({ text: "You have 3 new messages from Alice" });
+ * } else {
+ *   await speak({ text: "You have 3 new messages" });
+ * }
+ * ```
+ */
+export async function getAudioRoute(): Promise<AudioRouteResponse> {
+  return invoke<AudioRouteResponse>("plugin:tts|get_audio_route");
+}
+
+/**
+ * Hold audio focus (Android) or keep the audio session active (iOS) until
+ * `abandonAudioFocus()`, so other apps stay paused or ducked between utterances
+ *
+ * Desktop has no audio focus and always reports `granted: true`.
+ *
+ * @returns Whether focus was granted, with a `note` when it wasn't
+ *
+ * @example
+ * ```typescript
+ * import { requestAudioFocus, abandonAudioFocus, speak } from "tauri-plugin-tts-api";
+ *
+ * await requestAudioFocus();
+ * for (const step of steps) {
+ *   await speak({ text: step, queueMode: "add" });
+ * }
+ * // Once the walkthrough is done
+ * await abandonAudioFocus();
+ * ```
+ */
+export async function requestAudioFocus(): Promise<AudioFocusResponse> {
+  return invoke<AudioFocusResponse>("plugin:tts|request_audio_focus");
+}
+
+/**
+ * Give up audio focus held by `requestAudioFocus()`, letting other apps resume
+ *
+ * @returns Always `granted: false`
+ */
+export async function abandonAudioFocus(): Promise<AudioFocusResponse> {
+  return invoke<AudioFocusResponse>("plugin:tts|abandon_audio_focus");
+}
+
+/**
+ * Listen for audio focus being taken by another app, e.g. a phone call or a
+ * navigation prompt (mobile only)
+ *
+ * Fires whether or not speech is playing. On Android a permanent loss also
+ * releases focus held by `requestAudioFocus()`.
+ *
+ * @param callback - Function called with whether the loss is transient
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onAudioFocusLost(
+  callback: (event: AudioFocusLostEvent) => void
+): Promise<UnlistenFn> {
+  return listenTts<AudioFocusLostEvent>("audio:focusLost", callback);
+}
+
+/**
+ * Listen for the word about to be spoken, e.g. to highlight it (mobile, and
+ * desktop backends that report words, like `polly`)
+ *
+ * Offsets index the `text` passed to `speak()`, so `text.slice(charStart, charEnd)`
+ * is the word, whatever the script. Engines that don't report word boundaries,
+ * SSML requests and words added by preprocessing (emoji names, the prefix and
+ * suffix) fire no event.
+ *
+ * @example
+ * ```typescript
+ * import { onWordBoundary, speak } from "tauri-plugin-tts-api";
+ *
+ * const text = "Olá, mundo! こんにちは";
+ * await onWordBoundary(({ charStart, charEnd }) => {
+ *   highlight(text.slice(charStart, charEnd));
+ * });
+ * await speak({ text });
+ * ```
+ *
+ * @param callback - Function called with the utterance id and the word's offsets
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onWordBoundary(
+  callback: (event: WordBoundaryEvent) => void
+): Promise<UnlistenFn> {
+  return listenTts<WordBoundaryEvent>("word-boundary", callback);
+}
+
+/**
+ * Listen for the mouth shape of the sound about to be heard, e.g. to animate an
+ * avatar in sync with speech (desktop backends that report visemes, like `polly`)
+ *
+ * @example
+ * ```typescript
+ * import { onViseme, speak } from "tauri-plugin-tts-api";
+ *
+ * await onViseme(({ viseme }) => avatar.setMouth(viseme));
+ * await speak({ text: "Hello there", backend: "polly" });
+ * ```
+ *
+ * @param callback - Function called with the utterance id and the viseme
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onViseme(
+  callback: (event: VisemeEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<VisemeEvent>("viseme", callback, scope);
+}
+
+/**
+ * Listen for the backend speaking an utterance failing, the rest of it being
+ * spoken with the next backend of the `backendFallback` config (desktop)
+ *
+ * @example
+ * ```typescript
+ * import { onBackendFallback } from "tauri-plugin-tts-api";
+ *
+ * await onBackendFallback(({ from, to, error }) => {
+ *   console.warn(`${from} failed (${error}), speaking with ${to}`);
+ * });
+ * ```
+ *
+ * @param callback - Function called with the utterance id, both backends and the error
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onBackendFallback(
+  callback: (event: BackendFallbackEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<BackendFallbackEvent>("backend-fallback", callback, scope);
+}
+
+/**
+ * Listen for the sentence about to be spoken, e.g. to scroll to and highlight it
+ *
+ * Offsets index the `text` passed to `speak()` like those of `onWordBoundary()`,
+ * and `index` counts the sentences of the utterance from 0. Desktop reports each
+ * sentence as the plugin hands it to the engine; iOS and Android when the first
+ * word of a sentence starts, so engines without word boundaries fire none. SSML
+ * requests fire none on any platform.
+ *
+ * @example
+ * ```typescript
+ * import { onSentenceStart, speak } from "tauri-plugin-tts-api";
+ *
+ * await onSentenceStart(({ charStart, charEnd }) => {
+ *   scrollToAndHighlight(charStart, charEnd);
+ * });
+ * await speak({ text: chapter });
+ * ```
+ *
+ * @param callback - Function called with the sentence's index and offsets
+ * @param scope - Only receive events of utterances spoken with this `eventScope`
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onSentenceStart(
+  callback: (event: SentenceStartEvent) => void,
+  scope: string = "tts"
+): Promise<UnlistenFn> {
+  return listenTts<SentenceStartEvent>("sentence-start", callback, scope);
+}
+
+/**
+ * Get the voice the engine currently speaks with
+ *
+ * Resolves to `null` when the engine has no voice set or can't report it. iOS
+ * picks the voice per utterance, so it always resolves to `null` there.
+ *
+ * @example
+ * ```typescript
+ * import { getCurrentVoice } from "tauri-plugin-tts-api";
+ *
+ * const voice = await getCurrentVoice();
+ * console.log(voice ? `Speaking with ${voice.name}` : "Using the default voice");
+ * ```
+ */
+export async function getCurrentVoice(): Promise<Voice | null> {
+  return invoke<Voice | null>("plugin:tts|get_current_voice");
+}
+
+/**
+ * Pick the best voice to speak a language with
+ *
+ * Voices rank by locale first: exactly that locale, then a regional variant of
+ * a bare language ("en" finds "en-US"), then the same language in another
+ * variant ("pt-BR" finds "pt-PT"). Among those, higher quality wins, then the
+ * system's default voice, then the order of `getVoices()`. Voices that still
+ * have to be downloaded only win when no other voice speaks the language.
+ *
+ * @param language - Language code (e.g. "en" or "pt-BR")
+ * @param backend - Pick among a registered backend's voices (desktop only)
+ * @returns The best voice, or `null` if no voice speaks the language
+ *
+ * @example
+ * ```typescript
+ * import { getBestVoice, speak } from "tauri-plugin-tts-api";
+ *
+ * const voice = await getBestVoice("pt-BR");
+ * await speak({ text: "Olá!", voiceId: voice?.id, language: "pt-BR" });
+ * ```
+ */
+export async function getBestVoice(
+  language: string,
+  backend?: string
+): Promise<Voice | null> {
+  return invoke<Voice | null>("plugin:tts|get_best_voice", {
+    payload: { language, backend },
+  });
+}
+
+/**
+ * Get the system's default voice and speech language
+ *
+ * Use it to preselect a voice in settings screens rather than taking the first
+ * voice of `getVoices()`, whose order differs between platforms. Desktop reports
+ * the voice the engine started with and its language.
+ *
+ * @example
+ * ```typescript
+ * import { getDefaultVoice, getVoices } from "tauri-plugin-tts-api";
+ *
+ * const { voice, language } = await getDefaultVoice();
+ * const voices = await getVoices(language ?? undefined);
+ * const selected = voice?.id ?? voices[0]?.id;
+ * ```
+ */
+export async function getDefaultVoice(): Promise<DefaultVoiceResponse> {
+  return invoke<DefaultVoiceResponse>("plugin:tts|get_default_voice");
+}
+
+/**
+ * Find the current voice that best matches one saved earlier, e.g. on another
+ * device or before an OS update
+ *
+ * Tries the same `id`, then the same `name` in the same `language`, then the
+ * best voice of the language or its base language ("en" for "en-US"), picked
+ * like `getBestVoice()`.
+ * A `Voice` from `getVoices()` can be saved and passed as is.
+ *
+ * @param saved - The voice that was saved
+ * @returns The matching voice, or `null` if no voice speaks its language
+ *
+ * @example
+ * ```typescript
+ * import { resolveVoice, speak } from "tauri-plugin-tts-api";
+ *
+ * const saved = JSON.parse(localStorage.getItem("voice") ?? "null");
+ * const voice = saved ? await resolveVoice(saved) : null;
+ * await speak({ text: "Welcome back", voiceId: voice?.id });
+ * ```
+ */
+export async function resolveVoice(
+  saved: ResolveVoiceRequest
+): Promise<Voice | null> {
+  return invoke<Voice | null>("plugin:tts|resolve_voice", {
+    payload: {
+      id: saved.id,
+      name: saved.name,
+      language: saved.language,
+      platform: saved.platform,
+    },
+  });
+}
+
+/**
+ * Read back the parameters the live engine currently has set, which can differ
+ * from the plugin's voice profile
+ *
+ * Fields the engine can't report are `null`. Android only reports the voice and
+ * iOS reports nothing, since AVSpeechSynthesizer sets everything per utterance.
+ *
+ * @example
+ * ```typescript
+ * import { exportProfile, getEngineParams } from "tauri-plugin-tts-api";
+ *
+ * const [profile, engine] = await Promise.all([exportProfile(), getEngineParams()]);
+ * if (engine.rate !== null && Math.abs(engine.rate - profile.rate) > 0.01) {
+ *   console.warn("Engine rate drifted", profile.rate, engine.rate);
+ * }
+ * ```
+ */
+export async function getEngineParams(): Promise<EngineParamsResponse> {
+  return invoke<EngineParamsResponse>("plugin:tts|get_engine_params");
+}
+
+/**
+ * Retry creating the desktop TTS engine after it failed to initialize, e.g.
+ * because the audio stack came up after the app window
+ *
+ * Until then, desktop calls that need the engine reject with `NOT_INITIALIZED`.
+ * Does nothing once the engine runs. Mobile engines initialize themselves, so
+ * there it only reports the state like `isInitialized()`.
+ *
+ * @returns Object with initialized status and voice count
+ * @throws TtsError with the engine's error when it fails again
+ */
+export async function reinitialize(): Promise<{
+  initialized: boolean;
+  voiceCount: number;
+}> {
+  return invoke<{ initialized: boolean; voiceCount: number }>(
+    "plugin:tts|reinitialize"
+  );
+}
+
+/**
+ * Pause the current speech (iOS and desktop - Android not supported)
+ *
+ * Desktop engines can't pause, so the queue is held and the engine stopped.
+ * Resuming starts the interrupted sentence again from its beginning.
+ *
+ * @returns Promise with success status and optional reason
+ *
+ * @example
+ * ```typescript
+ * import { pauseSpeaking, resumeSpeaking } from "tauri-plugin-tts-api";
+ *
+ * const result = await pauseSpeaking();
+ * if (result.success) {
+ *   // Speech is paused
+ *   await resumeSpeaking();
+ * } else {
+ *   console.log("Cannot pause:", result.reason);
+ * }
+ * ```
+ */
+export async function pauseSpeaking(): Promise<PauseResumeResponse> {
+  return await invoke<PauseResumeResponse>("plugin:tts|pause_speaking");
+}
+
+/**
+ * Resume paused speech (iOS and desktop - Android not supported)
+ *
+ * @returns Promise with success status and optional reason
+ */
+export async function resumeSpeaking(): Promise<PauseResumeResponse> {
+  return await invoke<PauseResumeResponse>("plugin:tts|resume_speaking");
+}
+
+/**
+ * Preview a voice with sample text
+ *
+ * Useful for letting users hear what a voice sounds like before selecting it.
+ * Omitted rate, pitch and volume fall back to the active voice profile, so pass
+ * the values the user picked to hear the voice as it will really sound.
+ *
+ * @param options - The preview options including voiceId and optional text
+ * @returns Promise that resolves when preview has started
+ *
+ * @example
+ * ```typescript
+ * import { getVoices, previewVoice } from "tauri-plugin-tts-api";
+ *
+ * // Get available voices
+ * const voices = await getVoices();
+ *
+ * // Preview a specific voice
+ * await previewVoice({ voiceId: voices[0].id });
+ *
+ * // Preview with custom text
+ * await previewVoice({
+ *   voiceId: voices[0].id,
+ *   text: "Testing this voice!"
+ * });
+ *
+ * // Preview at the speed the user chose
+ * await previewVoice({ voiceId: voices[0].id, rate: 0.7 });
+ * ```
+ */
+export async function previewVoice(
+  options: PreviewVoiceOptions
+): Promise<void> {
+  await invoke("plugin:tts|preview_voice", {
+    payload: {
+      voiceId: options.voiceId,
+      text: options.text ?? null,
+      rate: options.rate ?? null,
+      pitch: options.pitch ?? null,
+      volume: options.volume ?? null,
+    },
+  });
+}
+
+/**
+ * Preview every voice of a language, one after another
+ *
+ * Each voice announces its name before speaking the sample text, with a short
+ * pause between voices. Any current speech is interrupted, and calling `stop()`
+ * cancels the remaining previews.
+ *
+ * @param options - The language to preview and optional sample text and gap
+ * @returns The voices queued for preview, in playback order, with
+ * `warning: "tts disabled"` and no voices while the plugin is disabled
+ *
+ * @example
+ * ```typescript
+ * import { previewAll, onPreviewStart } from "tauri-plugin-tts-api";
+ *
+ * const unlisten = await onPreviewStart(({ index, total, voice }) => {
+ *   console.log(`Now playing ${voice.name} (${index + 1}/${total})`);
+ * });
+ *
+ * const { voices } = await previewAll({ language: "en" });
+ * ```
+ */
+export async function previewAll(
+  options: PreviewAllOptions
+): Promise<PreviewAllResponse> {
+  return invoke<PreviewAllResponse>("plugin:tts|preview_all", {
+    payload: {
+      language: options.language,
+      text: options.text ?? null,
+      gapMs: options.gapMs ?? null,
+    },
+  });
+}
+
+/**
+ * Listen for the start of each voice preview queued by `previewAll()`
+ *
+ * Currently emitted on desktop only.
+ *
+ * @param callback - Function called when a voice preview starts
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onPreviewStart(
+  callback: (event: PreviewStartEvent) => void
+): Promise<UnlistenFn> {
+  return listen<PreviewStartEvent>("tts://preview:start", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Speak the same phrase at a sequence of rates, announcing each one, so a new
+ * user can pick a comfortable speed
+ *
+ * Interrupts current speech. Voice, pitch and volume come from the active voice
+ * profile unless `voiceId` or `language` is given. `stop()` cancels the
+ * remaining samples.
+ *
+ * @param options - Phrase, rates (default: 0.75, 1.0, 1.25, 1.5), voice and gap
+ * @returns The rates queued for playback, in order, after clamping to 0.1-3.0,
+ * with `warning: "tts disabled"` and no rates while the plugin is disabled
+ *
+ * @example
+ * ```typescript
+ * import { calibrationSamples, importProfile, onCalibrationStart } from "tauri-plugin-tts-api";
+ *
+ * const unlisten = await onCalibrationStart(({ rate }) => highlight(rate));
+ * const { rates } = await calibrationSamples();
+ * // Later, once the user picked one
+ * await importProfile({ rate: chosenRate });
+ * ```
+ */
+export async function calibrationSamples(
+  options: Partial<CalibrationOptions> = {}
+): Promise<CalibrationResponse> {
+  return invoke<CalibrationResponse>("plugin:tts|calibration_samples", {
+    payload: {
+      text: options.text ?? null,
+      rates: options.rates ?? null,
+      voiceId: options.voiceId ?? null,
+      language: options.language ?? null,
+      gapMs: options.gapMs ?? null,
+    },
+  });
+}
+
+/**
+ * Listen for the start of each sample queued by `calibrationSamples()`
+ *
+ * Currently emitted on desktop only.
+ *
+ * @param callback - Function called when a calibration sample starts
+ * @returns Promise that resolves to an unlisten function
+ */
+export async function onCalibrationStart(
+  callback: (event: CalibrationStartEvent) => void
+): Promise<UnlistenFn> {
+  return listen<CalibrationStartEvent>("tts://calibration:start", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Speak a standard phrase with a voice and measure how long it takes, for
+ * comparing voices across devices
+ *
+ * Interrupts current speech and resolves once the phrase has finished. The
+ * phrase depends on the voice's language and can be set per language with the
+ * `benchmarkPhrases` config option. Rate, pitch and volume are fixed at 1.0 and
+ * the voice profile and prefix/suffix are ignored.
+ *
+ * @param voiceId - Voice ID from `getVoices()`
+ * @returns The measured duration, the phrase spoken and any warnings. While the
+ * plugin is disabled nothing is measured and `warnings` is `["tts disabled"]`
+ *
+ * @example
+ * ```typescript
+ * import { benchmarkVoice, getVoices } from "tauri-plugin-tts-api";
+ *
+ * for (const voice of await getVoices("en")) {
+ *   const { durationMs, warnings } = await benchmarkVoice(voice.id);
+ *   console.log(voice.name, durationMs, warnings);
+ * }
+ * ```
+ */
+export async function benchmarkVoice(
+  voiceId: string
+): Promise<BenchmarkVoiceResponse> {
+  return invoke<BenchmarkVoiceResponse>("plugin:tts|benchmark_voice", {
+    payload: { voiceId },
+  });
+}
+
+/**
+ * Get the phonetic representation the engine will use for the given text,
+ * without speaking it
+ *
+ * Currently supported on macOS only. Other platforms reject with an
+ * `OPERATION_FAILED` error.
+ *
+ * @param text - The text to transcribe
+ * @param voiceId - Optional voice whose pronunciation rules should be used
+ * @returns The phonemes and the phoneme alphabet they are written in
+ *
+ * @example
+ * ```typescript
+ * import { getPronunciation } from "tauri-plugin-tts-api";
+ *
+ * const { phonemes, alphabet } = await getPronunciation("tomato");
+ * console.log(`${alphabet}: ${phonemes}`);
+ * ```
+ */
+export async function getPronunciation(
+  text: string,
+  voiceId?: string
+): Promise<PronunciationResponse> {
+  return await invoke<PronunciationResponse>("plugin:tts|get_pronunciation", {
+    payload: {
+      text,
+      voiceId: voiceId ?? null,
     },
   });
 }
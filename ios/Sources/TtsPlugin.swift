@@ -12,6 +12,32 @@ private let maxVoiceIdLength = 256
 private let maxLanguageLength = 35
 /// Allowed characters in voice ID (alphanumeric, dots, underscores, hyphens)
 private let voiceIdAllowedCharacters = CharacterSet.alphanumerics.union(CharacterSet(charactersIn: "._-"))
+/// Warning returned when SSML is spoken as plain text
+private let ssmlPlainTextWarning = "SSML is not supported by this engine, speaking the text without markup"
+private let noVoicesWarning = "No text-to-speech voices available on ios, speech may be silent"
+private let replacePendingWarning = "Pending speech can't be dropped without stopping on this engine, flushed instead"
+private let urgentWarning = "Interrupted speech can't be resumed on this engine, flushed instead"
+/// How many ended utterances waitForUtterance still knows the outcome of
+private let endedUtterancesKept = 64
+
+/// The words of an SSML document, already checked to be well-formed by the Rust side
+private func ssmlToPlainText(_ markup: String) -> String {
+    var text = markup.replacingOccurrences(of: "<!--[\\s\\S]*?-->", with: "", options: .regularExpression)
+    // Keep the words on either side of a break, sentence or paragraph apart
+    text = text.replacingOccurrences(of: "</?(speak|break|p|s)\\b[^>]*>", with: " ", options: .regularExpression)
+    text = text.replacingOccurrences(of: "<[^>]*>", with: "", options: .regularExpression)
+    while let range = text.range(of: "&#x?[0-9a-fA-F]+;", options: .regularExpression) {
+        let entity = text[range].dropFirst(2).dropLast()
+        let code = entity.hasPrefix("x") ? UInt32(entity.dropFirst(), radix: 16) : UInt32(entity)
+        text.replaceSubrange(range, with: code.flatMap(Unicode.Scalar.init).map { String(Character($0)) } ?? "")
+    }
+    for (entity, character) in [("&lt;", "<"), ("&gt;", ">"), ("&quot;", "\""), ("&apos;", "'"), ("&amp;", "&")] {
+        text = text.replacingOccurrences(of: entity, with: character)
+    }
+    return text
+        .replacingOccurrences(of: "\\s+", with: " ", options: .regularExpression)
+        .trimmingCharacters(in: .whitespaces)
+}
 
 enum TtsValidationError: Error, LocalizedError {
     case emptyText
@@ -81,6 +107,10 @@ class SpeakArgs: Decodable {
     let pitch: Float?
     let volume: Float?
     let queueMode: String?
+    let pauseAfterMs: Double?
+    let ssml: Bool?
+    let wordOffsets: [WordOffset]?
+    let sentenceOffsets: [SentenceOffset]?
     
     func validate() throws {
         try InputValidator.validateText(text)
@@ -94,7 +124,7 @@ class SpeakArgs: Decodable {
     
     var clampedRate: Float {
         guard let rate = rate else { return 1.0 }
-        return min(max(rate, 0.1), 4.0)
+        return min(max(rate, 0.1), 3.0)
     }
     
     var clampedPitch: Float {
@@ -108,13 +138,42 @@ class SpeakArgs: Decodable {
     }
 }
 
+/// Where a word of the spoken text came from in the caller's text, in UTF-16 offsets
+struct WordOffset: Decodable {
+    let spokenStart: Int
+    let spokenEnd: Int
+    let textStart: Int
+    let textEnd: Int
+}
+
+/// Where a sentence of the spoken text came from in the caller's text, in UTF-16 offsets
+struct SentenceOffset: Decodable {
+    let index: Int
+    let spokenStart: Int
+    let spokenEnd: Int
+    let textStart: Int
+    let textEnd: Int
+}
+
+/// The rest of a speakToFile call is read as `SpeakArgs`
+class SpeakToFileArgs: Decodable {
+    let outputPath: String
+}
+
 class GetVoicesArgs: Decodable {
     let language: String?
 }
 
+class WaitForUtteranceArgs: Decodable {
+    let utteranceId: String
+}
+
 class PreviewVoiceArgs: Decodable {
     let voiceId: String
     let text: String?
+    let rate: Float?
+    let pitch: Float?
+    let volume: Float?
     
     static let defaultSampleText = "Hello! This is a sample of how this voice sounds."
     
@@ -130,14 +189,76 @@ class PreviewVoiceArgs: Decodable {
     }
 }
 
+class AudioSessionArgs: Decodable {
+    let category: String?
+    let mode: String?
+    let options: [String]?
+    
+    var sessionCategory: AVAudioSession.Category {
+        switch category {
+        case "ambient": return .ambient
+        case "soloAmbient": return .soloAmbient
+        case "playAndRecord": return .playAndRecord
+        default: return .playback
+        }
+    }
+    
+    var sessionMode: AVAudioSession.Mode {
+        switch mode {
+        case "voicePrompt": return .voicePrompt
+        case "default": return .default
+        default: return .spokenAudio
+        }
+    }
+    
+    var sessionOptions: AVAudioSession.CategoryOptions {
+        guard let options = options else { return [.duckOthers] }
+        var result: AVAudioSession.CategoryOptions = []
+        for option in options {
+            switch option {
+            case "duckOthers": result.insert(.duckOthers)
+            case "mixWithOthers": result.insert(.mixWithOthers)
+            case "interruptSpokenAudioAndMixWithOthers": result.insert(.interruptSpokenAudioAndMixWithOthers)
+            case "allowBluetooth": result.insert(.allowBluetooth)
+            case "allowBluetoothA2DP": result.insert(.allowBluetoothA2DP)
+            case "defaultToSpeaker": result.insert(.defaultToSpeaker)
+            default: NSLog("[TtsPlugin]   Unknown audio session option: \(option)")
+            }
+        }
+        return result
+    }
+}
+
 class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
     private let synthesizer = AVSpeechSynthesizer()
-    private var currentUtteranceId: String?
+    /// Renders speakToFile calls, apart from `synthesizer` so writing never stops speech
+    private let fileSynthesizer = AVSpeechSynthesizer()
+    /// Plugin ids of the utterances handed to the synthesizer, reported by their events
+    private var utteranceIds = [ObjectIdentifier: String]()
+    /// waitForUtterance calls by utterance id, answered with how the utterance ended
+    private var utteranceWaiters = [String: [Invoke]]()
+    /// How recent utterances ended, for waitForUtterance calls arriving after the end
+    private var endedUtterances = [(id: String, outcome: String)]()
+    /// Utterances handed to the synthesizer that haven't started yet, in order, with the
+    /// voice requested for each, for getQueueLength and getQueue
+    private var queuedUtterances = [(utterance: AVSpeechUtterance, voiceId: String?)]()
+    /// Utterance ids and word offsets, translating synthesizer word ranges for word-boundary events
+    private var wordBoundaries = [ObjectIdentifier: (id: String, offsets: [WordOffset])]()
+    /// Utterance ids, sentence offsets and the index of the next sentence to report, a word
+    /// range entering a new sentence emits sentence-start
+    private var sentenceStarts = [ObjectIdentifier: (id: String, offsets: [SentenceOffset], next: Int)]()
+    /// Id and text of the utterance the synthesizer is speaking, for isSpeaking
+    private var speakingUtterance: (id: String?, text: String)?
     private var wasInterrupted: Bool = false
+    /// Set by requestAudioFocus: keep the session active between utterances until abandoned
+    private var holdsAudioSession: Bool = false
     private var isInForeground: Bool = true
     private var voiceCache: [AVSpeechSynthesisVoice]?
     private var voiceCacheTimestamp: Date?
     private let voiceCacheTTL: TimeInterval = 60.0
+    private var audioSessionCategory: AVAudioSession.Category = .playback
+    private var audioSessionMode: AVAudioSession.Mode = .spokenAudio
+    private var audioSessionOptions: AVAudioSession.CategoryOptions = [.duckOthers]
         override init() {
         super.init()
         NSLog("[TtsPlugin] PLUGIN INIT")
@@ -154,10 +275,10 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
         NSLog("[TtsPlugin] setupAudioSession() CALLED")
         do {
             let session = AVAudioSession.sharedInstance()
-            // Use playback category with spoken audio mode for TTS
-            try session.setCategory(.playback, mode: .spokenAudio, options: [.duckOthers])
+            // Defaults to playback + spokenAudio + duckOthers, overridable via plugin config
+            try session.setCategory(audioSessionCategory, mode: audioSessionMode, options: audioSessionOptions)
             try session.setActive(true)
-            NSLog("[TtsPlugin]   Audio session configured: category=playback, mode=spokenAudio")
+            NSLog("[TtsPlugin]   Audio session configured: category=\(audioSessionCategory.rawValue), mode=\(audioSessionMode.rawValue), options=\(audioSessionOptions.rawValue)")
         } catch {
             NSLog("[TtsPlugin]   ERROR: Failed to configure audio session: \(error.localizedDescription)")
         }
@@ -185,6 +306,15 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
             name: UIApplication.willTerminateNotification,
             object: nil
         )
+        
+        if #available(iOS 17.0, *) {
+            NotificationCenter.default.addObserver(
+                self,
+                selector: #selector(handleAvailableVoicesDidChange),
+                name: AVSpeechSynthesizer.availableVoicesDidChangeNotification,
+                object: nil
+            )
+        }
         NSLog("[TtsPlugin]   Lifecycle observers registered")
     }
     
@@ -208,6 +338,27 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
         // Note: We don't auto-resume to avoid unexpected audio
     }
     
+    /// A voice was downloaded or removed in the system settings (iOS 17+)
+    @objc private func handleAvailableVoicesDidChange() {
+        NSLog("[TtsPlugin] Available voices changed")
+        voiceCache = nil
+        voiceCacheTimestamp = nil
+        var voices = JSArray()
+        for voice in AVSpeechSynthesisVoice.speechVoices() {
+            var entry = JSObject()
+            for (key, value) in voiceEntry(voice) {
+                if let value = value as? JSValue {
+                    entry[key] = value
+                }
+            }
+            entry["platform"] = "ios"
+            voices.append(entry)
+        }
+        var event = JSObject()
+        event["voices"] = voices
+        trigger("voices-changed", data: event)
+    }
+    
     @objc private func handleAppWillTerminate() {
         NSLog("[TtsPlugin] App will terminate - cleaning up")
         synthesizer.stopSpeaking(at: .immediate)
@@ -245,7 +396,7 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
             if synthesizer.isSpeaking {
                 synthesizer.pauseSpeaking(at: .word)
                 NSLog("[TtsPlugin] Speech paused due to audio route change (device unavailable)")
-                trigger("speech:pause", data: JSObject())
+                trigger("speech:pause", data: utteranceEvent(speakingUtterance?.id))
             }
         case .newDeviceAvailable:
             NSLog("[TtsPlugin] New audio device available")
@@ -263,6 +414,12 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
         
         switch type {
         case .began:
+            // iOS interruptions always end, so focus is only ever lost transiently
+            var event = JSObject()
+            event["transient"] = true
+            event["canDuck"] = false
+            trigger("audio:focusLost", data: event)
+            
             // Interruption began - TTS will be paused automatically
             if synthesizer.isSpeaking {
                 wasInterrupted = true
@@ -299,82 +456,134 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
     }
     
     func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, didStart utterance: AVSpeechUtterance) {
-        var event = JSObject()
-        if let id = currentUtteranceId {
-            event["id"] = id
-        }
+        queuedUtterances.removeAll { $0.utterance === utterance }
+        let id = utteranceIds[ObjectIdentifier(utterance)]
+        speakingUtterance = (id, utterance.speechString)
+        let event = utteranceEvent(id)
         trigger("speech:start", data: event)
+        trigger("utterance-start", data: event)
         NSLog("[TtsPlugin] Speech started")
     }
     
-    func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, didFinish utterance: AVSpeechUtterance) {
-        var event = JSObject()
-        if let id = currentUtteranceId {
-            event["id"] = id
+    func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, willSpeakRangeOfSpeechString characterRange: NSRange, utterance: AVSpeechUtterance) {
+        if let starts = sentenceStarts[ObjectIdentifier(utterance)],
+           let sentence = starts.offsets.first(where: { characterRange.location >= $0.spokenStart && characterRange.location < $0.spokenEnd }),
+           sentence.index >= starts.next {
+            sentenceStarts[ObjectIdentifier(utterance)]?.next = sentence.index + 1
+            var event = JSObject()
+            event["utteranceId"] = starts.id
+            event["index"] = sentence.index
+            event["charStart"] = sentence.textStart
+            event["charEnd"] = sentence.textEnd
+            trigger("sentence-start", data: event)
         }
+        // Words added by preprocessing, like emoji names, have no offsets and no event
+        guard let boundaries = wordBoundaries[ObjectIdentifier(utterance)],
+              let word = boundaries.offsets.first(where: { characterRange.location >= $0.spokenStart && characterRange.location < $0.spokenEnd }) else {
+            return
+        }
+        var event = JSObject()
+        event["utteranceId"] = boundaries.id
+        event["charStart"] = word.textStart
+        event["charEnd"] = word.textEnd
+        event["charLength"] = word.textEnd - word.textStart
+        trigger("word-boundary", data: event)
+    }
+    
+    func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, didFinish utterance: AVSpeechUtterance) {
+        wordBoundaries.removeValue(forKey: ObjectIdentifier(utterance))
+        sentenceStarts.removeValue(forKey: ObjectIdentifier(utterance))
+        let id = utteranceIds.removeValue(forKey: ObjectIdentifier(utterance))
+        endUtterance(id, outcome: "completed")
+        let event = utteranceEvent(id)
         trigger("speech:finish", data: event)
-        currentUtteranceId = nil
+        trigger("utterance-end", data: event)
+        speakingUtterance = nil
         NSLog("[TtsPlugin] Speech finished")
         
-        // Optionally deactivate audio session to allow other audio
-        // try? AVAudioSession.sharedInstance().setActive(false, options: .notifyOthersOnDeactivation)
+        // Deactivate the session once the queue drains so ducked audio returns to full volume
+        if audioSessionOptions.contains(.duckOthers) && !synthesizer.isSpeaking && !holdsAudioSession {
+            try? AVAudioSession.sharedInstance().setActive(false, options: .notifyOthersOnDeactivation)
+        }
     }
     
     func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, didCancel utterance: AVSpeechUtterance) {
-        var event = JSObject()
-        if let id = currentUtteranceId {
-            event["id"] = id
-        }
+        wordBoundaries.removeValue(forKey: ObjectIdentifier(utterance))
+        sentenceStarts.removeValue(forKey: ObjectIdentifier(utterance))
+        let id = utteranceIds.removeValue(forKey: ObjectIdentifier(utterance))
+        endUtterance(id, outcome: "stopped")
+        let event = utteranceEvent(id)
         trigger("speech:cancel", data: event)
-        currentUtteranceId = nil
+        trigger("utterance-cancelled", data: event)
+        speakingUtterance = nil
         NSLog("[TtsPlugin] Speech cancelled")
     }
     
     func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, didPause utterance: AVSpeechUtterance) {
-        trigger("speech:pause", data: JSObject())
+        trigger("speech:pause", data: utteranceEvent(utteranceIds[ObjectIdentifier(utterance)]))
     }
     
     func speechSynthesizer(_ synthesizer: AVSpeechSynthesizer, didContinue utterance: AVSpeechUtterance) {
-        trigger("speech:resume", data: JSObject())
+        trigger("speech:resume", data: utteranceEvent(utteranceIds[ObjectIdentifier(utterance)]))
     }
     
-    @objc public func speak(_ invoke: Invoke) throws {
-        NSLog("[TtsPlugin] speak() CALLED")
-        
-        let args = try invoke.parseArgs(SpeakArgs.self)
-        
-        do {
-            try args.validate()
-        } catch let error as TtsValidationError {
-            NSLog("[TtsPlugin]   Validation error: \(error.localizedDescription)")
-            invoke.reject("\(error.errorCode): \(error.localizedDescription)")
-            return
+    /// Payload of a speech event, carrying the utterance's plugin id when it has one
+    private func utteranceEvent(_ id: String?) -> JSObject {
+        var event = JSObject()
+        if let id = id {
+            event["id"] = id
         }
+        return event
+    }
+    
+    /// Forget the utterances that never started. The current one keeps its id for didCancel
+    private func dropQueuedUtterances() {
+        for queued in queuedUtterances {
+            endUtterance(utteranceIds.removeValue(forKey: ObjectIdentifier(queued.utterance)), outcome: "stopped")
+        }
+        queuedUtterances.removeAll()
+    }
+    
+    /// Remember how an utterance ended and answer the waitForUtterance calls waiting on it
+    private func endUtterance(_ id: String?, outcome: String) {
+        guard let id = id else { return }
+        endedUtterances.append((id: id, outcome: outcome))
+        if endedUtterances.count > endedUtterancesKept {
+            endedUtterances.removeFirst()
+        }
+        for invoke in utteranceWaiters.removeValue(forKey: id) ?? [] {
+            invoke.resolve(["outcome": outcome])
+        }
+    }
+    
+    @objc public func configureAudioSession(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] configureAudioSession() CALLED")
         
-        NSLog("[TtsPlugin]   Text length: \(args.text.count) chars")
-        NSLog("[TtsPlugin]   Language: \(args.language ?? "default")")
-        NSLog("[TtsPlugin]   VoiceId: \(args.voiceId ?? "default")")
-        NSLog("[TtsPlugin]   Rate: \(args.clampedRate)")
-        NSLog("[TtsPlugin]   Pitch: \(args.clampedPitch)")
-        NSLog("[TtsPlugin]   Volume: \(args.clampedVolume)")
-        NSLog("[TtsPlugin]   QueueMode: \(args.queueMode ?? "flush")")
-        NSLog("[TtsPlugin]   Foreground: \(isInForeground)")
+        let args = try invoke.parseArgs(AudioSessionArgs.self)
+        audioSessionCategory = args.sessionCategory
+        audioSessionMode = args.sessionMode
+        audioSessionOptions = args.sessionOptions
         
         setupAudioSession()
-        
-        // Handle queue mode: "flush" (default) stops current speech, "add" queues it
-        let shouldFlush = (args.queueMode ?? "flush").lowercased() != "add"
-        if shouldFlush && synthesizer.isSpeaking {
-            NSLog("[TtsPlugin]   Flushing current speech")
-            synthesizer.stopSpeaking(at: .immediate)
+        invoke.resolve()
+    }
+    
+    /// Utterance for `args` with its voice and prosody, setting `warning` when the
+    /// voice, language or SSML had to fall back
+    private func makeUtterance(_ args: SpeakArgs, warning: inout String?) -> AVSpeechUtterance {
+        let utterance: AVSpeechUtterance
+        if args.ssml == true {
+            if #available(iOS 16.0, macOS 13.0, *), let ssmlUtterance = AVSpeechUtterance(ssmlRepresentation: args.text) {
+                utterance = ssmlUtterance
+            } else {
+                NSLog("[TtsPlugin]   SSML unavailable (needs iOS 16), speaking plain text")
+                utterance = AVSpeechUtterance(string: ssmlToPlainText(args.text))
+                warning = ssmlPlainTextWarning
+            }
+        } else {
+            utterance = AVSpeechUtterance(string: args.text)
         }
         
-        let utterance = AVSpeechUtterance(string: args.text)
-        
-        currentUtteranceId = UUID().uuidString
-        
-        var warning: String? = nil
-        
         if let voiceId = args.voiceId {
             if let voice = AVSpeechSynthesisVoice.speechVoices().first(where: { $0.identifier == voiceId }) {
                 utterance.voice = voice
@@ -410,11 +619,86 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
             }
         }
         
+        return utterance
+    }
+    
+    @objc public func speak(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] speak() CALLED")
+        
+        let args = try invoke.parseArgs(SpeakArgs.self)
+        
+        do {
+            try args.validate()
+        } catch let error as TtsValidationError {
+            NSLog("[TtsPlugin]   Validation error: \(error.localizedDescription)")
+            invoke.reject("\(error.errorCode): \(error.localizedDescription)")
+            return
+        }
+        
+        NSLog("[TtsPlugin]   Text length: \(args.text.count) chars")
+        NSLog("[TtsPlugin]   Language: \(args.language ?? "default")")
+        NSLog("[TtsPlugin]   VoiceId: \(args.voiceId ?? "default")")
+        NSLog("[TtsPlugin]   Rate: \(args.clampedRate)")
+        NSLog("[TtsPlugin]   Pitch: \(args.clampedPitch)")
+        NSLog("[TtsPlugin]   Volume: \(args.clampedVolume)")
+        NSLog("[TtsPlugin]   QueueMode: \(args.queueMode ?? "flush")")
+        NSLog("[TtsPlugin]   Foreground: \(isInForeground)")
+        
+        setupAudioSession()
+        
+        var warning: String? = nil
+        
+        // Simulators and stripped installs can lack voices, which then speak nothing
+        if AVSpeechSynthesisVoice.speechVoices().isEmpty {
+            NSLog("[TtsPlugin]   No voices available")
+            warning = noVoicesWarning
+        }
+        
+        // Handle queue mode: "flush" (default) stops current speech, "add" queues it.
+        // AVSpeechSynthesizer can't drop queued utterances alone, so "replacePending"
+        // only keeps current speech when nothing is waiting behind it
+        let mode = (args.queueMode ?? "flush").lowercased()
+        let replaceable = mode == "replacepending" && queuedUtterances.count <= 1
+        if mode == "replacepending" && !replaceable {
+            NSLog("[TtsPlugin]   Utterances are waiting, replacePending flushes instead")
+            warning = warning ?? replacePendingWarning
+        }
+        // The synthesizer can't put interrupted speech back, so "urgent" flushes
+        if mode == "urgent" && (synthesizer.isSpeaking || !queuedUtterances.isEmpty) {
+            NSLog("[TtsPlugin]   Speech is in progress, urgent flushes it")
+            warning = warning ?? urgentWarning
+        }
+        let shouldFlush = mode != "add" && !replaceable
+        if shouldFlush && synthesizer.isSpeaking {
+            NSLog("[TtsPlugin]   Flushing current speech")
+            synthesizer.stopSpeaking(at: .immediate)
+        }
+        if shouldFlush {
+            dropQueuedUtterances()
+            wordBoundaries.removeAll()
+            sentenceStarts.removeAll()
+        }
+        
+        let utterance = makeUtterance(args, warning: &warning)
+        let utteranceId = UUID().uuidString
+        
+        if let pauseAfterMs = args.pauseAfterMs, pauseAfterMs > 0 {
+            utterance.postUtteranceDelay = pauseAfterMs / 1000.0
+        }
+        
+        queuedUtterances.append((utterance: utterance, voiceId: args.voiceId))
+        utteranceIds[ObjectIdentifier(utterance)] = utteranceId
+        if let offsets = args.wordOffsets, !offsets.isEmpty {
+            wordBoundaries[ObjectIdentifier(utterance)] = (id: utteranceId, offsets: offsets)
+        }
+        if let offsets = args.sentenceOffsets, !offsets.isEmpty {
+            sentenceStarts[ObjectIdentifier(utterance)] = (id: utteranceId, offsets: offsets, next: 0)
+        }
         synthesizer.speak(utterance)
         
         var response: [String: Any] = [
             "success": true,
-            "utteranceId": currentUtteranceId as Any
+            "utteranceId": utteranceId
         ]
         if let w = warning {
             response["warning"] = w
@@ -422,10 +706,103 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
         invoke.resolve(response)
     }
     
+    /// Answers once the utterance finished or was stopped, for speak_and_wait
+    /// Render speech to a WAV file through `AVSpeechSynthesizer.write`, resolving once
+    /// the file is complete. Uses its own synthesizer, so speech in progress plays on
+    @objc public func speakToFile(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] speakToFile() CALLED")
+        
+        let args = try invoke.parseArgs(SpeakArgs.self)
+        let outputPath = try invoke.parseArgs(SpeakToFileArgs.self).outputPath
+        do {
+            try args.validate()
+        } catch let error as TtsValidationError {
+            invoke.reject("\(error.errorCode): \(error.localizedDescription)")
+            return
+        }
+        
+        var warning: String? = AVSpeechSynthesisVoice.speechVoices().isEmpty ? noVoicesWarning : nil
+        let utterance = makeUtterance(args, warning: &warning)
+        let url = URL(fileURLWithPath: outputPath)
+        try? FileManager.default.createDirectory(at: url.deletingLastPathComponent(), withIntermediateDirectories: true)
+        try? FileManager.default.removeItem(at: url)
+        
+        // Buffers arrive one by one, an empty one marks the end of the utterance
+        var file: AVAudioFile?
+        var settled = false
+        var response: [String: Any] = ["path": outputPath]
+        if let w = warning {
+            response["warning"] = w
+        }
+        fileSynthesizer.write(utterance) { buffer in
+            guard !settled, let pcm = buffer as? AVAudioPCMBuffer else { return }
+            if pcm.frameLength == 0 {
+                settled = true
+                // Dropping the file closes it
+                let wrote = file != nil
+                file = nil
+                if wrote {
+                    invoke.resolve(response)
+                } else {
+                    invoke.reject("The engine produced no audio for the text")
+                }
+                return
+            }
+            do {
+                if file == nil {
+                    var settings = pcm.format.settings
+                    settings[AVFormatIDKey] = kAudioFormatLinearPCM
+                    file = try AVAudioFile(
+                        forWriting: url,
+                        settings: settings,
+                        commonFormat: pcm.format.commonFormat,
+                        interleaved: pcm.format.isInterleaved
+                    )
+                }
+                try file?.write(from: pcm)
+            } catch {
+                settled = true
+                file = nil
+                NSLog("[TtsPlugin]   Failed to write speech: \(error.localizedDescription)")
+                invoke.reject("Failed to write speech to \(outputPath): \(error.localizedDescription)")
+            }
+        }
+    }
+    
+    @objc public func waitForUtterance(_ invoke: Invoke) throws {
+        let args = try invoke.parseArgs(WaitForUtteranceArgs.self)
+        if let ended = endedUtterances.last(where: { $0.id == args.utteranceId }) {
+            invoke.resolve(["outcome": ended.outcome])
+            return
+        }
+        utteranceWaiters[args.utteranceId, default: []].append(invoke)
+    }
+    
+    @objc public func getQueueLength(_ invoke: Invoke) throws {
+        invoke.resolve(["length": queuedUtterances.count])
+    }
+    
+    @objc public func getQueue(_ invoke: Invoke) throws {
+        let utterances: [[String: Any]] = queuedUtterances.map { queued in
+            var item: [String: Any] = [
+                "id": utteranceIds[ObjectIdentifier(queued.utterance)] ?? "",
+                "text": queued.utterance.speechString
+            ]
+            if let voiceId = queued.voiceId {
+                item["voiceId"] = voiceId
+            }
+            return item
+        }
+        invoke.resolve(["utterances": utterances])
+    }
+    
     @objc public func stop(_ invoke: Invoke) throws {
         NSLog("[TtsPlugin] stop() CALLED")
         NSLog("[TtsPlugin]   isSpeaking: \(synthesizer.isSpeaking)")
         synthesizer.stopSpeaking(at: .immediate)
+        dropQueuedUtterances()
+        wordBoundaries.removeAll()
+        sentenceStarts.removeAll()
         NSLog("[TtsPlugin]   Speech stopped")
         invoke.resolve(["success": true])
     }
@@ -484,18 +861,14 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
         NSLog("[TtsPlugin]   Total available voices: \(allVoices.count)")
         NSLog("[TtsPlugin]   Language filter: \(args.language ?? "none")")
         
-        var voices: [[String: String]] = []
+        var voices: [[String: Any]] = []
         
         for voice in allVoices {
             let languageFilter = args.language?.lowercased()
             let voiceLanguage = voice.language.lowercased()
             
             if languageFilter == nil || voiceLanguage.contains(languageFilter!) {
-                voices.append([
-                    "id": voice.identifier,
-                    "name": voice.name,
-                    "language": voice.language
-                ])
+                voices.append(voiceEntry(voice))
             }
         }
         
@@ -503,14 +876,128 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
         invoke.resolve(["voices": voices])
     }
     
+    @objc public func refreshVoices(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] refreshVoices() CALLED")
+        voiceCache = nil
+        voiceCacheTimestamp = nil
+        invoke.resolve()
+    }
+    
+    @objc public func getDefaultVoice(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] getDefaultVoice() CALLED")
+        // The language set in the system settings, and the voice iOS speaks it with
+        // when an utterance names no voice
+        let language = AVSpeechSynthesisVoice.currentLanguageCode()
+        var response: [String: Any] = ["language": language]
+        if let voice = AVSpeechSynthesisVoice(language: language) {
+            NSLog("[TtsPlugin]   Default voice: \(voice.identifier)")
+            response["voice"] = voiceEntry(voice)
+        }
+        invoke.resolve(response)
+    }
+    
+    /// A voice as the plugin's Voice model
+    private func voiceEntry(_ voice: AVSpeechSynthesisVoice) -> [String: Any] {
+        var entry: [String: Any] = [
+            "id": voice.identifier,
+            "name": voice.name,
+            "language": voice.language,
+            "quality": qualityName(voice),
+            // speechVoices() only lists voices downloaded to the device
+            "requiresNetwork": false,
+            "installed": true,
+            "engine": "AVSpeechSynthesizer"
+        ]
+        if let gender = genderName(voice) {
+            entry["gender"] = gender
+        }
+        return entry
+    }
+    
+    /// Name of the plugin's VoiceQuality tier for a voice
+    private func qualityName(_ voice: AVSpeechSynthesisVoice) -> String {
+        // AVSpeechSynthesisVoiceQuality.premium (3) only exists on iOS 16+ / macOS 13+
+        if voice.quality.rawValue >= 3 {
+            return "premium"
+        }
+        return voice.quality == .enhanced ? "enhanced" : "normal"
+    }
+    
+    /// Gender of a voice, nil when unspecified or before iOS 13 / macOS 10.15
+    private func genderName(_ voice: AVSpeechSynthesisVoice) -> String? {
+        guard #available(iOS 13.0, macOS 10.15, *) else {
+            return nil
+        }
+        switch voice.gender {
+        case .male:
+            return "male"
+        case .female:
+            return "female"
+        default:
+            return nil
+        }
+    }
+    
     @objc public func isSpeaking(_ invoke: Invoke) throws {
         NSLog("[TtsPlugin] isSpeaking() CALLED")
         NSLog("[TtsPlugin]   Speaking: \(synthesizer.isSpeaking), Paused: \(synthesizer.isPaused)")
-        // Only return "speaking" field for consistency with Desktop and Android
-        // The paused state can be inferred from pauseSpeaking/resumeSpeaking API
-        invoke.resolve([
-            "speaking": synthesizer.isSpeaking
-        ])
+        // The paused state is reported by isPaused
+        var response = JSObject()
+        response["speaking"] = synthesizer.isSpeaking
+        if synthesizer.isSpeaking, let current = speakingUtterance {
+            if let id = current.id {
+                response["currentUtteranceId"] = id
+            }
+            response["currentText"] = current.text
+        }
+        invoke.resolve(response)
+    }
+    
+    @objc public func isPaused(_ invoke: Invoke) throws {
+        invoke.resolve(["paused": synthesizer.isPaused])
+    }
+    
+    @objc public func requestAudioFocus(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] requestAudioFocus() CALLED")
+        do {
+            try AVAudioSession.sharedInstance().setActive(true)
+            holdsAudioSession = true
+            invoke.resolve(["granted": true])
+        } catch {
+            NSLog("[TtsPlugin]   Failed to activate audio session: \(error.localizedDescription)")
+            invoke.resolve(["granted": false, "note": error.localizedDescription])
+        }
+    }
+    
+    @objc public func abandonAudioFocus(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] abandonAudioFocus() CALLED")
+        holdsAudioSession = false
+        if !synthesizer.isSpeaking {
+            try? AVAudioSession.sharedInstance().setActive(false, options: .notifyOthersOnDeactivation)
+        }
+        invoke.resolve(["granted": false])
+    }
+    
+    @objc public func getAudioRoute(_ invoke: Invoke) throws {
+        NSLog("[TtsPlugin] getAudioRoute() CALLED")
+        let outputs = AVAudioSession.sharedInstance().currentRoute.outputs
+        let route: String
+        switch outputs.first?.portType {
+        case .builtInSpeaker?:
+            route = "speaker"
+        case .builtInReceiver?:
+            route = "earpiece"
+        case .headphones?, .usbAudio?:
+            route = "headphones"
+        case .bluetoothA2DP?, .bluetoothHFP?, .bluetoothLE?:
+            route = "bluetooth"
+        case nil:
+            route = "unknown"
+        default:
+            route = "other"
+        }
+        NSLog("[TtsPlugin]   Route: \(route) (\(outputs.map { $0.portType.rawValue }))")
+        invoke.resolve(["route": route])
     }
     
     @objc public func isInitialized(_ invoke: Invoke) throws {
@@ -545,9 +1032,11 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
             NSLog("[TtsPlugin]   Stopping current speech")
             synthesizer.stopSpeaking(at: .immediate)
         }
+        dropQueuedUtterances()
+        wordBoundaries.removeAll()
+        sentenceStarts.removeAll()
         
         let utterance = AVSpeechUtterance(string: args.sampleText)
-        currentUtteranceId = UUID().uuidString
         
         if let voice = AVSpeechSynthesisVoice.speechVoices().first(where: { $0.identifier == args.voiceId }) {
             utterance.voice = voice
@@ -558,10 +1047,13 @@ class TtsPlugin: Plugin, AVSpeechSynthesizerDelegate {
             return
         }
         
-        utterance.rate = AVSpeechUtteranceDefaultSpeechRate
-        utterance.pitchMultiplier = 1.0
-        utterance.volume = 1.0
+        // Same mapping as speak(): 1.0 is AVSpeechUtteranceDefaultSpeechRate (0.5)
+        let rate = (args.rate ?? 1.0) * 0.5
+        utterance.rate = min(max(rate, AVSpeechUtteranceMinimumSpeechRate), AVSpeechUtteranceMaximumSpeechRate)
+        utterance.pitchMultiplier = args.pitch ?? 1.0
+        utterance.volume = args.volume ?? 1.0
         
+        utteranceIds[ObjectIdentifier(utterance)] = UUID().uuidString
         synthesizer.speak(utterance)
         NSLog("[TtsPlugin]   Preview started")
         invoke.resolve(["success": true])
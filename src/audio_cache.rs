@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::models::{CacheStatsResponse, ClearCacheResponse, TtsConfig};
+
+/// Directory of the app cache directory audio is kept in
+const CACHE_DIR: &str = "tts-cache";
+const ENTRY_EXTENSION: &str = "audio";
+/// Bytes of each entry ahead of the audio: the key's second hash, which tells
+/// apart keys whose file names collide
+const HEADER_LEN: usize = 8;
+
+/// What synthesized audio is cached under: a hash of everything that shapes it,
+/// like the backend, voice, text and prosody. The text itself isn't kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CacheKey {
+    file: u64,
+    check: u64,
+}
+
+impl CacheKey {
+    pub fn new(parts: &[&str]) -> Self {
+        let hash = |seed: u8| {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            parts.hash(&mut hasher);
+            hasher.finish()
+        };
+        Self {
+            file: hash(0),
+            check: hash(1),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        format!("{:016x}.{}", self.file, ENTRY_EXTENSION)
+    }
+}
+
+/// Synthesized audio kept on disk across launches, so repeated phrases aren't
+/// synthesized (and paid for) again. The least recently used entries go first
+/// once the cache outgrows its cap
+pub(crate) struct AudioCache {
+    /// `None` when the app cache directory can't be resolved, which disables caching
+    dir: Option<PathBuf>,
+    max_bytes: u64,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Entries by file name, `None` until the directory is first read
+    entries: Option<HashMap<String, Entry>>,
+    hits: u64,
+    misses: u64,
+}
+
+struct Entry {
+    bytes: u64,
+    used: SystemTime,
+}
+
+impl AudioCache {
+    pub fn new(dir: Option<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// The cache in the app cache directory, capped at the configured size
+    pub fn load<R: Runtime>(app: &AppHandle<R>, config: &TtsConfig) -> Self {
+        let dir = match app.path().app_cache_dir() {
+            Ok(dir) => Some(dir.join(CACHE_DIR)),
+            Err(e) => {
+                log::warn!("Synthesized audio won't be cached: {}", e);
+                None
+            }
+        };
+        Self::new(dir, config.cache_max_bytes())
+    }
+
+    fn enabled(&self) -> Option<&PathBuf> {
+        self.dir.as_ref().filter(|_| self.max_bytes > 0)
+    }
+
+    /// Cached audio for `key`, marking it as just used
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        let dir = self.enabled()?;
+        let mut state = self.state.lock().ok()?;
+        let name = key.file_name();
+        let audio = state
+            .entries(dir)
+            .contains_key(&name)
+            .then(|| fs::read(dir.join(&name)).ok())
+            .flatten()
+            .filter(|entry| entry.get(..HEADER_LEN) == Some(&key.check.to_le_bytes()[..]))
+            .map(|mut entry| entry.split_off(HEADER_LEN));
+        let Some(audio) = audio else {
+            state.misses += 1;
+            return None;
+        };
+        state.hits += 1;
+        let now = SystemTime::now();
+        if let Some(entry) = state.entries(dir).get_mut(&name) {
+            entry.used = now;
+        }
+        // Recency survives a restart through the file's modification time
+        if let Ok(file) = fs::File::options().append(true).open(dir.join(&name)) {
+            let _ = file.set_modified(now);
+        }
+        Some(audio)
+    }
+
+    /// Keep `audio` under `key`, dropping the least recently used entries to stay
+    /// within the cap. Failures are only logged, caching is best effort
+    pub fn put(&self, key: &CacheKey, audio: &[u8]) {
+        let Some(dir) = self.enabled() else {
+            return;
+        };
+        let bytes = (HEADER_LEN + audio.len()) as u64;
+        if bytes > self.max_bytes {
+            return;
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let name = key.file_name();
+        let path = dir.join(&name);
+        let partial = path.with_extension("part");
+        let written = fs::create_dir_all(dir)
+            .and_then(|()| fs::write(&partial, [&key.check.to_le_bytes()[..], audio].concat()))
+            .and_then(|()| fs::rename(&partial, &path));
+        if let Err(e) = written {
+            log::warn!("Failed to cache synthesized audio: {}", e);
+            return;
+        }
+        let entries = state.entries(dir);
+        entries.insert(
+            name,
+            Entry {
+                bytes,
+                used: SystemTime::now(),
+            },
+        );
+        let mut total: u64 = entries.values().map(|entry| entry.bytes).sum();
+        while total > self.max_bytes {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(name, _)| name.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = entries.remove(&oldest) {
+                total -= entry.bytes;
+            }
+            if let Err(e) = fs::remove_file(dir.join(&oldest)) {
+                log::warn!("Failed to evict cached audio: {}", e);
+            }
+        }
+    }
+
+    /// Cached audio for `key`, else the audio `render` makes, which is cached
+    pub fn get_or_insert_with(
+        &self,
+        key: &CacheKey,
+        render: impl FnOnce() -> crate::Result<Vec<u8>>,
+    ) -> crate::Result<Vec<u8>> {
+        if let Some(audio) = self.get(key) {
+            return Ok(audio);
+        }
+        let audio = render()?;
+        self.put(key, &audio);
+        Ok(audio)
+    }
+
+    /// Pass `audio` through while keeping a copy, cached under `key` once it has
+    /// been read to the end. Audio cut short, like by a stop, isn't cached
+    #[cfg_attr(not(any(feature = "elevenlabs", feature = "openai")), allow(dead_code))]
+    pub fn recording<R: Read>(&self, key: CacheKey, audio: R) -> Recording<'_, R> {
+        Recording {
+            cache: self,
+            key,
+            inner: audio,
+            audio: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub fn stats(&self) -> crate::Result<CacheStatsResponse> {
+        let mut state = self.state.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let (entries, bytes) = match self.enabled() {
+            Some(dir) => {
+                let entries = state.entries(dir);
+                (
+                    entries.len() as u32,
+                    entries.values().map(|entry| entry.bytes).sum(),
+                )
+            }
+            None => (0, 0),
+        };
+        Ok(CacheStatsResponse {
+            entries,
+            bytes,
+            max_bytes: self.max_bytes,
+            hits: state.hits,
+            misses: state.misses,
+            directory: self.dir.as_ref().map(|dir| dir.display().to_string()),
+        })
+    }
+
+    /// Delete every cached entry, including those of earlier launches
+    pub fn clear(&self) -> crate::Result<ClearCacheResponse> {
+        let mut state = self.state.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let mut response = ClearCacheResponse::default();
+        if let Some(ref dir) = self.dir {
+            for (name, entry) in state.entries(dir).drain() {
+                fs::remove_file(dir.join(name))?;
+                response.removed_entries += 1;
+                response.freed_bytes += entry.bytes;
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl CacheState {
+    /// The entries in `dir`, read from it on first use
+    fn entries(&mut self, dir: &Path) -> &mut HashMap<String, Entry> {
+        self.entries.get_or_insert_with(|| {
+            let Ok(files) = fs::read_dir(dir) else {
+                return HashMap::new();
+            };
+            files
+                .filter_map(|file| {
+                    let file = file.ok()?;
+                    let name = file.file_name().into_string().ok()?;
+                    let path = dir.join(&name);
+                    if path.extension()? != ENTRY_EXTENSION {
+                        return None;
+                    }
+                    let metadata = file.metadata().ok()?;
+                    let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    Some((
+                        name,
+                        Entry {
+                            bytes: metadata.len(),
+                            used,
+                        },
+                    ))
+                })
+                .collect()
+        })
+    }
+}
+
+/// Audio read through [`AudioCache::recording`]
+#[cfg_attr(not(any(feature = "elevenlabs", feature = "openai")), allow(dead_code))]
+pub(crate) struct Recording<'a, R> {
+    cache: &'a AudioCache,
+    key: CacheKey,
+    inner: R,
+    audio: Vec<u8>,
+    done: bool,
+}
+
+impl<R: Read> Read for Recording<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 && !buf.is_empty() {
+            self.done = true;
+        }
+        self.audio.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R> Drop for Recording<'_, R> {
+    fn drop(&mut self) {
+        if self.done {
+            self.cache.put(&self.key, &self.audio);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_bytes: u64) -> (AudioCache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("tts-cache-test-{}", uuid::Uuid::new_v4()));
+        (AudioCache::new(Some(dir.clone()), max_bytes), dir)
+    }
+
+    #[test]
+    fn test_cached_audio_is_found_by_its_key() {
+        let (cache, dir) = cache(1024);
+        let key = CacheKey::new(&["azure", "en-US-AvaNeural", "Hello", "1", "1"]);
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, b"RIFF audio");
+        assert_eq!(cache.get(&key).as_deref(), Some(&b"RIFF audio"[..]));
+        assert_eq!(
+            cache.get(&CacheKey::new(&[
+                "azure",
+                "en-US-AvaNeural",
+                "Hello",
+                "1.5",
+                "1"
+            ])),
+            None
+        );
+
+        // A later launch reads the entries back from disk
+        let relaunched = AudioCache::new(Some(dir.clone()), 1024);
+        assert_eq!(relaunched.get(&key).as_deref(), Some(&b"RIFF audio"[..]));
+        let stats = cache.stats().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!((stats.entries, stats.bytes), (1, 18));
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+    }
+
+    #[test]
+    fn test_keys_differ_by_part_boundaries() {
+        assert_ne!(CacheKey::new(&["ab", "c"]), CacheKey::new(&["a", "bc"]));
+    }
+
+    #[test]
+    fn test_least_recently_used_entries_are_evicted() {
+        let (cache, dir) = cache(3 * (HEADER_LEN as u64 + 4));
+        let keys: Vec<_> = ["one", "two", "three", "four"]
+            .iter()
+            .map(|text| CacheKey::new(&[text]))
+            .collect();
+        for key in &keys[..3] {
+            cache.put(key, b"wav!");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        // Using the oldest makes the second the least recently used
+        assert!(cache.get(&keys[0]).is_some());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.put(&keys[3], b"wav!");
+
+        let kept: Vec<bool> = keys.iter().map(|key| cache.get(key).is_some()).collect();
+        let cleared = cache.clear().unwrap();
+        let stats = cache.stats().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(kept, vec![true, false, true, true]);
+        assert_eq!(cleared.removed_entries, 3);
+        assert_eq!(stats.entries, 0);
+    }
+
+    #[test]
+    fn test_only_fully_read_audio_is_recorded() {
+        let (cache, dir) = cache(1024);
+        let key = CacheKey::new(&["openai", "alloy", "Hi"]);
+        let mut partial = cache.recording(key.clone(), &b"pcm data"[..]);
+        let mut buf = [0; 3];
+        partial.read_exact(&mut buf).unwrap();
+        drop(partial);
+        assert_eq!(cache.get(&key), None);
+
+        let mut audio = Vec::new();
+        cache
+            .recording(key.clone(), &b"pcm data"[..])
+            .read_to_end(&mut audio)
+            .unwrap();
+        let cached = cache.get(&key);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(cached.as_deref(), Some(&b"pcm data"[..]));
+    }
+
+    #[test]
+    fn test_zero_cap_disables_caching() {
+        let (cache, dir) = cache(0);
+        let key = CacheKey::new(&["system", "Hello"]);
+        cache.put(&key, b"audio");
+        assert_eq!(cache.get(&key), None);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,270 @@
+//! Speech engines the desktop plugin speaks with, the OS one among them, chosen
+//! per request by the `backend` field
+
+#[cfg(feature = "azure")]
+mod azure;
+#[cfg(feature = "elevenlabs")]
+mod elevenlabs;
+#[cfg(feature = "gcp-tts")]
+mod gcp;
+#[cfg(feature = "http-tts")]
+mod http;
+#[cfg(feature = "openai")]
+mod openai;
+#[cfg(feature = "piper")]
+pub(crate) mod piper;
+#[cfg(feature = "playback")]
+mod player;
+#[cfg(feature = "polly")]
+mod polly;
+#[cfg(feature = "polly")]
+mod sigv4;
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::audio_cache::AudioCache;
+use crate::models::{TtsConfig, TtsFeatures, ValidatedSpeakRequest, Voice, SYSTEM_BACKEND};
+
+/// A speech engine registered with [`crate::desktop::Tts::register_backend`],
+/// e.g. a cloud or neural one. Requests naming it in `backend` are spoken
+/// through it by the plugin queue, which emits their events the same way as for
+/// the OS engine, itself the `system` backend.
+///
+/// Text reaches the backend preprocessed, a sentence at a time. `speak` is
+/// called from the queue worker and blocks until the sentence has played, while
+/// `stop` may be called from any thread and must make a `speak` in progress
+/// return.
+pub trait TtsBackend: Send + Sync + 'static {
+    /// Name requests select the backend by. `system` is taken by the OS engine
+    fn name(&self) -> &str;
+
+    /// Speak `text` with the voice and prosody of `request`, returning once it
+    /// has played or was stopped
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()>;
+
+    /// Like `speak`, calling `on_mark` as each word or viseme is heard. The
+    /// plugin turns marks into `word-boundary` and `viseme` events. Backends
+    /// without timing information keep the default, which reports none
+    fn speak_marked(
+        &self,
+        request: &ValidatedSpeakRequest,
+        text: &str,
+        on_mark: &mut dyn FnMut(SpeechMark),
+    ) -> crate::Result<()> {
+        let _ = on_mark;
+        self.speak(request, text)
+    }
+
+    /// Stop the speech `speak` is playing, if any
+    fn stop(&self) -> crate::Result<()>;
+
+    /// Fade the speech `speak` is playing out over `duration`, returning once it
+    /// is silent, and whether the backend can fade at all. Backends that can't keep
+    /// the default, and are stopped right away by `stop_gentle`
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        let _ = duration;
+        Ok(false)
+    }
+
+    /// Voices requests may name in `voiceId` when using this backend
+    fn voices(&self) -> crate::Result<Vec<Voice>>;
+
+    /// What the backend supports, reported by `get_backends`
+    fn capabilities(&self) -> TtsFeatures;
+
+    /// Render `text` to WAV without playing it, for `speak_to_file` and
+    /// `synthesize`. Backends that only play speech keep the default, which fails
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let _ = (request, text);
+        Err(crate::Error::OperationFailed(format!(
+            "The {} backend can't synthesize speech to audio",
+            self.name()
+        )))
+    }
+}
+
+/// Timing information a backend reports while speaking through
+/// [`TtsBackend::speak_marked`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpeechMark {
+    /// A word about to be heard, as byte offsets into the text being spoken
+    Word(Range<usize>),
+    /// The mouth shape of the sound about to be heard, in the backend's viseme alphabet
+    Viseme(String),
+}
+
+/// Registered backends by name, the OS engine's under `system`
+pub(crate) struct BackendRegistry {
+    backends: RwLock<HashMap<String, Arc<dyn TtsBackend>>>,
+}
+
+impl BackendRegistry {
+    /// Registry holding only `system`, the backend of the OS engine
+    pub fn new(system: Arc<dyn TtsBackend>) -> Self {
+        let backends = HashMap::from([(SYSTEM_BACKEND.to_string(), system)]);
+        Self {
+            backends: RwLock::new(backends),
+        }
+    }
+
+    /// Add `backend`, replacing any registered under the same name. `system`
+    /// stays the OS engine
+    pub fn register(&self, backend: Arc<dyn TtsBackend>) -> crate::Result<()> {
+        let name = backend.name().to_string();
+        if name.trim().is_empty() || name == SYSTEM_BACKEND {
+            return Err(crate::Error::OperationFailed(format!(
+                "Invalid backend name '{}'",
+                name
+            )));
+        }
+        self.backends
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .insert(name, backend);
+        Ok(())
+    }
+
+    /// Add the built-in backends `config` sets up, caching their audio in `cache`
+    pub fn register_configured(
+        &self,
+        config: &TtsConfig,
+        cache: &Arc<AudioCache>,
+    ) -> crate::Result<()> {
+        #[cfg(feature = "azure")]
+        if let Some(ref azure) = config.azure {
+            self.register(Arc::new(azure::AzureBackend::new(
+                azure.clone(),
+                Arc::clone(cache),
+            )))?;
+        }
+        #[cfg(feature = "gcp-tts")]
+        if let Some(ref gcp) = config.gcp {
+            self.register(Arc::new(gcp::GcpBackend::new(
+                gcp.clone(),
+                Arc::clone(cache),
+            )))?;
+        }
+        #[cfg(feature = "polly")]
+        if let Some(ref polly) = config.polly {
+            self.register(Arc::new(polly::PollyBackend::new(
+                polly.clone(),
+                Arc::clone(cache),
+            )))?;
+        }
+        #[cfg(feature = "elevenlabs")]
+        if let Some(ref elevenlabs) = config.elevenlabs {
+            self.register(Arc::new(elevenlabs::ElevenLabsBackend::new(
+                elevenlabs.clone(),
+                Arc::clone(cache),
+            )))?;
+        }
+        #[cfg(feature = "openai")]
+        if let Some(ref openai) = config.openai {
+            self.register(Arc::new(openai::OpenAiBackend::new(
+                openai.clone(),
+                Arc::clone(cache),
+            )))?;
+        }
+        #[cfg(feature = "http-tts")]
+        if let Some(ref http) = config.http {
+            self.register(Arc::new(http::HttpBackend::new(
+                http.clone(),
+                Arc::clone(cache),
+            )))?;
+        }
+        let _ = (config, cache);
+        Ok(())
+    }
+
+    /// The backend a request names, the OS engine's when it names none
+    pub fn get(&self, name: Option<&str>) -> crate::Result<Arc<dyn TtsBackend>> {
+        let name = name.unwrap_or(SYSTEM_BACKEND);
+        self.backends
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .get(name)
+            .cloned()
+            .ok_or_else(|| crate::Error::BackendNotFound(name.to_string()))
+    }
+
+    /// Every registered backend, by name
+    pub fn all(&self) -> crate::Result<Vec<Arc<dyn TtsBackend>>> {
+        let mut backends: Vec<_> = self
+            .backends
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .values()
+            .cloned()
+            .collect();
+        backends.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(backends)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Silent(&'static str);
+
+    impl TtsBackend for Silent {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn speak(&self, _request: &ValidatedSpeakRequest, _text: &str) -> crate::Result<()> {
+            Ok(())
+        }
+
+        fn stop(&self) -> crate::Result<()> {
+            Ok(())
+        }
+
+        fn voices(&self) -> crate::Result<Vec<Voice>> {
+            Ok(Vec::new())
+        }
+
+        fn capabilities(&self) -> TtsFeatures {
+            TtsFeatures::default()
+        }
+    }
+
+    fn registry() -> BackendRegistry {
+        BackendRegistry::new(Arc::new(Silent(SYSTEM_BACKEND)))
+    }
+
+    #[test]
+    fn test_requests_find_backends_by_name() {
+        let registry = registry();
+        registry.register(Arc::new(Silent("piper"))).unwrap();
+        registry.register(Arc::new(Silent("cloud"))).unwrap();
+
+        assert_eq!(registry.get(None).unwrap().name(), SYSTEM_BACKEND);
+        assert_eq!(
+            registry.get(Some(SYSTEM_BACKEND)).unwrap().name(),
+            SYSTEM_BACKEND
+        );
+        assert_eq!(registry.get(Some("piper")).unwrap().name(), "piper");
+        assert!(matches!(
+            registry.get(Some("missing")),
+            Err(crate::Error::BackendNotFound(name)) if name == "missing"
+        ));
+        let names: Vec<_> = registry
+            .all()
+            .unwrap()
+            .iter()
+            .map(|backend| backend.name().to_string())
+            .collect();
+        assert_eq!(names, ["cloud", "piper", SYSTEM_BACKEND]);
+    }
+
+    #[test]
+    fn test_system_name_is_reserved() {
+        let registry = registry();
+        assert!(registry.register(Arc::new(Silent(SYSTEM_BACKEND))).is_err());
+        assert!(registry.register(Arc::new(Silent(" "))).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Backend speaking with Azure AI Speech through its REST API
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::player::{Level, Player};
+use super::TtsBackend;
+use crate::audio_cache::{AudioCache, CacheKey};
+use crate::models::{
+    AzureConfig, TtsFeatures, ValidatedSpeakRequest, Voice, VoiceGender, VoiceQuality,
+};
+
+/// Name requests select the backend by
+const NAME: &str = "azure";
+const DEFAULT_VOICE: &str = "en-US-AvaMultilingualNeural";
+/// WAV, so `synthesize` can return it as is
+const OUTPUT_FORMAT: &str = "riff-24khz-16bit-mono-pcm";
+
+pub(crate) struct AzureBackend {
+    config: AzureConfig,
+    player: Player,
+    cache: Arc<AudioCache>,
+    /// Fetched on first use, the list rarely changes
+    voices: Mutex<Option<Vec<Voice>>>,
+}
+
+/// A voice in the `voices/list` response
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AzureVoice {
+    short_name: String,
+    display_name: String,
+    locale: String,
+    #[serde(default)]
+    gender: String,
+    #[serde(default)]
+    voice_type: String,
+}
+
+impl From<AzureVoice> for Voice {
+    fn from(voice: AzureVoice) -> Self {
+        Voice {
+            id: voice.short_name,
+            name: voice.display_name,
+            language: voice.locale,
+            gender: match voice.gender.as_str() {
+                "Male" => Some(VoiceGender::Male),
+                "Female" => Some(VoiceGender::Female),
+                _ => None,
+            },
+            quality: Some(if voice.voice_type.starts_with("Neural") {
+                VoiceQuality::Enhanced
+            } else {
+                VoiceQuality::Normal
+            }),
+            requires_network: Some(true),
+            installed: None,
+            engine: Some(NAME.to_string()),
+            platform: NAME.to_string(),
+        }
+    }
+}
+
+impl AzureBackend {
+    pub fn new(config: AzureConfig, cache: Arc<AudioCache>) -> Self {
+        Self {
+            config,
+            player: Player::default(),
+            cache,
+            voices: Mutex::new(None),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "https://{}.tts.speech.microsoft.com/cognitiveservices/{}",
+            self.config.region, path
+        )
+    }
+
+    fn render(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let voice = request
+            .voice_id
+            .as_deref()
+            .or(self.config.voice.as_deref())
+            .unwrap_or(DEFAULT_VOICE);
+        // The markup holds everything that shapes the audio
+        let markup = ssml(voice, self.config.style.as_deref(), request, text);
+        let key = CacheKey::new(&[NAME, &self.config.region, OUTPUT_FORMAT, &markup]);
+        self.cache.get_or_insert_with(&key, || {
+            ureq::post(self.url("v1"))
+                .header("Ocp-Apim-Subscription-Key", &self.config.key)
+                .header("Content-Type", "application/ssml+xml")
+                .header("X-Microsoft-OutputFormat", OUTPUT_FORMAT)
+                .header("User-Agent", "tauri-plugin-tts")
+                .send(markup.as_str())
+                .and_then(|response| response.into_body().read_to_vec())
+                .map_err(request_failed)
+        })
+    }
+}
+
+impl TtsBackend for AzureBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        // Volume is part of the markup
+        self.player
+            .play(self.render(request, text)?, Level::synthesized(request))
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        let mut cache = self
+            .voices
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        if let Some(ref voices) = *cache {
+            return Ok(voices.clone());
+        }
+        let voices: Vec<AzureVoice> = ureq::get(self.url("voices/list"))
+            .header("Ocp-Apim-Subscription-Key", &self.config.key)
+            .call()
+            .and_then(|response| response.into_body().read_json())
+            .map_err(request_failed)?;
+        let voices: Vec<Voice> = voices.into_iter().map(Voice::from).collect();
+        *cache = Some(voices.clone());
+        Ok(voices)
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: true,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        self.render(request, text)
+    }
+}
+
+fn request_failed(e: ureq::Error) -> crate::Error {
+    crate::Error::OperationFailed(format!("Azure request failed: {}", e))
+}
+
+/// SSML document speaking `text` with `voice`, in `style` when given, and the
+/// request's prosody. Rate and pitch are relative to the voice's own, volume is
+/// absolute
+fn ssml(voice: &str, style: Option<&str>, request: &ValidatedSpeakRequest, text: &str) -> String {
+    // Azure requires a language even though the voice implies one
+    let language = request
+        .language
+        .clone()
+        .unwrap_or_else(|| voice.split('-').take(2).collect::<Vec<_>>().join("-"));
+
+    let mut markup = String::from(
+        "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" \
+         xmlns:mstts=\"https://www.w3.org/2001/mstts\" xml:lang=\"",
+    );
+    crate::ssml::push_escaped(&mut markup, &language);
+    markup.push_str("\"><voice name=\"");
+    crate::ssml::push_escaped(&mut markup, voice);
+    markup.push_str("\">");
+    if let Some(style) = style {
+        markup.push_str("<mstts:express-as style=\"");
+        crate::ssml::push_escaped(&mut markup, style);
+        markup.push_str("\">");
+    }
+    markup.push_str(&format!(
+        "<prosody rate=\"{:+.0}%\" pitch=\"{:+.0}%\" volume=\"{:.0}\">",
+        (request.rate - 1.0) * 100.0,
+        (request.pitch - 1.0) * 100.0,
+        request.volume.clamp(0.0, 1.0) * 100.0
+    ));
+    crate::ssml::push_escaped(&mut markup, text);
+    markup.push_str("</prosody>");
+    if style.is_some() {
+        markup.push_str("</mstts:express-as>");
+    }
+    markup.push_str("</voice></speak>");
+    markup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SpeakRequest;
+
+    fn request(rate: f32, pitch: f32, volume: f32) -> ValidatedSpeakRequest {
+        SpeakRequest {
+            text: "unused".to_string(),
+            rate: Some(rate),
+            pitch: Some(pitch),
+            volume: Some(volume),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ssml_carries_voice_style_and_prosody() {
+        let markup = ssml(
+            "en-US-JennyNeural",
+            Some("cheerful"),
+            &request(1.5, 0.8, 0.5),
+            "Hi",
+        );
+        assert_eq!(
+            markup,
+            "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" \
+             xmlns:mstts=\"https://www.w3.org/2001/mstts\" xml:lang=\"en-US\">\
+             <voice name=\"en-US-JennyNeural\"><mstts:express-as style=\"cheerful\">\
+             <prosody rate=\"+50%\" pitch=\"-20%\" volume=\"50\">Hi</prosody>\
+             </mstts:express-as></voice></speak>"
+        );
+    }
+
+    #[test]
+    fn test_ssml_escapes_text() {
+        let markup = ssml(
+            "en-US-JennyNeural",
+            None,
+            &request(1.0, 1.0, 1.0),
+            "A & <B>",
+        );
+        assert!(markup.contains(
+            "<prosody rate=\"+0%\" pitch=\"+0%\" volume=\"100\">A &amp; &lt;B&gt;</prosody></voice>"
+        ));
+        assert!(!markup.contains("express-as"));
+    }
+
+    #[test]
+    fn test_voices_map_gender_and_neural_quality() {
+        let voices: Vec<AzureVoice> = serde_json::from_str(
+            r#"[{"Name": "Microsoft Server Speech Text to Speech Voice (pt-BR, FranciscaNeural)",
+                 "ShortName": "pt-BR-FranciscaNeural", "DisplayName": "Francisca",
+                 "Locale": "pt-BR", "Gender": "Female", "VoiceType": "Neural",
+                 "StyleList": ["calm"]}]"#,
+        )
+        .unwrap();
+        let voice = Voice::from(voices.into_iter().next().unwrap());
+        assert_eq!(voice.id, "pt-BR-FranciscaNeural");
+        assert_eq!(voice.name, "Francisca");
+        assert_eq!(voice.language, "pt-BR");
+        assert_eq!(voice.gender, Some(VoiceGender::Female));
+        assert_eq!(voice.quality, Some(VoiceQuality::Enhanced));
+        assert_eq!(voice.platform, "azure");
+    }
+}
@@ -0,0 +1,279 @@
+//! Backend speaking with ElevenLabs through its REST API, playing the audio as
+//! it streams in
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use super::player::{Level, Player};
+use super::TtsBackend;
+use crate::audio_cache::{AudioCache, CacheKey};
+use crate::models::{
+    ElevenLabsConfig, TtsFeatures, ValidatedSpeakRequest, Voice, VoiceGender, VoiceQuality,
+};
+
+/// Name requests select the backend by
+const NAME: &str = "elevenlabs";
+const API: &str = "https://api.elevenlabs.io/v1";
+/// "Rachel", one of the voices every account has
+const DEFAULT_VOICE: &str = "21m00Tcm4TlvDq8ikWAM";
+const DEFAULT_MODEL: &str = "eleven_multilingual_v2";
+/// Raw PCM, so playback can start with the first bytes
+const OUTPUT_FORMAT: &str = "pcm_22050";
+const SAMPLE_RATE: u32 = 22_050;
+
+pub(crate) struct ElevenLabsBackend {
+    config: ElevenLabsConfig,
+    player: Player,
+    cache: Arc<AudioCache>,
+    /// Fetched on first use, the list rarely changes
+    voices: Mutex<Option<Vec<Voice>>>,
+}
+
+#[derive(Deserialize)]
+struct VoicesResponse {
+    #[serde(default)]
+    voices: Vec<ElevenLabsVoice>,
+}
+
+/// A voice in the `voices` response
+#[derive(Deserialize)]
+struct ElevenLabsVoice {
+    voice_id: String,
+    name: String,
+    #[serde(default)]
+    labels: Labels,
+    #[serde(default)]
+    verified_languages: Vec<VerifiedLanguage>,
+}
+
+#[derive(Default, Deserialize)]
+struct Labels {
+    gender: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VerifiedLanguage {
+    language: String,
+    locale: Option<String>,
+}
+
+impl From<ElevenLabsVoice> for Voice {
+    fn from(voice: ElevenLabsVoice) -> Self {
+        // Voices speak many languages, the first verified one is their own
+        let language = voice
+            .verified_languages
+            .into_iter()
+            .next()
+            .map(|verified| verified.locale.unwrap_or(verified.language))
+            .unwrap_or_else(|| "en".to_string());
+        Voice {
+            id: voice.voice_id,
+            name: voice.name,
+            language,
+            gender: match voice.labels.gender.as_deref() {
+                Some("male") => Some(VoiceGender::Male),
+                Some("female") => Some(VoiceGender::Female),
+                _ => None,
+            },
+            quality: Some(VoiceQuality::Premium),
+            requires_network: Some(true),
+            installed: None,
+            engine: Some(NAME.to_string()),
+            platform: NAME.to_string(),
+        }
+    }
+}
+
+impl ElevenLabsBackend {
+    pub fn new(config: ElevenLabsConfig, cache: Arc<AudioCache>) -> Self {
+        Self {
+            config,
+            player: Player::default(),
+            cache,
+            voices: Mutex::new(None),
+        }
+    }
+
+    /// Start synthesizing `text`, with `stream` returning the audio as it is made
+    fn request(
+        &self,
+        request: &ValidatedSpeakRequest,
+        text: &str,
+        stream: bool,
+    ) -> crate::Result<ureq::Body> {
+        let url = format!(
+            "{}/text-to-speech/{}{}?output_format={}",
+            API,
+            self.voice(request),
+            if stream { "/stream" } else { "" },
+            OUTPUT_FORMAT
+        );
+        ureq::post(url)
+            .header("xi-api-key", &self.config.api_key)
+            .send_json(self.body(request, text))
+            .map(|response| response.into_body())
+            .map_err(request_failed)
+    }
+
+    fn voice<'a>(&'a self, request: &'a ValidatedSpeakRequest) -> &'a str {
+        request
+            .voice_id
+            .as_deref()
+            .or(self.config.voice.as_deref())
+            .unwrap_or(DEFAULT_VOICE)
+    }
+
+    fn body(&self, request: &ValidatedSpeakRequest, text: &str) -> serde_json::Value {
+        speech_body(
+            request,
+            text,
+            self.config.model.as_deref().unwrap_or(DEFAULT_MODEL),
+        )
+    }
+
+    /// What the audio of `text` is cached under, streamed or not
+    fn cache_key(&self, request: &ValidatedSpeakRequest, text: &str) -> CacheKey {
+        CacheKey::new(&[
+            NAME,
+            self.voice(request),
+            OUTPUT_FORMAT,
+            &self.body(request, text).to_string(),
+        ])
+    }
+}
+
+impl TtsBackend for ElevenLabsBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        let key = self.cache_key(request, text);
+        if let Some(pcm) = self.cache.get(&key) {
+            return self
+                .player
+                .play_stream(pcm.as_slice(), SAMPLE_RATE, Level::of(request));
+        }
+        let audio = self.request(request, text, true)?;
+        self.player.play_stream(
+            self.cache.recording(key, audio.into_reader()),
+            SAMPLE_RATE,
+            Level::of(request),
+        )
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        let mut cache = self
+            .voices
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        if let Some(ref voices) = *cache {
+            return Ok(voices.clone());
+        }
+        let response: VoicesResponse = ureq::get(format!("{}/voices", API))
+            .header("xi-api-key", &self.config.api_key)
+            .call()
+            .and_then(|response| response.into_body().read_json())
+            .map_err(request_failed)?;
+        let voices: Vec<Voice> = response.voices.into_iter().map(Voice::from).collect();
+        *cache = Some(voices.clone());
+        Ok(voices)
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: false,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let pcm = self
+            .cache
+            .get_or_insert_with(&self.cache_key(request, text), || {
+                self.request(request, text, false)?
+                    .read_to_vec()
+                    .map_err(request_failed)
+            })?;
+        // The API has no volume, which `speak` only applies on playback
+        Ok(crate::wav::pcm16_to_wav(&pcm, SAMPLE_RATE))
+    }
+}
+
+fn request_failed(e: ureq::Error) -> crate::Error {
+    crate::Error::OperationFailed(format!("ElevenLabs request failed: {}", e))
+}
+
+/// Body of a text-to-speech request. Rate becomes the voice's speed, within the
+/// 0.7 to 1.2 the API accepts
+fn speech_body(request: &ValidatedSpeakRequest, text: &str, model: &str) -> serde_json::Value {
+    // No `language_code`: most models reject it, and the voice sets the language
+    json!({
+        "text": text,
+        "model_id": model,
+        "voice_settings": { "speed": request.rate.clamp(0.7, 1.2) },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SpeakRequest;
+
+    #[test]
+    fn test_rate_becomes_clamped_speed() {
+        let request = SpeakRequest {
+            text: "unused".to_string(),
+            rate: Some(2.0),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        let body = speech_body(&request, "Olá", "eleven_turbo_v2_5");
+        assert_eq!(body["text"], "Olá");
+        assert_eq!(body["model_id"], "eleven_turbo_v2_5");
+        assert_eq!(
+            body["voice_settings"]["speed"].as_f64(),
+            Some(1.2f32 as f64)
+        );
+    }
+
+    #[test]
+    fn test_voices_take_their_first_verified_language() {
+        let response: VoicesResponse = serde_json::from_str(
+            r#"{"voices": [
+                {"voice_id": "abc", "name": "Aria", "category": "premade",
+                 "labels": {"gender": "female", "accent": "american"},
+                 "verified_languages": [{"language": "en", "model_id": "eleven_multilingual_v2",
+                                         "locale": "en-US"}]},
+                {"voice_id": "def", "name": "Clone", "labels": {}}
+            ]}"#,
+        )
+        .unwrap();
+        let voices: Vec<Voice> = response.voices.into_iter().map(Voice::from).collect();
+        assert_eq!(voices[0].id, "abc");
+        assert_eq!(voices[0].language, "en-US");
+        assert_eq!(voices[0].gender, Some(VoiceGender::Female));
+        assert_eq!(voices[1].language, "en");
+        assert_eq!(voices[1].gender, None);
+        assert_eq!(voices[1].platform, "elevenlabs");
+    }
+}
@@ -0,0 +1,254 @@
+//! Backend speaking with Google Cloud Text-to-Speech through its REST API
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::player::{Level, Player};
+use super::TtsBackend;
+use crate::audio_cache::{AudioCache, CacheKey};
+use crate::models::{
+    GcpConfig, TtsFeatures, ValidatedSpeakRequest, Voice, VoiceGender, VoiceQuality,
+};
+
+/// Name requests select the backend by
+const NAME: &str = "gcp";
+const DEFAULT_VOICE: &str = "en-US-Neural2-F";
+const API: &str = "https://texttospeech.googleapis.com/v1";
+
+pub(crate) struct GcpBackend {
+    config: GcpConfig,
+    player: Player,
+    cache: Arc<AudioCache>,
+    /// Fetched on first use, the list rarely changes
+    voices: Mutex<Option<Vec<Voice>>>,
+}
+
+#[derive(Deserialize)]
+struct VoicesResponse {
+    #[serde(default)]
+    voices: Vec<GcpVoice>,
+}
+
+/// A voice in the `voices` response
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GcpVoice {
+    name: String,
+    language_codes: Vec<String>,
+    #[serde(default)]
+    ssml_gender: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SynthesizeResponse {
+    audio_content: String,
+}
+
+impl From<GcpVoice> for Voice {
+    fn from(voice: GcpVoice) -> Self {
+        // Names read "<language>-<type>-<variant>", e.g. "en-US-Wavenet-D"
+        let premium = ["-Wavenet-", "-Neural2-", "-Studio-", "-Chirp"]
+            .iter()
+            .any(|kind| voice.name.contains(kind));
+        Voice {
+            language: voice.language_codes.into_iter().next().unwrap_or_default(),
+            gender: match voice.ssml_gender.as_str() {
+                "MALE" => Some(VoiceGender::Male),
+                "FEMALE" => Some(VoiceGender::Female),
+                _ => None,
+            },
+            quality: Some(if premium {
+                VoiceQuality::Enhanced
+            } else {
+                VoiceQuality::Normal
+            }),
+            id: voice.name.clone(),
+            name: voice.name,
+            requires_network: Some(true),
+            installed: None,
+            engine: Some(NAME.to_string()),
+            platform: NAME.to_string(),
+        }
+    }
+}
+
+impl GcpBackend {
+    pub fn new(config: GcpConfig, cache: Arc<AudioCache>) -> Self {
+        Self {
+            config,
+            player: Player::default(),
+            cache,
+            voices: Mutex::new(None),
+        }
+    }
+
+    fn render(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let voice = request
+            .voice_id
+            .as_deref()
+            .or(self.config.voice.as_deref())
+            .unwrap_or(DEFAULT_VOICE);
+        let body = synthesize_body(voice, request, text);
+        let key = CacheKey::new(&[NAME, &body.to_string()]);
+        self.cache.get_or_insert_with(&key, || {
+            let response: SynthesizeResponse = ureq::post(format!("{}/text:synthesize", API))
+                .header("X-Goog-Api-Key", &self.config.api_key)
+                .send_json(&body)
+                .and_then(|response| response.into_body().read_json())
+                .map_err(request_failed)?;
+            // LINEAR16 content comes with a WAV header
+            base64::engine::general_purpose::STANDARD
+                .decode(response.audio_content)
+                .map_err(|e| {
+                    crate::Error::OperationFailed(format!("Invalid Google Cloud audio: {}", e))
+                })
+        })
+    }
+}
+
+impl TtsBackend for GcpBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        // Volume is part of the audio config
+        self.player
+            .play(self.render(request, text)?, Level::synthesized(request))
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        let mut cache = self
+            .voices
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        if let Some(ref voices) = *cache {
+            return Ok(voices.clone());
+        }
+        let response: VoicesResponse = ureq::get(format!("{}/voices", API))
+            .header("X-Goog-Api-Key", &self.config.api_key)
+            .call()
+            .and_then(|response| response.into_body().read_json())
+            .map_err(request_failed)?;
+        let voices: Vec<Voice> = response.voices.into_iter().map(Voice::from).collect();
+        *cache = Some(voices.clone());
+        Ok(voices)
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: true,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        self.render(request, text)
+    }
+}
+
+fn request_failed(e: ureq::Error) -> crate::Error {
+    crate::Error::OperationFailed(format!("Google Cloud request failed: {}", e))
+}
+
+/// `text:synthesize` body speaking `text` with `voice`. Pitch becomes semitones
+/// and volume a gain in decibels, clamped to the ranges the API accepts
+fn synthesize_body(voice: &str, request: &ValidatedSpeakRequest, text: &str) -> serde_json::Value {
+    // The API wants the language alongside the voice that implies it
+    let language = request
+        .language
+        .clone()
+        .unwrap_or_else(|| voice.split('-').take(2).collect::<Vec<_>>().join("-"));
+    let volume_gain_db = if request.volume > 0.0 {
+        (20.0 * request.volume.log10()).clamp(-96.0, 16.0)
+    } else {
+        -96.0
+    };
+    json!({
+        "input": { "text": text },
+        "voice": { "languageCode": language, "name": voice },
+        "audioConfig": {
+            "audioEncoding": "LINEAR16",
+            "speakingRate": request.rate.clamp(0.25, 4.0),
+            "pitch": (12.0 * request.pitch.log2()).clamp(-20.0, 20.0),
+            "volumeGainDb": volume_gain_db,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SpeakRequest;
+
+    fn request(rate: f32, pitch: f32, volume: f32) -> ValidatedSpeakRequest {
+        SpeakRequest {
+            text: "unused".to_string(),
+            rate: Some(rate),
+            pitch: Some(pitch),
+            volume: Some(volume),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prosody_maps_to_audio_config() {
+        let body = synthesize_body("de-DE-Wavenet-B", &request(0.1, 2.0, 0.5), "Hallo");
+        assert_eq!(body["input"]["text"], "Hallo");
+        assert_eq!(body["voice"]["languageCode"], "de-DE");
+        assert_eq!(body["voice"]["name"], "de-DE-Wavenet-B");
+        let audio = &body["audioConfig"];
+        assert_eq!(audio["audioEncoding"], "LINEAR16");
+        // Below the API's slowest rate
+        assert_eq!(audio["speakingRate"], 0.25);
+        assert_eq!(audio["pitch"], 12.0);
+        assert!((audio["volumeGainDb"].as_f64().unwrap() + 6.02).abs() < 0.01);
+
+        let silent = synthesize_body("de-DE-Wavenet-B", &request(1.0, 1.0, 0.0), "Hallo");
+        assert_eq!(silent["audioConfig"]["pitch"], 0.0);
+        assert_eq!(silent["audioConfig"]["volumeGainDb"], -96.0);
+    }
+
+    #[test]
+    fn test_voices_map_premium_types_to_enhanced() {
+        let response: VoicesResponse = serde_json::from_str(
+            r#"{"voices": [
+                {"languageCodes": ["en-US"], "name": "en-US-Neural2-C", "ssmlGender": "FEMALE",
+                 "naturalSampleRateHertz": 24000},
+                {"languageCodes": ["en-US"], "name": "en-US-Standard-B", "ssmlGender": "MALE",
+                 "naturalSampleRateHertz": 24000}
+            ]}"#,
+        )
+        .unwrap();
+        let voices: Vec<Voice> = response.voices.into_iter().map(Voice::from).collect();
+        assert_eq!(voices[0].id, "en-US-Neural2-C");
+        assert_eq!(voices[0].language, "en-US");
+        assert_eq!(voices[0].gender, Some(VoiceGender::Female));
+        assert_eq!(voices[0].quality, Some(VoiceQuality::Enhanced));
+        assert_eq!(voices[1].gender, Some(VoiceGender::Male));
+        assert_eq!(voices[1].quality, Some(VoiceQuality::Normal));
+        assert_eq!(voices[1].platform, "gcp");
+    }
+}
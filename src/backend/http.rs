@@ -0,0 +1,255 @@
+//! Backend POSTing text to a self-hosted speech server and playing the audio it
+//! answers with
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::player::{Level, Player};
+use super::TtsBackend;
+use crate::audio_cache::{AudioCache, CacheKey};
+use crate::models::{HttpBodyFormat, HttpTtsConfig, TtsFeatures, ValidatedSpeakRequest, Voice};
+
+/// Name requests select the backend by
+const NAME: &str = "http";
+const DEFAULT_TEXT_FIELD: &str = "text";
+const DEFAULT_VOICE_FIELD: &str = "voice";
+const DEFAULT_LANGUAGE_FIELD: &str = "language";
+
+pub(crate) struct HttpBackend {
+    config: HttpTtsConfig,
+    player: Player,
+    cache: Arc<AudioCache>,
+}
+
+impl HttpBackend {
+    pub fn new(config: HttpTtsConfig, cache: Arc<AudioCache>) -> Self {
+        Self {
+            config,
+            player: Player::default(),
+            cache,
+        }
+    }
+
+    /// Audio the server answers `text` with, cached by the fields sent
+    fn request(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let fields = fields(&self.config, request, text);
+        let mut key = vec![NAME, self.config.url.as_str()];
+        key.extend(fields.iter().flat_map(|&(name, value)| [name, value]));
+        self.cache
+            .get_or_insert_with(&CacheKey::new(&key), || self.send(fields))
+    }
+
+    fn send(&self, fields: Vec<(&str, &str)>) -> crate::Result<Vec<u8>> {
+        let mut http = ureq::post(&self.config.url);
+        for (name, value) in &self.config.headers {
+            http = http.header(name, value);
+        }
+        if let Some(ref token) = self.config.bearer_token {
+            http = http.header("Authorization", &format!("Bearer {}", token));
+        }
+        let response = match self.config.format {
+            HttpBodyFormat::Json => http.send_json(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value.into()))
+                    .collect::<serde_json::Map<_, _>>(),
+            ),
+            HttpBodyFormat::Form => http.send_form(fields),
+            HttpBodyFormat::Text => {
+                let text_field = self
+                    .config
+                    .text_field
+                    .as_deref()
+                    .unwrap_or(DEFAULT_TEXT_FIELD);
+                for (name, value) in fields.iter().filter(|(name, _)| *name != text_field) {
+                    http = http.query(name, value);
+                }
+                let text = fields
+                    .iter()
+                    .find(|(name, _)| *name == text_field)
+                    .map_or("", |(_, text)| *text);
+                http.header("Content-Type", "text/plain; charset=utf-8")
+                    .send(text)
+            }
+        };
+        response
+            .and_then(|response| response.into_body().read_to_vec())
+            .map_err(|e| {
+                crate::Error::OperationFailed(format!("Speech server request failed: {}", e))
+            })
+    }
+}
+
+impl TtsBackend for HttpBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        let audio = self.request(request, text)?;
+        self.player.play(audio, Level::of(request))
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        Ok(self
+            .config
+            .voices
+            .iter()
+            .cloned()
+            .map(|mut voice| {
+                if voice.platform.is_empty() {
+                    voice.platform = NAME.to_string();
+                }
+                voice.requires_network.get_or_insert(true);
+                voice.engine.get_or_insert_with(|| NAME.to_string());
+                voice
+            })
+            .collect())
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        // Rate and pitch have no common field across servers, add them with `params`
+        TtsFeatures {
+            stop: true,
+            rate: false,
+            pitch: false,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let audio = self.request(request, text)?;
+        if !audio.starts_with(b"RIFF") {
+            return Err(crate::Error::OperationFailed(
+                "The speech server answered with audio that isn't WAV".to_string(),
+            ));
+        }
+        // The server has no volume, which `speak` only applies on playback
+        Ok(audio)
+    }
+}
+
+/// Fields a request sends: the configured `params`, then the text, voice and
+/// language, which replace params of the same name when set
+fn fields<'a>(
+    config: &'a HttpTtsConfig,
+    request: &'a ValidatedSpeakRequest,
+    text: &'a str,
+) -> Vec<(&'a str, &'a str)> {
+    let named = [
+        (
+            config.text_field.as_deref().unwrap_or(DEFAULT_TEXT_FIELD),
+            Some(text),
+        ),
+        (
+            config.voice_field.as_deref().unwrap_or(DEFAULT_VOICE_FIELD),
+            request.voice_id.as_deref(),
+        ),
+        (
+            config
+                .language_field
+                .as_deref()
+                .unwrap_or(DEFAULT_LANGUAGE_FIELD),
+            request.language.as_deref(),
+        ),
+    ];
+    let mut fields: Vec<(&str, &str)> = config
+        .params
+        .iter()
+        .filter(|(name, _)| {
+            !named
+                .iter()
+                .any(|(field, value)| field == name && value.is_some())
+        })
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    fields.extend(
+        named
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value?))),
+    );
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SpeakRequest;
+
+    fn config() -> HttpTtsConfig {
+        HttpTtsConfig {
+            url: "http://localhost:59125/process".to_string(),
+            format: HttpBodyFormat::Form,
+            text_field: Some("INPUT_TEXT".to_string()),
+            voice_field: Some("VOICE".to_string()),
+            language_field: Some("LOCALE".to_string()),
+            params: [
+                ("INPUT_TYPE", "TEXT"),
+                ("OUTPUT_TYPE", "AUDIO"),
+                ("LOCALE", "en_US"),
+            ]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+            headers: Default::default(),
+            bearer_token: None,
+            voices: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_fields_follow_params_and_skip_unnamed_voice() {
+        let config = config();
+        let request = SpeakRequest {
+            text: "unused".to_string(),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(
+            fields(&config, &request, "Hello"),
+            vec![
+                ("INPUT_TYPE", "TEXT"),
+                ("LOCALE", "en_US"),
+                ("OUTPUT_TYPE", "AUDIO"),
+                ("INPUT_TEXT", "Hello"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_request_voice_and_language_replace_params() {
+        let config = config();
+        let request = SpeakRequest {
+            text: "unused".to_string(),
+            voice_id: Some("cmu-slt-hsmm".to_string()),
+            language: Some("de".to_string()),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(
+            fields(&config, &request, "Hallo"),
+            vec![
+                ("INPUT_TYPE", "TEXT"),
+                ("OUTPUT_TYPE", "AUDIO"),
+                ("INPUT_TEXT", "Hallo"),
+                ("VOICE", "cmu-slt-hsmm"),
+                ("LOCALE", "de"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Backend speaking with the OpenAI audio API, playing the audio as it streams in
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::player::{Level, Player};
+use super::TtsBackend;
+use crate::audio_cache::{AudioCache, CacheKey};
+use crate::models::{OpenAiConfig, TtsFeatures, ValidatedSpeakRequest, Voice, VoiceQuality};
+
+/// Name requests select the backend by
+const NAME: &str = "openai";
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "tts-1";
+const DEFAULT_VOICE: &str = "alloy";
+/// The API has no voice list. Each speaks every language the model does
+const VOICES: [&str; 11] = [
+    "alloy", "ash", "ballad", "coral", "echo", "fable", "nova", "onyx", "sage", "shimmer", "verse",
+];
+/// Rate of the raw PCM the `pcm` format returns
+const SAMPLE_RATE: u32 = 24_000;
+
+pub(crate) struct OpenAiBackend {
+    config: OpenAiConfig,
+    player: Player,
+    cache: Arc<AudioCache>,
+}
+
+impl OpenAiBackend {
+    pub fn new(config: OpenAiConfig, cache: Arc<AudioCache>) -> Self {
+        Self {
+            config,
+            player: Player::default(),
+            cache,
+        }
+    }
+
+    fn url(&self) -> String {
+        let base_url = self.config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        format!("{}/audio/speech", base_url.trim_end_matches('/'))
+    }
+
+    fn body(&self, request: &ValidatedSpeakRequest, text: &str) -> serde_json::Value {
+        speech_body(
+            request,
+            text,
+            self.config.model.as_deref().unwrap_or(DEFAULT_MODEL),
+            self.config.voice.as_deref().unwrap_or(DEFAULT_VOICE),
+        )
+    }
+
+    /// Start synthesizing `body`, the response body streaming the audio
+    fn request(&self, body: &serde_json::Value) -> crate::Result<ureq::Body> {
+        ureq::post(self.url())
+            .header("Authorization", &format!("Bearer {}", self.config.api_key))
+            .send_json(body)
+            .map(|response| response.into_body())
+            .map_err(request_failed)
+    }
+}
+
+impl TtsBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        let body = self.body(request, text);
+        let key = CacheKey::new(&[NAME, &self.url(), &body.to_string()]);
+        if let Some(pcm) = self.cache.get(&key) {
+            return self
+                .player
+                .play_stream(pcm.as_slice(), SAMPLE_RATE, Level::of(request));
+        }
+        let audio = self.request(&body)?;
+        self.player.play_stream(
+            self.cache.recording(key, audio.into_reader()),
+            SAMPLE_RATE,
+            Level::of(request),
+        )
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        Ok(VOICES
+            .iter()
+            .map(|voice| Voice {
+                id: voice.to_string(),
+                name: voice.to_string(),
+                language: "en".to_string(),
+                gender: None,
+                quality: Some(VoiceQuality::Enhanced),
+                requires_network: Some(true),
+                installed: None,
+                engine: Some(NAME.to_string()),
+                platform: NAME.to_string(),
+            })
+            .collect())
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: false,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let body = self.body(request, text);
+        let key = CacheKey::new(&[NAME, &self.url(), &body.to_string()]);
+        let pcm = self.cache.get_or_insert_with(&key, || {
+            self.request(&body)?.read_to_vec().map_err(request_failed)
+        })?;
+        // The API has no volume, which `speak` only applies on playback
+        Ok(crate::wav::pcm16_to_wav(&pcm, SAMPLE_RATE))
+    }
+}
+
+fn request_failed(e: ureq::Error) -> crate::Error {
+    crate::Error::OperationFailed(format!("OpenAI request failed: {}", e))
+}
+
+/// Body of an `audio/speech` request. Rate becomes the speed, within the 0.25
+/// to 4.0 the API accepts
+fn speech_body(
+    request: &ValidatedSpeakRequest,
+    text: &str,
+    model: &str,
+    default_voice: &str,
+) -> serde_json::Value {
+    serde_json::json!({
+        "model": model,
+        "input": text,
+        "voice": request.voice_id.as_deref().unwrap_or(default_voice),
+        "response_format": "pcm",
+        "speed": request.rate.clamp(0.25, 4.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SpeakRequest;
+
+    #[test]
+    fn test_request_names_model_voice_and_speed() {
+        let request = SpeakRequest {
+            text: "unused".to_string(),
+            voice_id: Some("nova".to_string()),
+            rate: Some(0.1),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        let body = speech_body(&request, "Hello", "tts-1-hd", "alloy");
+        assert_eq!(body["model"], "tts-1-hd");
+        assert_eq!(body["input"], "Hello");
+        assert_eq!(body["voice"], "nova");
+        assert_eq!(body["response_format"], "pcm");
+        assert_eq!(body["speed"], 0.25);
+
+        let unnamed = SpeakRequest {
+            text: "unused".to_string(),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(
+            speech_body(&unnamed, "Hi", "tts-1", "alloy")["voice"],
+            "alloy"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Backend speaking with Piper neural voices offline, through the `piper`
+//! executable, with the voices kept in the app data directory
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::player::{Level, Player};
+use super::TtsBackend;
+use crate::models::{
+    PiperConfig, PiperVoicesResponse, TtsFeatures, ValidatedSpeakRequest, Voice, VoiceQuality,
+};
+
+/// Name requests select the backend by
+pub(crate) const NAME: &str = "piper";
+/// Directory of the app data directory voices are kept in
+const VOICES_DIR: &str = "piper-voices";
+const DEFAULT_BINARY: &str = "piper";
+const DEFAULT_VOICE: &str = "en_US-lessac-medium";
+/// Where `download` fetches voices from, laid out as
+/// `<family>/<locale>/<name>/<quality>/<id>.onnx`
+const VOICES_URL: &str = "https://huggingface.co/rhasspy/piper-voices/resolve/main";
+
+pub(crate) struct PiperBackend {
+    binary: PathBuf,
+    default_voice: String,
+    /// `None` when the app data directory can't be resolved, which leaves no voices
+    dir: Option<PathBuf>,
+    player: Player,
+}
+
+/// The parts of a voice's `.onnx.json` the backend needs
+#[derive(Deserialize)]
+struct ModelConfig {
+    audio: AudioConfig,
+    #[serde(default)]
+    language: Option<LanguageConfig>,
+}
+
+#[derive(Deserialize)]
+struct AudioConfig {
+    sample_rate: u32,
+    #[serde(default)]
+    quality: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LanguageConfig {
+    code: String,
+}
+
+/// The parts of a voice ID like "en_US-lessac-medium"
+struct VoiceId<'a> {
+    locale: &'a str,
+    name: &'a str,
+    quality: &'a str,
+}
+
+impl<'a> VoiceId<'a> {
+    /// `None` for anything but `<locale>-<name>-<quality>`, which also keeps IDs
+    /// from naming paths outside the voices directory
+    fn parse(id: &'a str) -> Option<Self> {
+        let mut parts = id.split('-');
+        let (locale, name, quality) = (parts.next()?, parts.next()?, parts.next()?);
+        let valid = |part: &str| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        (parts.next().is_none() && valid(locale) && valid(name) && valid(quality)).then_some(Self {
+            locale,
+            name,
+            quality,
+        })
+    }
+
+    /// Path of the voice's files below [`VOICES_URL`], without extension
+    fn url_path(&self, id: &str) -> String {
+        let family = self.locale.split('_').next().unwrap_or(self.locale);
+        format!(
+            "{}/{}/{}/{}/{}",
+            family, self.locale, self.name, self.quality, id
+        )
+    }
+}
+
+impl PiperBackend {
+    pub fn new(config: PiperConfig, data_dir: Option<PathBuf>) -> Self {
+        Self {
+            binary: config
+                .binary
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_BINARY)),
+            default_voice: config.voice.unwrap_or_else(|| DEFAULT_VOICE.to_string()),
+            dir: data_dir.map(|dir| dir.join(VOICES_DIR)),
+            player: Player::default(),
+        }
+    }
+
+    fn dir(&self) -> crate::Result<&Path> {
+        self.dir.as_deref().ok_or_else(|| {
+            crate::Error::OperationFailed(
+                "The app data directory holding Piper voices can't be resolved".to_string(),
+            )
+        })
+    }
+
+    /// Installed voices, with where they are kept
+    pub fn installed(&self) -> crate::Result<PiperVoicesResponse> {
+        Ok(PiperVoicesResponse {
+            directory: self.dir.as_ref().map(|dir| dir.display().to_string()),
+            voices: self.voices()?,
+        })
+    }
+
+    /// Fetch a voice from the Piper voice repository into the voices directory,
+    /// replacing any installed copy
+    pub fn download(&self, id: &str) -> crate::Result<Voice> {
+        let parsed = VoiceId::parse(id).ok_or_else(|| {
+            crate::Error::OperationFailed(format!("Invalid Piper voice ID '{}'", id))
+        })?;
+        let dir = self.dir()?;
+        fs::create_dir_all(dir)?;
+        let url = format!("{}/{}", VOICES_URL, parsed.url_path(id));
+        // The config first, so a voice is only listed once its model is complete
+        for extension in ["onnx.json", "onnx"] {
+            let response = ureq::get(format!("{}.{}", url, extension))
+                .call()
+                .map_err(|e| {
+                    crate::Error::OperationFailed(format!(
+                        "Failed to download Piper voice '{}': {}",
+                        id, e
+                    ))
+                })?;
+            let path = dir.join(format!("{}.{}", id, extension));
+            let partial = dir.join(format!("{}.{}.part", id, extension));
+            let mut file = fs::File::create(&partial)?;
+            std::io::copy(&mut response.into_body().into_reader(), &mut file)?;
+            file.sync_all()?;
+            fs::rename(&partial, &path)?;
+        }
+        self.voice(id)
+    }
+
+    /// The installed voice `id`, from its config
+    fn voice(&self, id: &str) -> crate::Result<Voice> {
+        let config = self.model_config(id)?;
+        Ok(Voice {
+            id: id.to_string(),
+            name: id.to_string(),
+            language: config
+                .language
+                .map(|language| language.code.replace('_', "-"))
+                .unwrap_or_default(),
+            gender: None,
+            quality: Some(match config.audio.quality.as_deref() {
+                Some("x_low") | Some("low") => VoiceQuality::Low,
+                Some("high") => VoiceQuality::Enhanced,
+                _ => VoiceQuality::Normal,
+            }),
+            requires_network: Some(false),
+            installed: Some(true),
+            engine: Some(NAME.to_string()),
+            platform: NAME.to_string(),
+        })
+    }
+
+    fn model_config(&self, id: &str) -> crate::Result<ModelConfig> {
+        if VoiceId::parse(id).is_none() {
+            return Err(crate::Error::OperationFailed(format!(
+                "Invalid Piper voice ID '{}'",
+                id
+            )));
+        }
+        let dir = self.dir()?;
+        if !dir.join(format!("{}.onnx", id)).exists() {
+            return Err(crate::Error::OperationFailed(format!(
+                "Piper voice '{}' isn't installed, download it with downloadPiperVoice()",
+                id
+            )));
+        }
+        let config = fs::read_to_string(dir.join(format!("{}.onnx.json", id)))?;
+        serde_json::from_str(&config).map_err(|e| {
+            crate::Error::OperationFailed(format!("Invalid config of Piper voice '{}': {}", id, e))
+        })
+    }
+
+    /// Start `piper` speaking `text` with the request's voice and rate, writing
+    /// raw 16-bit PCM to its stdout. Returns the process and its sample rate
+    fn spawn(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<(Child, u32)> {
+        let id = request.voice_id.as_deref().unwrap_or(&self.default_voice);
+        let config = self.model_config(id)?;
+        let model = self.dir()?.join(format!("{}.onnx", id));
+        let mut child = Command::new(&self.binary)
+            .arg("--model")
+            .arg(&model)
+            .arg("--output_raw")
+            .arg("--length_scale")
+            .arg(length_scale(request.rate).to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| {
+                crate::Error::OperationFailed(format!(
+                    "Failed to run {}: {}",
+                    self.binary.display(),
+                    e
+                ))
+            })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // Piper speaks a line at a time
+            stdin.write_all(text.replace('\n', " ").as_bytes())?;
+        }
+        Ok((child, config.audio.sample_rate))
+    }
+}
+
+impl TtsBackend for PiperBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        let (mut child, sample_rate) = self.spawn(request, text)?;
+        let played = match child.stdout.take() {
+            Some(pcm) => self
+                .player
+                .play_stream(pcm, sample_rate, Level::of(request)),
+            None => Ok(()),
+        };
+        // Still synthesizing when playback was stopped
+        let _ = child.kill();
+        let _ = child.wait();
+        played
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        let Some(ref dir) = self.dir else {
+            return Ok(Vec::new());
+        };
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut voices: Vec<Voice> = fs::read_dir(dir)?
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                let id = name.strip_suffix(".onnx")?;
+                // Voices whose config is missing or broken can't be spoken
+                self.voice(id).ok()
+            })
+            .collect();
+        voices.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(voices)
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: false,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let (child, sample_rate) = self.spawn(request, text)?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(crate::Error::OperationFailed(format!(
+                "Piper exited with {}",
+                output.status
+            )));
+        }
+        // Piper has no volume, which `speak` only applies on playback
+        Ok(crate::wav::pcm16_to_wav(&output.stdout, sample_rate))
+    }
+}
+
+/// Piper's phoneme length for `rate`: higher is slower
+fn length_scale(rate: f32) -> f32 {
+    1.0 / rate.clamp(0.25, 4.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voice_ids_map_to_repository_paths() {
+        let id = "pt_BR-faber-medium";
+        assert_eq!(
+            VoiceId::parse(id).unwrap().url_path(id),
+            "pt/pt_BR/faber/medium/pt_BR-faber-medium"
+        );
+        assert_eq!(
+            VoiceId::parse("en_US-hfc_female-x_low").unwrap().name,
+            "hfc_female"
+        );
+        assert!(VoiceId::parse("../etc-passwd-x").is_none());
+        assert!(VoiceId::parse("en_US-lessac").is_none());
+        assert!(VoiceId::parse("en_US-lessac-medium-extra").is_none());
+    }
+
+    #[test]
+    fn test_installed_voices_are_read_from_their_config() {
+        let data_dir = std::env::temp_dir().join(format!("piper-test-{}", uuid::Uuid::new_v4()));
+        let backend = PiperBackend::new(PiperConfig::default(), Some(data_dir.clone()));
+        assert!(backend.voices().unwrap().is_empty());
+
+        let dir = data_dir.join(VOICES_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("de_DE-thorsten-high.onnx"), b"").unwrap();
+        fs::write(
+            dir.join("de_DE-thorsten-high.onnx.json"),
+            r#"{"audio": {"sample_rate": 22050, "quality": "high"},
+                "language": {"code": "de_DE"}}"#,
+        )
+        .unwrap();
+        // A model without its config isn't listed
+        fs::write(dir.join("en_GB-alan-low.onnx"), b"").unwrap();
+
+        let voices = backend.voices().unwrap();
+        fs::remove_dir_all(&data_dir).unwrap();
+        assert_eq!(voices.len(), 1);
+        assert_eq!(voices[0].id, "de_DE-thorsten-high");
+        assert_eq!(voices[0].language, "de-DE");
+        assert_eq!(voices[0].quality, Some(VoiceQuality::Enhanced));
+        assert_eq!(voices[0].platform, "piper");
+    }
+
+    #[test]
+    fn test_faster_rates_shorten_phonemes() {
+        assert_eq!(length_scale(2.0), 0.5);
+        assert_eq!(length_scale(0.1), 4.0);
+    }
+}
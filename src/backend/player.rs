@@ -0,0 +1,365 @@
+//! Playback of the audio backends synthesize, on the default output device
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::{Decoder, OutputStreamBuilder, Sink, Source};
+
+use super::SpeechMark;
+use crate::models::ValidatedSpeakRequest;
+
+/// How often playback is checked for marks that are due
+const MARK_POLL: Duration = Duration::from_millis(10);
+/// Bytes of streamed audio read before handing them to the output, about 0.1s
+/// of 22 kHz audio
+const STREAM_CHUNK: usize = 4096;
+/// How often the volume is lowered while fading out
+const FADE_STEP: Duration = Duration::from_millis(20);
+/// How much slower than at its start a clip plays by the end of a fade
+const FADE_SLOWDOWN: f32 = 0.2;
+/// RMS level normalized audio is brought to, about -20 dBFS
+const TARGET_RMS: f64 = 0.1;
+/// Highest sample normalized audio may reach, keeping it from clipping
+const PEAK_CEILING: f32 = 0.98;
+/// Most a quiet voice is amplified, so noise and near silence aren't blown up
+const MAX_GAIN: f32 = 4.0;
+/// Weight of each new clip in a voice's learned gain
+const LEARNING_RATE: f32 = 0.3;
+
+/// Plays one clip at a time, which another thread may cut short
+#[derive(Default)]
+pub(crate) struct Player {
+    sink: Mutex<Option<Arc<Sink>>>,
+    /// Gain that evens out each voice's loudness, learned from the clips it played
+    gains: Mutex<HashMap<String, f32>>,
+}
+
+/// How loud `Player` plays a request's audio
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Level<'a> {
+    /// Volume of the output, 0.0 to 1.0
+    volume: f32,
+    /// Volume the backend already synthesized into the audio, which
+    /// normalization keeps
+    synthesized: f32,
+    /// Voice whose loudness is normalized, `None` to play the audio as it is
+    voice: Option<&'a str>,
+}
+
+impl<'a> Level<'a> {
+    /// Play `request`'s audio at its volume
+    #[cfg_attr(
+        not(any(
+            feature = "elevenlabs",
+            feature = "openai",
+            feature = "piper",
+            feature = "http-tts"
+        )),
+        allow(dead_code)
+    )]
+    pub fn of(request: &'a ValidatedSpeakRequest) -> Self {
+        Self {
+            volume: request.volume,
+            synthesized: 1.0,
+            voice: Self::voice(request),
+        }
+    }
+
+    /// Play `request`'s audio, which the backend synthesized at its volume
+    #[cfg_attr(
+        not(any(feature = "azure", feature = "gcp-tts", feature = "polly")),
+        allow(dead_code)
+    )]
+    pub fn synthesized(request: &'a ValidatedSpeakRequest) -> Self {
+        Self {
+            volume: 1.0,
+            synthesized: request.volume,
+            voice: Self::voice(request),
+        }
+    }
+
+    fn voice(request: &'a ValidatedSpeakRequest) -> Option<&'a str> {
+        request
+            .normalize_loudness
+            .then(|| request.voice_id.as_deref().unwrap_or_default())
+    }
+}
+
+/// Running RMS and peak of the samples played so far
+#[derive(Debug, Default)]
+struct Loudness {
+    sum_squares: f64,
+    count: u64,
+    peak: f32,
+}
+
+impl Loudness {
+    fn add(&mut self, samples: &[f32]) {
+        for sample in samples {
+            self.sum_squares += f64::from(*sample) * f64::from(*sample);
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.count += samples.len() as u64;
+    }
+
+    /// Gain bringing the audio to `TARGET_RMS` scaled by `synthesized`, without
+    /// its peak clipping. `None` for silence
+    fn gain(&self, synthesized: f32) -> Option<f32> {
+        if self.count == 0 || self.peak <= f32::EPSILON {
+            return None;
+        }
+        let rms = (self.sum_squares / self.count as f64).sqrt();
+        let gain = (TARGET_RMS * f64::from(synthesized) / rms) as f32;
+        Some(gain.min(PEAK_CEILING / self.peak).min(MAX_GAIN))
+    }
+}
+
+impl Player {
+    /// Play WAV, MP3 or Ogg Vorbis `audio` at `level`, returning once it has
+    /// played or `stop` was called
+    #[cfg_attr(
+        not(any(
+            feature = "azure",
+            feature = "gcp-tts",
+            feature = "polly",
+            feature = "http-tts"
+        )),
+        allow(dead_code)
+    )]
+    pub fn play(&self, audio: Vec<u8>, level: Level) -> crate::Result<()> {
+        let (source, volume) = self.decode(audio, level)?;
+        self.play_with(volume, |sink| {
+            sink.append(source);
+            sink.sleep_until_end();
+            Ok(())
+        })
+    }
+
+    /// Like `play`, passing each of `marks` to `on_mark` once playback reaches
+    /// its time. Marks must be in time order
+    #[cfg_attr(not(feature = "polly"), allow(dead_code))]
+    pub fn play_marked(
+        &self,
+        audio: Vec<u8>,
+        level: Level,
+        marks: Vec<(Duration, SpeechMark)>,
+        on_mark: &mut dyn FnMut(SpeechMark),
+    ) -> crate::Result<()> {
+        let (source, volume) = self.decode(audio, level)?;
+        let mut marks = marks.into_iter().peekable();
+        self.play_with(volume, |sink| {
+            sink.append(source);
+            while !sink.empty() {
+                let position = sink.get_pos();
+                while let Some((_, mark)) = marks.next_if(|(time, _)| *time <= position) {
+                    on_mark(mark);
+                }
+                std::thread::sleep(MARK_POLL);
+            }
+            Ok(())
+        })
+    }
+
+    /// Play mono 16-bit little-endian PCM at `sample_rate` as it is read from
+    /// `pcm`, so playback starts before the audio has fully arrived. A voice
+    /// whose gain isn't known yet is normalized as its audio arrives
+    #[cfg_attr(
+        not(any(feature = "elevenlabs", feature = "openai", feature = "piper")),
+        allow(dead_code)
+    )]
+    pub fn play_stream(
+        &self,
+        mut pcm: impl Read,
+        sample_rate: u32,
+        level: Level,
+    ) -> crate::Result<()> {
+        let learned = self.learned_gain(level)?;
+        let mut loudness = Loudness::default();
+        self.play_with(level.volume * learned.unwrap_or(1.0), |sink| {
+            let mut chunk = vec![0; STREAM_CHUNK];
+            // A sample split between two reads
+            let mut pending = Vec::new();
+            loop {
+                let read = pcm.read(&mut chunk).map_err(playback_failed)?;
+                if read == 0 {
+                    break;
+                }
+                pending.extend_from_slice(&chunk[..read]);
+                let whole = pending.len() - pending.len() % 2;
+                let samples: Vec<f32> = pending
+                    .drain(..whole)
+                    .collect::<Vec<_>>()
+                    .chunks_exact(2)
+                    .map(|bytes| f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / 32768.0)
+                    .collect();
+                if samples.is_empty() {
+                    continue;
+                }
+                // Appending to a stopped sink would start it again
+                let current = self.sink.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+                if !is_current(&current, sink) {
+                    return Ok(());
+                }
+                if level.voice.is_some() {
+                    loudness.add(&samples);
+                    if let (None, Some(gain)) = (learned, loudness.gain(level.synthesized)) {
+                        sink.set_volume(level.volume * gain);
+                    }
+                }
+                sink.append(SamplesBuffer::new(1, sample_rate, samples));
+            }
+            sink.sleep_until_end();
+            Ok(())
+        })?;
+        self.learn(level, &loudness)
+    }
+
+    /// Decode `audio`, and when `level` normalizes it, learn its voice's gain
+    /// from it. Returns the audio with the volume to play it at
+    fn decode(&self, audio: Vec<u8>, level: Level) -> crate::Result<(Box<dyn Source + Send>, f32)> {
+        let source = Decoder::new(Cursor::new(audio)).map_err(playback_failed)?;
+        if level.voice.is_none() {
+            return Ok((Box::new(source), level.volume));
+        }
+        let (channels, sample_rate) = (source.channels(), source.sample_rate());
+        let samples: Vec<f32> = source.collect();
+        let mut loudness = Loudness::default();
+        loudness.add(&samples);
+        self.learn(level, &loudness)?;
+        let gain = self.learned_gain(level)?.unwrap_or(1.0);
+        Ok((
+            Box::new(SamplesBuffer::new(channels, sample_rate, samples)),
+            level.volume * gain,
+        ))
+    }
+
+    /// Gain learned for `level`'s voice, `None` when not normalized or not
+    /// learned yet
+    fn learned_gain(&self, level: Level) -> crate::Result<Option<f32>> {
+        let Some(voice) = level.voice else {
+            return Ok(None);
+        };
+        let gains = self.gains.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(gains.get(voice).copied())
+    }
+
+    /// Fold the gain `loudness` calls for into what's known of `level`'s voice
+    fn learn(&self, level: Level, loudness: &Loudness) -> crate::Result<()> {
+        let (Some(voice), Some(gain)) = (level.voice, loudness.gain(level.synthesized)) else {
+            return Ok(());
+        };
+        let mut gains = self.gains.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        gains
+            .entry(voice.to_string())
+            .and_modify(|learned| *learned += (gain - *learned) * LEARNING_RATE)
+            .or_insert(gain);
+        Ok(())
+    }
+
+    /// Open the output and let `play` feed it at `volume`, blocking until done
+    fn play_with(
+        &self,
+        volume: f32,
+        play: impl FnOnce(&Arc<Sink>) -> crate::Result<()>,
+    ) -> crate::Result<()> {
+        // The stream is opened per clip since it can't leave the thread it was made on
+        let mut stream = OutputStreamBuilder::open_default_stream().map_err(playback_failed)?;
+        stream.log_on_drop(false);
+        let sink = Arc::new(Sink::connect_new(stream.mixer()));
+        sink.set_volume(volume);
+
+        *self.sink.lock().map_err(|_| crate::Error::MutexPoisoned)? = Some(Arc::clone(&sink));
+        let played = play(&sink);
+        self.sink
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .take();
+        played
+    }
+
+    /// Lower the volume of the clip `play` is playing to silence over `duration`,
+    /// slowing it down a little as it goes, then stop it. Returns early when the
+    /// clip ends or is stopped meanwhile
+    pub fn fade_out(&self, duration: Duration) -> crate::Result<()> {
+        let Some(sink) = self
+            .sink
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clone()
+        else {
+            return Ok(());
+        };
+        let (volume, speed) = (sink.volume(), sink.speed());
+        let steps = (duration.as_millis() / FADE_STEP.as_millis()).max(1) as u32;
+        for step in 1..=steps {
+            std::thread::sleep(duration / steps);
+            let current = self.sink.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+            if !is_current(&current, &sink) || sink.empty() {
+                return Ok(());
+            }
+            let done = step as f32 / steps as f32;
+            sink.set_volume(volume * (1.0 - done));
+            sink.set_speed(speed * (1.0 - FADE_SLOWDOWN * done));
+        }
+        let mut current = self.sink.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        if is_current(&current, &sink) {
+            current.take();
+            sink.stop();
+        }
+        Ok(())
+    }
+
+    /// Stop the clip `play` is playing, if any
+    pub fn stop(&self) -> crate::Result<()> {
+        if let Some(sink) = self
+            .sink
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .take()
+        {
+            sink.stop();
+        }
+        Ok(())
+    }
+}
+
+/// Whether `sink` is the one `current` plays, and wasn't stopped or replaced
+fn is_current(current: &Option<Arc<Sink>>, sink: &Arc<Sink>) -> bool {
+    current
+        .as_ref()
+        .is_some_and(|current| Arc::ptr_eq(current, sink))
+}
+
+fn playback_failed(e: impl std::fmt::Display) -> crate::Error {
+    crate::Error::OperationFailed(format!("Playback failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gain(samples: &[f32], synthesized: f32) -> Option<f32> {
+        let mut loudness = Loudness::default();
+        loudness.add(samples);
+        loudness.gain(synthesized)
+    }
+
+    #[test]
+    fn test_loudness_gain() {
+        assert_eq!(gain(&[], 1.0), None);
+        assert_eq!(gain(&[0.0; 4], 1.0), None);
+        // A square wave's RMS is its amplitude
+        assert!((gain(&[0.05, -0.05], 1.0).unwrap() - 2.0).abs() < 1e-4);
+        assert!((gain(&[0.2, -0.2], 1.0).unwrap() - 0.5).abs() < 1e-4);
+        // Audio synthesized at half volume stays at half the target
+        assert!((gain(&[0.025, -0.025], 0.5).unwrap() - 2.0).abs() < 1e-4);
+        // Capped so near silence isn't blown up, nor peaks clipped
+        assert_eq!(gain(&[0.001, -0.001], 1.0), Some(MAX_GAIN));
+        let mut spike = vec![0.0; 100];
+        spike[0] = 0.5;
+        assert!((gain(&spike, 1.0).unwrap() - 1.96).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,419 @@
+//! Backend speaking with Amazon Polly through its REST API, reporting Polly's
+//! speech marks as word and viseme marks
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use super::player::{Level, Player};
+use super::sigv4::{uri_encode, SignedRequest, Signer};
+use super::{SpeechMark, TtsBackend};
+use crate::audio_cache::{AudioCache, CacheKey};
+use crate::models::{
+    PollyConfig, TtsFeatures, ValidatedSpeakRequest, Voice, VoiceGender, VoiceQuality,
+};
+
+/// Name requests select the backend by
+const NAME: &str = "polly";
+const DEFAULT_VOICE: &str = "Joanna";
+const DEFAULT_ENGINE: &str = "neural";
+/// Rate of the PCM Polly returns, one every engine offers
+const SAMPLE_RATE: u32 = 16_000;
+
+pub(crate) struct PollyBackend {
+    config: PollyConfig,
+    player: Player,
+    cache: Arc<AudioCache>,
+    /// Fetched on first use, the list rarely changes
+    voices: Mutex<Option<Vec<Voice>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VoicesResponse {
+    #[serde(default)]
+    voices: Vec<PollyVoice>,
+    next_token: Option<String>,
+}
+
+/// A voice in the `DescribeVoices` response
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PollyVoice {
+    id: String,
+    name: String,
+    language_code: String,
+    #[serde(default)]
+    gender: String,
+}
+
+/// A line of the speech marks `SynthesizeSpeech` returns for the `json` format
+#[derive(Deserialize)]
+struct PollyMark {
+    /// Milliseconds into the audio
+    time: u64,
+    #[serde(rename = "type")]
+    kind: String,
+    /// Byte offsets into the SSML, for words
+    start: Option<usize>,
+    end: Option<usize>,
+    value: String,
+}
+
+/// SSML sent to Polly, with where each byte of the text landed in it so speech
+/// marks can be mapped back
+struct Markup {
+    ssml: String,
+    /// Offset in `ssml` of each character of the text, by its offset in the text,
+    /// ending with the offsets just past both
+    positions: Vec<(usize, usize)>,
+}
+
+impl Markup {
+    /// Byte offset into the text of `ssml_offset`, when it starts a character
+    fn text_offset(&self, ssml_offset: usize) -> Option<usize> {
+        self.positions
+            .binary_search_by_key(&ssml_offset, |&(ssml, _)| ssml)
+            .ok()
+            .map(|index| self.positions[index].1)
+    }
+
+    /// A speech mark as a mark of the text, `None` for types other than words
+    /// and visemes or words outside the text
+    fn mark(&self, mark: PollyMark) -> Option<(Duration, SpeechMark)> {
+        let time = Duration::from_millis(mark.time);
+        match mark.kind.as_str() {
+            "word" => {
+                let start = self.text_offset(mark.start?)?;
+                let end = self.text_offset(mark.end?)?;
+                Some((time, SpeechMark::Word(start..end)))
+            }
+            "viseme" => Some((time, SpeechMark::Viseme(mark.value))),
+            _ => None,
+        }
+    }
+}
+
+impl From<PollyVoice> for Voice {
+    fn from(voice: PollyVoice) -> Self {
+        Voice {
+            id: voice.id,
+            name: voice.name,
+            language: voice.language_code,
+            gender: match voice.gender.as_str() {
+                "Male" => Some(VoiceGender::Male),
+                "Female" => Some(VoiceGender::Female),
+                _ => None,
+            },
+            quality: None,
+            requires_network: Some(true),
+            installed: None,
+            engine: Some(NAME.to_string()),
+            platform: NAME.to_string(),
+        }
+    }
+}
+
+impl PollyBackend {
+    pub fn new(config: PollyConfig, cache: Arc<AudioCache>) -> Self {
+        Self {
+            config,
+            player: Player::default(),
+            cache,
+            voices: Mutex::new(None),
+        }
+    }
+
+    fn engine(&self) -> &str {
+        self.config.engine.as_deref().unwrap_or(DEFAULT_ENGINE)
+    }
+
+    fn host(&self) -> String {
+        format!("polly.{}.amazonaws.com", self.config.region)
+    }
+
+    /// Send a request signed with the configured credentials
+    fn send(&self, method: &str, path: &str, query: &str, body: &[u8]) -> crate::Result<Vec<u8>> {
+        let host = self.host();
+        let content_type = [("Content-Type", "application/json")];
+        let request = SignedRequest {
+            method,
+            host: &host,
+            path,
+            query,
+            headers: if body.is_empty() { &[] } else { &content_type },
+            payload: body,
+        };
+        let signer = Signer {
+            access_key_id: &self.config.access_key_id,
+            secret_access_key: &self.config.secret_access_key,
+            session_token: self.config.session_token.as_deref(),
+            region: &self.config.region,
+            service: "polly",
+        };
+        let mut url = format!("https://{}{}", host, path);
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+        let mut builder = ureq::http::Request::builder().method(method).uri(url);
+        for (name, value) in request.headers {
+            builder = builder.header(*name, *value);
+        }
+        for (name, value) in signer.sign(&request, SystemTime::now()) {
+            builder = builder.header(name, value);
+        }
+        let request = builder.body(body.to_vec()).map_err(|e| {
+            crate::Error::OperationFailed(format!("Invalid Amazon Polly request: {}", e))
+        })?;
+        ureq::run(request)
+            .and_then(|response| response.into_body().read_to_vec())
+            .map_err(|e| {
+                crate::Error::OperationFailed(format!("Amazon Polly request failed: {}", e))
+            })
+    }
+
+    /// Audio or speech marks for `text`, depending on `output`. Both are cached,
+    /// marks being as costly as audio
+    fn synthesize_speech(
+        &self,
+        markup: &Markup,
+        voice: &str,
+        output: serde_json::Value,
+    ) -> crate::Result<Vec<u8>> {
+        let mut body = json!({
+            "Engine": self.engine(),
+            "Text": markup.ssml,
+            "TextType": "ssml",
+            "VoiceId": voice,
+        });
+        if let (Some(body), Some(output)) = (body.as_object_mut(), output.as_object()) {
+            body.extend(output.clone());
+        }
+        let body = body.to_string();
+        let key = CacheKey::new(&[NAME, &self.config.region, &body]);
+        self.cache.get_or_insert_with(&key, || {
+            self.send("POST", "/v1/speech", "", body.as_bytes())
+        })
+    }
+
+    fn voice<'a>(&'a self, request: &'a ValidatedSpeakRequest) -> &'a str {
+        request
+            .voice_id
+            .as_deref()
+            .or(self.config.voice.as_deref())
+            .unwrap_or(DEFAULT_VOICE)
+    }
+
+    fn render(&self, markup: &Markup, voice: &str) -> crate::Result<Vec<u8>> {
+        let pcm = self.synthesize_speech(
+            markup,
+            voice,
+            json!({ "OutputFormat": "pcm", "SampleRate": SAMPLE_RATE.to_string() }),
+        )?;
+        Ok(crate::wav::pcm16_to_wav(&pcm, SAMPLE_RATE))
+    }
+}
+
+impl TtsBackend for PollyBackend {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        // Volume is part of the markup
+        self.player
+            .play(self.synthesize(request, text)?, Level::synthesized(request))
+    }
+
+    fn speak_marked(
+        &self,
+        request: &ValidatedSpeakRequest,
+        text: &str,
+        on_mark: &mut dyn FnMut(SpeechMark),
+    ) -> crate::Result<()> {
+        let markup = markup(request, text, self.engine());
+        let voice = self.voice(request);
+        let marks = self.synthesize_speech(
+            &markup,
+            voice,
+            json!({ "OutputFormat": "json", "SpeechMarkTypes": ["word", "viseme"] }),
+        )?;
+        let marks = String::from_utf8_lossy(&marks)
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter_map(|mark| markup.mark(mark))
+            .collect();
+        self.player.play_marked(
+            self.render(&markup, voice)?,
+            Level::synthesized(request),
+            marks,
+            on_mark,
+        )
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        self.player.stop()
+    }
+
+    fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+        self.player.fade_out(duration)?;
+        Ok(true)
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        let mut cache = self
+            .voices
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        if let Some(ref voices) = *cache {
+            return Ok(voices.clone());
+        }
+        let quality = if self.engine() == "standard" {
+            VoiceQuality::Normal
+        } else {
+            VoiceQuality::Enhanced
+        };
+        let mut voices = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let mut query = format!("Engine={}", uri_encode(self.engine()));
+            if let Some(ref token) = next_token {
+                query.push_str(&format!("&NextToken={}", uri_encode(token)));
+            }
+            let page = self.send("GET", "/v1/voices", &query, b"")?;
+            let page: VoicesResponse = serde_json::from_slice(&page).map_err(|e| {
+                crate::Error::OperationFailed(format!("Invalid Amazon Polly voice list: {}", e))
+            })?;
+            voices.extend(page.voices.into_iter().map(|voice| Voice {
+                quality: Some(quality),
+                ..Voice::from(voice)
+            }));
+            next_token = page.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+        *cache = Some(voices.clone());
+        Ok(voices)
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        TtsFeatures {
+            stop: true,
+            rate: true,
+            // Only standard voices take a pitch
+            pitch: self.engine() == "standard",
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: false,
+            pause_resume: true,
+        }
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        self.render(&markup(request, text, self.engine()), self.voice(request))
+    }
+}
+
+/// SSML speaking `text` with the request's prosody, within the ranges Polly
+/// accepts. Pitch is left out for engines other than "standard", which reject it
+fn markup(request: &ValidatedSpeakRequest, text: &str, engine: &str) -> Markup {
+    let volume = if request.volume > 0.0 {
+        format!("{:+.1}dB", (20.0 * request.volume.log10()).max(-40.0))
+    } else {
+        "silent".to_string()
+    };
+    let mut ssml = format!(
+        "<speak><prosody rate=\"{:.0}%\" volume=\"{}\"",
+        (request.rate * 100.0).clamp(20.0, 200.0),
+        volume
+    );
+    if engine == "standard" {
+        ssml.push_str(&format!(
+            " pitch=\"{:+.0}%\"",
+            ((request.pitch - 1.0) * 100.0).clamp(-33.3, 50.0)
+        ));
+    }
+    ssml.push('>');
+
+    let mut positions = Vec::with_capacity(text.len() + 1);
+    for (offset, c) in text.char_indices() {
+        positions.push((ssml.len(), offset));
+        crate::ssml::push_escaped(&mut ssml, c.encode_utf8(&mut [0; 4]));
+    }
+    positions.push((ssml.len(), text.len()));
+    ssml.push_str("</prosody></speak>");
+    Markup { ssml, positions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SpeakRequest;
+
+    fn request(rate: f32, pitch: f32, volume: f32) -> ValidatedSpeakRequest {
+        SpeakRequest {
+            text: "unused".to_string(),
+            rate: Some(rate),
+            pitch: Some(pitch),
+            volume: Some(volume),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prosody_fits_polly_ranges() {
+        let neural = markup(&request(0.1, 1.5, 0.5), "Hi", "neural");
+        assert_eq!(
+            neural.ssml,
+            "<speak><prosody rate=\"20%\" volume=\"-6.0dB\">Hi</prosody></speak>"
+        );
+        let standard = markup(&request(1.0, 1.5, 0.0), "Hi", "standard");
+        assert_eq!(
+            standard.ssml,
+            "<speak><prosody rate=\"100%\" volume=\"silent\" pitch=\"+50%\">Hi</prosody></speak>"
+        );
+    }
+
+    #[test]
+    fn test_word_marks_point_into_the_text() {
+        let text = "Tom & Jerry";
+        let markup = markup(&request(1.0, 1.0, 1.0), text, "neural");
+        let start = markup.ssml.find("Jerry").unwrap();
+        let marks: Vec<_> = [
+            format!(
+                r#"{{"time":0,"type":"word","start":{},"end":{},"value":"Tom"}}"#,
+                start - 10,
+                start - 7
+            ),
+            r#"{"time":5,"type":"viseme","value":"t"}"#.to_string(),
+            format!(
+                r#"{{"time":420,"type":"word","start":{},"end":{},"value":"Jerry"}}"#,
+                start,
+                start + 5
+            ),
+            r#"{"time":0,"type":"sentence","start":0,"end":99,"value":"..."}"#.to_string(),
+        ]
+        .iter()
+        .filter_map(|line| markup.mark(serde_json::from_str(line).unwrap()))
+        .collect();
+
+        assert_eq!(
+            marks,
+            [
+                (Duration::ZERO, SpeechMark::Word(0..3)),
+                (
+                    Duration::from_millis(5),
+                    SpeechMark::Viseme("t".to_string())
+                ),
+                (Duration::from_millis(420), SpeechMark::Word(6..11)),
+            ]
+        );
+        assert_eq!(&text[6..11], "Jerry");
+    }
+}
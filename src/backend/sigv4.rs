@@ -0,0 +1,229 @@
+//! AWS Signature Version 4, which the Polly API authenticates requests with
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+/// What a request is signed with
+pub(crate) struct Signer<'a> {
+    pub access_key_id: &'a str,
+    pub secret_access_key: &'a str,
+    pub session_token: Option<&'a str>,
+    pub region: &'a str,
+    pub service: &'a str,
+}
+
+/// A request to sign. `query` must already be in canonical form: URI-encoded
+/// parameters sorted by name
+pub(crate) struct SignedRequest<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    pub path: &'a str,
+    pub query: &'a str,
+    /// Headers besides `host` to sign, sent as given
+    pub headers: &'a [(&'a str, &'a str)],
+    pub payload: &'a [u8],
+}
+
+impl Signer<'_> {
+    /// Headers to send along with those of `request` to authenticate it at `now`
+    pub fn sign(&self, request: &SignedRequest, now: SystemTime) -> Vec<(String, String)> {
+        let (timestamp, date) = amz_date(now);
+        let mut added = vec![("x-amz-date".to_string(), timestamp.clone())];
+        if let Some(token) = self.session_token {
+            added.push(("x-amz-security-token".to_string(), token.to_string()));
+        }
+
+        let mut headers: Vec<(String, &str)> = request
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.trim()))
+            .chain([("host".to_string(), request.host)])
+            .chain(
+                added
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.as_str())),
+            )
+            .collect();
+        headers.sort();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method,
+            request.path,
+            request.query,
+            canonical_headers,
+            signed_headers,
+            hex(&Sha256::digest(request.payload)),
+        );
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes())),
+        );
+        let signature = hex(&hmac(&self.signing_key(&date), string_to_sign.as_bytes()));
+
+        added.push((
+            "Authorization".to_string(),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key_id, scope, signed_headers, signature
+            ),
+        ));
+        added
+    }
+
+    fn signing_key(&self, date: &str) -> [u8; 32] {
+        let secret = format!("AWS4{}", self.secret_access_key);
+        let key = hmac(secret.as_bytes(), date.as_bytes());
+        let key = hmac(&key, self.region.as_bytes());
+        let key = hmac(&key, self.service.as_bytes());
+        hmac(&key, b"aws4_request")
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// `now` in UTC as the `x-amz-date` timestamp ("20150830T123600Z") and the date
+/// of the credential scope ("20150830")
+fn amz_date(now: SystemTime) -> (String, String) {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default();
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+    // Civil date of a day count (Howard Hinnant's `civil_from_days`)
+    let shifted = days as i64 + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{:04}{:02}{:02}", year, month, day);
+    let timestamp = format!(
+        "{}T{:02}{:02}{:02}Z",
+        date,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    );
+    (timestamp, date)
+}
+
+/// Percent-encode a query parameter value the way SigV4 expects
+pub(crate) fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_hmac_matches_rfc_4231() {
+        assert_eq!(
+            hex(&hmac(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_dates_are_utc() {
+        let at = |seconds| amz_date(UNIX_EPOCH + Duration::from_secs(seconds));
+        assert_eq!(at(1_440_938_160).0, "20150830T123600Z");
+        assert_eq!(at(951_782_400).1, "20000229");
+        assert_eq!(at(0).0, "19700101T000000Z");
+    }
+
+    #[test]
+    fn test_signature_matches_aws_example() {
+        // The example request of the AWS Signature Version 4 documentation
+        let signer = Signer {
+            access_key_id: "AKIDEXAMPLE",
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            session_token: None,
+            region: "us-east-1",
+            service: "iam",
+        };
+        let headers = signer.sign(
+            &SignedRequest {
+                method: "GET",
+                host: "iam.amazonaws.com",
+                path: "/",
+                query: "Action=ListUsers&Version=2010-05-08",
+                headers: &[(
+                    "Content-Type",
+                    "application/x-www-form-urlencoded; charset=utf-8",
+                )],
+                payload: b"",
+            },
+            UNIX_EPOCH + Duration::from_secs(1_440_938_160),
+        );
+        assert_eq!(
+            headers,
+            [
+                ("x-amz-date".to_string(), "20150830T123600Z".to_string()),
+                (
+                    "Authorization".to_string(),
+                    "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+                     SignedHeaders=content-type;host;x-amz-date, \
+                     Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_values_are_percent_encoded() {
+        assert_eq!(uri_encode("a b/c~d"), "a%20b%2Fc~d");
+    }
+}
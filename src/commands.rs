@@ -1,3 +1,4 @@
+use tauri::ipc::{Channel, InvokeResponseBody};
 use tauri::{command, AppHandle, Runtime};
 
 use crate::models::*;
@@ -13,12 +14,78 @@ pub(crate) async fn speak<R: Runtime>(
     app.tts().speak(payload)
 }
 
+/// Speak the given text and resolve once it has finished, been stopped or failed
+#[command]
+pub(crate) async fn speak_and_wait<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakRequest,
+) -> Result<SpeakAndWaitResponse> {
+    // Blocks until speech ends, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || app.tts().speak_and_wait(payload))
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
 /// Stop any ongoing speech
 #[command]
 pub(crate) async fn stop<R: Runtime>(app: AppHandle<R>) -> Result<StopResponse> {
     app.tts().stop()
 }
 
+/// Stop speaking only if the current utterance matches a regular expression
+#[command]
+pub(crate) async fn stop_if_matches<R: Runtime>(
+    app: AppHandle<R>,
+    payload: StopIfMatchesRequest,
+) -> Result<StopIfMatchesResponse> {
+    app.tts().stop_if_matches(payload)
+}
+
+/// Stop speech gradually, fading out over the given duration where the backend allows it
+#[command]
+pub(crate) async fn stop_gentle<R: Runtime>(
+    app: AppHandle<R>,
+    payload: StopGentleRequest,
+) -> Result<StopGentleResponse> {
+    // Blocks while speech fades out, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || app.tts().stop_gentle(payload))
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
+/// Count the utterances waiting behind the one being spoken
+#[command]
+pub(crate) async fn get_queue_length<R: Runtime>(app: AppHandle<R>) -> Result<QueueLengthResponse> {
+    app.tts().get_queue_length()
+}
+
+/// List the utterances waiting behind the one being spoken
+#[command]
+pub(crate) async fn get_queue<R: Runtime>(app: AppHandle<R>) -> Result<GetQueueResponse> {
+    app.tts().get_queue()
+}
+
+/// Drop pending utterances while letting the current one finish
+#[command]
+pub(crate) async fn clear_queue<R: Runtime>(app: AppHandle<R>) -> Result<ClearQueueResponse> {
+    app.tts().clear_queue()
+}
+
+/// Cut the current utterance short and go on with the next queued one
+#[command]
+pub(crate) async fn skip_to_next<R: Runtime>(app: AppHandle<R>) -> Result<SkipToNextResponse> {
+    app.tts().skip_to_next()
+}
+
+/// Drop one pending utterance by id while the rest of the queue plays on
+#[command]
+pub(crate) async fn remove_from_queue<R: Runtime>(
+    app: AppHandle<R>,
+    payload: RemoveFromQueueRequest,
+) -> Result<RemoveFromQueueResponse> {
+    app.tts().remove_from_queue(payload)
+}
+
 /// Get available voices, optionally filtered by language
 #[command]
 pub(crate) async fn get_voices<R: Runtime>(
@@ -28,18 +95,208 @@ pub(crate) async fn get_voices<R: Runtime>(
     app.tts().get_voices(payload)
 }
 
+/// Enumerate the engine's voices again instead of serving the cached list
+#[command]
+pub(crate) async fn refresh_voices<R: Runtime>(app: AppHandle<R>) -> Result<GetVoicesResponse> {
+    app.tts().refresh_voices()
+}
+
+/// Get the languages of the available voices, with how many voices speak each
+#[command]
+pub(crate) async fn get_languages<R: Runtime>(app: AppHandle<R>) -> Result<GetLanguagesResponse> {
+    app.tts().get_languages()
+}
+
 /// Check if TTS is currently speaking
 #[command]
 pub(crate) async fn is_speaking<R: Runtime>(app: AppHandle<R>) -> Result<IsSpeakingResponse> {
     app.tts().is_speaking()
 }
 
+/// Check whether speech is paused
+#[command]
+pub(crate) async fn is_paused<R: Runtime>(app: AppHandle<R>) -> Result<IsPausedResponse> {
+    app.tts().is_paused()
+}
+
 /// Check if TTS engine is initialized and ready
 #[command]
 pub(crate) async fn is_initialized<R: Runtime>(app: AppHandle<R>) -> Result<IsInitializedResponse> {
     app.tts().is_initialized()
 }
 
+/// Retry creating the TTS engine after it failed to initialize
+#[command]
+pub(crate) async fn reinitialize<R: Runtime>(app: AppHandle<R>) -> Result<IsInitializedResponse> {
+    // Creating the engine can take a while, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || app.tts().reinitialize())
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
+/// Get platform capabilities relevant to the frontend
+#[command]
+pub(crate) async fn get_capabilities<R: Runtime>(app: AppHandle<R>) -> Result<Capabilities> {
+    app.tts().get_capabilities()
+}
+
+/// Get the speech rate range in words per minute
+#[command]
+pub(crate) async fn get_rate_info<R: Runtime>(app: AppHandle<R>) -> Result<RateInfoResponse> {
+    app.tts().get_rate_info()
+}
+
+/// Get which speech features the current engine supports
+#[command]
+pub(crate) async fn get_features<R: Runtime>(app: AppHandle<R>) -> Result<TtsFeatures> {
+    app.tts().get_features()
+}
+
+/// List the backends registered besides the OS engine, with what each supports
+#[command]
+pub(crate) async fn get_backends<R: Runtime>(app: AppHandle<R>) -> Result<GetBackendsResponse> {
+    app.tts().get_backends()
+}
+
+/// Get how much synthesized audio is cached and how often it was reused
+#[command]
+pub(crate) async fn get_cache_stats<R: Runtime>(app: AppHandle<R>) -> Result<CacheStatsResponse> {
+    app.tts().get_cache_stats()
+}
+
+/// Delete all cached synthesized audio
+#[command]
+pub(crate) async fn clear_tts_cache<R: Runtime>(app: AppHandle<R>) -> Result<ClearCacheResponse> {
+    app.tts().clear_tts_cache()
+}
+
+/// List the voices installed for the Piper backend
+#[command]
+pub(crate) async fn list_piper_voices<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<PiperVoicesResponse> {
+    app.tts().list_piper_voices()
+}
+
+/// Download a voice from the Piper voice repository for the Piper backend
+#[command]
+pub(crate) async fn download_piper_voice<R: Runtime>(
+    app: AppHandle<R>,
+    payload: DownloadPiperVoiceRequest,
+) -> Result<Voice> {
+    // Blocks until the download completes, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || app.tts().download_piper_voice(payload))
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
+/// Read back the parameters the live engine currently has set
+#[command]
+pub(crate) async fn get_engine_params<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<EngineParamsResponse> {
+    app.tts().get_engine_params()
+}
+
+/// Get the voice the engine currently speaks with, `None` if it has none set
+#[command]
+pub(crate) async fn get_current_voice<R: Runtime>(app: AppHandle<R>) -> Result<Option<Voice>> {
+    app.tts().get_current_voice()
+}
+
+/// Find the best voice for a language, `None` if no voice speaks it
+#[command]
+pub(crate) async fn get_best_voice<R: Runtime>(
+    app: AppHandle<R>,
+    payload: GetBestVoiceRequest,
+) -> Result<Option<Voice>> {
+    app.tts().get_best_voice(payload)
+}
+
+/// Get the system's default voice and speech language
+#[command]
+pub(crate) async fn get_default_voice<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<DefaultVoiceResponse> {
+    app.tts().get_default_voice()
+}
+
+/// Find the current voice that best matches one saved earlier, `None` if no voice fits
+#[command]
+pub(crate) async fn resolve_voice<R: Runtime>(
+    app: AppHandle<R>,
+    payload: ResolveVoiceRequest,
+) -> Result<Option<Voice>> {
+    app.tts().resolve_voice(payload)
+}
+
+/// Synthesize speech into an audio file instead of playing it
+#[command]
+pub(crate) async fn speak_to_file<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakToFileRequest,
+) -> Result<SpeakToFileResponse> {
+    // Rendering waits for the engine, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || app.tts().speak_to_file(payload))
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
+/// Synthesize speech into WAV bytes, handed to the webview as an `ArrayBuffer`
+#[command]
+pub(crate) async fn synthesize<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakRequest,
+) -> Result<tauri::ipc::Response> {
+    // Rendering waits for the engine, so keep it off the async runtime
+    let wav = tauri::async_runtime::spawn_blocking(move || app.tts().synthesize(payload))
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))??;
+    Ok(tauri::ipc::Response::new(wav))
+}
+
+/// Synthesize speech a sentence at a time, sending each sentence's WAV bytes
+/// over `on_chunk` as soon as it is ready
+#[command]
+pub(crate) async fn synthesize_stream<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakRequest,
+    on_chunk: Channel,
+) -> Result<SynthesizeStreamResponse> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.tts().synthesize_stream(payload, |wav| {
+            on_chunk
+                .send(InvokeResponseBody::Raw(wav))
+                .map_err(|e| crate::Error::OperationFailed(e.to_string()))
+        })
+    })
+    .await
+    .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
+/// Get where audio output is currently routed (headset, Bluetooth, speaker...)
+#[command]
+pub(crate) async fn get_audio_route<R: Runtime>(app: AppHandle<R>) -> Result<AudioRouteResponse> {
+    app.tts().get_audio_route()
+}
+
+/// Hold audio focus (Android) or keep the audio session active (iOS) between
+/// utterances. Desktop has no audio focus and always grants it
+#[command]
+pub(crate) async fn request_audio_focus<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<AudioFocusResponse> {
+    app.tts().request_audio_focus()
+}
+
+/// Give up audio focus held by `request_audio_focus`, letting other apps resume
+#[command]
+pub(crate) async fn abandon_audio_focus<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<AudioFocusResponse> {
+    app.tts().abandon_audio_focus()
+}
+
 /// Pause the current speech (mobile only, desktop will return error)
 #[command]
 pub(crate) async fn pause_speaking<R: Runtime>(app: AppHandle<R>) -> Result<PauseResumeResponse> {
@@ -52,6 +309,250 @@ pub(crate) async fn resume_speaking<R: Runtime>(app: AppHandle<R>) -> Result<Pau
     app.tts().resume_speaking()
 }
 
+/// Count the words and characters that would be spoken, without speaking
+#[command]
+pub(crate) async fn count_words<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakRequest,
+) -> Result<WordCountResponse> {
+    app.tts().count_words(payload)
+}
+
+/// Export the active voice settings as a shareable profile
+#[command]
+pub(crate) async fn export_profile<R: Runtime>(app: AppHandle<R>) -> Result<VoiceProfile> {
+    app.tts().export_profile()
+}
+
+/// Apply a voice profile, falling back with warnings for unavailable settings
+#[command]
+pub(crate) async fn import_profile<R: Runtime>(
+    app: AppHandle<R>,
+    payload: VoiceProfile,
+) -> Result<ImportProfileResponse> {
+    app.tts().import_profile(payload)
+}
+
+/// Set the voice `speak()` uses when a request sets neither `voice_id` nor `language`
+#[command]
+pub(crate) async fn set_voice<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetVoiceRequest,
+) -> Result<SetVoiceResponse> {
+    app.tts().set_voice(payload)
+}
+
+/// Save the voice `speak()` uses across launches, for all requests or one language
+#[command]
+pub(crate) async fn set_default_voice<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetDefaultVoiceRequest,
+) -> Result<SetDefaultVoiceResponse> {
+    app.tts().set_default_voice(payload)
+}
+
+/// Set the rate `speak()` uses when a request omits it
+#[command]
+pub(crate) async fn set_rate<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetProsodyRequest,
+) -> Result<ProsodyResponse> {
+    app.tts().set_rate(payload)
+}
+
+/// Set the pitch `speak()` uses when a request omits it
+#[command]
+pub(crate) async fn set_pitch<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetProsodyRequest,
+) -> Result<ProsodyResponse> {
+    app.tts().set_pitch(payload)
+}
+
+/// Set the volume `speak()` uses when a request omits it
+#[command]
+pub(crate) async fn set_volume<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetProsodyRequest,
+) -> Result<ProsodyResponse> {
+    app.tts().set_volume(payload)
+}
+
+/// Speak the same phrase at a sequence of rates so the user can pick one
+#[command]
+pub(crate) async fn calibration_samples<R: Runtime>(
+    app: AppHandle<R>,
+    payload: CalibrationRequest,
+) -> Result<CalibrationResponse> {
+    app.tts().calibration_samples(payload)
+}
+
+/// Speak a standard phrase with a voice and measure how long it takes
+#[command]
+pub(crate) async fn benchmark_voice<R: Runtime>(
+    app: AppHandle<R>,
+    payload: BenchmarkVoiceRequest,
+) -> Result<BenchmarkVoiceResponse> {
+    // Blocks until speech ends, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || app.tts().benchmark_voice(payload))
+        .await
+        .map_err(|e| crate::Error::OperationFailed(e.to_string()))?
+}
+
+/// Reset the characters counted against `session_char_limit`
+#[command]
+pub(crate) async fn reset_session_usage<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<SessionUsageResponse> {
+    app.tts().reset_session_usage()
+}
+
+/// Queue several utterances back to back, applying `sequence_overlap` to earlier sequences
+#[command]
+pub(crate) async fn speak_sequence<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakSequenceRequest,
+) -> Result<SpeakSequenceResponse> {
+    app.tts().speak_sequence(payload)
+}
+
+/// Queue segments that may each use a different voice or language, flushing only once
+#[command]
+pub(crate) async fn speak_segments<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakSegmentsRequest,
+) -> Result<SpeakSegmentsResponse> {
+    app.tts().speak_segments(payload)
+}
+
+/// Speak a document starting part-way through, at a grapheme offset
+#[command]
+pub(crate) async fn speak_from_offset<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakFromOffsetRequest,
+) -> Result<SpeakFromOffsetResponse> {
+    app.tts().speak_from_offset(payload)
+}
+
+/// Speak the headings of a document as a numbered list and return them
+#[command]
+pub(crate) async fn speak_outline<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakOutlineRequest,
+) -> Result<SpeakOutlineResponse> {
+    app.tts().speak_outline(payload)
+}
+
+/// Read a document from one of its outline sections to the end
+#[command]
+pub(crate) async fn speak_section<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakSectionRequest,
+) -> Result<SpeakResponse> {
+    app.tts().speak_section(payload)
+}
+
+/// Start a user-paced list of utterances, spoken one per `speak_list_next`
+#[command]
+pub(crate) async fn speak_list_begin<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakListBeginRequest,
+) -> Result<SpeakListBeginResponse> {
+    app.tts().speak_list_begin(payload)
+}
+
+/// Speak the next item of a list and report whether more remain
+#[command]
+pub(crate) async fn speak_list_next<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakListSessionRequest,
+) -> Result<SpeakListNextResponse> {
+    app.tts().speak_list_next(payload)
+}
+
+/// Forget a list before it was spoken to the end
+#[command]
+pub(crate) async fn speak_list_reset<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakListSessionRequest,
+) -> Result<SpeakListResetResponse> {
+    app.tts().speak_list_reset(payload)
+}
+
+/// Split text into words, spoken one per `speak_word_next`
+#[command]
+pub(crate) async fn speak_words_begin<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakWordsBeginRequest,
+) -> Result<SpeakWordsBeginResponse> {
+    app.tts().speak_words_begin(payload)
+}
+
+/// Speak the next word of a session and report how many remain
+#[command]
+pub(crate) async fn speak_word_next<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SpeakListSessionRequest,
+) -> Result<SpeakWordNextResponse> {
+    app.tts().speak_word_next(payload)
+}
+
+/// Set or clear the global prefix/suffix added to all spoken text
+#[command]
+pub(crate) async fn set_affixes<R: Runtime>(app: AppHandle<R>, payload: Affixes) -> Result<()> {
+    app.tts().set_affixes(payload)
+}
+
+/// Add or replace a lexicon respelling applied to all spoken text, saved across launches
+#[command]
+pub(crate) async fn add_pronunciation<R: Runtime>(
+    app: AppHandle<R>,
+    payload: LexiconEntry,
+) -> Result<()> {
+    app.tts().add_pronunciation(payload)
+}
+
+/// Remove the lexicon respelling for a word
+#[command]
+pub(crate) async fn remove_pronunciation<R: Runtime>(
+    app: AppHandle<R>,
+    payload: RemovePronunciationRequest,
+) -> Result<RemovePronunciationResponse> {
+    app.tts().remove_pronunciation(payload)
+}
+
+/// List the lexicon respellings
+#[command]
+pub(crate) async fn list_pronunciations<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<ListPronunciationsResponse> {
+    app.tts().list_pronunciations()
+}
+
+/// Turn all speaking calls on or off. `stop` keeps working while disabled
+#[command]
+pub(crate) async fn set_enabled<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetEnabledRequest,
+) -> Result<()> {
+    app.tts().set_enabled(payload)
+}
+
+/// Check whether speaking calls are enabled
+#[command]
+pub(crate) async fn is_enabled<R: Runtime>(app: AppHandle<R>) -> Result<IsEnabledResponse> {
+    app.tts().is_enabled()
+}
+
+/// Set or clear the volume gain applied to one voice
+#[command]
+pub(crate) async fn set_voice_gain<R: Runtime>(
+    app: AppHandle<R>,
+    payload: SetVoiceGainRequest,
+) -> Result<()> {
+    app.tts().set_voice_gain(payload)
+}
+
 /// Preview a voice by speaking a sample text
 #[command]
 pub(crate) async fn preview_voice<R: Runtime>(
@@ -60,3 +561,21 @@ pub(crate) async fn preview_voice<R: Runtime>(
 ) -> Result<SpeakResponse> {
     app.tts().preview_voice(payload)
 }
+
+/// Preview every voice of a language one after another
+#[command]
+pub(crate) async fn preview_all<R: Runtime>(
+    app: AppHandle<R>,
+    payload: PreviewAllRequest,
+) -> Result<PreviewAllResponse> {
+    app.tts().preview_all(payload)
+}
+
+/// Get the phonetic representation the engine will use for the given text
+#[command]
+pub(crate) async fn get_pronunciation<R: Runtime>(
+    app: AppHandle<R>,
+    payload: GetPronunciationRequest,
+) -> Result<PronunciationResponse> {
+    app.tts().get_pronunciation(payload)
+}
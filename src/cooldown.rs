@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use crate::models::{InterruptCooldownAction, QueueMode, TtsConfig};
+
+/// Enforces a minimum time between interruptions, so a burst of flush requests
+/// can't keep cutting each other off
+#[derive(Debug)]
+pub(crate) struct InterruptCooldown {
+    cooldown: Duration,
+    action: InterruptCooldownAction,
+    last_interrupt: Option<Instant>,
+}
+
+impl InterruptCooldown {
+    pub fn new(config: &TtsConfig) -> Self {
+        Self {
+            cooldown: Duration::from_millis(u64::from(config.interrupt_cooldown_ms)),
+            action: config.interrupt_cooldown_action,
+            last_interrupt: None,
+        }
+    }
+
+    /// Decide how a request with `mode` should be queued at `now`,
+    /// or `None` if it should be dropped
+    pub fn resolve(&mut self, mode: QueueMode, now: Instant) -> Option<QueueMode> {
+        if mode != QueueMode::Flush {
+            return Some(mode);
+        }
+
+        let cooling_down = self
+            .last_interrupt
+            .is_some_and(|last| now.saturating_duration_since(last) < self.cooldown);
+        if cooling_down {
+            return match self.action {
+                InterruptCooldownAction::Queue => Some(QueueMode::Add),
+                InterruptCooldownAction::Drop => None,
+            };
+        }
+
+        self.last_interrupt = Some(now);
+        Some(QueueMode::Flush)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cooldown(ms: u32, action: InterruptCooldownAction) -> InterruptCooldown {
+        InterruptCooldown::new(&TtsConfig {
+            interrupt_cooldown_ms: ms,
+            interrupt_cooldown_action: action,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_disabled_cooldown_never_downgrades() {
+        let mut cooldown = cooldown(0, InterruptCooldownAction::Drop);
+        let now = Instant::now();
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, now),
+            Some(QueueMode::Flush)
+        );
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, now),
+            Some(QueueMode::Flush)
+        );
+    }
+
+    #[test]
+    fn test_flush_within_cooldown_is_queued() {
+        let mut cooldown = cooldown(200, InterruptCooldownAction::Queue);
+        let start = Instant::now();
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, start),
+            Some(QueueMode::Flush)
+        );
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, start + Duration::from_millis(100)),
+            Some(QueueMode::Add)
+        );
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, start + Duration::from_millis(250)),
+            Some(QueueMode::Flush)
+        );
+    }
+
+    #[test]
+    fn test_flush_within_cooldown_is_dropped() {
+        let mut cooldown = cooldown(200, InterruptCooldownAction::Drop);
+        let start = Instant::now();
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, start),
+            Some(QueueMode::Flush)
+        );
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, start + Duration::from_millis(50)),
+            None
+        );
+        // Adds are never affected
+        assert_eq!(
+            cooldown.resolve(QueueMode::Add, start + Duration::from_millis(50)),
+            Some(QueueMode::Add)
+        );
+    }
+
+    #[test]
+    fn test_downgraded_flush_does_not_extend_cooldown() {
+        let mut cooldown = cooldown(200, InterruptCooldownAction::Queue);
+        let start = Instant::now();
+        cooldown.resolve(QueueMode::Flush, start);
+        cooldown.resolve(QueueMode::Flush, start + Duration::from_millis(150));
+        assert_eq!(
+            cooldown.resolve(QueueMode::Flush, start + Duration::from_millis(210)),
+            Some(QueueMode::Flush)
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::models::{SetDefaultVoiceResponse, SpeakRequest};
+
+/// File in the app data directory the default voices are kept in
+const DEFAULT_VOICES_FILE: &str = "tts-default-voices.json";
+
+/// Voices saved with `set_default_voice`, kept on disk across launches
+#[derive(Debug, Default)]
+pub(crate) struct DefaultVoices {
+    saved: SavedVoices,
+    /// `None` when the app data directory can't be resolved, which keeps the
+    /// voices for this session only
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedVoices {
+    /// For requests that set neither a voice nor a language
+    #[serde(default)]
+    voice_id: Option<String>,
+    /// Keyed by lowercase language tag
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
+}
+
+impl DefaultVoices {
+    /// Load the voices saved by an earlier launch. A missing or unreadable file
+    /// starts with none, so a damaged file can't keep the plugin from loading
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> Self {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(DEFAULT_VOICES_FILE)),
+            Err(e) => {
+                log::warn!("Default voices won't be saved: {}", e);
+                None
+            }
+        };
+        let saved = path
+            .as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| match read_saved(path) {
+                Ok(saved) => Some(saved),
+                Err(e) => {
+                    log::error!("Failed to load default voices: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self { saved, path }
+    }
+
+    /// Save `voice_id` for `language`, or for requests without one, removing the
+    /// saved voice when it is `None`. Unknown voices keep the saved one
+    pub fn set(
+        &mut self,
+        voice_id: Option<String>,
+        language: Option<String>,
+        voice_exists: impl FnOnce(&str) -> bool,
+    ) -> crate::Result<SetDefaultVoiceResponse> {
+        if let Some(ref voice_id) = voice_id {
+            if !voice_exists(voice_id) {
+                return Ok(SetDefaultVoiceResponse {
+                    voice_id: self.saved_for(language.as_deref()).cloned(),
+                    language,
+                    warning: Some(format!(
+                        "Voice '{}' not available, keeping the saved voice",
+                        voice_id
+                    )),
+                });
+            }
+        }
+        match &language {
+            Some(language) => match &voice_id {
+                Some(voice_id) => {
+                    self.saved
+                        .languages
+                        .insert(language_key(language), voice_id.clone());
+                }
+                None => {
+                    self.saved.languages.remove(&language_key(language));
+                }
+            },
+            None => self.saved.voice_id.clone_from(&voice_id),
+        }
+        self.save()?;
+        Ok(SetDefaultVoiceResponse {
+            voice_id,
+            language,
+            warning: None,
+        })
+    }
+
+    /// The voice saved for exactly `language`
+    fn saved_for(&self, language: Option<&str>) -> Option<&String> {
+        match language {
+            Some(language) => self.saved.languages.get(&language_key(language)),
+            None => self.saved.voice_id.as_ref(),
+        }
+    }
+
+    /// The voice saved for `language`, falling back to the one for its base
+    /// language ("pt" for "pt-BR"), or the one for requests without a language
+    pub fn voice_for(&self, language: Option<&str>) -> Option<&str> {
+        let Some(language) = language else {
+            return self.saved.voice_id.as_deref();
+        };
+        let key = language_key(language);
+        let base = key.split('-').next().unwrap_or(&key);
+        self.saved
+            .languages
+            .get(&key)
+            .or_else(|| self.saved.languages.get(base))
+            .map(String::as_str)
+    }
+
+    /// Give a request for the OS engine that names no voice the one saved for
+    /// its language
+    pub fn apply(&self, request: &mut SpeakRequest) {
+        if request.voice_id.is_some() || request.backend.is_some() {
+            return;
+        }
+        request.voice_id = self
+            .voice_for(request.language.as_deref())
+            .map(str::to_string);
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(&self.saved).map_err(std::io::Error::from)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// Language tags compare case-insensitively, with `_` read as `-`
+fn language_key(language: &str) -> String {
+    language.trim().replace('_', "-").to_lowercase()
+}
+
+fn read_saved(path: &Path) -> std::io::Result<SavedVoices> {
+    let json = std::fs::read(path)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(voice_id: Option<&str>, language: Option<&str>) -> SpeakRequest {
+        serde_json::from_value(serde_json::json!({
+            "text": "Hello",
+            "voiceId": voice_id,
+            "language": language,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_voice_for_language_falls_back_to_base_language() {
+        let mut voices = DefaultVoices::default();
+        voices
+            .set(Some("daniel".into()), Some("en".into()), |_| true)
+            .unwrap();
+        voices
+            .set(Some("luciana".into()), Some("pt_BR".into()), |_| true)
+            .unwrap();
+        voices.set(Some("samantha".into()), None, |_| true).unwrap();
+
+        assert_eq!(voices.voice_for(Some("en-GB")), Some("daniel"));
+        assert_eq!(voices.voice_for(Some("PT-br")), Some("luciana"));
+        assert_eq!(voices.voice_for(Some("pt-PT")), None);
+        assert_eq!(voices.voice_for(None), Some("samantha"));
+
+        voices.set(None, Some("en".into()), |_| true).unwrap();
+        assert_eq!(voices.voice_for(Some("en-GB")), None);
+    }
+
+    #[test]
+    fn test_set_keeps_saved_voice_when_unknown() {
+        let mut voices = DefaultVoices::default();
+        voices.set(Some("samantha".into()), None, |_| true).unwrap();
+
+        let response = voices.set(Some("missing".into()), None, |_| false).unwrap();
+        assert_eq!(response.voice_id.as_deref(), Some("samantha"));
+        assert!(response.warning.unwrap().contains("missing"));
+        assert_eq!(voices.voice_for(None), Some("samantha"));
+    }
+
+    #[test]
+    fn test_apply_leaves_explicit_voices_and_backends_alone() {
+        let mut voices = DefaultVoices::default();
+        voices.set(Some("samantha".into()), None, |_| true).unwrap();
+        voices
+            .set(Some("luciana".into()), Some("pt".into()), |_| true)
+            .unwrap();
+
+        let mut plain = request(None, None);
+        voices.apply(&mut plain);
+        assert_eq!(plain.voice_id.as_deref(), Some("samantha"));
+
+        let mut portuguese = request(None, Some("pt-BR"));
+        voices.apply(&mut portuguese);
+        assert_eq!(portuguese.voice_id.as_deref(), Some("luciana"));
+
+        let mut explicit = request(Some("alex"), None);
+        voices.apply(&mut explicit);
+        assert_eq!(explicit.voice_id.as_deref(), Some("alex"));
+
+        let mut cloud = request(None, None);
+        cloud.backend = Some("azure".into());
+        voices.apply(&mut cloud);
+        assert_eq!(cloud.voice_id, None);
+    }
+}
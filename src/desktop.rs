@@ -1,11 +1,35 @@
+use regex::Regex;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::sync::{Arc, Mutex, RwLock};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock};
+use std::thread;
 use std::time::{Duration, Instant};
+#[cfg(feature = "piper")]
+use tauri::Manager;
 use tauri::{plugin::PluginApi, AppHandle, Emitter, Runtime};
-use tts::{Features, Tts as TtsEngine};
+use tts::Gender;
+#[cfg(not(feature = "mock"))]
+use tts::{Features, Tts as TtsEngine, UtteranceId, Voice as EngineVoice};
 
+use crate::audio_cache::{AudioCache, CacheKey};
+#[cfg(feature = "piper")]
+use crate::backend::piper::PiperBackend;
+use crate::backend::{BackendRegistry, SpeechMark, TtsBackend};
+use crate::cooldown::InterruptCooldown;
+use crate::default_voices::DefaultVoices;
+use crate::filter::TextFilter;
+use crate::lexicon::Lexicon;
+#[cfg(feature = "mock")]
+use crate::mock::{Features, MockCall, MockEngine as TtsEngine, UtteranceId, Voice as EngineVoice};
 use crate::models::*;
+use crate::queue::{QueuedUtterance, SequenceAdmission, SpeechQueue, UtteranceOutcome};
+use crate::rate::{map_rate, unmap_rate};
+use crate::speak_list::SpeakLists;
+use crate::usage::{spoken_chars, SessionUsage};
+use crate::word_offsets::{sentence_offsets, sentence_words, WordAligner};
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,356 +43,3475 @@ pub struct SpeechEvent {
     pub event_type: Option<String>,
 }
 
-struct VoiceCache {
-    voices: Vec<Voice>,
-    cached_at: Instant,
+struct EventEmitter<R: Runtime> {
+    app: AppHandle<R>,
 }
 
-impl VoiceCache {
-    const TTL: Duration = Duration::from_secs(60);
-
-    fn new(voices: Vec<Voice>) -> Self {
-        Self {
-            voices,
-            cached_at: Instant::now(),
-        }
+impl<R: Runtime> EventEmitter<R> {
+    /// Emit under `<scope>://` instead of `tts://` when the utterance has an event scope
+    fn emit_scoped<S: Serialize + Clone>(&self, scope: Option<&str>, event_name: &str, payload: S) {
+        emit_tts_event(&self.app, scope, event_name, payload);
     }
 
-    fn is_valid(&self) -> bool {
-        self.cached_at.elapsed() < Self::TTL
+    /// Report a failure the frontend has no pending command for on `tts://error`
+    fn emit_error(&self, error: &crate::Error, id: Option<&str>) {
+        emit_tts_event(&self.app, None, "error", ErrorEvent::new(error, id));
     }
 }
 
-struct EventEmitter<R: Runtime> {
-    app: AppHandle<R>,
+/// `utterance-*` lifecycle event emitted along with a `speech:*` event, if any
+fn lifecycle_event(event_name: &str) -> Option<&'static str> {
+    match event_name {
+        "speech:start" => Some("utterance-start"),
+        "speech:finish" => Some("utterance-end"),
+        "speech:cancel" => Some("utterance-cancelled"),
+        _ => None,
+    }
 }
 
-impl<R: Runtime> EventEmitter<R> {
-    fn emit(&self, event_name: &str, event: SpeechEvent) {
-        let full_event_name = format!("tts://{}", event_name);
-        if let Err(e) = self.app.emit(&full_event_name, event) {
-            log::warn!("Failed to emit TTS event '{}': {}", event_name, e);
+fn emit_tts_event<R: Runtime, S: Serialize + Clone>(
+    app: &AppHandle<R>,
+    scope: Option<&str>,
+    event_name: &str,
+    payload: S,
+) {
+    let names = std::iter::once(event_name).chain(lifecycle_event(event_name));
+    for name in names {
+        let full_event_name = format!("{}://{}", scope.unwrap_or("tts"), name);
+        if let Err(e) = app.emit(&full_event_name, payload.clone()) {
+            log::warn!("Failed to emit TTS event '{}': {}", full_event_name, e);
         }
     }
 }
 
-/// Normalize user rate (1.0 = normal) to platform-specific rate
-/// Each platform has different rate scales:
-/// - AVFoundation (macOS): 0.1-2.0, normal = 0.5
-/// - WinRT (Windows): 0.5-6.0, normal = 1.0
-/// - SpeechDispatcher (Linux): -100 to 100, normal = 0.0
-/// - AppKit (macOS legacy): 10-500, normal = 175.0
-fn normalize_rate_for_platform(engine: &TtsEngine, user_rate: f32) -> f32 {
-    let normal = engine.normal_rate();
-    let min = engine.min_rate();
-    let max = engine.max_rate();
+/// The utterance the queue worker handed to its backend
+#[derive(Debug, Clone)]
+struct Speaking {
+    /// Plugin utterance id, carried by its events
+    id: String,
+    /// The utterance's text as handed to the backend, reported by `is_speaking`
+    text: String,
+    event_scope: Option<String>,
+    /// Backend speaking it, which stops reach. `None` is the OS engine
+    backend: Option<String>,
+    /// Queue generation it was handed over in, a flush since means it mustn't start
+    generation: u64,
+}
 
-    // User rate: 1.0 = normal, <1 = slower, >1 = faster
-    // Map user_rate to platform scale using normal_rate as anchor
-    if user_rate <= 1.0 {
-        // Map 0.25-1.0 → min-normal
-        let t = (user_rate - 0.25) / 0.75; // 0.25→0, 1.0→1
-        let t = t.clamp(0.0, 1.0);
-        min + t * (normal - min)
-    } else {
-        // Map 1.0-4.0 → normal-max
-        let t = (user_rate - 1.0) / 3.0; // 1.0→0, 4.0→1
-        let t = t.clamp(0.0, 1.0);
-        normal + t * (max - normal)
-    }
+/// Note on desktop pause/resume, which restarts the interrupted sentence
+const APPROXIMATE_PAUSE: &str =
+    "Approximate pause on desktop: speech resumes from the start of the interrupted sentence";
+
+/// Warning for pronunciation overrides, which need SSML that no desktop backend reads
+const PRONUNCIATIONS_WARNING: &str =
+    "Pronunciations are not supported by this engine, speaking the text as written";
+
+/// Longest a benchmark may take before it is abandoned
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(120);
+/// Longest fade `stop_gentle` waits for before stopping
+const MAX_FADE: Duration = Duration::from_secs(30);
+
+/// State shared between the plugin, the queue worker thread and the engine callbacks.
+///
+/// Engine callbacks may run synchronously inside `engine.stop()` (WinRT), so they only
+/// ever touch `ended` and never the engine mutex.
+#[derive(Default)]
+struct QueueShared {
+    queue: Mutex<SpeechQueue>,
+    /// Signalled when utterances are queued or the queue is flushed
+    wake: Condvar,
+    /// Engine utterance ids reported as finished or stopped
+    ended: Mutex<Vec<String>>,
+    ended_cv: Condvar,
+    /// Utterance being spoken, only changed under `handoff`
+    speaking: Mutex<Option<Speaking>>,
+    /// Held while the worker hands a sentence to its backend and while speech is
+    /// stopped, so a stop can't miss a sentence that is just starting
+    handoff: Mutex<()>,
 }
 
-pub fn init<R: Runtime, C: DeserializeOwned>(
-    app: &AppHandle<R>,
-    _api: PluginApi<R, C>,
-) -> crate::Result<Tts<R>> {
-    let engine = TtsEngine::default().map_err(|e| {
-        // Provide better error message for Linux when speech-dispatcher is not installed
-        #[cfg(target_os = "linux")]
-        {
-            let err_msg = e.to_string();
-            if err_msg.contains("speech-dispatcher") || err_msg.contains("Speech Dispatcher") {
-                return crate::Error::TtsEngineError(
-                    "Speech Dispatcher not available. Please install it:\n\
-                    Ubuntu/Debian: sudo apt install speech-dispatcher\n\
-                    Fedora: sudo dnf install speech-dispatcher\n\
-                    Arch: sudo pacman -S speech-dispatcher"
-                        .to_string(),
-                );
+impl QueueShared {
+    /// How long to trust the engine's `is_speaking()` only after speech was submitted
+    const START_GRACE: Duration = Duration::from_millis(300);
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    fn utterance_key(id: &UtteranceId) -> String {
+        format!("{:?}", id)
+    }
+
+    fn mark_ended(&self, id: &UtteranceId) {
+        if let Ok(mut ended) = self.ended.lock() {
+            ended.push(Self::utterance_key(id));
+        }
+        self.ended_cv.notify_all();
+    }
+
+    fn notify(&self) {
+        self.wake.notify_all();
+        self.ended_cv.notify_all();
+    }
+
+    /// Lock the handoff. It guards no data, so a panic while it was held leaves
+    /// nothing to recover
+    fn hand_off(&self) -> MutexGuard<'_, ()> {
+        self.handoff.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn speaking(&self) -> Option<Speaking> {
+        self.speaking
+            .lock()
+            .ok()
+            .and_then(|speaking| speaking.clone())
+    }
+
+    fn set_speaking(&self, speaking: Option<Speaking>) {
+        if let Ok(mut current) = self.speaking.lock() {
+            *current = speaking;
+        }
+    }
+
+    fn is_flushed(&self, generation: u64) -> bool {
+        self.queue
+            .lock()
+            .map(|queue| queue.generation() != generation)
+            .unwrap_or(true)
+    }
+
+    /// Block until the engine is done with the utterance or the queue is flushed
+    fn wait_for_utterance(
+        &self,
+        engine: &Mutex<Option<TtsEngine>>,
+        utterance: Option<&UtteranceId>,
+        utterance_callbacks: bool,
+        generation: u64,
+    ) {
+        let key = utterance.map(Self::utterance_key);
+        let track_callbacks = utterance_callbacks && key.is_some();
+        let submitted_at = Instant::now();
+
+        loop {
+            if self.is_flushed(generation) {
+                return;
+            }
+            if !track_callbacks && submitted_at.elapsed() >= Self::START_GRACE {
+                let speaking = lock_engine(engine)
+                    .ok()
+                    .and_then(|engine| engine.as_ref()?.is_speaking().ok())
+                    .unwrap_or(false);
+                if !speaking {
+                    return;
+                }
+            }
+
+            let Ok(mut ended) = self.ended.lock() else {
+                return;
+            };
+            if let Some(ref key) = key {
+                if let Some(pos) = ended.iter().position(|k| k == key) {
+                    ended.remove(pos);
+                    return;
+                }
             }
+            let _ = self.ended_cv.wait_timeout(ended, Self::POLL_INTERVAL);
         }
-        crate::Error::from(e)
-    })?;
+    }
 
-    // Set up utterance callbacks if supported
-    let Features {
-        utterance_callbacks,
-        ..
-    } = engine.supported_features();
+    /// Sleep for `pause`, returning early if the queue is flushed meanwhile
+    fn pause(&self, pause: Duration, generation: u64) {
+        if pause.is_zero() {
+            return;
+        }
+        let deadline = Instant::now() + pause;
+        let Ok(mut queue) = self.queue.lock() else {
+            return;
+        };
+        while queue.generation() == generation {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            match self.wake.wait_timeout(queue, deadline - now) {
+                Ok((guard, _)) => queue = guard,
+                Err(_) => return,
+            }
+        }
+    }
+}
 
-    let emitter = Arc::new(EventEmitter { app: app.clone() });
+/// Voice and prosody the system backend last set on the engine, which keeps them for
+/// later utterances until they're changed again
+struct EngineSettings {
+    /// Voice the engine started with, `None` when it can't report it
+    initial_voice: Option<EngineVoice>,
+    /// Id of the voice in use, `None` when it isn't known
+    voice_id: Option<String>,
+    rate: f32,
+    pitch: f32,
+    volume: f32,
+}
 
-    if utterance_callbacks {
-        // Clone emitter for each callback
-        let end_emitter = Arc::clone(&emitter);
-        let stop_emitter = Arc::clone(&emitter);
+impl EngineSettings {
+    fn new(initial_voice: Option<EngineVoice>) -> Self {
+        Self {
+            voice_id: initial_voice.as_ref().map(EngineVoice::id),
+            initial_voice,
+            rate: 1.0,
+            pitch: 1.0,
+            volume: 1.0,
+        }
+    }
+}
 
-        // Set up on_utterance_end callback (natural completion)
-        if let Err(e) = engine.on_utterance_end(Some(Box::new(move |_utterance_id| {
-            end_emitter.emit(
-                "speech:finish",
-                SpeechEvent {
-                    id: None,
-                    event_type: Some("finish".to_string()),
-                },
+/// Apply the request's voice and prosody to the engine and submit `text` without
+/// interrupting. Only settings that differ from what the engine has are set, so a
+/// request without a voice or at the default rate undoes an earlier one
+fn speak_with_engine(
+    engine: &mut TtsEngine,
+    request: &ValidatedSpeakRequest,
+    text: &str,
+    applied: &mut EngineSettings,
+) -> crate::Result<Option<UtteranceId>> {
+    let default_voice = applied.initial_voice.clone();
+    // Requests without a voice get the engine's initial one back
+    let voice_id = request
+        .voice_id
+        .clone()
+        .or_else(|| default_voice.as_ref().map(EngineVoice::id));
+    if let Some(voice_id) = voice_id.filter(|id| applied.voice_id.as_ref() != Some(id)) {
+        let voice = engine
+            .voices()
+            .ok()
+            .and_then(|voices| voices.into_iter().find(|v| v.id() == voice_id))
+            // `speak` warned about unknown voices, which get the engine's initial voice
+            .or(default_voice);
+        if let Some(voice) = voice {
+            match engine.set_voice(&voice) {
+                Ok(()) => applied.voice_id = Some(voice.id()),
+                Err(e) => log::warn!("Failed to set voice '{}': {}", voice_id, e),
+            }
+        }
+    } else if request.voice_id.is_none() && default_voice.is_none() {
+        if let Some(voice_id) = applied.voice_id.take() {
+            log::warn!(
+                "Engine can't report its initial voice, '{}' stays in use",
+                voice_id
             );
-        }))) {
-            log::warn!("Failed to set on_utterance_end callback: {:?}", e);
         }
+    }
 
-        // Set up on_utterance_stop callback (cancelled/interrupted)
-        if let Err(e) = engine.on_utterance_stop(Some(Box::new(move |_utterance_id| {
-            stop_emitter.emit(
-                "speech:cancel",
-                SpeechEvent {
-                    id: None,
-                    event_type: Some("cancel".to_string()),
-                },
-            );
-        }))) {
-            log::warn!("Failed to set on_utterance_stop callback: {:?}", e);
+    // WORKAROUND: values the engine already has are not set again, so nothing is
+    // configured while every request uses the defaults (1.0)
+    // Some engines (especially Google TTS) have bugs when default values are explicitly set
+    if request.rate != applied.rate {
+        // Normalize user rate (1.0 = normal) to platform-specific scale
+        // Each platform has different rate ranges and normal values:
+        // - AVFoundation (macOS): 0.1-2.0, normal = 0.5
+        // - WinRT (Windows): 0.5-6.0, normal = 1.0
+        // - SpeechDispatcher (Linux): -100 to 100, normal = 0.0
+        let rate_to_set = normalize_rate_for_platform(engine, request.rate);
+        if engine.set_rate(rate_to_set).is_ok() {
+            applied.rate = request.rate;
         }
+    }
 
-        log::info!("TTS utterance callbacks enabled for speech:finish events");
-    } else {
-        log::warn!("TTS engine does not support utterance callbacks - speech:finish events will not be emitted");
+    if request.pitch != applied.pitch {
+        // Pitch: tts library uses 0.5-2.0, same as our API (already validated/clamped)
+        if engine.set_pitch(request.pitch).is_ok() {
+            applied.pitch = request.pitch;
+        }
     }
 
-    Ok(Tts {
-        app: app.clone(),
-        engine: Mutex::new(engine),
-        voice_cache: RwLock::new(None),
+    if request.volume != applied.volume {
+        // Volume: both use 0.0-1.0 (already validated/clamped)
+        if engine.set_volume(request.volume).is_ok() {
+            applied.volume = request.volume;
+        }
+    }
+
+    // Queue mode is handled by the plugin queue, the engine only ever gets one utterance
+    Ok(engine.speak(text, false)?)
+}
+
+/// Speak `text` through a backend, blocking until it has played. Called without
+/// the handoff lock, which stops take to reach the backend
+fn speak_with_backend(
+    backend: &dyn TtsBackend,
+    shared: &QueueShared,
+    request: &ValidatedSpeakRequest,
+    text: &str,
+    generation: u64,
+    on_mark: &mut dyn FnMut(SpeechMark),
+) -> crate::Result<()> {
+    // A stop since the handoff lock was released found nothing playing yet
+    if shared.is_flushed(generation) {
+        return Ok(());
+    }
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        backend.speak_marked(request, text, on_mark)
+    }))
+    .unwrap_or_else(|_| {
+        Err(crate::Error::OperationFailed(format!(
+            "The {} backend panicked while speaking",
+            backend.name()
+        )))
     })
 }
 
-pub struct Tts<R: Runtime> {
-    app: AppHandle<R>,
-    engine: Mutex<TtsEngine>,
-    voice_cache: RwLock<Option<VoiceCache>>,
+/// Like [`speak_with_backend`], stopping the backend once `deadline` passes.
+/// Returns whether the sentence was cut off at the deadline
+fn speak_until(
+    backend: &dyn TtsBackend,
+    shared: &QueueShared,
+    request: &ValidatedSpeakRequest,
+    text: &str,
+    generation: u64,
+    deadline: Option<Instant>,
+    on_mark: &mut dyn FnMut(SpeechMark),
+) -> crate::Result<bool> {
+    let Some(deadline) = deadline else {
+        return speak_with_backend(backend, shared, request, text, generation, on_mark)
+            .map(|()| false);
+    };
+    let finished = Mutex::new(false);
+    let wake = Condvar::new();
+    let timed_out = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let watchdog = thread::Builder::new()
+            .name("tts-deadline".to_string())
+            .spawn_scoped(scope, || {
+                let Ok(finished) = finished.lock() else {
+                    return;
+                };
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                let Ok((finished, _)) =
+                    wake.wait_timeout_while(finished, timeout, |finished| !*finished)
+                else {
+                    return;
+                };
+                if *finished {
+                    return;
+                }
+                drop(finished);
+                timed_out.store(true, Ordering::Relaxed);
+                if let Err(e) = backend.stop() {
+                    log::warn!(
+                        "Failed to stop the {} backend at its limit: {}",
+                        backend.name(),
+                        e
+                    );
+                }
+            });
+        if let Err(e) = watchdog {
+            log::warn!("Utterance can't be stopped at its limit: {}", e);
+        }
+        let spoken = speak_with_backend(backend, shared, request, text, generation, on_mark);
+        if let Ok(mut finished) = finished.lock() {
+            *finished = true;
+        }
+        wake.notify_all();
+        spoken
+    })
+    .map(|()| timed_out.load(Ordering::Relaxed))
 }
 
-impl<R: Runtime> Tts<R> {
-    /// Helper to acquire engine lock with proper error handling
-    fn with_engine<T, F>(&self, f: F) -> crate::Result<T>
-    where
-        F: FnOnce(&mut TtsEngine) -> crate::Result<T>,
-    {
-        let mut engine = self
-            .engine
-            .lock()
-            .map_err(|_| crate::Error::MutexPoisoned)?;
-        f(&mut engine)
+/// Switch `item` to the next registered backend of its fallback chain after its
+/// current one failed with `error`. `None` once the chain is used up
+fn fall_back<R: Runtime>(
+    backends: &BackendRegistry,
+    emitter: &EventEmitter<R>,
+    item: &mut QueuedUtterance,
+    error: &crate::Error,
+) -> Option<Arc<dyn TtsBackend>> {
+    while !item.request.fallback.is_empty() {
+        let name = item.request.fallback.remove(0);
+        let Ok(backend) = backends.get(Some(&name)) else {
+            continue;
+        };
+        let from = item
+            .request
+            .backend
+            .replace(name.clone())
+            .unwrap_or_else(|| SYSTEM_BACKEND.to_string());
+        log::warn!(
+            "Backend {} failed to speak utterance {}, falling back to {}: {}",
+            from,
+            item.id,
+            name,
+            error
+        );
+        emitter.emit_scoped(
+            item.request.event_scope.as_deref(),
+            "backend-fallback",
+            BackendFallbackEvent {
+                utterance_id: item.id.clone(),
+                from,
+                to: name,
+                error: error.to_string(),
+            },
+        );
+        return Some(backend);
     }
+    None
+}
 
-    fn emit_event(&self, event_name: &str, event: SpeechEvent) {
-        let full_event_name = format!("tts://{}", event_name);
-        if let Err(e) = self.app.emit(&full_event_name, event) {
-            log::warn!("Failed to emit TTS event '{}': {}", event_name, e);
+/// Emit the event for a mark a backend reported while speaking `sentence`, the
+/// `index`th of the utterance. Words the caller didn't write, like a prefix, have
+/// no event
+fn emit_mark<R: Runtime>(
+    emitter: &EventEmitter<R>,
+    item: &QueuedUtterance,
+    index: usize,
+    sentence: &str,
+    mark: SpeechMark,
+) {
+    let scope = item.request.event_scope.as_deref();
+    match mark {
+        SpeechMark::Word(range) => {
+            let Some(start) = sentence.get(..range.start) else {
+                return;
+            };
+            let start = start.encode_utf16().count() as u32;
+            let words = item.request.words.get(index).map(Vec::as_slice);
+            let Some(word) = words
+                .unwrap_or_default()
+                .iter()
+                .find(|word| word.spoken_start == start)
+            else {
+                return;
+            };
+            emitter.emit_scoped(
+                scope,
+                "word-boundary",
+                WordBoundaryEvent {
+                    utterance_id: item.id.clone(),
+                    char_start: word.text_start,
+                    char_end: word.text_end,
+                    char_length: word.text_end - word.text_start,
+                },
+            );
         }
+        SpeechMark::Viseme(viseme) => emitter.emit_scoped(
+            scope,
+            "viseme",
+            VisemeEvent {
+                utterance_id: item.id.clone(),
+                viseme,
+            },
+        ),
     }
+}
 
-    pub fn speak(&self, payload: SpeakRequest) -> crate::Result<SpeakResponse> {
-        // Validate input first (before acquiring lock)
-        let validated = payload.validate()?;
+#[cfg(not(feature = "piper"))]
+fn piper_disabled() -> crate::Error {
+    crate::Error::OperationFailed(
+        "Piper voices need the plugin's `piper` feature enabled".to_string(),
+    )
+}
 
-        // Generate utterance ID for tracking
-        let utterance_id = uuid::Uuid::new_v4().to_string();
+/// Lock the engine slot, recovering it if a panic poisoned the lock. The backend may
+/// have been left mid-call, so it is stopped before being used again
+fn lock_engine(
+    engine: &Mutex<Option<TtsEngine>>,
+) -> crate::Result<MutexGuard<'_, Option<TtsEngine>>> {
+    match engine.lock() {
+        Ok(engine) => Ok(engine),
+        Err(poisoned) => {
+            log::warn!("TTS engine lock poisoned, resetting the engine");
+            let mut guard = poisoned.into_inner();
+            if let Some(Err(e)) = guard.as_mut().map(TtsEngine::stop) {
+                log::error!("Failed to reset the TTS engine: {}", e);
+                return Err(crate::Error::MutexPoisoned);
+            }
+            engine.clear_poison();
+            Ok(guard)
+        }
+    }
+}
 
-        // Emit speech:start event
-        self.emit_event(
-            "speech:start",
-            SpeechEvent {
-                id: Some(utterance_id.clone()),
-                event_type: Some("start".to_string()),
-            },
-        );
+/// Worker loop feeding queued utterances to the backends they name, one at a time
+fn run_queue<R: Runtime>(
+    backends: Arc<BackendRegistry>,
+    shared: Arc<QueueShared>,
+    emitter: Arc<EventEmitter<R>>,
+) {
+    loop {
+        let (mut item, generation) = {
+            let Ok(mut queue) = shared.queue.lock() else {
+                log::error!("TTS queue lock poisoned, stopping queue worker");
+                emitter.emit_error(&crate::Error::MutexPoisoned, None);
+                return;
+            };
+            loop {
+                if let Some(item) = queue.start_next() {
+                    break (item, queue.generation());
+                }
+                queue = match shared.wake.wait(queue) {
+                    Ok(queue) => queue,
+                    Err(_) => {
+                        log::error!("TTS queue lock poisoned, stopping queue worker");
+                        emitter.emit_error(&crate::Error::MutexPoisoned, None);
+                        return;
+                    }
+                };
+            }
+        };
 
-        let result = self.with_engine(|engine| {
-            // Set voice if specified
-            if let Some(ref voice_id) = validated.voice_id {
-                if let Ok(voices) = engine.voices() {
-                    if let Some(voice) = voices.into_iter().find(|v| v.id() == *voice_id) {
-                        let _ = engine.set_voice(&voice);
+        if let Ok(mut ended) = shared.ended.lock() {
+            ended.clear();
+        }
+
+        // Spoken a sentence at a time, so a pause can resume at the interrupted one
+        let mut sentences: Vec<String> = crate::preprocess::sentences(&item.request.text)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if sentences.is_empty() {
+            sentences.push(item.request.text.clone());
+        }
+        let started = Instant::now();
+        let deadline = item.max_duration().map(|max| started + max);
+        let mut spoken = 0;
+        let (mut backend, mut failure) = match backends.get(item.request.backend.as_deref()) {
+            Ok(backend) => (Some(backend), None),
+            Err(e) => match fall_back(&backends, &emitter, &mut item, &e) {
+                Some(backend) => (Some(backend), None),
+                None => (None, Some(e)),
+            },
+        };
+        let mut timed_out = false;
+        // Set while a sentence is retried with a fallback backend, whose start was
+        // already reported
+        let mut retrying = false;
+        let mut index = 0;
+        while let Some(sentence) = sentences.get(index) {
+            let Some(current) = backend.clone() else {
+                break;
+            };
+            {
+                let _handoff = shared.hand_off();
+                // Re-check under the handoff so a flush racing with us is never spoken over
+                if shared.is_flushed(generation) {
+                    break;
+                }
+                let scope = item.request.event_scope.as_deref();
+                shared.set_speaking(Some(Speaking {
+                    id: item.id.clone(),
+                    text: item.request.text.clone(),
+                    event_scope: item.request.event_scope.clone(),
+                    backend: item.request.backend.clone(),
+                    generation,
+                }));
+                // Chunks of a long text start and finish as one utterance
+                if index == 0 && !retrying && !item.resumed && item.request.first_chunk {
+                    if let Some(ref event) = item.on_start {
+                        emitter.emit_scoped(scope, event.name, event.payload.clone());
                     }
+                    emitter.emit_scoped(
+                        scope,
+                        "speech:start",
+                        SpeechEvent {
+                            id: Some(item.id.clone()),
+                            event_type: Some("start".to_string()),
+                        },
+                    );
+                }
+                if let Some(Some(sentence)) =
+                    item.request.sentences.get(index).filter(|_| !retrying)
+                {
+                    emitter.emit_scoped(
+                        scope,
+                        "sentence-start",
+                        SentenceStartEvent {
+                            utterance_id: item.id.clone(),
+                            index: sentence.index,
+                            char_start: sentence.text_start,
+                            char_end: sentence.text_end,
+                        },
+                    );
                 }
             }
-
-            // WORKAROUND: If all values are default (1.0), do not configure anything
-            // Some engines (especially Google TTS) have bugs when default values are explicitly set
-            let all_defaults =
-                validated.rate == 1.0 && validated.pitch == 1.0 && validated.volume == 1.0;
-
-            if !all_defaults {
-                if validated.rate != 1.0 {
-                    // Normalize user rate (1.0 = normal) to platform-specific scale
-                    // Each platform has different rate ranges and normal values:
-                    // - AVFoundation (macOS): 0.1-2.0, normal = 0.5
-                    // - WinRT (Windows): 0.5-6.0, normal = 1.0
-                    // - SpeechDispatcher (Linux): -100 to 100, normal = 0.0
-                    let rate_to_set = normalize_rate_for_platform(engine, validated.rate);
-                    let _ = engine.set_rate(rate_to_set);
+            let cut = speak_until(
+                &*current,
+                &shared,
+                &item.request,
+                sentence,
+                generation,
+                deadline,
+                &mut |mark| emit_mark(&emitter, &item, index, sentence, mark),
+            );
+            match cut {
+                Ok(cut) => {
+                    timed_out = cut;
+                    if timed_out {
+                        break;
+                    }
                 }
-
-                if validated.pitch != 1.0 {
-                    // Pitch: tts library uses 0.5-2.0, same as our API (already validated/clamped)
-                    let _ = engine.set_pitch(validated.pitch);
+                Err(e) => {
+                    if !shared.is_flushed(generation) {
+                        if let Some(next) = fall_back(&backends, &emitter, &mut item, &e) {
+                            backend = Some(next);
+                            retrying = true;
+                            continue;
+                        }
+                    }
+                    failure = Some(e);
+                    break;
                 }
+            }
+            if shared.is_flushed(generation) {
+                break;
+            }
+            retrying = false;
+            index += 1;
+            spoken = index;
+        }
 
-                if validated.volume != 1.0 {
-                    // Volume: both use 0.0-1.0 (already validated/clamped)
-                    let _ = engine.set_volume(validated.volume);
-                }
+        if let Some(e) = failure {
+            log::warn!("Failed to speak queued utterance {}: {}", item.id, e);
+            {
+                let _handoff = shared.hand_off();
+                shared.set_speaking(None);
+            }
+            emitter.emit_error(&e, Some(&item.id));
+            item.notify_finished(UtteranceOutcome::Failed(e.to_string()));
+        } else if timed_out {
+            // Stopped before the worker moved on, so the next utterance can't be cut instead
+            {
+                let _handoff = shared.hand_off();
+                shared.set_speaking(None);
+            }
+            let scope = item.request.event_scope.as_deref();
+            emitter.emit_scoped(
+                scope,
+                "speech:cancel",
+                SpeechEvent {
+                    id: Some(item.id.clone()),
+                    event_type: Some("cancel".to_string()),
+                },
+            );
+            emitter.emit_scoped(
+                scope,
+                "utterance-timeout",
+                UtteranceTimeoutEvent {
+                    id: item.id.clone(),
+                    max_duration_ms: item.request.max_duration_ms.unwrap_or_default(),
+                },
+            );
+            item.notify_finished(UtteranceOutcome::Interrupted);
+        } else if spoken < sentences.len() {
+            let Ok(mut queue) = shared.queue.lock() else {
+                log::error!("TTS queue lock poisoned, stopping queue worker");
+                emitter.emit_error(&crate::Error::MutexPoisoned, Some(&item.id));
+                return;
+            };
+            if queue.interrupted_to_resume(generation) {
+                // Resumes at the start of the sentence that was cut off. Joined by line
+                // breaks, the rest splits into the same sentences again
+                let done = spoken.min(item.request.sentences.len());
+                item.request.sentences.drain(..done);
+                item.request
+                    .words
+                    .drain(..done.min(item.request.words.len()));
+                queue.requeue(item, sentences[spoken..].join("\n"));
+                continue;
+            }
+            drop(queue);
+            item.notify_finished(UtteranceOutcome::Interrupted);
+        } else {
+            // Flushes clear it themselves, after the backend confirmed the stop
+            {
+                let _handoff = shared.hand_off();
+                shared.set_speaking(None);
+            }
+            // Backends return once the last sentence has played, so its end is known here
+            if item.request.last_chunk {
+                emitter.emit_scoped(
+                    item.request.event_scope.as_deref(),
+                    "speech:finish",
+                    SpeechEvent {
+                        id: Some(item.id.clone()),
+                        event_type: Some("finish".to_string()),
+                    },
+                );
             }
+            item.notify_finished(UtteranceOutcome::Finished(started.elapsed()));
+            shared.pause(item.pause_after(), generation);
+        }
 
-            // Determine if we should interrupt current speech
-            // flush (default) = interrupt, add = queue
-            let interrupt = validated.queue_mode != QueueMode::Add;
+        if let Ok(mut queue) = shared.queue.lock() {
+            queue.finish(&item.id);
+        }
+    }
+}
 
-            engine.speak(&validated.text, interrupt)?;
+/// Engine tts-rs drives for the system voices, as in `Voice::engine`
+#[cfg(feature = "mock")]
+const ENGINE: &str = "mock";
+#[cfg(all(not(feature = "mock"), target_os = "windows"))]
+const ENGINE: &str = "WinRT";
+#[cfg(all(not(feature = "mock"), target_os = "macos"))]
+const ENGINE: &str = "AVFoundation";
+#[cfg(all(
+    not(feature = "mock"),
+    not(any(target_os = "windows", target_os = "macos"))
+))]
+const ENGINE: &str = "speech-dispatcher";
 
-            Ok(SpeakResponse {
-                success: true,
-                warning: None,
-            })
-        });
-        result
+/// Describe an engine voice. tts-rs has no notion of voice quality, and only
+/// lists voices installed on this machine, which speak without a connection
+fn voice_from_engine(voice: &EngineVoice) -> Voice {
+    Voice {
+        id: voice.id().to_string(),
+        name: voice.name().to_string(),
+        language: voice.language().to_string(),
+        gender: voice.gender().map(|gender| match gender {
+            Gender::Male => VoiceGender::Male,
+            Gender::Female => VoiceGender::Female,
+        }),
+        quality: None,
+        requires_network: Some(false),
+        installed: Some(true),
+        engine: Some(ENGINE.to_string()),
+        platform: PLATFORM.to_string(),
     }
+}
 
-    pub fn stop(&self) -> crate::Result<StopResponse> {
-        // Note: speech:cancel is emitted via on_utterance_stop callback set up in init()
-        // for platforms that support it. We still emit here as fallback for legacy backends.
-        self.emit_event(
-            "speech:cancel",
-            SpeechEvent {
-                id: None,
-                event_type: Some("cancel".to_string()),
-            },
-        );
+/// Normalize user rate (1.0 = normal) to the engine's own scale, e.g.
+/// AVFoundation 0.1-2.0 (normal 0.5), WinRT 0.5-6.0 (normal 1.0) or
+/// SpeechDispatcher -100-100 (normal 0)
+fn normalize_rate_for_platform(engine: &TtsEngine, user_rate: f32) -> f32 {
+    map_rate(
+        user_rate,
+        engine.min_rate(),
+        engine.normal_rate(),
+        engine.max_rate(),
+    )
+}
 
-        self.with_engine(|engine| {
-            engine.stop()?;
-            Ok(StopResponse { success: true })
+/// Inverse of [`normalize_rate_for_platform`]: map an engine rate back to the
+/// user scale (1.0 = normal)
+fn rate_from_platform(engine: &TtsEngine, platform_rate: f32) -> f32 {
+    unmap_rate(
+        platform_rate,
+        engine.min_rate(),
+        engine.normal_rate(),
+        engine.max_rate(),
+    )
+}
+
+/// Ask the AppKit speech synthesizer for the phonemes it would use for `text`.
+/// AVFoundation has no equivalent API, so this goes through `NSSpeechSynthesizer`.
+#[cfg(target_os = "macos")]
+fn macos_phonemes_from_text(text: &str, voice_id: Option<&str>) -> crate::Result<String> {
+    use cocoa_foundation::base::{id, nil};
+    use cocoa_foundation::foundation::{NSAutoreleasePool, NSString};
+    use objc::runtime::{BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+
+    unsafe {
+        let pool = NSAutoreleasePool::new(nil);
+        let synth: id = msg_send![class!(NSSpeechSynthesizer), new];
+        if synth == nil {
+            pool.drain();
+            return Err(crate::Error::OperationFailed(
+                "Failed to create NSSpeechSynthesizer".to_string(),
+            ));
+        }
+
+        if let Some(voice_id) = voice_id {
+            let ns_voice = NSString::alloc(nil).init_str(voice_id);
+            let accepted: BOOL = msg_send![synth, setVoice: ns_voice];
+            let _: () = msg_send![ns_voice, release];
+            if accepted == NO {
+                log::debug!(
+                    "NSSpeechSynthesizer rejected voice '{}', using system voice for phonemes",
+                    voice_id
+                );
+            }
+        }
+
+        let ns_text = NSString::alloc(nil).init_str(text);
+        let ns_phonemes: id = msg_send![synth, phonemesFromText: ns_text];
+        let phonemes = if ns_phonemes == nil {
+            None
+        } else {
+            Some(
+                CStr::from_ptr(ns_phonemes.UTF8String())
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        };
+
+        let _: () = msg_send![ns_text, release];
+        let _: () = msg_send![synth, release];
+        pool.drain();
+
+        phonemes.ok_or_else(|| {
+            crate::Error::OperationFailed("Engine returned no phonemes for text".to_string())
         })
     }
+}
 
-    pub fn get_voices(&self, payload: GetVoicesRequest) -> crate::Result<GetVoicesResponse> {
-        // Try to use cached voices first
-        {
-            let cache = self
-                .voice_cache
-                .read()
-                .map_err(|_| crate::Error::MutexPoisoned)?;
-            if let Some(ref c) = *cache {
-                if c.is_valid() {
-                    return Ok(self.filter_voices(&c.voices, &payload.language));
+/// Render `text` to WAV with `NSSpeechSynthesizer`, which writes an AIFF file,
+/// converted once complete. Pitch has no setting there and is left alone
+#[cfg(target_os = "macos")]
+fn render_wav(text: &str, request: &ValidatedSpeakRequest) -> crate::Result<Vec<u8>> {
+    use cocoa_foundation::base::{id, nil};
+    use cocoa_foundation::foundation::{NSAutoreleasePool, NSString};
+    use objc::runtime::{BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let aiff_path =
+        std::env::temp_dir().join(format!("tauri-plugin-tts-{}.aiff", uuid::Uuid::new_v4()));
+    unsafe {
+        let pool = NSAutoreleasePool::new(nil);
+        let synth: id = msg_send![class!(NSSpeechSynthesizer), new];
+        if synth == nil {
+            pool.drain();
+            return Err(crate::Error::OperationFailed(
+                "Failed to create NSSpeechSynthesizer".to_string(),
+            ));
+        }
+
+        if let Some(ref voice_id) = request.voice_id {
+            let ns_voice = NSString::alloc(nil).init_str(voice_id);
+            let accepted: BOOL = msg_send![synth, setVoice: ns_voice];
+            let _: () = msg_send![ns_voice, release];
+            if accepted == NO {
+                log::debug!(
+                    "NSSpeechSynthesizer rejected voice '{}', writing with the system voice",
+                    voice_id
+                );
+            }
+        }
+        // The rate is in words per minute, the voice's own pace being normal
+        if request.rate != 1.0 {
+            let wpm: f32 = msg_send![synth, rate];
+            let _: () = msg_send![synth, setRate: wpm * request.rate];
+        }
+        if request.volume != 1.0 {
+            let _: () = msg_send![synth, setVolume: request.volume];
+        }
+
+        let ns_path = NSString::alloc(nil).init_str(&aiff_path.to_string_lossy());
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        let ns_text = NSString::alloc(nil).init_str(text);
+        let started: BOOL = msg_send![synth, startSpeakingString: ns_text toURL: url];
+        // Rendering runs in the background and is done once the synthesizer goes quiet
+        if started != NO {
+            loop {
+                let speaking: BOOL = msg_send![synth, isSpeaking];
+                if speaking == NO {
+                    break;
                 }
+                thread::sleep(Duration::from_millis(50));
             }
         }
 
-        // Cache miss or expired - fetch from engine
-        let voices = self.with_engine(|engine| {
-            let native_voices = engine.voices()?;
-            Ok(native_voices
-                .into_iter()
-                .map(|v| Voice {
-                    id: v.id().to_string(),
-                    name: v.name().to_string(),
-                    language: v.language().to_string(),
-                })
-                .collect::<Vec<Voice>>())
-        })?;
+        let _: () = msg_send![ns_text, release];
+        let _: () = msg_send![ns_path, release];
+        let _: () = msg_send![synth, release];
+        pool.drain();
 
-        // Update cache
-        {
-            let mut cache = self
-                .voice_cache
-                .write()
-                .map_err(|_| crate::Error::MutexPoisoned)?;
-            *cache = Some(VoiceCache::new(voices.clone()));
+        if started == NO {
+            return Err(crate::Error::OperationFailed(
+                "Failed to start synthesizing speech".to_string(),
+            ));
         }
-
-        Ok(self.filter_voices(&voices, &payload.language))
     }
 
-    fn filter_voices(&self, voices: &[Voice], language: &Option<String>) -> GetVoicesResponse {
-        let filtered: Vec<Voice> = voices
-            .iter()
-            .filter(|v| {
-                if let Some(ref lang_filter) = language {
-                    v.language
-                        .to_lowercase()
-                        .contains(&lang_filter.to_lowercase())
-                } else {
-                    true
-                }
-            })
-            .cloned()
-            .collect();
+    let aiff = std::fs::read(&aiff_path);
+    let _ = std::fs::remove_file(&aiff_path);
+    crate::wav::aiff_to_wav(&aiff?).map_err(crate::Error::OperationFailed)
+}
 
-        GetVoicesResponse { voices: filtered }
+/// Render `text` to WAV with the WinRT speech synthesizer, whose stream already is WAV
+#[cfg(windows)]
+fn render_wav(text: &str, request: &ValidatedSpeakRequest) -> crate::Result<Vec<u8>> {
+    use windows::Media::SpeechSynthesis::SpeechSynthesizer;
+    use windows::Storage::Streams::DataReader;
+
+    let failed = |e: windows::core::Error| {
+        crate::Error::OperationFailed(format!("Failed to synthesize speech: {}", e))
+    };
+    let synth = SpeechSynthesizer::new().map_err(failed)?;
+    if let Some(ref voice_id) = request.voice_id {
+        let voices = SpeechSynthesizer::AllVoices().map_err(failed)?;
+        let voice = voices
+            .into_iter()
+            .find(|voice| voice.Id().is_ok_and(|id| id.to_string_lossy() == *voice_id));
+        match voice {
+            Some(voice) => synth.SetVoice(&voice).map_err(failed)?,
+            None => log::debug!(
+                "Voice '{}' not found, writing with the default voice",
+                voice_id
+            ),
+        }
     }
+    // Same scales as the tts crate's WinRT backend uses for speaking
+    let options = synth.Options().map_err(failed)?;
+    options
+        .SetSpeakingRate(map_rate(request.rate, 0.5, 1.0, 6.0).into())
+        .map_err(failed)?;
+    options
+        .SetAudioPitch(request.pitch.into())
+        .map_err(failed)?;
+    options
+        .SetAudioVolume(request.volume.into())
+        .map_err(failed)?;
 
-    pub fn is_speaking(&self) -> crate::Result<IsSpeakingResponse> {
-        self.with_engine(|engine| {
-            let speaking = engine.is_speaking()?;
-            Ok(IsSpeakingResponse { speaking })
-        })
+    let stream = synth
+        .SynthesizeTextToStreamAsync(&text.into())
+        .and_then(|operation| operation.get())
+        .map_err(failed)?;
+    let size = stream.Size().map_err(failed)? as u32;
+    let reader = DataReader::CreateDataReader(&stream.GetInputStreamAt(0).map_err(failed)?)
+        .map_err(failed)?;
+    reader
+        .LoadAsync(size)
+        .and_then(|operation| operation.get())
+        .map_err(failed)?;
+    let mut wav = vec![0; size as usize];
+    reader.ReadBytes(&mut wav).map_err(failed)?;
+    Ok(wav)
+}
+
+/// The OS engine tts-rs drives, registered as the `system` backend. The engine
+/// is missing until `start` succeeds, which may take `reinitialize` calls
+struct SystemBackend {
+    engine: Mutex<Option<TtsEngine>>,
+    shared: Arc<QueueShared>,
+    /// Rendered audio, shared with the built-in backends
+    cache: Arc<AudioCache>,
+    /// What the engine was last set to, read from it once it is first spoken with
+    settings: Mutex<Option<EngineSettings>>,
+    /// Voice the engine started with, the system default
+    default_voice: Mutex<Option<Voice>>,
+    /// Engine voices, kept until `refresh_voices` since enumerating them can be slow
+    voice_cache: RwLock<Option<Vec<Voice>>>,
+}
+
+impl SystemBackend {
+    fn new(shared: Arc<QueueShared>, cache: Arc<AudioCache>) -> Self {
+        Self {
+            engine: Mutex::new(None),
+            shared,
+            cache,
+            settings: Mutex::new(None),
+            default_voice: Mutex::new(None),
+            voice_cache: RwLock::new(None),
+        }
     }
 
-    pub fn is_initialized(&self) -> crate::Result<IsInitializedResponse> {
-        // Desktop TTS is always initialized after construction
-        // Get voice count from cache or fetch
-        let voice_count = self
-            .get_voices(GetVoicesRequest { language: None })
-            .map(|r| r.voices.len() as u32)
-            .unwrap_or(0);
-        Ok(IsInitializedResponse {
-            initialized: true,
-            voice_count,
-        })
+    /// Create the engine unless it is running, hooking its callbacks up to the
+    /// queue. Returns whether it was created
+    fn start(&self) -> crate::Result<bool> {
+        let mut slot = lock_engine(&self.engine)?;
+        if slot.is_some() {
+            return Ok(false);
+        }
+        let engine = TtsEngine::default().map_err(|e| {
+            // Provide better error message for Linux when speech-dispatcher is not installed
+            #[cfg(target_os = "linux")]
+            {
+                let err_msg = e.to_string();
+                if err_msg.contains("speech-dispatcher") || err_msg.contains("Speech Dispatcher") {
+                    return crate::Error::OperationFailed(
+                        "Speech Dispatcher not available. Please install it:\n\
+                        Ubuntu/Debian: sudo apt install speech-dispatcher\n\
+                        Fedora: sudo dnf install speech-dispatcher\n\
+                        Arch: sudo pacman -S speech-dispatcher"
+                            .to_string(),
+                    );
+                }
+            }
+            crate::Error::from(e)
+        })?;
+
+        // Set up utterance callbacks if supported. They only wake the queue worker,
+        // which reports how the utterance ended like for every backend
+        let Features {
+            utterance_callbacks,
+            ..
+        } = engine.supported_features();
+        if utterance_callbacks {
+            let end_shared = Arc::clone(&self.shared);
+            let stop_shared = Arc::clone(&self.shared);
+            if let Err(e) = engine.on_utterance_end(Some(Box::new(move |utterance_id| {
+                end_shared.mark_ended(&utterance_id);
+            }))) {
+                log::warn!("Failed to set on_utterance_end callback: {:?}", e);
+            }
+            if let Err(e) = engine.on_utterance_stop(Some(Box::new(move |utterance_id| {
+                stop_shared.mark_ended(&utterance_id);
+            }))) {
+                log::warn!("Failed to set on_utterance_stop callback: {:?}", e);
+            }
+            log::info!("TTS utterance callbacks enabled for speech:finish events");
+        } else {
+            log::warn!("TTS engine does not support utterance callbacks - speech:finish events will be detected by polling");
+        }
+
+        // Voice the engine starts with, used when a requested voice doesn't exist
+        if let Ok(mut default_voice) = self.default_voice.lock() {
+            *default_voice = engine
+                .voice()
+                .ok()
+                .flatten()
+                .as_ref()
+                .map(voice_from_engine);
+        }
+        // A new engine starts from its own settings
+        *self.settings.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        *slot = Some(engine);
+        Ok(true)
     }
 
-    pub fn pause_speaking(&self) -> crate::Result<PauseResumeResponse> {
-        // Desktop TTS library (tts-rs) doesn't support pause/resume
-        // Return a descriptive error
-        Ok(PauseResumeResponse {
-            success: false,
-            reason: Some("Pause is not supported on desktop platform".to_string()),
-        })
+    fn is_started(&self) -> crate::Result<bool> {
+        Ok(lock_engine(&self.engine)?.is_some())
     }
 
-    pub fn resume_speaking(&self) -> crate::Result<PauseResumeResponse> {
-        // Desktop TTS library (tts-rs) doesn't support pause/resume
-        Ok(PauseResumeResponse {
-            success: false,
-            reason: Some("Resume is not supported on desktop platform".to_string()),
-        })
+    fn with_engine<T, F>(&self, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&mut TtsEngine) -> crate::Result<T>,
+    {
+        let mut engine = lock_engine(&self.engine)?;
+        let engine = engine.as_mut().ok_or(crate::Error::NotInitialized)?;
+        f(engine)
+    }
+
+    fn default_voice(&self) -> crate::Result<Option<Voice>> {
+        Ok(self
+            .default_voice
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clone())
+    }
+
+    /// Ask the engine for its voices and cache them, along with how they differ
+    /// from the cached ones. A failure, or an engine without voices, clears the
+    /// cache, so the next `voices` asks the engine again
+    fn refresh_voices(&self) -> crate::Result<(Vec<Voice>, Option<VoicesChangedEvent>)> {
+        let voices = self
+            .with_engine(|engine| {
+                let native_voices = engine.voices()?;
+                Ok(native_voices
+                    .iter()
+                    .map(voice_from_engine)
+                    .collect::<Vec<Voice>>())
+            })
+            .and_then(|voices| {
+                if voices.is_empty() {
+                    return Err(crate::Error::NoVoicesAvailable(PLATFORM));
+                }
+                Ok(voices)
+            });
+
+        let mut cache = self
+            .voice_cache
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        let changed = voices
+            .as_ref()
+            .ok()
+            .and_then(|voices| VoicesChangedEvent::between(cache.as_deref(), voices));
+        *cache = voices.as_ref().ok().cloned();
+        Ok((voices?, changed))
+    }
+
+    fn features(&self) -> crate::Result<TtsFeatures> {
+        self.with_engine(|engine| {
+            let Features {
+                stop,
+                rate,
+                pitch,
+                volume,
+                is_speaking,
+                voice,
+                utterance_callbacks,
+                ..
+            } = engine.supported_features();
+            Ok(TtsFeatures {
+                stop,
+                rate,
+                pitch,
+                volume,
+                voice,
+                is_speaking,
+                utterance_callbacks,
+                // Approximated by the plugin queue, tts-rs has none on any backend
+                pause_resume: true,
+            })
+        })
+    }
+}
+
+impl TtsBackend for SystemBackend {
+    fn name(&self) -> &str {
+        SYSTEM_BACKEND
+    }
+
+    fn speak(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<()> {
+        let (utterance, utterance_callbacks, generation) = {
+            let mut engine = lock_engine(&self.engine)?;
+            let engine = engine.as_mut().ok_or(crate::Error::NotInitialized)?;
+            // Checked under the engine lock, which a stop takes to reach the engine
+            let Some(generation) = self
+                .shared
+                .speaking()
+                .map(|speaking| speaking.generation)
+                .filter(|&generation| !self.shared.is_flushed(generation))
+            else {
+                return Ok(());
+            };
+            // A panic while setting the engine up leaves its settings unknown
+            let mut settings = self.settings.lock().unwrap_or_else(|poisoned| {
+                let mut settings = poisoned.into_inner();
+                *settings = None;
+                settings
+            });
+            let settings =
+                settings.get_or_insert_with(|| EngineSettings::new(engine.voice().ok().flatten()));
+            let utterance = speak_with_engine(engine, request, text, settings)?;
+            let utterance_callbacks = engine.supported_features().utterance_callbacks;
+            (utterance, utterance_callbacks, generation)
+        };
+        self.shared.wait_for_utterance(
+            &self.engine,
+            utterance.as_ref(),
+            utterance_callbacks,
+            generation,
+        );
+        Ok(())
+    }
+
+    fn stop(&self) -> crate::Result<()> {
+        if let Some(engine) = lock_engine(&self.engine)?.as_mut() {
+            engine.stop()?;
+        }
+        Ok(())
+    }
+
+    fn voices(&self) -> crate::Result<Vec<Voice>> {
+        {
+            let cache = self
+                .voice_cache
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?;
+            if let Some(ref voices) = *cache {
+                return Ok(voices.clone());
+            }
+        }
+        Ok(self.refresh_voices()?.0)
+    }
+
+    fn capabilities(&self) -> TtsFeatures {
+        // Nothing is supported until the engine starts
+        self.features().unwrap_or_default()
+    }
+
+    fn synthesize(&self, request: &ValidatedSpeakRequest, text: &str) -> crate::Result<Vec<u8>> {
+        let key = CacheKey::new(&[
+            SYSTEM_BACKEND,
+            PLATFORM,
+            request.voice_id.as_deref().unwrap_or_default(),
+            text,
+            &request.rate.to_string(),
+            &request.pitch.to_string(),
+            &request.volume.to_string(),
+        ]);
+        self.cache.get_or_insert_with(&key, || {
+            #[cfg(any(target_os = "macos", windows))]
+            {
+                render_wav(text, request)
+            }
+            // Speech Dispatcher only plays speech, it can't render it
+            #[cfg(not(any(target_os = "macos", windows)))]
+            {
+                Err(crate::Error::OperationFailed(
+                    "Synthesizing speech to audio is not supported on Linux".to_string(),
+                ))
+            }
+        })
+    }
+}
+
+/// Whether a request's `backend` is the OS engine, which requests naming none use
+fn is_system_backend(backend: Option<&str>) -> bool {
+    backend.unwrap_or(SYSTEM_BACKEND) == SYSTEM_BACKEND
+}
+
+/// Start the worker feeding queued utterances to the backends
+fn start_worker<R: Runtime>(
+    app: &AppHandle<R>,
+    backends: &Arc<BackendRegistry>,
+    shared: &Arc<QueueShared>,
+) -> crate::Result<()> {
+    let emitter = Arc::new(EventEmitter { app: app.clone() });
+    let backends = Arc::clone(backends);
+    let shared = Arc::clone(shared);
+    thread::Builder::new()
+        .name("tts-queue".to_string())
+        .spawn(move || run_queue(backends, shared, emitter))
+        .map_err(|e| crate::Error::OperationFailed(format!("Failed to start TTS queue: {}", e)))?;
+    Ok(())
+}
+
+pub fn init<R: Runtime, C: DeserializeOwned>(
+    app: &AppHandle<R>,
+    _api: PluginApi<R, C>,
+    config: TtsConfig,
+    filters: Vec<Box<dyn TextFilter>>,
+) -> crate::Result<Tts<R>> {
+    let shared = Arc::new(QueueShared::default());
+    let cache = Arc::new(AudioCache::load(app, &config));
+    let system = Arc::new(SystemBackend::new(Arc::clone(&shared), Arc::clone(&cache)));
+    let backends = Arc::new(BackendRegistry::new(system.clone()));
+    backends.register_configured(&config, &cache)?;
+    #[cfg(feature = "piper")]
+    let piper = {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| log::warn!("Piper voices can't be installed: {}", e))
+            .ok();
+        let piper = Arc::new(PiperBackend::new(
+            config.piper.clone().unwrap_or_default(),
+            data_dir,
+        ));
+        backends.register(piper.clone())?;
+        piper
+    };
+    // A missing audio stack at launch must not take the app down, `reinitialize`
+    // retries once it is up
+    if let Err(e) = system.start() {
+        log::error!(
+            "TTS engine failed to initialize, retry with reinitialize: {}",
+            e
+        );
+    }
+
+    config.affixes.validate()?;
+    config.validate_voice_gains()?;
+    let profile = config.default_profile()?;
+    config.validate_max_chunk_length()?;
+    start_worker(app, &backends, &shared)?;
+
+    Ok(Tts {
+        app: app.clone(),
+        affixes: RwLock::new(config.affixes.clone()),
+        filters,
+        lexicon: RwLock::new(Lexicon::load(app)),
+        default_voices: RwLock::new(DefaultVoices::load(app)),
+        voice_gains: RwLock::new(config.voice_gains.clone()),
+        cooldown: Mutex::new(InterruptCooldown::new(&config)),
+        usage: Mutex::new(SessionUsage::new(&config)),
+        lists: Mutex::new(SpeakLists::default()),
+        words: Mutex::new(SpeakLists::default()),
+        enabled: AtomicBool::new(true),
+        stop_pattern: Mutex::new(None),
+        profile: RwLock::new(profile),
+        config,
+        system,
+        backends,
+        #[cfg(feature = "piper")]
+        piper,
+        cache,
+        queue: shared,
+    })
+}
+
+pub struct Tts<R: Runtime> {
+    app: AppHandle<R>,
+    config: TtsConfig,
+    /// Also in `backends`, kept for what only the OS engine offers
+    system: Arc<SystemBackend>,
+    /// The OS engine and the engines registered by the app, spoken through by the
+    /// same queue worker
+    backends: Arc<BackendRegistry>,
+    /// Also in `backends`, kept for managing its voices
+    #[cfg(feature = "piper")]
+    piper: Arc<PiperBackend>,
+    /// Synthesized audio of the OS engine and the built-in backends
+    cache: Arc<AudioCache>,
+    queue: Arc<QueueShared>,
+    affixes: RwLock<Affixes>,
+    /// Host app rewrites from `init_with_filters`, run ahead of the lexicon
+    filters: Vec<Box<dyn TextFilter>>,
+    lexicon: RwLock<Lexicon>,
+    /// Voices saved with `set_default_voice`, applied after the profile's
+    default_voices: RwLock<DefaultVoices>,
+    voice_gains: RwLock<HashMap<String, f32>>,
+    cooldown: Mutex<InterruptCooldown>,
+    usage: Mutex<SessionUsage>,
+    lists: Mutex<SpeakLists>,
+    /// Word-by-word sessions, kept apart from lists so their ids don't mix
+    words: Mutex<SpeakLists>,
+    /// Cleared by `set_enabled(false)`: speaking calls succeed without speaking
+    enabled: AtomicBool,
+    /// Last `stop_if_matches` pattern, kept compiled for repeated checks
+    stop_pattern: Mutex<Option<Regex>>,
+    profile: RwLock<VoiceProfile>,
+}
+
+impl<R: Runtime> Tts<R> {
+    /// Helper to acquire engine lock with proper error handling
+    fn with_engine<T, F>(&self, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&mut TtsEngine) -> crate::Result<T>,
+    {
+        self.system.with_engine(f)
+    }
+
+    fn ensure_initialized(&self) -> crate::Result<()> {
+        self.with_engine(|_| Ok(()))
+    }
+
+    /// Stop the backend speaking the current utterance, the OS engine when none is.
+    /// Called under the handoff lock, so the worker can't start the next sentence
+    /// before the stop and have it cut instead
+    fn stop_speech(&self) -> crate::Result<()> {
+        let backend = self.queue.speaking().and_then(|speaking| speaking.backend);
+        self.backends.get(backend.as_deref())?.stop()
+    }
+
+    /// Speak requests naming `backend` through it instead of the OS engine
+    pub fn register_backend(&self, backend: impl TtsBackend) -> crate::Result<()> {
+        self.backends.register(Arc::new(backend))
+    }
+
+    pub fn get_backends(&self) -> crate::Result<GetBackendsResponse> {
+        let backends = self
+            .backends
+            .all()?
+            .iter()
+            .map(|backend| BackendInfo {
+                name: backend.name().to_string(),
+                features: backend.capabilities(),
+            })
+            .collect();
+        Ok(GetBackendsResponse { backends })
+    }
+
+    pub fn get_cache_stats(&self) -> crate::Result<CacheStatsResponse> {
+        self.cache.stats()
+    }
+
+    pub fn clear_tts_cache(&self) -> crate::Result<ClearCacheResponse> {
+        self.cache.clear()
+    }
+
+    #[cfg(feature = "piper")]
+    pub fn list_piper_voices(&self) -> crate::Result<PiperVoicesResponse> {
+        self.piper.installed()
+    }
+
+    #[cfg(not(feature = "piper"))]
+    pub fn list_piper_voices(&self) -> crate::Result<PiperVoicesResponse> {
+        Err(piper_disabled())
+    }
+
+    #[cfg(feature = "piper")]
+    pub fn download_piper_voice(&self, payload: DownloadPiperVoiceRequest) -> crate::Result<Voice> {
+        self.piper.download(&payload.voice_id)
+    }
+
+    #[cfg(not(feature = "piper"))]
+    pub fn download_piper_voice(
+        &self,
+        _payload: DownloadPiperVoiceRequest,
+    ) -> crate::Result<Voice> {
+        Err(piper_disabled())
+    }
+
+    /// Emit an event about the current utterance, under its event scope if it has one
+    /// and with its id
+    fn emit_event(&self, event_name: &str, mut event: SpeechEvent) {
+        let speaking = self.queue.speaking();
+        if event.id.is_none() {
+            event.id = speaking.as_ref().map(|s| s.id.clone());
+        }
+        let scope = speaking.and_then(|s| s.event_scope);
+        emit_tts_event(&self.app, scope.as_deref(), event_name, event);
+    }
+
+    /// Hand utterances to the queue worker, flushing pending and current speech first
+    /// when `mode` is flush, or only the pending speech when it is replacePending
+    fn enqueue(&self, items: Vec<QueuedUtterance>, mode: QueueMode) -> crate::Result<()> {
+        if mode == QueueMode::Urgent {
+            return self.enqueue_urgent(items);
+        }
+        if items
+            .iter()
+            .any(|item| is_system_backend(item.request.backend.as_deref()))
+        {
+            self.ensure_initialized()?;
+        }
+        if mode == QueueMode::Flush {
+            self.flush_queue()?;
+        }
+        {
+            let mut queue = self
+                .queue
+                .queue
+                .lock()
+                .map_err(|_| crate::Error::MutexPoisoned)?;
+            if mode == QueueMode::ReplacePending {
+                // Same as clear_queue: the engine only has the current utterance, so it
+                // is left to finish without engine.stop()
+                queue.clear_pending();
+            }
+            for item in items {
+                queue.push(item);
+            }
+        }
+        self.queue.notify();
+        Ok(())
+    }
+
+    /// Queue urgent utterances ahead of everything else and interrupt the current
+    /// one, which the worker puts back to be resumed after them
+    fn enqueue_urgent(&self, items: Vec<QueuedUtterance>) -> crate::Result<()> {
+        {
+            // Under the handoff lock, so the worker can't start an urgent utterance
+            // before the stop and have it cut instead
+            let _handoff = self.queue.hand_off();
+            let preempted = {
+                let mut queue = self
+                    .queue
+                    .queue
+                    .lock()
+                    .map_err(|_| crate::Error::MutexPoisoned)?;
+                for item in items {
+                    queue.push_urgent(item);
+                }
+                queue.preempt()
+            };
+            // Like a pause, the interruption isn't reported as a cancel
+            if preempted {
+                self.stop_speech()?;
+                self.queue.set_speaking(None);
+            }
+        }
+        self.queue.notify();
+        Ok(())
+    }
+
+    /// Drop queued utterances and silence the engine
+    fn flush_queue(&self) -> crate::Result<()> {
+        self.queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .flush();
+        self.silence_engine()
+    }
+
+    /// Wake the worker and stop the engine once the queue was flushed
+    fn silence_engine(&self) -> crate::Result<()> {
+        self.queue.notify();
+        // Stopping after the flush means the worker can't submit a stale utterance afterwards
+        let _handoff = self.queue.hand_off();
+        self.stop_speech()?;
+        self.queue.set_speaking(None);
+        Ok(())
+    }
+
+    /// Point the request at the first registered backend of its fallback chain,
+    /// returning the ones to try after it. Fails when none is registered
+    fn resolve_backend(&self, payload: &mut SpeakRequest) -> crate::Result<Vec<String>> {
+        let mut chain = self.config.backend_chain(payload.backend.as_deref());
+        let Some(index) = chain
+            .iter()
+            .position(|name| self.backends.get(Some(name)).is_ok())
+        else {
+            return Err(crate::Error::BackendNotFound(chain.swap_remove(0)));
+        };
+        let fallback = chain.split_off(index + 1);
+        payload.backend = chain.pop();
+        Ok(fallback)
+    }
+
+    /// Resolve defaults, validate and produce the final text the engine will speak,
+    /// one request per segment when the text is split for structural pauses
+    fn prepare(&self, mut payload: SpeakRequest) -> crate::Result<Vec<ValidatedSpeakRequest>> {
+        // Fail before any session or cooldown state changes
+        let fallback = self.resolve_backend(&mut payload)?;
+        if is_system_backend(payload.backend.as_deref()) {
+            self.ensure_initialized()?;
+        }
+        // Ahead of the profile, whose rate it overrides. Only `speak` reports clamping
+        payload.apply_rate_wpm(crate::wpm::NORMAL_WPM);
+        // A detected language picks its own voice instead of the profile's
+        payload.apply_detected_language();
+        payload.apply_profile(
+            &*self
+                .profile
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?,
+        );
+        self.default_voices
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .apply(&mut payload);
+        // Callers may name a voice by its display name, or only by a language, which
+        // the engine has no notion of. Either is resolved to a voice ahead of its gain
+        if payload.voice_id.is_some() || payload.language.is_some() {
+            // Without a voice list, the default voice speaks it with a warning
+            let voices = self
+                .get_voices(GetVoicesRequest {
+                    backend: payload.backend.clone(),
+                    ..Default::default()
+                })
+                .map(|available| available.voices)
+                .unwrap_or_default();
+            payload.match_voice_name(&voices);
+            if let (None, Some(language)) = (&payload.voice_id, payload.language.as_deref()) {
+                let default_voice = self.default_voice_for(payload.backend.as_deref())?;
+                let default_voice_id = default_voice.as_ref().map(|voice| voice.id.as_str());
+                payload.voice_id = match voice_for_language(&voices, language, default_voice_id) {
+                    Some(LanguageMatch::Speaks(voice)) => Some(voice.id.clone()),
+                    Some(LanguageMatch::OtherVariant(_)) if payload.strict_voice => {
+                        return Err(crate::Error::VoiceNotFound(format!(
+                            "No voice found for language '{}'",
+                            language
+                        )));
+                    }
+                    Some(LanguageMatch::OtherVariant(voice)) => {
+                        payload.voice_warning = Some(format!(
+                            "No voice found for language '{}', using '{}' ({})",
+                            language, voice.name, voice.language
+                        ));
+                        Some(voice.id.clone())
+                    }
+                    None => None,
+                };
+            }
+        }
+        payload.apply_defaults(&self.config);
+        payload.apply_voice_gain(
+            &*self
+                .voice_gains
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?,
+        );
+        // Validate the whole text before splitting it up
+        payload.validate()?;
+
+        // SSML skips affixes like on mobile, where they'd break the markup
+        let affixes = if payload.use_affixes && !payload.ssml {
+            self.affixes
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?
+                .clone()
+        } else {
+            Affixes::default()
+        };
+        let ssml = payload.ssml;
+        let original = payload.text.clone();
+        let segments = if ssml {
+            // None of the tts crate's backends take markup, its breaks become pauses
+            log::debug!("{}", crate::ssml::PLAIN_TEXT_WARNING);
+            payload.into_plain_segments()
+        } else {
+            payload.into_segments()
+        };
+        let last = segments.len() - 1;
+        let max_chunk_length = self.config.max_chunk_length();
+        let lexicon = self
+            .lexicon
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        let mut words = WordAligner::new(&original);
+        let mut sentence_index = 0;
+        let mut prepared = Vec::with_capacity(segments.len());
+        for (index, segment) in segments.into_iter().enumerate() {
+            let raw = segment.preprocess_options().raw;
+            let mut validated = segment.validate()?;
+            validated.fallback.clone_from(&fallback);
+            // Host filters and respellings are left out of raw text like every other rewrite
+            if !raw {
+                let filtered = crate::filter::apply(&self.filters, validated.text);
+                validated.text = lexicon.apply(&filtered).into_owned();
+            }
+            // Affixes go on after preprocessing so normalization can't mangle them
+            let text = affixes.apply_at(&validated.text, index == 0, index == last);
+            // Sentence starts are reported against the caller's text, which SSML isn't
+            if !ssml {
+                let start = affixes.text_start_at(index == 0);
+                let aligned = words.align(&text, start, validated.text.trim());
+                validated.sentences = sentence_offsets(&text, &aligned, &mut sentence_index);
+                validated.words = sentence_words(&validated.sentences, &aligned);
+            }
+            validated.text = text;
+            // Some engines truncate long input, so long text is fed to them in chunks
+            prepared.extend(validated.into_chunks(max_chunk_length));
+        }
+        Ok(prepared)
+    }
+
+    pub fn export_profile(&self) -> crate::Result<VoiceProfile> {
+        Ok(self
+            .profile
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clone())
+    }
+
+    pub fn import_profile(&self, payload: VoiceProfile) -> crate::Result<ImportProfileResponse> {
+        let voices = self.voices_or_none()?;
+        let (profile, warnings) =
+            payload.sanitize(|voice_id| voices.iter().any(|v| v.id == voice_id))?;
+        for warning in &warnings {
+            log::warn!("Importing voice profile: {}", warning);
+        }
+
+        *self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)? = profile.clone();
+        Ok(ImportProfileResponse { profile, warnings })
+    }
+
+    pub fn set_voice(&self, payload: SetVoiceRequest) -> crate::Result<SetVoiceResponse> {
+        payload.validate()?;
+        let voices = self.voices_or_none()?;
+        Ok(self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .set_voice(payload.voice_id, |voice_id| {
+                voices.iter().any(|v| v.id == voice_id)
+            }))
+    }
+
+    pub fn set_default_voice(
+        &self,
+        payload: SetDefaultVoiceRequest,
+    ) -> crate::Result<SetDefaultVoiceResponse> {
+        payload.validate()?;
+        let voices = self.voices_or_none()?;
+        self.default_voices
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .set(payload.voice_id, payload.language, |voice_id| {
+                voices.iter().any(|v| v.id == voice_id)
+            })
+    }
+
+    pub fn set_rate(&self, payload: SetProsodyRequest) -> crate::Result<ProsodyResponse> {
+        let mut profile = self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(ProsodyResponse {
+            value: profile.set_rate(payload.value)?,
+        })
+    }
+
+    pub fn set_pitch(&self, payload: SetProsodyRequest) -> crate::Result<ProsodyResponse> {
+        let mut profile = self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(ProsodyResponse {
+            value: profile.set_pitch(payload.value)?,
+        })
+    }
+
+    pub fn set_volume(&self, payload: SetProsodyRequest) -> crate::Result<ProsodyResponse> {
+        let mut profile = self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(ProsodyResponse {
+            value: profile.set_volume(payload.value)?,
+        })
+    }
+
+    pub fn set_affixes(&self, payload: Affixes) -> crate::Result<()> {
+        payload.validate()?;
+        *self
+            .affixes
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)? = payload;
+        Ok(())
+    }
+
+    pub fn add_pronunciation(&self, payload: LexiconEntry) -> crate::Result<()> {
+        self.lexicon
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .add(payload)
+    }
+
+    pub fn remove_pronunciation(
+        &self,
+        payload: RemovePronunciationRequest,
+    ) -> crate::Result<RemovePronunciationResponse> {
+        let removed = self
+            .lexicon
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .remove(&payload.word)?;
+        Ok(RemovePronunciationResponse { removed })
+    }
+
+    pub fn list_pronunciations(&self) -> crate::Result<ListPronunciationsResponse> {
+        Ok(ListPronunciationsResponse {
+            entries: self
+                .lexicon
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?
+                .entries()
+                .to_vec(),
+        })
+    }
+
+    pub fn set_enabled(&self, payload: SetEnabledRequest) -> crate::Result<()> {
+        self.enabled.store(payload.enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> crate::Result<IsEnabledResponse> {
+        Ok(IsEnabledResponse {
+            enabled: self.enabled.load(Ordering::Relaxed),
+        })
+    }
+
+    pub fn set_voice_gain(&self, payload: SetVoiceGainRequest) -> crate::Result<()> {
+        payload.validate()?;
+        let mut gains = self
+            .voice_gains
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        match payload.gain {
+            Some(gain) => gains.insert(payload.voice_id, gain),
+            None => gains.remove(&payload.voice_id),
+        };
+        Ok(())
+    }
+
+    pub fn speak(&self, payload: SpeakRequest) -> crate::Result<SpeakResponse> {
+        self.speak_notifying(payload, None)
+    }
+
+    /// Speak like `speak`, then block until the utterance ends
+    pub fn speak_and_wait(&self, payload: SpeakRequest) -> crate::Result<SpeakAndWaitResponse> {
+        let (sender, receiver) = mpsc::channel();
+        let spoken = self.speak_notifying(payload, Some(sender))?;
+        if !spoken.success {
+            return Ok(SpeakAndWaitResponse::new(SpeechOutcome::Stopped, spoken));
+        }
+        // Nothing was queued while the plugin is disabled
+        if spoken.utterance_id.is_none() {
+            return Ok(SpeakAndWaitResponse::new(SpeechOutcome::Skipped, spoken));
+        }
+
+        // Every part reports how it ended, the channel closes once all of them have
+        for outcome in receiver {
+            match outcome {
+                UtteranceOutcome::Finished(_) => {}
+                UtteranceOutcome::Interrupted => {
+                    return Ok(SpeakAndWaitResponse::new(SpeechOutcome::Stopped, spoken))
+                }
+                UtteranceOutcome::Failed(e) => return Ok(SpeakAndWaitResponse::failed(e, spoken)),
+            }
+        }
+        Ok(SpeakAndWaitResponse::new(SpeechOutcome::Completed, spoken))
+    }
+
+    /// Validate and queue a request, reporting how each of its parts ends to `on_finish`
+    fn speak_notifying(
+        &self,
+        mut payload: SpeakRequest,
+        on_finish: Option<mpsc::Sender<UtteranceOutcome>>,
+    ) -> crate::Result<SpeakResponse> {
+        // Validate input first (before touching the queue). A disabled plugin
+        // doesn't need the engine or backend `prepare` resolves
+        let ssml = payload.ssml;
+        let pronunciations = payload.uses_pronunciations();
+        let rate_warning = payload.apply_rate_wpm(crate::wpm::NORMAL_WPM);
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakResponse::disabled());
+        }
+        let segments = self.prepare(payload)?;
+
+        // Held until the speech is queued so concurrent requests can't overshoot the limit
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let chars = spoken_chars(segments.iter().map(|segment| segment.text.as_str()));
+        usage.check(chars)?;
+
+        let items = QueuedUtterance::from_requests(segments)
+            .into_iter()
+            .map(|item| match on_finish {
+                Some(ref sender) => item.with_finish_notifier(sender.clone()),
+                None => item,
+            })
+            .collect();
+        let mut response = self.queue_items(items)?;
+        if ssml && response.success && response.warning.is_none() {
+            response.warning = Some(crate::ssml::PLAIN_TEXT_WARNING.to_string());
+        }
+        if pronunciations && response.success && response.warning.is_none() {
+            response.warning = Some(PRONUNCIATIONS_WARNING.to_string());
+        }
+        if response.success && response.warning.is_none() {
+            response.warning = rate_warning;
+        }
+        response.remaining_chars = if response.success {
+            usage.charge(chars)
+        } else {
+            usage.remaining()
+        };
+        Ok(response)
+    }
+
+    /// Warning for an engine that lists no voices, which often speaks nothing at all
+    fn no_voices_warning(&self) -> Option<String> {
+        match self.get_voices(GetVoicesRequest::default()) {
+            Err(e @ crate::Error::NoVoicesAvailable(_)) => {
+                Some(format!("{}, speech may be silent", e))
+            }
+            _ => None,
+        }
+    }
+
+    /// All voices, or none when the engine lists none, for lookups that accept an
+    /// unavailable voice with a warning
+    fn voices_or_none(&self) -> crate::Result<Vec<Voice>> {
+        match self.get_voices(GetVoicesRequest::default()) {
+            Ok(response) => Ok(response.voices),
+            Err(crate::Error::NoVoicesAvailable(_)) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Warning for a requested voice or language the engine won't use, which then
+    /// speaks with its default voice. A `strict_voice` request fails instead
+    fn voice_warning(&self, request: &ValidatedSpeakRequest) -> crate::Result<Option<String>> {
+        if request.voice_warning.is_some() {
+            return Ok(request.voice_warning.clone());
+        }
+        match self.voice_fallback(request)? {
+            Some(reason) if request.strict_voice => Err(crate::Error::VoiceNotFound(reason)),
+            Some(reason) => Ok(Some(format!("{}, using default voice", reason))),
+            None => Ok(None),
+        }
+    }
+
+    /// Why the engine's default voice speaks `request` in place of the one it asked for
+    fn voice_fallback(&self, request: &ValidatedSpeakRequest) -> crate::Result<Option<String>> {
+        let Some(voice_id) = request.voice_id.as_deref() else {
+            // `prepare` found no voice for the language
+            return Ok(request
+                .language
+                .as_ref()
+                .map(|language| format!("No voice found for language '{}'", language)));
+        };
+        let backend = self.backends.get(request.backend.as_deref())?;
+        if !backend.capabilities().voice {
+            return Ok(Some(format!(
+                "Voice '{}' can't be selected on this engine",
+                voice_id
+            )));
+        }
+        // Without a voice list, leave it to the engine rather than fail the request
+        let Ok(available) = self.get_voices(GetVoicesRequest {
+            backend: request.backend.clone(),
+            ..Default::default()
+        }) else {
+            return Ok(None);
+        };
+        if available.voices.iter().any(|voice| voice.id == voice_id) {
+            return Ok(None);
+        }
+        Ok(Some(format!("Voice '{}' not found", voice_id)))
+    }
+
+    /// The voice `request` starts speaking with, `None` when the engine keeps
+    /// whichever voice it has
+    fn used_voice_id(&self, request: &ValidatedSpeakRequest) -> crate::Result<Option<String>> {
+        if self.voice_fallback(request)?.is_none() {
+            return Ok(request.voice_id.clone());
+        }
+        Ok(self
+            .default_voice_for(request.backend.as_deref())?
+            .map(|voice| voice.id))
+    }
+
+    /// Queue utterances, subject to the interrupt cooldown but not the session limit.
+    /// The first one's queue mode applies to all of them
+    fn queue_items(&self, items: Vec<QueuedUtterance>) -> crate::Result<SpeakResponse> {
+        let resolved = self
+            .cooldown
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .resolve(items[0].request.queue_mode, Instant::now());
+        let Some(queue_mode) = resolved else {
+            return Ok(SpeakResponse {
+                success: false,
+                warning: Some(
+                    "Dropped: interrupted too soon after the last interruption".to_string(),
+                ),
+                ..Default::default()
+            });
+        };
+
+        // Utterances may each pick a voice, warn about the first one the engine won't use.
+        // An OS engine without any voices is worth knowing about first
+        let system = is_system_backend(items[0].request.backend.as_deref());
+        let mut warning = if system {
+            self.no_voices_warning()
+        } else {
+            None
+        };
+        let mut checked = None;
+        for request in items.iter().map(|item| &item.request) {
+            let voice = Some((
+                request.backend.as_deref(),
+                request.voice_id.as_deref(),
+                request.language.as_deref(),
+                request.strict_voice,
+            ));
+            if voice == checked {
+                continue;
+            }
+            checked = voice;
+            // Strict voices are still checked once there's a warning, to fail instead
+            if warning.is_none() || request.strict_voice {
+                warning = warning.or(self.voice_warning(request)?);
+            }
+        }
+        // The OS engine plays its own audio, which the plugin can't measure
+        if warning.is_none() && items[0].request.normalize_loudness && system {
+            warning = Some(
+                "Loudness isn't normalized for the system speech engine, use voiceGains"
+                    .to_string(),
+            );
+        }
+        let used_voice_id = self.used_voice_id(&items[0].request)?;
+
+        // speech:start is emitted by the queue worker once the engine actually starts
+        // each utterance
+        let utterance_id = items.last().map(|item| item.id.clone());
+        let backend = items[0]
+            .request
+            .backend
+            .clone()
+            .unwrap_or_else(|| SYSTEM_BACKEND.to_string());
+
+        self.enqueue(items, queue_mode)?;
+
+        Ok(SpeakResponse {
+            success: true,
+            warning,
+            utterance_id,
+            backend: Some(backend),
+            used_voice_id,
+            ..Default::default()
+        })
+    }
+
+    pub fn speak_segments(
+        &self,
+        payload: SpeakSegmentsRequest,
+    ) -> crate::Result<SpeakSegmentsResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakSegmentsResponse::new(
+                Vec::new(),
+                SpeakResponse::disabled(),
+            ));
+        }
+        let ssml = payload.segments.iter().any(|segment| segment.ssml);
+
+        let segments = payload
+            .segments
+            .into_iter()
+            .map(|segment| self.prepare(segment))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        // Held until the speech is queued so concurrent requests can't overshoot the limit
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let chars = spoken_chars(segments.iter().flatten().map(|part| part.text.as_str()));
+        usage.check(chars)?;
+
+        // Like a single `speak`, a segment split into parts is identified by its last part
+        let total = segments.len();
+        let mut utterance_ids = Vec::with_capacity(total);
+        let mut items = Vec::new();
+        for (index, parts) in segments.into_iter().enumerate() {
+            let parts = QueuedUtterance::from_requests(parts);
+            let utterance_id = parts.last().map(|item| item.id.clone()).unwrap_or_default();
+            let event = SegmentStartEvent {
+                index: index as u32,
+                total: total as u32,
+                utterance_id: utterance_id.clone(),
+            };
+            let event = serde_json::to_value(event)
+                .map_err(|e| crate::Error::OperationFailed(e.to_string()))?;
+            for (part, item) in parts.into_iter().enumerate() {
+                items.push(if part == 0 {
+                    item.with_start_event("segment:start", event.clone())
+                } else {
+                    item
+                });
+            }
+            utterance_ids.push(utterance_id);
+        }
+
+        let mut response = self.queue_items(items)?;
+        if ssml && response.success && response.warning.is_none() {
+            response.warning = Some(crate::ssml::PLAIN_TEXT_WARNING.to_string());
+        }
+        response.remaining_chars = if response.success {
+            usage.charge(chars)
+        } else {
+            utterance_ids.clear();
+            usage.remaining()
+        };
+        Ok(SpeakSegmentsResponse::new(utterance_ids, response))
+    }
+
+    pub fn reset_session_usage(&self) -> crate::Result<SessionUsageResponse> {
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        usage.reset();
+        Ok(usage.snapshot())
+    }
+
+    pub fn speak_sequence(
+        &self,
+        payload: SpeakSequenceRequest,
+    ) -> crate::Result<SpeakSequenceResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakSequenceResponse::disabled());
+        }
+        let mut segments = Vec::new();
+        for item in payload.items {
+            segments.extend(self.prepare(item)?);
+        }
+
+        // Held until the speech is queued, which also keeps overlapping sequences in order
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let chars = spoken_chars(segments.iter().map(|segment| segment.text.as_str()));
+        usage.check(chars)?;
+
+        let admission = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .admit_sequence(self.config.sequence_overlap)?;
+        let queue_mode = match admission {
+            // Without an earlier sequence, the first item decides how to treat other speech
+            SequenceAdmission::Idle => segments[0].queue_mode,
+            SequenceAdmission::Appended => QueueMode::Add,
+            SequenceAdmission::Interrupted { stop_current } => {
+                if stop_current {
+                    self.emit_event(
+                        "speech:cancel",
+                        SpeechEvent {
+                            id: None,
+                            event_type: Some("cancel".to_string()),
+                        },
+                    );
+                    self.silence_engine()?;
+                }
+                QueueMode::Add
+            }
+        };
+
+        let sequence_id = uuid::Uuid::new_v4().to_string();
+        let items = QueuedUtterance::from_requests(segments)
+            .into_iter()
+            .map(|item| item.with_sequence(&sequence_id))
+            .collect();
+        self.enqueue(items, queue_mode)?;
+
+        Ok(SpeakSequenceResponse {
+            success: true,
+            sequence_id,
+            interrupted: matches!(admission, SequenceAdmission::Interrupted { .. }),
+            warning: None,
+            remaining_chars: usage.charge(chars),
+        })
+    }
+
+    pub fn speak_from_offset(
+        &self,
+        payload: SpeakFromOffsetRequest,
+    ) -> crate::Result<SpeakFromOffsetResponse> {
+        let (request, offset) = payload.remainder()?;
+        Ok(SpeakFromOffsetResponse::new(offset, self.speak(request)?))
+    }
+
+    pub fn speak_outline(
+        &self,
+        payload: SpeakOutlineRequest,
+    ) -> crate::Result<SpeakOutlineResponse> {
+        payload.speak.validate()?;
+        let outline = crate::preprocess::outline(&payload.speak.text);
+        if outline.is_empty() {
+            let remaining_chars = self
+                .usage
+                .lock()
+                .map_err(|_| crate::Error::MutexPoisoned)?
+                .remaining();
+            return Ok(SpeakOutlineResponse {
+                outline,
+                remaining_chars,
+            });
+        }
+
+        let spoken = self.speak_sequence(SpeakSequenceRequest {
+            items: payload.items(&outline),
+        })?;
+        Ok(SpeakOutlineResponse {
+            outline,
+            remaining_chars: spoken.remaining_chars,
+        })
+    }
+
+    pub fn speak_section(&self, payload: SpeakSectionRequest) -> crate::Result<SpeakResponse> {
+        self.speak(payload.section_request()?)
+    }
+
+    pub fn speak_list_begin(
+        &self,
+        payload: SpeakListBeginRequest,
+    ) -> crate::Result<SpeakListBeginResponse> {
+        payload.validate()?;
+        let total = payload.items.len() as u32;
+        let session_id = self
+            .lists
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .begin(payload.items);
+        Ok(SpeakListBeginResponse { session_id, total })
+    }
+
+    pub fn speak_list_next(
+        &self,
+        payload: SpeakListSessionRequest,
+    ) -> crate::Result<SpeakListNextResponse> {
+        // Held while speaking so concurrent calls can't speak the same item twice
+        let mut lists = self.lists.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let (index, item) = lists.peek(&payload.session_id)?;
+        let spoken = self.speak(item)?;
+        // Dropped items stay current so the next call retries them
+        let has_more = !spoken.success || lists.advance(&payload.session_id);
+        Ok(SpeakListNextResponse::new(index, has_more, spoken))
+    }
+
+    pub fn speak_list_reset(
+        &self,
+        payload: SpeakListSessionRequest,
+    ) -> crate::Result<SpeakListResetResponse> {
+        let removed = self
+            .lists
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .reset(&payload.session_id);
+        Ok(SpeakListResetResponse { removed })
+    }
+
+    pub fn speak_words_begin(
+        &self,
+        payload: SpeakWordsBeginRequest,
+    ) -> crate::Result<SpeakWordsBeginResponse> {
+        let items = payload.items();
+        if items.is_empty() {
+            // Nothing to step through, so no session is kept
+            return Ok(SpeakWordsBeginResponse::default());
+        }
+        payload.speak.validate()?;
+        let total = items.len() as u32;
+        let session_id = self
+            .words
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .begin(items);
+        Ok(SpeakWordsBeginResponse {
+            session_id: Some(session_id),
+            total,
+        })
+    }
+
+    pub fn speak_word_next(
+        &self,
+        payload: SpeakListSessionRequest,
+    ) -> crate::Result<SpeakWordNextResponse> {
+        // Held while speaking so a bouncing switch can't speak the same word twice
+        let mut words = self.words.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let (_, item) = words.peek(&payload.session_id)?;
+        let word = item.text.clone();
+        let spoken = self.speak(item)?;
+        // Finished sessions are removed by advance. A dropped word is still left,
+        // as the next call retries it
+        if spoken.success {
+            words.advance(&payload.session_id);
+        }
+        let remaining = words.remaining(&payload.session_id);
+        Ok(SpeakWordNextResponse {
+            success: spoken.success,
+            word,
+            remaining: remaining as u32,
+            warning: spoken.warning,
+            remaining_chars: spoken.remaining_chars,
+        })
+    }
+
+    pub fn stop(&self) -> crate::Result<StopResponse> {
+        // Backends don't report stops, so the cancel is emitted here for all of them
+        self.emit_event(
+            "speech:cancel",
+            SpeechEvent {
+                id: None,
+                event_type: Some("cancel".to_string()),
+            },
+        );
+
+        self.flush_queue()?;
+        Ok(StopResponse { success: true })
+    }
+
+    pub fn get_queue_length(&self) -> crate::Result<QueueLengthResponse> {
+        let queue = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(QueueLengthResponse {
+            length: queue.pending_len() as u32,
+        })
+    }
+
+    pub fn get_queue(&self) -> crate::Result<GetQueueResponse> {
+        let queue = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        let items = queue
+            .pending()
+            .enumerate()
+            .map(|(position, item)| {
+                let request = &item.request;
+                QueuedItem::new(
+                    item.id.clone(),
+                    &request.text,
+                    request.voice_id.clone(),
+                    position,
+                )
+            })
+            .collect();
+        Ok(GetQueueResponse { items })
+    }
+
+    pub fn clear_queue(&self) -> crate::Result<ClearQueueResponse> {
+        // The worker only hands the engine one utterance at a time, so dropping the
+        // pending ones can't touch what is being spoken
+        let cleared = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clear_pending();
+        Ok(ClearQueueResponse {
+            cleared: cleared as u32,
+        })
+    }
+
+    pub fn remove_from_queue(
+        &self,
+        payload: RemoveFromQueueRequest,
+    ) -> crate::Result<RemoveFromQueueResponse> {
+        // Like clear_queue, the utterance being spoken is out of reach
+        let removed = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .remove_pending(&payload.utterance_id);
+        Ok(RemoveFromQueueResponse { removed })
+    }
+
+    pub fn stop_if_matches(
+        &self,
+        payload: StopIfMatchesRequest,
+    ) -> crate::Result<StopIfMatchesResponse> {
+        let pattern = self.compile_stop_pattern(&payload)?;
+        {
+            // Checked and flushed under one lock so the next utterance can't be cut instead
+            let mut queue = self
+                .queue
+                .queue
+                .lock()
+                .map_err(|_| crate::Error::MutexPoisoned)?;
+            if !queue
+                .current_text()
+                .is_some_and(|text| pattern.is_match(text))
+            {
+                return Ok(StopIfMatchesResponse::default());
+            }
+            queue.flush();
+        }
+
+        self.emit_event(
+            "speech:cancel",
+            SpeechEvent {
+                id: None,
+                event_type: Some("cancel".to_string()),
+            },
+        );
+        self.silence_engine()?;
+        Ok(StopIfMatchesResponse {
+            stopped: true,
+            note: None,
+        })
+    }
+
+    pub fn skip_to_next(&self) -> crate::Result<SkipToNextResponse> {
+        let next_utterance_id = {
+            // Under the handoff lock, so the worker can't start the next utterance
+            // before the stop and have it cut instead
+            let _handoff = self.queue.hand_off();
+            let mut queue = self
+                .queue
+                .queue
+                .lock()
+                .map_err(|_| crate::Error::MutexPoisoned)?;
+            if !queue.skip_current() {
+                return Ok(SkipToNextResponse::default());
+            }
+            let next_utterance_id = queue.pending().next().map(|item| item.id.clone());
+            drop(queue);
+            // Nothing to cancel during the pause after an utterance, only the pause is cut
+            if self.queue.speaking().is_some() {
+                self.emit_event(
+                    "speech:cancel",
+                    SpeechEvent {
+                        id: None,
+                        event_type: Some("cancel".to_string()),
+                    },
+                );
+            }
+            self.stop_speech()?;
+            self.queue.set_speaking(None);
+            next_utterance_id
+        };
+        self.queue.notify();
+        Ok(SkipToNextResponse {
+            skipped: true,
+            next_utterance_id,
+        })
+    }
+
+    /// Compile the pattern, reusing the last one when it didn't change
+    fn compile_stop_pattern(&self, payload: &StopIfMatchesRequest) -> crate::Result<Regex> {
+        let mut cached = self
+            .stop_pattern
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        if let Some(ref regex) = *cached {
+            if regex.as_str() == payload.pattern {
+                return Ok(regex.clone());
+            }
+        }
+        let regex = payload.compile()?;
+        *cached = Some(regex.clone());
+        Ok(regex)
+    }
+
+    pub fn stop_gentle(&self, payload: StopGentleRequest) -> crate::Result<StopGentleResponse> {
+        let Some(speaking) = self.queue.speaking() else {
+            self.stop()?;
+            return Ok(StopGentleResponse {
+                success: true,
+                faded: false,
+                note: None,
+            });
+        };
+        let backend = self.backends.get(speaking.backend.as_deref())?;
+        // Nothing queued may start while the current utterance fades
+        self.queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clear_pending();
+        let duration = Duration::from_millis(u64::from(payload.duration_ms)).min(MAX_FADE);
+        let faded = backend.fade_out(duration)?;
+        self.stop()?;
+        Ok(if faded {
+            StopGentleResponse::faded()
+        } else {
+            StopGentleResponse::backend_stopped_immediately(backend.name())
+        })
+    }
+
+    /// Resolve a request into the one utterance the OS engine or its backend
+    /// renders, its text joined without structural pauses, plus any voice warning.
+    /// The tts crate only plays speech, so audio comes from the engine directly.
+    /// Rendering makes no sound, so it isn't muted by set_enabled and doesn't count
+    /// against the session limit
+    fn prepare_render(
+        &self,
+        payload: SpeakRequest,
+    ) -> crate::Result<(ValidatedSpeakRequest, Option<String>)> {
+        let segments = self.prepare(payload)?;
+        let system = is_system_backend(segments[0].backend.as_deref());
+        let warning = if system {
+            self.no_voices_warning()
+        } else {
+            None
+        };
+        let warning = match warning {
+            Some(warning) => Some(warning),
+            None => self.voice_warning(&segments[0])?,
+        };
+        #[cfg(target_os = "macos")]
+        let warning = warning.or_else(|| {
+            (system && segments[0].pitch != 1.0)
+                .then(|| "Pitch isn't applied when synthesizing speech on macOS".to_string())
+        });
+        let text = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let request = ValidatedSpeakRequest {
+            text,
+            ..segments[0].clone()
+        };
+        Ok((request, warning))
+    }
+
+    /// Render `text` to WAV with the backend the request names, or the OS engine,
+    /// then each of its fallbacks until one succeeds
+    fn render_wav(&self, text: &str, request: &ValidatedSpeakRequest) -> crate::Result<Vec<u8>> {
+        let mut rendered = self.render_wav_with(text, request);
+        for name in &request.fallback {
+            let Err(ref e) = rendered else {
+                break;
+            };
+            if self.backends.get(Some(name)).is_err() {
+                continue;
+            }
+            log::warn!(
+                "Failed to synthesize speech, falling back to {}: {}",
+                name,
+                e
+            );
+            let fallback = ValidatedSpeakRequest {
+                backend: Some(name.clone()),
+                ..request.clone()
+            };
+            rendered = self.render_wav_with(text, &fallback);
+        }
+        rendered
+    }
+
+    fn render_wav_with(
+        &self,
+        text: &str,
+        request: &ValidatedSpeakRequest,
+    ) -> crate::Result<Vec<u8>> {
+        self.backends
+            .get(request.backend.as_deref())?
+            .synthesize(request, text)
+    }
+
+    pub fn speak_to_file(&self, payload: SpeakToFileRequest) -> crate::Result<SpeakToFileResponse> {
+        // Still reject invalid requests so callers find out on every platform
+        payload.validate()?;
+        let SpeakToFileRequest { speak, output_path } = payload;
+        let (request, warning) = self.prepare_render(speak)?;
+        let wav = self.render_wav(&request.text, &request)?;
+        let path = std::path::Path::new(&output_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, wav)?;
+        Ok(SpeakToFileResponse {
+            path: output_path,
+            warning,
+        })
+    }
+
+    pub fn synthesize(&self, payload: SpeakRequest) -> crate::Result<Vec<u8>> {
+        payload.validate()?;
+        let (request, warning) = self.prepare_render(payload)?;
+        if let Some(warning) = warning {
+            log::warn!("{}", warning);
+        }
+        self.render_wav(&request.text, &request)
+    }
+
+    /// Render speech a sentence at a time, handing each sentence's WAV to
+    /// `on_chunk` as soon as it is ready, so playback can start before the whole
+    /// text is synthesized
+    pub fn synthesize_stream(
+        &self,
+        payload: SpeakRequest,
+        mut on_chunk: impl FnMut(Vec<u8>) -> crate::Result<()>,
+    ) -> crate::Result<SynthesizeStreamResponse> {
+        payload.validate()?;
+        let (request, warning) = self.prepare_render(payload)?;
+        let mut chunks = 0;
+        // SSML was already reduced to plain text, so the text splits into sentences
+        for text in crate::preprocess::sentences(&request.text) {
+            on_chunk(self.render_wav(text, &request)?)?;
+            chunks += 1;
+        }
+        Ok(SynthesizeStreamResponse { chunks, warning })
+    }
+
+    pub fn count_words(&self, payload: SpeakRequest) -> crate::Result<WordCountResponse> {
+        let text = self
+            .prepare(payload)?
+            .into_iter()
+            .map(|segment| segment.text)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(WordCountResponse::for_spoken_text(&text))
+    }
+
+    pub fn get_voices(&self, payload: GetVoicesRequest) -> crate::Result<GetVoicesResponse> {
+        // The OS engine's voices are cached until `refresh_voices`
+        let voices = self.backends.get(payload.backend.as_deref())?.voices()?;
+        Ok(self.filter_voices(&voices, &payload))
+    }
+
+    /// Served from the voice cache like `get_voices`
+    pub fn get_languages(&self) -> crate::Result<GetLanguagesResponse> {
+        let voices = self.get_voices(GetVoicesRequest::default())?.voices;
+        Ok(GetLanguagesResponse::from_voices(&voices))
+    }
+
+    /// Re-read the voice list from the engine, e.g. after system voices were installed.
+    /// `tts://voices-changed` is emitted when it differs from the cached one
+    pub fn refresh_voices(&self) -> crate::Result<GetVoicesResponse> {
+        let (voices, changed) = self.system.refresh_voices()?;
+        if let Some(event) = changed {
+            emit_tts_event(&self.app, None, "voices-changed", event);
+        }
+        Ok(GetVoicesResponse { voices })
+    }
+
+    fn filter_voices(&self, voices: &[Voice], payload: &GetVoicesRequest) -> GetVoicesResponse {
+        let filtered: Vec<Voice> = voices
+            .iter()
+            .filter(|v| payload.matches(v))
+            .cloned()
+            .collect();
+
+        GetVoicesResponse { voices: filtered }
+    }
+
+    pub fn is_speaking(&self) -> crate::Result<IsSpeakingResponse> {
+        let queued = !self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .is_idle();
+        let speaking =
+            queued || self.with_engine(|engine| engine.is_speaking().map_err(Into::into))?;
+        // Empty between utterances, e.g. during `pause_after_ms`
+        let current = self.queue.speaking().filter(|_| speaking);
+        Ok(IsSpeakingResponse {
+            speaking,
+            current_utterance_id: current.as_ref().map(|s| s.id.clone()),
+            current_text: current.map(|s| s.text),
+        })
+    }
+
+    pub fn get_engine_params(&self) -> crate::Result<EngineParamsResponse> {
+        // Getters fail with UnsupportedFeature on backends that can't report a value
+        self.with_engine(|engine| {
+            let native_rate = engine.get_rate().ok();
+            Ok(EngineParamsResponse {
+                rate: native_rate.map(|rate| rate_from_platform(engine, rate)),
+                native_rate,
+                pitch: engine.get_pitch().ok(),
+                volume: engine.get_volume().ok(),
+                voice_id: engine.voice().ok().flatten().map(|voice| voice.id()),
+            })
+        })
+    }
+
+    pub fn resolve_voice(&self, payload: ResolveVoiceRequest) -> crate::Result<Option<Voice>> {
+        payload.validate()?;
+        let voices = self.get_voices(GetVoicesRequest::default())?.voices;
+        Ok(payload.resolve(&voices).cloned())
+    }
+
+    pub fn get_current_voice(&self) -> crate::Result<Option<Voice>> {
+        // Backends that can't report their voice fail with UnsupportedFeature
+        self.with_engine(|engine| {
+            let voice = engine.voice().ok().flatten();
+            Ok(voice.as_ref().map(voice_from_engine))
+        })
+    }
+
+    pub fn get_best_voice(&self, payload: GetBestVoiceRequest) -> crate::Result<Option<Voice>> {
+        payload.validate()?;
+        let voices = self
+            .get_voices(GetVoicesRequest {
+                backend: payload.backend.clone(),
+                ..Default::default()
+            })?
+            .voices;
+        let default_voice = self.default_voice_for(payload.backend.as_deref())?;
+        let default_voice_id = default_voice.as_ref().map(|voice| voice.id.as_str());
+        Ok(
+            voice_for_language(&voices, &payload.language, default_voice_id)
+                .map(|found| found.voice().clone()),
+        )
+    }
+
+    /// The system default voice, which only the OS engine speaks with
+    fn default_voice_for(&self, backend: Option<&str>) -> crate::Result<Option<Voice>> {
+        if !is_system_backend(backend) {
+            return Ok(None);
+        }
+        self.system.default_voice()
+    }
+
+    pub fn get_default_voice(&self) -> crate::Result<DefaultVoiceResponse> {
+        // Fails until the engine starts. tts-rs can't read the system locale, so
+        // the default voice's language stands in for it
+        self.with_engine(|_| Ok(()))?;
+        let voice = self.default_voice_for(None)?;
+        Ok(DefaultVoiceResponse {
+            language: voice.as_ref().map(|voice| voice.language.clone()),
+            voice,
+        })
+    }
+
+    pub fn is_paused(&self) -> crate::Result<IsPausedResponse> {
+        let paused = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .is_paused();
+        Ok(IsPausedResponse { paused })
+    }
+
+    pub fn is_initialized(&self) -> crate::Result<IsInitializedResponse> {
+        if !self.system.is_started()? {
+            return Ok(IsInitializedResponse::default());
+        }
+        // Get voice count from cache or fetch
+        let voice_count = self
+            .get_voices(GetVoicesRequest::default())
+            .map(|r| r.voices.len() as u32)
+            .unwrap_or(0);
+        Ok(IsInitializedResponse {
+            initialized: true,
+            voice_count,
+        })
+    }
+
+    /// Retry creating the engine after it failed at startup, e.g. because the audio
+    /// stack came up after the app. Does nothing once the engine is running
+    pub fn reinitialize(&self) -> crate::Result<IsInitializedResponse> {
+        if self.system.start()? {
+            log::info!("TTS engine initialized");
+        }
+        self.is_initialized()
+    }
+
+    pub fn get_capabilities(&self) -> crate::Result<Capabilities> {
+        // Speech goes through the OS engine rather than webview audio, so the
+        // webview's autoplay policy never applies
+        Ok(Capabilities {
+            requires_user_gesture: false,
+        })
+    }
+
+    pub fn get_rate_info(&self) -> crate::Result<RateInfoResponse> {
+        Ok(crate::wpm::rate_info())
+    }
+
+    pub fn get_features(&self) -> crate::Result<TtsFeatures> {
+        self.system.features()
+    }
+
+    pub fn get_audio_route(&self) -> crate::Result<AudioRouteResponse> {
+        // Desktop audio routing isn't exposed through tts-rs
+        Ok(AudioRoute::Unknown.into())
+    }
+
+    pub fn request_audio_focus(&self) -> crate::Result<AudioFocusResponse> {
+        // Desktop mixers play every app at once, there is no focus to take
+        Ok(AudioFocusResponse {
+            granted: true,
+            note: Some("Audio focus is not used on desktop".to_string()),
+        })
+    }
+
+    pub fn abandon_audio_focus(&self) -> crate::Result<AudioFocusResponse> {
+        Ok(AudioFocusResponse {
+            granted: false,
+            note: Some("Audio focus is not used on desktop".to_string()),
+        })
+    }
+
+    pub fn pause_speaking(&self) -> crate::Result<PauseResumeResponse> {
+        // tts-rs can't pause, so the queue is held and the engine stopped. The worker
+        // puts the interrupted sentence back to be spoken again on resume
+        let paused = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .pause();
+        if !paused {
+            return Ok(PauseResumeResponse {
+                success: false,
+                reason: Some("Nothing is being spoken".to_string()),
+            });
+        }
+        self.queue.notify();
+        {
+            let _handoff = self.queue.hand_off();
+            self.stop_speech()?;
+            self.queue.set_speaking(None);
+        }
+        Ok(PauseResumeResponse {
+            success: true,
+            reason: Some(APPROXIMATE_PAUSE.to_string()),
+        })
+    }
+
+    pub fn resume_speaking(&self) -> crate::Result<PauseResumeResponse> {
+        let resumed = self
+            .queue
+            .queue
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .resume();
+        if !resumed {
+            return Ok(PauseResumeResponse {
+                success: false,
+                reason: Some("Speech is not paused".to_string()),
+            });
+        }
+        self.queue.notify();
+        Ok(PauseResumeResponse {
+            success: true,
+            reason: Some(APPROXIMATE_PAUSE.to_string()),
+        })
+    }
+
+    pub fn get_pronunciation(
+        &self,
+        payload: GetPronunciationRequest,
+    ) -> crate::Result<PronunciationResponse> {
+        payload.validate()?;
+
+        // Only the macOS speech synthesizer exposes its phonetic transcription.
+        // Windows voices are WinRT ones, which have no pronunciation API, and SAPI's
+        // lexicon has its own voices, so it could disagree with what is spoken
+        #[cfg(target_os = "macos")]
+        {
+            let phonemes = macos_phonemes_from_text(&payload.text, payload.voice_id.as_deref())?;
+            Ok(PronunciationResponse {
+                phonemes,
+                alphabet: "apple".to_string(),
+            })
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            Err(crate::Error::OperationFailed(
+                "Pronunciation lookup is not supported by this platform's TTS engine".to_string(),
+            ))
+        }
     }
 
     pub fn preview_voice(&self, payload: PreviewVoiceRequest) -> crate::Result<SpeakResponse> {
         // Validate the preview request
         payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakResponse::disabled());
+        }
 
         // Create a speak request with the sample text and specified voice
         let speak_request = SpeakRequest {
             text: payload.sample_text().into_owned(),
-            language: None,
             voice_id: Some(payload.voice_id),
-            rate: 1.0,
-            pitch: 1.0,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+            rate: payload.rate,
+            pitch: payload.pitch,
+            volume: payload.volume,
+            queue_mode: Some(QueueMode::Flush),
+            use_affixes: false,
+            ..Default::default()
+        };
+        // Previews don't count against the session limit
+        let items = QueuedUtterance::from_requests(self.prepare(speak_request)?);
+        self.queue_items(items)
+    }
+
+    pub fn benchmark_voice(
+        &self,
+        payload: BenchmarkVoiceRequest,
+    ) -> crate::Result<BenchmarkVoiceResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(BenchmarkVoiceResponse::disabled());
+        }
+
+        let voice = self
+            .get_voices(GetVoicesRequest::default())?
+            .voices
+            .into_iter()
+            .find(|voice| voice.id == payload.voice_id)
+            .ok_or_else(|| {
+                crate::Error::OperationFailed(format!("Voice not found: {}", payload.voice_id))
+            })?;
+        let (phrase, warning) = self.config.benchmark_phrase(&voice.language);
+        let mut warnings: Vec<String> = warning.into_iter().collect();
+        let request = payload.speak_request(phrase).validate()?;
+
+        let utterance_callbacks =
+            self.with_engine(|engine| Ok(engine.supported_features().utterance_callbacks))?;
+        if !utterance_callbacks {
+            warnings.push(
+                "Engine doesn't report when speech ends, duration was measured by polling"
+                    .to_string(),
+            );
+        }
+
+        // Benchmarks bypass the interrupt cooldown and session limit
+        let (sender, receiver) = mpsc::channel();
+        let item = QueuedUtterance::new(uuid::Uuid::new_v4().to_string(), request)
+            .with_finish_notifier(sender);
+        self.enqueue(vec![item], QueueMode::Flush)?;
+
+        let duration = match receiver.recv_timeout(BENCHMARK_TIMEOUT) {
+            Ok(UtteranceOutcome::Finished(duration)) => duration,
+            Ok(UtteranceOutcome::Failed(e)) => {
+                return Err(crate::Error::OperationFailed(format!(
+                    "Benchmark failed: {}",
+                    e
+                )))
+            }
+            Ok(UtteranceOutcome::Interrupted) | Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(crate::Error::OperationFailed(
+                    "Benchmark was interrupted".to_string(),
+                ))
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                self.flush_queue()?;
+                return Err(crate::Error::OperationFailed(
+                    "Benchmark timed out".to_string(),
+                ));
+            }
+        };
+
+        Ok(BenchmarkVoiceResponse::new(
+            voice, phrase, duration, warnings,
+        ))
+    }
+
+    pub fn calibration_samples(
+        &self,
+        payload: CalibrationRequest,
+    ) -> crate::Result<CalibrationResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(CalibrationResponse::disabled());
+        }
+
+        let rates = payload.rates();
+        let total = rates.len();
+        let mut items = Vec::with_capacity(total);
+        for (index, &rate) in rates.iter().enumerate() {
+            let event = CalibrationStartEvent {
+                index: index as u32,
+                total: total as u32,
+                rate,
+            };
+            let event = serde_json::to_value(event)
+                .map_err(|e| crate::Error::OperationFailed(e.to_string()))?;
+            // Voice, pitch and volume come from the active profile
+            let segments = self.prepare(payload.sample(index, rate, index + 1 == total))?;
+            for item in QueuedUtterance::from_requests(segments) {
+                items.push(item.with_start_event("calibration:start", event.clone()));
+            }
+        }
+
+        self.enqueue(items, QueueMode::Flush)?;
+
+        Ok(CalibrationResponse::new(rates))
+    }
+
+    pub fn preview_all(&self, payload: PreviewAllRequest) -> crate::Result<PreviewAllResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(PreviewAllResponse::disabled());
+        }
+
+        let candidates = self
+            .get_voices(GetVoicesRequest {
+                language: Some(payload.language.clone()),
+                ..Default::default()
+            })?
+            .voices;
+
+        // Skip voices whose ids the engine reports but our validation rejects
+        let mut previews = Vec::with_capacity(candidates.len());
+        for voice in candidates {
+            let request = SpeakRequest {
+                text: payload.announcement(&voice),
+                voice_id: Some(voice.id.clone()),
+                queue_mode: Some(QueueMode::Add),
+                pause_after_ms: Some(payload.gap_ms()),
+                use_affixes: false,
+                ..Default::default()
+            };
+            match request.validate() {
+                Ok(validated) => previews.push((voice, validated)),
+                Err(e) => log::warn!("Skipping preview of voice '{}': {}", voice.id, e),
+            }
+        }
+
+        let total = previews.len();
+        let mut voices = Vec::with_capacity(total);
+        let mut items = Vec::with_capacity(total);
+        for (index, (voice, mut request)) in previews.into_iter().enumerate() {
+            if index + 1 == total {
+                // No gap needed after the last preview
+                request.pause_after_ms = None;
+            }
+            let event = PreviewStartEvent {
+                index: index as u32,
+                total: total as u32,
+                voice: voice.clone(),
+            };
+            let payload = serde_json::to_value(event)
+                .map_err(|e| crate::Error::OperationFailed(e.to_string()))?;
+            items.push(
+                QueuedUtterance::new(uuid::Uuid::new_v4().to_string(), request)
+                    .with_start_event("preview:start", payload),
+            );
+            voices.push(voice);
+        }
+
+        // Previewing replaces whatever was playing, even if nothing matched
+        self.enqueue(items, QueueMode::Flush)?;
+
+        Ok(PreviewAllResponse::new(voices))
+    }
+
+    /// Calls the mock engine received, oldest first. Speech reaches the engine from
+    /// the queue worker, so wait for `is_speaking` to report `false` before
+    /// asserting on spoken text
+    #[cfg(feature = "mock")]
+    pub fn mock_calls(&self) -> crate::Result<Vec<MockCall>> {
+        self.with_engine(|engine| Ok(engine.calls().to_vec()))
+    }
+
+    /// Forget the calls recorded so far, e.g. between test cases
+    #[cfg(feature = "mock")]
+    pub fn clear_mock_calls(&self) -> crate::Result<()> {
+        self.with_engine(|engine| {
+            engine.clear_calls();
+            Ok(())
+        })
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::TtsExt;
+    use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+
+    fn app() -> tauri::App<MockRuntime> {
+        mock_builder()
+            .plugin(crate::init())
+            .build(mock_context(noop_assets()))
+            .unwrap()
+    }
+
+    fn request(voice_id: Option<&str>, rate: f32) -> ValidatedSpeakRequest {
+        SpeakRequest {
+            text: "Hello".to_string(),
+            voice_id: voice_id.map(str::to_string),
+            rate: Some(rate),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_speak_with_engine_resets_earlier_settings() {
+        let mut engine = TtsEngine::default().unwrap();
+        let mut applied = EngineSettings::new(engine.voice().unwrap());
+
+        for request in [
+            request(None, 1.0),
+            request(None, 0.75),
+            request(None, 1.0),
+            request(Some("mock-pt-BR"), 1.0),
+            request(None, 1.0),
+        ] {
+            speak_with_engine(&mut engine, &request, &request.text, &mut applied).unwrap();
+        }
+
+        let speak = || MockCall::Speak("Hello".to_string());
+        assert_eq!(
+            engine.calls(),
+            [
+                speak(),
+                MockCall::SetRate(0.75),
+                speak(),
+                MockCall::SetRate(1.0),
+                speak(),
+                MockCall::SetVoice("mock-pt-BR".to_string()),
+                speak(),
+                MockCall::SetVoice("mock-en-US".to_string()),
+                speak(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_speak_reaches_the_engine() {
+        let app = app();
+        let tts = app.tts();
+        tts.clear_mock_calls().unwrap();
+
+        tts.speak_and_wait(SpeakRequest {
+            text: "Hello".to_string(),
+            voice_id: Some("mock-pt-BR".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            tts.mock_calls().unwrap(),
+            [
+                MockCall::Stop,
+                MockCall::SetVoice("mock-pt-BR".to_string()),
+                MockCall::Speak("Hello".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder_combines_config_and_filters() {
+        let app = mock_builder()
+            .plugin(
+                crate::Builder::new()
+                    .config(TtsConfig {
+                        default_voice_id: Some("mock-pt-BR".to_string()),
+                        ..Default::default()
+                    })
+                    .filter(|text: &str| text.replace("Hello", "Hi"))
+                    .build(),
+            )
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let tts = app.tts();
+        tts.clear_mock_calls().unwrap();
+
+        tts.speak_and_wait(SpeakRequest {
+            text: "Hello".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            tts.mock_calls().unwrap(),
+            [
+                MockCall::Stop,
+                MockCall::SetVoice("mock-pt-BR".to_string()),
+                MockCall::Speak("Hi".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disabled_speech_is_skipped_but_stops() {
+        let app = app();
+        let tts = app.tts();
+        tts.set_enabled(SetEnabledRequest { enabled: false })
+            .unwrap();
+        tts.clear_mock_calls().unwrap();
+        let hello = || SpeakRequest {
+            text: "Hello".to_string(),
+            ..Default::default()
+        };
+
+        let spoken = tts.speak(hello()).unwrap();
+        assert!(spoken.success);
+        assert_eq!(spoken.warning.as_deref(), Some(DISABLED_WARNING));
+        let sequence = tts
+            .speak_sequence(SpeakSequenceRequest {
+                items: vec![hello(), hello()],
+            })
+            .unwrap();
+        assert!(sequence.success);
+        assert_eq!(sequence.warning.as_deref(), Some(DISABLED_WARNING));
+        let preview = tts
+            .preview_voice(PreviewVoiceRequest {
+                voice_id: "mock-pt-BR".to_string(),
+                text: None,
+                rate: None,
+                pitch: None,
+                volume: None,
+            })
+            .unwrap();
+        assert!(preview.success);
+        assert_eq!(preview.warning.as_deref(), Some(DISABLED_WARNING));
+        assert!(tts.mock_calls().unwrap().is_empty());
+
+        assert!(tts.stop().unwrap().success);
+        assert_eq!(tts.mock_calls().unwrap(), [MockCall::Stop]);
+    }
+
+    fn disabled_app() -> tauri::App<MockRuntime> {
+        let app = app();
+        app.tts()
+            .set_enabled(SetEnabledRequest { enabled: false })
+            .unwrap();
+        app.tts().clear_mock_calls().unwrap();
+        app
+    }
+
+    /// A request only a disabled plugin accepts, as its backend doesn't exist
+    fn unspeakable() -> SpeakRequest {
+        SpeakRequest {
+            text: "Hello".to_string(),
+            backend: Some("missing".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_disabled_speak_skips_before_preparing() {
+        let app = disabled_app();
+        let spoken = app.tts().speak(unspeakable()).unwrap();
+        assert!(spoken.success);
+        assert_eq!(spoken.warning.as_deref(), Some(DISABLED_WARNING));
+
+        let app = self::app();
+        assert!(matches!(
+            app.tts().speak(unspeakable()),
+            Err(crate::Error::BackendNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_disabled_speak_and_wait_is_skipped() {
+        let app = disabled_app();
+        let waited = app.tts().speak_and_wait(unspeakable()).unwrap();
+        assert_eq!(waited.outcome, SpeechOutcome::Skipped);
+        assert_eq!(waited.warning.as_deref(), Some(DISABLED_WARNING));
+    }
+
+    #[test]
+    fn test_disabled_speak_segments_skips_before_preparing() {
+        let app = disabled_app();
+        let spoken = app
+            .tts()
+            .speak_segments(SpeakSegmentsRequest {
+                segments: vec![unspeakable(), unspeakable()],
+            })
+            .unwrap();
+        assert!(spoken.success);
+        assert!(spoken.utterance_ids.is_empty());
+        assert_eq!(spoken.warning.as_deref(), Some(DISABLED_WARNING));
+    }
+
+    #[test]
+    fn test_disabled_speak_sequence_skips_before_preparing() {
+        let app = disabled_app();
+        let sequence = app
+            .tts()
+            .speak_sequence(SpeakSequenceRequest {
+                items: vec![unspeakable(), unspeakable()],
+            })
+            .unwrap();
+        assert!(sequence.success);
+        assert_eq!(sequence.warning.as_deref(), Some(DISABLED_WARNING));
+    }
+
+    #[test]
+    fn test_disabled_calibration_samples_are_skipped() {
+        let app = disabled_app();
+        let samples = app
+            .tts()
+            .calibration_samples(CalibrationRequest::default())
+            .unwrap();
+        assert!(samples.success);
+        assert!(samples.rates.is_empty());
+        assert_eq!(samples.warning.as_deref(), Some(DISABLED_WARNING));
+        assert!(app.tts().mock_calls().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_disabled_preview_all_is_skipped() {
+        let app = disabled_app();
+        let previews = app
+            .tts()
+            .preview_all(PreviewAllRequest {
+                language: "en".to_string(),
+                text: None,
+                gap_ms: None,
+            })
+            .unwrap();
+        assert!(previews.success);
+        assert!(previews.voices.is_empty());
+        assert_eq!(previews.warning.as_deref(), Some(DISABLED_WARNING));
+        assert!(app.tts().mock_calls().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_disabled_benchmark_is_skipped() {
+        let app = disabled_app();
+        let benchmark = app
+            .tts()
+            .benchmark_voice(BenchmarkVoiceRequest {
+                voice_id: "mock-pt-BR".to_string(),
+            })
+            .unwrap();
+        assert!(benchmark.success);
+        assert_eq!(benchmark.duration_ms, None);
+        assert_eq!(benchmark.warnings, [DISABLED_WARNING]);
+        assert!(app.tts().mock_calls().unwrap().is_empty());
+    }
+
+    /// Rates the engine was set to, back on the user scale
+    fn set_rates(tts: &Tts<MockRuntime>) -> Vec<f32> {
+        let engine = TtsEngine::default().unwrap();
+        tts.mock_calls()
+            .unwrap()
+            .into_iter()
+            .filter_map(|call| match call {
+                MockCall::SetRate(rate) => Some(rate_from_platform(&engine, rate)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn assert_rates(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-4, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn test_default_rate_undoes_an_earlier_one() {
+        let app = app();
+        let tts = app.tts();
+        tts.clear_mock_calls().unwrap();
+        let hello = |rate| SpeakRequest {
+            text: "Hello".to_string(),
+            rate,
+            ..Default::default()
+        };
+
+        tts.set_rate(SetProsodyRequest { value: 1.5 }).unwrap();
+        tts.speak_and_wait(hello(None)).unwrap();
+        tts.set_rate(SetProsodyRequest { value: 1.0 }).unwrap();
+        tts.speak_and_wait(hello(None)).unwrap();
+        assert_rates(&set_rates(tts), &[1.5, 1.0]);
+
+        // Segments each bring their own rate, the ones without get the default back
+        tts.clear_mock_calls().unwrap();
+        tts.speak_segments(SpeakSegmentsRequest {
+            segments: vec![hello(Some(2.0)), hello(None)],
+        })
+        .unwrap();
+        while tts.is_speaking().unwrap().speaking {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_rates(&set_rates(tts), &[2.0, 1.0]);
+    }
+
+    /// Backend whose speech plays until stopped, recording fades
+    #[derive(Default)]
+    struct Endless {
+        stopped: Mutex<bool>,
+        wake: Condvar,
+        fades: Mutex<Vec<Duration>>,
+    }
+
+    impl TtsBackend for Arc<Endless> {
+        fn name(&self) -> &str {
+            "endless"
+        }
+
+        fn speak(&self, _request: &ValidatedSpeakRequest, _text: &str) -> crate::Result<()> {
+            let stopped = self.stopped.lock().unwrap();
+            let _stopped = self.wake.wait_while(stopped, |stopped| !*stopped).unwrap();
+            Ok(())
+        }
+
+        fn stop(&self) -> crate::Result<()> {
+            *self.stopped.lock().unwrap() = true;
+            self.wake.notify_all();
+            Ok(())
+        }
+
+        fn fade_out(&self, duration: Duration) -> crate::Result<bool> {
+            self.fades.lock().unwrap().push(duration);
+            Ok(true)
+        }
+
+        fn voices(&self) -> crate::Result<Vec<Voice>> {
+            Ok(Vec::new())
+        }
+
+        fn capabilities(&self) -> TtsFeatures {
+            TtsFeatures::default()
+        }
+    }
+
+    #[test]
+    fn test_stop_gentle_fades_backends() {
+        let app = app();
+        let tts = app.tts();
+        let gentle = |duration_ms| tts.stop_gentle(StopGentleRequest { duration_ms }).unwrap();
+        // Nothing to fade
+        assert_eq!(gentle(1000).note, None);
+
+        let backend = Arc::new(Endless::default());
+        tts.register_backend(Arc::clone(&backend)).unwrap();
+        tts.speak(SpeakRequest {
+            text: "Hello".to_string(),
+            backend: Some("endless".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        while tts.queue.speaking().is_none() {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let response = gentle(1000);
+        assert!(response.faded);
+        assert_eq!(*backend.fades.lock().unwrap(), [Duration::from_secs(1)]);
+        assert!(*backend.stopped.lock().unwrap());
+    }
+
+    #[test]
+    fn test_max_duration_stops_any_backend() {
+        let app = app();
+        let tts = app.tts();
+        let backend = Arc::new(Endless::default());
+        tts.register_backend(Arc::clone(&backend)).unwrap();
+
+        let waited = tts
+            .speak_and_wait(SpeakRequest {
+                text: "Hello".to_string(),
+                backend: Some("endless".to_string()),
+                max_duration_ms: Some(50),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(waited.outcome, SpeechOutcome::Stopped);
+        assert!(*backend.stopped.lock().unwrap());
+    }
+
+    #[test]
+    fn test_os_engine_is_the_system_backend() {
+        let app = app();
+        let tts = app.tts();
+        let backends = tts.get_backends().unwrap().backends;
+        let system = backends
+            .iter()
+            .find(|backend| backend.name == SYSTEM_BACKEND)
+            .unwrap();
+        assert!(system.features.voice);
+        tts.clear_mock_calls().unwrap();
+
+        let spoken = tts
+            .speak(SpeakRequest {
+                text: "Hello".to_string(),
+                backend: Some(SYSTEM_BACKEND.to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(spoken.backend.as_deref(), Some(SYSTEM_BACKEND));
+        while tts.is_speaking().unwrap().speaking {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(
+            tts.mock_calls().unwrap(),
+            [MockCall::Stop, MockCall::Speak("Hello".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_normalize_loudness_warns_for_the_engine() {
+        let app = mock_builder()
+            .plugin(crate::init_with_config(TtsConfig {
+                normalize_loudness: true,
+                ..Default::default()
+            }))
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let response = app
+            .tts()
+            .speak(SpeakRequest {
+                text: "Hello".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(response
+            .warning
+            .unwrap()
+            .starts_with("Loudness isn't normalized"));
+    }
+
+    #[test]
+    fn test_dropped_word_is_still_remaining() {
+        let app = mock_builder()
+            .plugin(crate::init_with_config(TtsConfig {
+                interrupt_cooldown_ms: 60_000,
+                interrupt_cooldown_action: InterruptCooldownAction::Drop,
+                ..Default::default()
+            }))
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let tts = app.tts();
+        let session_id = tts
+            .speak_words_begin(SpeakWordsBeginRequest {
+                speak: SpeakRequest {
+                    text: "Hello there".to_string(),
+                    queue_mode: Some(QueueMode::Flush),
+                    ..Default::default()
+                },
+            })
+            .unwrap()
+            .session_id
+            .unwrap();
+        let next = || {
+            tts.speak_word_next(SpeakListSessionRequest {
+                session_id: session_id.clone(),
+            })
+            .unwrap()
         };
-        self.speak(speak_request)
+
+        let spoken = next();
+        assert!(spoken.success);
+        assert_eq!(spoken.remaining, 1);
+        // Interrupting again within the cooldown drops the word, which is retried
+        let dropped = next();
+        assert!(!dropped.success);
+        assert_eq!(dropped.word, "there");
+        assert_eq!(dropped.remaining, 1);
     }
 }
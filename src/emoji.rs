@@ -0,0 +1,908 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::CharIndices;
+use std::sync::OnceLock;
+
+use crate::models::EmojiMode;
+
+const ZWJ: char = '\u{200D}';
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+const KEYCAP: char = '\u{20E3}';
+const BLACK_FLAG: char = '\u{1F3F4}';
+const CANCEL_TAG: char = '\u{E007F}';
+
+/// Replace, drop or keep the emoji in `text` according to `mode`
+pub(crate) fn apply(text: &str, mode: EmojiMode) -> String {
+    if mode == EmojiMode::Keep {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let Some(end) = cluster_end(c, start, &mut chars) else {
+            // A stray selector is invisible and only confuses engines. Stray joiners are
+            // kept, since some scripts (e.g. Malayalam) need them between letters
+            if c != VARIATION_SELECTOR {
+                out.push(c);
+            }
+            continue;
+        };
+        if mode == EmojiMode::Describe {
+            if let Some(name) = describe(&text[start..end]) {
+                out.push(' ');
+                out.push_str(&name);
+                out.push(' ');
+            }
+        }
+    }
+    out
+}
+
+/// Consume the rest of the emoji starting with `c`, returning the byte offset where it
+/// ends, or `None` (consuming nothing) if `c` doesn't start an emoji
+fn cluster_end(c: char, start: usize, chars: &mut Peekable<CharIndices<'_>>) -> Option<usize> {
+    let next = chars.peek().map(|&(_, next)| next);
+    let mut end = start + c.len_utf8();
+
+    // Flags are pairs of regional indicator letters
+    if is_regional_indicator(c) {
+        if let Some((i, second)) = chars.next_if(|&(_, n)| is_regional_indicator(n)) {
+            end = i + second.len_utf8();
+        }
+        return Some(end);
+    }
+
+    // Keycaps: a digit, `#` or `*`, an optional selector and the enclosing keycap
+    if matches!(c, '0'..='9' | '#' | '*') {
+        let mut lookahead = chars.clone();
+        lookahead.next_if(|&(_, n)| n == VARIATION_SELECTOR);
+        let (i, keycap) = lookahead.next_if(|&(_, n)| n == KEYCAP)?;
+        *chars = lookahead;
+        return Some(i + keycap.len_utf8());
+    }
+
+    if !is_emoji_start(c, next) {
+        return None;
+    }
+    end = consume_modifiers(chars, end);
+
+    // Subdivision flags (England, Scotland, Wales) spell their region in tag characters
+    if c == BLACK_FLAG {
+        while let Some((i, tag)) = chars.next_if(|&(_, n)| is_tag(n)) {
+            end = i + tag.len_utf8();
+            if tag == CANCEL_TAG {
+                break;
+            }
+        }
+    }
+
+    // ZWJ sequences join several emoji into one (families, professions, ...)
+    loop {
+        let mut lookahead = chars.clone();
+        if lookahead.next_if(|&(_, n)| n == ZWJ).is_none() {
+            break;
+        }
+        let mut after = lookahead.clone();
+        after.next();
+        let after = after.peek().map(|&(_, n)| n);
+        let Some((i, joined)) = lookahead.next_if(|&(_, n)| is_emoji_start(n, after)) else {
+            break;
+        };
+        *chars = lookahead;
+        end = consume_modifiers(chars, i + joined.len_utf8());
+    }
+    Some(end)
+}
+
+/// Skip an optional variation selector and skin tone after an emoji
+fn consume_modifiers(chars: &mut Peekable<CharIndices<'_>>, mut end: usize) -> usize {
+    while let Some((i, c)) = chars.next_if(|&(_, n)| n == VARIATION_SELECTOR || is_skin_tone(n)) {
+        end = i + c.len_utf8();
+    }
+    end
+}
+
+fn is_emoji_start(c: char, next: Option<char>) -> bool {
+    if c.is_ascii() {
+        return false;
+    }
+    matches!(c, '\u{1F000}'..='\u{1FAFF}')
+        || next == Some(VARIATION_SELECTOR)
+        || names().contains_key(c.encode_utf8(&mut [0; 4]) as &str)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+fn is_skin_tone(c: char) -> bool {
+    matches!(c, '\u{1F3FB}'..='\u{1F3FF}')
+}
+
+fn is_tag(c: char) -> bool {
+    matches!(c, '\u{E0020}'..='\u{E007F}')
+}
+
+/// CLDR short name of one emoji cluster, `None` if it isn't in the bundled table
+fn describe(cluster: &str) -> Option<String> {
+    let key: String = cluster
+        .chars()
+        .filter(|&c| c != VARIATION_SELECTOR)
+        .collect();
+    if let Some(name) = names().get(key.as_str()) {
+        return Some(name.to_string());
+    }
+
+    let first = key.chars().next()?;
+
+    if is_regional_indicator(first) {
+        let code: String = key
+            .chars()
+            .filter(|&c| is_regional_indicator(c))
+            .map(|c| char::from(b'A' + (u32::from(c) - 0x1F1E6) as u8))
+            .collect();
+        if code.len() != 2 {
+            return None;
+        }
+        let country = country_name(&code).map(str::to_string).unwrap_or(code);
+        return Some(format!("flag: {}", country));
+    }
+
+    if key.chars().nth(1) == Some(KEYCAP) {
+        return Some(format!("keycap: {}", first));
+    }
+
+    if first == BLACK_FLAG && key.chars().any(is_tag) {
+        let region: String = key
+            .chars()
+            .filter(|&c| is_tag(c) && c != CANCEL_TAG)
+            .filter_map(|c| char::from_u32(u32::from(c) - 0xE0000))
+            .collect();
+        return subdivision_name(&region).map(|name| format!("flag: {}", name));
+    }
+
+    // Unlisted ZWJ sequences are read as their parts
+    if key.contains(ZWJ) {
+        let parts: Vec<String> = key.split(ZWJ).filter_map(describe).collect();
+        return (!parts.is_empty()).then(|| parts.join(", "));
+    }
+
+    // Skin tones follow CLDR: "thumbs up: medium skin tone"
+    let last = key.chars().last()?;
+    if is_skin_tone(last) && key.chars().count() > 1 {
+        let base = &key[..key.len() - last.len_utf8()];
+        let base_name = names().get(base)?;
+        let tone = names().get(last.encode_utf8(&mut [0; 4]) as &str)?;
+        return Some(format!("{}: {}", base_name, tone));
+    }
+    None
+}
+
+fn names() -> &'static HashMap<&'static str, &'static str> {
+    static NAMES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    NAMES.get_or_init(|| EMOJI_NAMES.iter().copied().collect())
+}
+
+fn country_name(code: &str) -> Option<&'static str> {
+    COUNTRY_NAMES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+fn subdivision_name(region: &str) -> Option<&'static str> {
+    match region {
+        "gbeng" => Some("England"),
+        "gbsct" => Some("Scotland"),
+        "gbwls" => Some("Wales"),
+        _ => None,
+    }
+}
+
+/// CLDR short names for common emoji, keyed without variation selectors
+const EMOJI_NAMES: &[(&str, &str)] = &[
+    // Smileys & emotion
+    ("😀", "grinning face"),
+    ("😃", "grinning face with big eyes"),
+    ("😄", "grinning face with smiling eyes"),
+    ("😁", "beaming face with smiling eyes"),
+    ("😆", "grinning squinting face"),
+    ("😅", "grinning face with sweat"),
+    ("🤣", "rolling on the floor laughing"),
+    ("😂", "face with tears of joy"),
+    ("🙂", "slightly smiling face"),
+    ("🙃", "upside-down face"),
+    ("😉", "winking face"),
+    ("😊", "smiling face with smiling eyes"),
+    ("😇", "smiling face with halo"),
+    ("🥰", "smiling face with hearts"),
+    ("😍", "smiling face with heart-eyes"),
+    ("🤩", "star-struck"),
+    ("😘", "face blowing a kiss"),
+    ("😗", "kissing face"),
+    ("☺", "smiling face"),
+    ("😚", "kissing face with closed eyes"),
+    ("😙", "kissing face with smiling eyes"),
+    ("🥲", "smiling face with tear"),
+    ("😋", "face savoring food"),
+    ("😛", "face with tongue"),
+    ("😜", "winking face with tongue"),
+    ("🤪", "zany face"),
+    ("😝", "squinting face with tongue"),
+    ("🤑", "money-mouth face"),
+    ("🤗", "smiling face with open hands"),
+    ("🤭", "face with hand over mouth"),
+    ("🤫", "shushing face"),
+    ("🤔", "thinking face"),
+    ("🤐", "zipper-mouth face"),
+    ("🤨", "face with raised eyebrow"),
+    ("😐", "neutral face"),
+    ("😑", "expressionless face"),
+    ("😶", "face without mouth"),
+    ("😏", "smirking face"),
+    ("😒", "unamused face"),
+    ("🙄", "face with rolling eyes"),
+    ("😬", "grimacing face"),
+    ("🤥", "lying face"),
+    ("😌", "relieved face"),
+    ("😔", "pensive face"),
+    ("😪", "sleepy face"),
+    ("🤤", "drooling face"),
+    ("😴", "sleeping face"),
+    ("😷", "face with medical mask"),
+    ("🤒", "face with thermometer"),
+    ("🤕", "face with head-bandage"),
+    ("🤢", "nauseated face"),
+    ("🤮", "face vomiting"),
+    ("🤧", "sneezing face"),
+    ("🥵", "hot face"),
+    ("🥶", "cold face"),
+    ("🥴", "woozy face"),
+    ("😵", "face with crossed-out eyes"),
+    ("🤯", "exploding head"),
+    ("🤠", "cowboy hat face"),
+    ("🥳", "partying face"),
+    ("😎", "smiling face with sunglasses"),
+    ("🤓", "nerd face"),
+    ("🧐", "face with monocle"),
+    ("😕", "confused face"),
+    ("😟", "worried face"),
+    ("🙁", "slightly frowning face"),
+    ("☹", "frowning face"),
+    ("😮", "face with open mouth"),
+    ("😯", "hushed face"),
+    ("😲", "astonished face"),
+    ("😳", "flushed face"),
+    ("🥺", "pleading face"),
+    ("😦", "frowning face with open mouth"),
+    ("😧", "anguished face"),
+    ("😨", "fearful face"),
+    ("😰", "anxious face with sweat"),
+    ("😥", "sad but relieved face"),
+    ("😢", "crying face"),
+    ("😭", "loudly crying face"),
+    ("😱", "face screaming in fear"),
+    ("😖", "confounded face"),
+    ("😣", "persevering face"),
+    ("😞", "disappointed face"),
+    ("😓", "downcast face with sweat"),
+    ("😩", "weary face"),
+    ("😫", "tired face"),
+    ("🥱", "yawning face"),
+    ("😤", "face with steam from nose"),
+    ("😡", "enraged face"),
+    ("😠", "angry face"),
+    ("🤬", "face with symbols on mouth"),
+    ("😈", "smiling face with horns"),
+    ("👿", "angry face with horns"),
+    ("💀", "skull"),
+    ("☠", "skull and crossbones"),
+    ("💩", "pile of poo"),
+    ("🤡", "clown face"),
+    ("👻", "ghost"),
+    ("👽", "alien"),
+    ("🤖", "robot"),
+    ("😺", "grinning cat"),
+    ("😹", "cat with tears of joy"),
+    ("😻", "smiling cat with heart-eyes"),
+    ("🙈", "see-no-evil monkey"),
+    ("🙉", "hear-no-evil monkey"),
+    ("🙊", "speak-no-evil monkey"),
+    ("💋", "kiss mark"),
+    ("💯", "hundred points"),
+    ("💢", "anger symbol"),
+    ("💥", "collision"),
+    ("💫", "dizzy"),
+    ("💦", "sweat droplets"),
+    ("💨", "dashing away"),
+    ("💬", "speech balloon"),
+    ("💭", "thought balloon"),
+    ("💤", "ZZZ"),
+    // Hearts
+    ("❤", "red heart"),
+    ("🧡", "orange heart"),
+    ("💛", "yellow heart"),
+    ("💚", "green heart"),
+    ("💙", "blue heart"),
+    ("💜", "purple heart"),
+    ("🖤", "black heart"),
+    ("🤍", "white heart"),
+    ("🤎", "brown heart"),
+    ("💔", "broken heart"),
+    ("❣", "heart exclamation"),
+    ("💕", "two hearts"),
+    ("💞", "revolving hearts"),
+    ("💓", "beating heart"),
+    ("💗", "growing heart"),
+    ("💖", "sparkling heart"),
+    ("💘", "heart with arrow"),
+    ("💝", "heart with ribbon"),
+    ("❤\u{200D}🔥", "heart on fire"),
+    ("❤\u{200D}🩹", "mending heart"),
+    // Hands & body
+    ("👋", "waving hand"),
+    ("🤚", "raised back of hand"),
+    ("🖐", "hand with fingers splayed"),
+    ("✋", "raised hand"),
+    ("🖖", "vulcan salute"),
+    ("👌", "OK hand"),
+    ("🤌", "pinched fingers"),
+    ("🤏", "pinching hand"),
+    ("✌", "victory hand"),
+    ("🤞", "crossed fingers"),
+    ("🤟", "love-you gesture"),
+    ("🤘", "sign of the horns"),
+    ("🤙", "call me hand"),
+    ("👈", "backhand index pointing left"),
+    ("👉", "backhand index pointing right"),
+    ("👆", "backhand index pointing up"),
+    ("👇", "backhand index pointing down"),
+    ("☝", "index pointing up"),
+    ("👍", "thumbs up"),
+    ("👎", "thumbs down"),
+    ("✊", "raised fist"),
+    ("👊", "oncoming fist"),
+    ("🤛", "left-facing fist"),
+    ("🤜", "right-facing fist"),
+    ("👏", "clapping hands"),
+    ("🙌", "raising hands"),
+    ("👐", "open hands"),
+    ("🤲", "palms up together"),
+    ("🤝", "handshake"),
+    ("🙏", "folded hands"),
+    ("✍", "writing hand"),
+    ("💅", "nail polish"),
+    ("💪", "flexed biceps"),
+    ("👀", "eyes"),
+    ("👁", "eye"),
+    ("👂", "ear"),
+    ("👃", "nose"),
+    ("🧠", "brain"),
+    ("👅", "tongue"),
+    ("👄", "mouth"),
+    // Skin tones
+    ("🏻", "light skin tone"),
+    ("🏼", "medium-light skin tone"),
+    ("🏽", "medium skin tone"),
+    ("🏾", "medium-dark skin tone"),
+    ("🏿", "dark skin tone"),
+    // People
+    ("👶", "baby"),
+    ("🧒", "child"),
+    ("👦", "boy"),
+    ("👧", "girl"),
+    ("🧑", "person"),
+    ("👨", "man"),
+    ("👩", "woman"),
+    ("🧓", "older person"),
+    ("👴", "old man"),
+    ("👵", "old woman"),
+    ("🙋", "person raising hand"),
+    ("🤷", "person shrugging"),
+    ("🤦", "person facepalming"),
+    ("🙇", "person bowing"),
+    ("💃", "woman dancing"),
+    ("🕺", "man dancing"),
+    ("🏃", "person running"),
+    ("🚶", "person walking"),
+    ("👪", "family"),
+    ("👨\u{200D}👩\u{200D}👦", "family: man, woman, boy"),
+    ("👨\u{200D}👩\u{200D}👧", "family: man, woman, girl"),
+    (
+        "👨\u{200D}👩\u{200D}👧\u{200D}👦",
+        "family: man, woman, girl, boy",
+    ),
+    ("👩\u{200D}👩\u{200D}👦", "family: woman, woman, boy"),
+    ("👨\u{200D}👨\u{200D}👦", "family: man, man, boy"),
+    ("👨\u{200D}💻", "man technologist"),
+    ("👩\u{200D}💻", "woman technologist"),
+    ("🧑\u{200D}💻", "technologist"),
+    ("👨\u{200D}🍳", "man cook"),
+    ("👩\u{200D}🍳", "woman cook"),
+    ("👨\u{200D}⚕", "man health worker"),
+    ("👩\u{200D}⚕", "woman health worker"),
+    ("👨\u{200D}🚀", "man astronaut"),
+    ("👩\u{200D}🚀", "woman astronaut"),
+    ("🤷\u{200D}♂", "man shrugging"),
+    ("🤷\u{200D}♀", "woman shrugging"),
+    ("🤦\u{200D}♂", "man facepalming"),
+    ("🤦\u{200D}♀", "woman facepalming"),
+    ("🏳\u{200D}🌈", "rainbow flag"),
+    ("🏳\u{200D}⚧", "transgender flag"),
+    ("🏴\u{200D}☠", "pirate flag"),
+    ("😶\u{200D}🌫", "face in clouds"),
+    ("😮\u{200D}💨", "face exhaling"),
+    ("🐕\u{200D}🦺", "service dog"),
+    ("🐈\u{200D}⬛", "black cat"),
+    // Animals & nature
+    ("🐶", "dog face"),
+    ("🐕", "dog"),
+    ("🐱", "cat face"),
+    ("🐈", "cat"),
+    ("🐭", "mouse face"),
+    ("🐹", "hamster"),
+    ("🐰", "rabbit face"),
+    ("🦊", "fox"),
+    ("🐻", "bear"),
+    ("🐼", "panda"),
+    ("🐨", "koala"),
+    ("🐯", "tiger face"),
+    ("🦁", "lion"),
+    ("🐮", "cow face"),
+    ("🐷", "pig face"),
+    ("🐸", "frog"),
+    ("🐵", "monkey face"),
+    ("🐔", "chicken"),
+    ("🐧", "penguin"),
+    ("🐦", "bird"),
+    ("🐤", "baby chick"),
+    ("🦆", "duck"),
+    ("🦅", "eagle"),
+    ("🦉", "owl"),
+    ("🐺", "wolf"),
+    ("🐴", "horse face"),
+    ("🦄", "unicorn"),
+    ("🐝", "honeybee"),
+    ("🐛", "bug"),
+    ("🦋", "butterfly"),
+    ("🐌", "snail"),
+    ("🐞", "lady beetle"),
+    ("🐢", "turtle"),
+    ("🐍", "snake"),
+    ("🐙", "octopus"),
+    ("🐠", "tropical fish"),
+    ("🐟", "fish"),
+    ("🐬", "dolphin"),
+    ("🐳", "spouting whale"),
+    ("🦈", "shark"),
+    ("🐘", "elephant"),
+    ("🦒", "giraffe"),
+    ("💐", "bouquet"),
+    ("🌸", "cherry blossom"),
+    ("🌹", "rose"),
+    ("🌻", "sunflower"),
+    ("🌷", "tulip"),
+    ("🌱", "seedling"),
+    ("🌲", "evergreen tree"),
+    ("🌳", "deciduous tree"),
+    ("🌴", "palm tree"),
+    ("🌵", "cactus"),
+    ("🍀", "four leaf clover"),
+    ("🍁", "maple leaf"),
+    ("🍂", "fallen leaf"),
+    // Weather & sky
+    ("☀", "sun"),
+    ("🌞", "sun with face"),
+    ("🌝", "full moon face"),
+    ("🌙", "crescent moon"),
+    ("⭐", "star"),
+    ("🌟", "glowing star"),
+    ("✨", "sparkles"),
+    ("⚡", "high voltage"),
+    ("🔥", "fire"),
+    ("🌈", "rainbow"),
+    ("☁", "cloud"),
+    ("⛅", "sun behind cloud"),
+    ("🌧", "cloud with rain"),
+    ("⛈", "cloud with lightning and rain"),
+    ("❄", "snowflake"),
+    ("☃", "snowman"),
+    ("⛄", "snowman without snow"),
+    ("🌊", "water wave"),
+    ("💧", "droplet"),
+    ("☔", "umbrella with rain drops"),
+    ("🌍", "globe showing Europe-Africa"),
+    ("🌎", "globe showing Americas"),
+    ("🌏", "globe showing Asia-Australia"),
+    // Food & drink
+    ("🍎", "red apple"),
+    ("🍏", "green apple"),
+    ("🍊", "tangerine"),
+    ("🍋", "lemon"),
+    ("🍌", "banana"),
+    ("🍉", "watermelon"),
+    ("🍇", "grapes"),
+    ("🍓", "strawberry"),
+    ("🍒", "cherries"),
+    ("🍑", "peach"),
+    ("🥭", "mango"),
+    ("🍍", "pineapple"),
+    ("🥥", "coconut"),
+    ("🥑", "avocado"),
+    ("🍅", "tomato"),
+    ("🥕", "carrot"),
+    ("🌽", "ear of corn"),
+    ("🌶", "hot pepper"),
+    ("🥦", "broccoli"),
+    ("🍞", "bread"),
+    ("🧀", "cheese wedge"),
+    ("🥚", "egg"),
+    ("🍳", "cooking"),
+    ("🥓", "bacon"),
+    ("🍔", "hamburger"),
+    ("🍟", "french fries"),
+    ("🍕", "pizza"),
+    ("🌭", "hot dog"),
+    ("🌮", "taco"),
+    ("🌯", "burrito"),
+    ("🍿", "popcorn"),
+    ("🍣", "sushi"),
+    ("🍜", "steaming bowl"),
+    ("🍝", "spaghetti"),
+    ("🍚", "cooked rice"),
+    ("🍦", "soft ice cream"),
+    ("🍩", "doughnut"),
+    ("🍪", "cookie"),
+    ("🎂", "birthday cake"),
+    ("🍰", "shortcake"),
+    ("🧁", "cupcake"),
+    ("🍫", "chocolate bar"),
+    ("🍬", "candy"),
+    ("☕", "hot beverage"),
+    ("🍵", "teacup without handle"),
+    ("🍺", "beer mug"),
+    ("🍻", "clinking beer mugs"),
+    ("🍷", "wine glass"),
+    ("🥂", "clinking glasses"),
+    ("🍸", "cocktail glass"),
+    ("🥤", "cup with straw"),
+    // Activities & celebration
+    ("🎉", "party popper"),
+    ("🎊", "confetti ball"),
+    ("🎈", "balloon"),
+    ("🎁", "wrapped gift"),
+    ("🎄", "Christmas tree"),
+    ("🎃", "jack-o-lantern"),
+    ("🎆", "fireworks"),
+    ("🏆", "trophy"),
+    ("🥇", "1st place medal"),
+    ("🥈", "2nd place medal"),
+    ("🥉", "3rd place medal"),
+    ("🏅", "sports medal"),
+    ("⚽", "soccer ball"),
+    ("🏀", "basketball"),
+    ("🏈", "american football"),
+    ("⚾", "baseball"),
+    ("🎾", "tennis"),
+    ("🎮", "video game"),
+    ("🎲", "game die"),
+    ("🎯", "bullseye"),
+    ("🎵", "musical note"),
+    ("🎶", "musical notes"),
+    ("🎤", "microphone"),
+    ("🎧", "headphone"),
+    ("🎸", "guitar"),
+    ("🎹", "musical keyboard"),
+    ("🎬", "clapper board"),
+    ("🎨", "artist palette"),
+    // Travel & places
+    ("🚗", "automobile"),
+    ("🚕", "taxi"),
+    ("🚌", "bus"),
+    ("🚲", "bicycle"),
+    ("🏍", "motorcycle"),
+    ("🚀", "rocket"),
+    ("✈", "airplane"),
+    ("🚂", "locomotive"),
+    ("🚢", "ship"),
+    ("⛵", "sailboat"),
+    ("🏠", "house"),
+    ("🏡", "house with garden"),
+    ("🏢", "office building"),
+    ("🏥", "hospital"),
+    ("🏫", "school"),
+    ("⛪", "church"),
+    ("🗽", "Statue of Liberty"),
+    ("🗼", "Tokyo tower"),
+    ("🏖", "beach with umbrella"),
+    ("⛰", "mountain"),
+    ("🌋", "volcano"),
+    ("🏳", "white flag"),
+    ("🏴", "black flag"),
+    ("🚩", "triangular flag"),
+    ("🏁", "chequered flag"),
+    // Objects
+    ("⌚", "watch"),
+    ("📱", "mobile phone"),
+    ("💻", "laptop"),
+    ("⌨", "keyboard"),
+    ("🖥", "desktop computer"),
+    ("🖨", "printer"),
+    ("📷", "camera"),
+    ("📺", "television"),
+    ("📻", "radio"),
+    ("⏰", "alarm clock"),
+    ("⌛", "hourglass done"),
+    ("⏳", "hourglass not done"),
+    ("🔋", "battery"),
+    ("🔌", "electric plug"),
+    ("💡", "light bulb"),
+    ("🔦", "flashlight"),
+    ("💸", "money with wings"),
+    ("💰", "money bag"),
+    ("💵", "dollar banknote"),
+    ("💳", "credit card"),
+    ("💎", "gem stone"),
+    ("🔧", "wrench"),
+    ("🔨", "hammer"),
+    ("🛠", "hammer and wrench"),
+    ("⚙", "gear"),
+    ("🔑", "key"),
+    ("🔒", "locked"),
+    ("🔓", "unlocked"),
+    ("🔔", "bell"),
+    ("🔕", "bell with slash"),
+    ("📢", "loudspeaker"),
+    ("📣", "megaphone"),
+    ("📌", "pushpin"),
+    ("📎", "paperclip"),
+    ("✂", "scissors"),
+    ("📝", "memo"),
+    ("✏", "pencil"),
+    ("📖", "open book"),
+    ("📚", "books"),
+    ("📅", "calendar"),
+    ("📆", "tear-off calendar"),
+    ("📈", "chart increasing"),
+    ("📉", "chart decreasing"),
+    ("📊", "bar chart"),
+    ("📋", "clipboard"),
+    ("📁", "file folder"),
+    ("📦", "package"),
+    ("📧", "e-mail"),
+    ("✉", "envelope"),
+    ("📩", "envelope with arrow"),
+    ("📞", "telephone receiver"),
+    ("☎", "telephone"),
+    ("🔍", "magnifying glass tilted left"),
+    ("🔎", "magnifying glass tilted right"),
+    ("💊", "pill"),
+    ("💉", "syringe"),
+    ("🧪", "test tube"),
+    ("🛒", "shopping cart"),
+    ("🎓", "graduation cap"),
+    ("👑", "crown"),
+    ("💍", "ring"),
+    ("👓", "glasses"),
+    ("👕", "t-shirt"),
+    ("👟", "running shoe"),
+    ("🎒", "backpack"),
+    ("☂", "umbrella"),
+    // Symbols
+    ("✅", "check mark button"),
+    ("✔", "check mark"),
+    ("☑", "check box with check"),
+    ("❌", "cross mark"),
+    ("❎", "cross mark button"),
+    ("✖", "multiply"),
+    ("➕", "plus"),
+    ("➖", "minus"),
+    ("➗", "divide"),
+    ("❓", "red question mark"),
+    ("❔", "white question mark"),
+    ("❗", "red exclamation mark"),
+    ("❕", "white exclamation mark"),
+    ("‼", "double exclamation mark"),
+    ("⁉", "exclamation question mark"),
+    ("⚠", "warning"),
+    ("⛔", "no entry"),
+    ("🚫", "prohibited"),
+    ("🔞", "no one under eighteen"),
+    ("♻", "recycling symbol"),
+    ("🆗", "OK button"),
+    ("🆕", "NEW button"),
+    ("🆓", "FREE button"),
+    ("🆘", "SOS button"),
+    ("🔴", "red circle"),
+    ("🟠", "orange circle"),
+    ("🟡", "yellow circle"),
+    ("🟢", "green circle"),
+    ("🔵", "blue circle"),
+    ("🟣", "purple circle"),
+    ("⚫", "black circle"),
+    ("⚪", "white circle"),
+    ("🟥", "red square"),
+    ("🟩", "green square"),
+    ("⬛", "black large square"),
+    ("⬜", "white large square"),
+    ("🔺", "red triangle pointed up"),
+    ("🔻", "red triangle pointed down"),
+    ("➡", "right arrow"),
+    ("⬅", "left arrow"),
+    ("⬆", "up arrow"),
+    ("⬇", "down arrow"),
+    ("↩", "right arrow curving left"),
+    ("🔁", "repeat button"),
+    ("🔄", "counterclockwise arrows button"),
+    ("▶", "play button"),
+    ("⏸", "pause button"),
+    ("⏹", "stop button"),
+    ("⏩", "fast-forward button"),
+    ("⏪", "fast reverse button"),
+    ("🔊", "speaker high volume"),
+    ("🔇", "muted speaker"),
+    ("♀", "female sign"),
+    ("♂", "male sign"),
+    ("⚧", "transgender symbol"),
+    ("☮", "peace symbol"),
+    ("☯", "yin yang"),
+    ("✝", "latin cross"),
+    ("☪", "star and crescent"),
+    ("🕉", "om"),
+    ("✡", "star of David"),
+    ("♈", "Aries"),
+    ("♉", "Taurus"),
+    ("♊", "Gemini"),
+    ("♋", "Cancer"),
+    ("♌", "Leo"),
+    ("♍", "Virgo"),
+    ("♎", "Libra"),
+    ("♏", "Scorpio"),
+    ("♐", "Sagittarius"),
+    ("♑", "Capricorn"),
+    ("♒", "Aquarius"),
+    ("♓", "Pisces"),
+    ("©", "copyright"),
+    ("®", "registered"),
+    ("™", "trade mark"),
+    ("ℹ", "information"),
+    ("🔟", "keycap: 10"),
+];
+
+/// Names of the countries whose flags are most common in chat, by ISO 3166-1 code.
+/// Other flags are read with their two-letter code
+const COUNTRY_NAMES: &[(&str, &str)] = &[
+    ("AR", "Argentina"),
+    ("AT", "Austria"),
+    ("AU", "Australia"),
+    ("BE", "Belgium"),
+    ("BR", "Brazil"),
+    ("CA", "Canada"),
+    ("CH", "Switzerland"),
+    ("CL", "Chile"),
+    ("CN", "China"),
+    ("CO", "Colombia"),
+    ("CZ", "Czechia"),
+    ("DE", "Germany"),
+    ("DK", "Denmark"),
+    ("EG", "Egypt"),
+    ("ES", "Spain"),
+    ("EU", "European Union"),
+    ("FI", "Finland"),
+    ("FR", "France"),
+    ("GB", "United Kingdom"),
+    ("GR", "Greece"),
+    ("HK", "Hong Kong SAR China"),
+    ("ID", "Indonesia"),
+    ("IE", "Ireland"),
+    ("IL", "Israel"),
+    ("IN", "India"),
+    ("IT", "Italy"),
+    ("JP", "Japan"),
+    ("KR", "South Korea"),
+    ("MX", "Mexico"),
+    ("MY", "Malaysia"),
+    ("NG", "Nigeria"),
+    ("NL", "Netherlands"),
+    ("NO", "Norway"),
+    ("NZ", "New Zealand"),
+    ("PE", "Peru"),
+    ("PH", "Philippines"),
+    ("PK", "Pakistan"),
+    ("PL", "Poland"),
+    ("PT", "Portugal"),
+    ("RO", "Romania"),
+    ("RU", "Russia"),
+    ("SA", "Saudi Arabia"),
+    ("SE", "Sweden"),
+    ("SG", "Singapore"),
+    ("TH", "Thailand"),
+    ("TR", "Türkiye"),
+    ("TW", "Taiwan"),
+    ("UA", "Ukraine"),
+    ("UN", "United Nations"),
+    ("US", "United States"),
+    ("VN", "Vietnam"),
+    ("ZA", "South Africa"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe_all(text: &str) -> String {
+        crate::preprocess::preprocess(
+            text,
+            &crate::models::PreprocessOptions {
+                emoji_mode: EmojiMode::Describe,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_describe_single_emoji() {
+        assert_eq!(
+            describe_all("Good morning 😀"),
+            "Good morning grinning face"
+        );
+        assert_eq!(describe_all("Nice👍!"), "Nice thumbs up !");
+        assert_eq!(describe_all("I ❤️ you"), "I red heart you");
+    }
+
+    #[test]
+    fn test_describe_skin_tone() {
+        assert_eq!(describe_all("👍🏽"), "thumbs up: medium skin tone");
+    }
+
+    #[test]
+    fn test_describe_flags() {
+        assert_eq!(
+            describe_all("🇧🇷 vs 🇺🇸"),
+            "flag: Brazil vs flag: United States"
+        );
+        assert_eq!(describe_all("🇦🇶"), "flag: AQ");
+        assert_eq!(
+            describe_all("🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}"),
+            "flag: England"
+        );
+    }
+
+    #[test]
+    fn test_describe_zwj_sequences() {
+        assert_eq!(describe_all("👨‍👩‍👧‍👦"), "family: man, woman, girl, boy");
+        assert_eq!(describe_all("🏳️‍🌈"), "rainbow flag");
+        assert_eq!(describe_all("🤷‍♀️"), "woman shrugging");
+        // Not in the table, so read as its parts
+        assert_eq!(describe_all("👩‍🎤"), "woman, microphone");
+    }
+
+    #[test]
+    fn test_describe_keycap() {
+        assert_eq!(describe_all("Press 1️⃣ now"), "Press keycap: 1 now");
+        assert_eq!(describe_all("Room 101"), "Room 101");
+    }
+
+    #[test]
+    fn test_skip_removes_whole_clusters() {
+        assert_eq!(
+            apply("Hi 👋🏻 👨‍👩‍👧 🇫🇷!", EmojiMode::Skip)
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            ["Hi", "!"]
+        );
+        assert_eq!(apply("café ©", EmojiMode::Skip), "café ");
+    }
+
+    #[test]
+    fn test_keep_passes_through() {
+        let text = "Hi 👋🏻 👨‍👩‍👧 🇫🇷!";
+        assert_eq!(apply(text, EmojiMode::Keep), text);
+    }
+
+    #[test]
+    fn test_unknown_emoji_is_dropped_when_describing() {
+        assert_eq!(describe_all("Look 🪼 here"), "Look here");
+    }
+}
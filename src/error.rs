@@ -31,6 +31,25 @@ pub enum Error {
 
     #[error("TTS operation failed: {0}")]
     OperationFailed(String),
+
+    #[error("Session character limit exceeded: {requested} requested, {remaining} remaining")]
+    SessionLimitExceeded { requested: u32, remaining: u32 },
+
+    #[error("Speak list not found: {0}")]
+    SpeakListNotFound(String),
+
+    #[error("Another speech sequence is still playing")]
+    SequenceActive,
+
+    #[error("No text-to-speech voices available on {0}")]
+    NoVoicesAvailable(&'static str),
+
+    #[error("TTS backend not found: {0}")]
+    BackendNotFound(String),
+
+    /// A `strictVoice` request whose voice or language has no voice
+    #[error("{0}")]
+    VoiceNotFound(String),
 }
 
 impl Error {
@@ -46,6 +65,12 @@ impl Error {
             Error::NotInitialized => "NOT_INITIALIZED",
             Error::Validation(_) => "VALIDATION_ERROR",
             Error::OperationFailed(_) => "OPERATION_FAILED",
+            Error::SessionLimitExceeded { .. } => "SESSION_LIMIT_EXCEEDED",
+            Error::SpeakListNotFound(_) => "SPEAK_LIST_NOT_FOUND",
+            Error::SequenceActive => "SEQUENCE_ACTIVE",
+            Error::NoVoicesAvailable(_) => "NO_VOICES",
+            Error::BackendNotFound(_) => "BACKEND_NOT_FOUND",
+            Error::VoiceNotFound(_) => "VOICE_NOT_FOUND",
         }
     }
 }
@@ -0,0 +1,49 @@
+/// Rust-side rewrite of the text of every utterance, registered with
+/// [`crate::Builder::filter`] or [`crate::init_with_filters`]
+///
+/// Filters run in the order they were registered, after the plugin's own
+/// preprocessing and before the pronunciation lexicon and affixes. Like those
+/// steps they are skipped for `raw` text and SSML. Any `Fn(&str) -> String`
+/// closure is a filter.
+pub trait TextFilter: Send + Sync + 'static {
+    fn filter(&self, text: &str) -> String;
+}
+
+impl<F> TextFilter for F
+where
+    F: Fn(&str) -> String + Send + Sync + 'static,
+{
+    fn filter(&self, text: &str) -> String {
+        self(text)
+    }
+}
+
+/// Run `text` through every filter in turn
+pub(crate) fn apply(filters: &[Box<dyn TextFilter>], text: String) -> String {
+    filters
+        .iter()
+        .fold(text, |text, filter| filter.filter(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Censor(&'static str);
+
+    impl TextFilter for Censor {
+        fn filter(&self, text: &str) -> String {
+            text.replace(self.0, "beep")
+        }
+    }
+
+    #[test]
+    fn test_filters_run_in_order() {
+        let filters: Vec<Box<dyn TextFilter>> = vec![
+            Box::new(|text: &str| text.replace("darn", "heck")),
+            Box::new(Censor("heck")),
+        ];
+        assert_eq!(apply(&filters, "Oh darn it".into()), "Oh beep it");
+        assert_eq!(apply(&[], "unchanged".into()), "unchanged");
+    }
+}
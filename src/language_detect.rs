@@ -0,0 +1,127 @@
+use whatlang::Lang;
+
+/// Language code of the text, for picking a voice when a request asks for
+/// `autoDetectLanguage`. `None` when the text is too short or ambiguous for the
+/// detection to be reliable, which is common for a few words in related languages
+pub(crate) fn detect_language(text: &str) -> Option<&'static str> {
+    let info = whatlang::detect(text)?;
+    if !info.is_reliable() {
+        log::debug!(
+            "Language detection unsure of the text ({:?}, confidence {:.2})",
+            info.lang(),
+            info.confidence()
+        );
+        return None;
+    }
+    Some(language_code(info.lang()))
+}
+
+/// BCP 47 language code voices report, from the ISO 639-3 one whatlang gives
+fn language_code(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Afr => "af",
+        Lang::Aka => "ak",
+        Lang::Amh => "am",
+        Lang::Ara => "ar",
+        Lang::Aze => "az",
+        Lang::Bel => "be",
+        Lang::Ben => "bn",
+        Lang::Bul => "bg",
+        Lang::Cat => "ca",
+        Lang::Ces => "cs",
+        Lang::Cmn => "zh",
+        Lang::Dan => "da",
+        Lang::Deu => "de",
+        Lang::Ell => "el",
+        Lang::Eng => "en",
+        Lang::Epo => "eo",
+        Lang::Est => "et",
+        Lang::Fin => "fi",
+        Lang::Fra => "fr",
+        Lang::Guj => "gu",
+        Lang::Heb => "he",
+        Lang::Hin => "hi",
+        Lang::Hrv => "hr",
+        Lang::Hun => "hu",
+        Lang::Hye => "hy",
+        Lang::Ind => "id",
+        Lang::Ita => "it",
+        Lang::Jav => "jv",
+        Lang::Jpn => "ja",
+        Lang::Kan => "kn",
+        Lang::Kat => "ka",
+        Lang::Khm => "km",
+        Lang::Kor => "ko",
+        Lang::Lat => "la",
+        Lang::Lav => "lv",
+        Lang::Lit => "lt",
+        Lang::Mal => "ml",
+        Lang::Mar => "mr",
+        Lang::Mkd => "mk",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Nld => "nl",
+        Lang::Nob => "nb",
+        Lang::Ori => "or",
+        Lang::Pan => "pa",
+        Lang::Pes => "fa",
+        Lang::Pol => "pl",
+        Lang::Por => "pt",
+        Lang::Ron => "ro",
+        Lang::Rus => "ru",
+        Lang::Sin => "si",
+        Lang::Slk => "sk",
+        Lang::Slv => "sl",
+        Lang::Sna => "sn",
+        Lang::Spa => "es",
+        Lang::Srp => "sr",
+        Lang::Swe => "sv",
+        Lang::Tam => "ta",
+        Lang::Tel => "te",
+        Lang::Tgl => "fil",
+        Lang::Tha => "th",
+        Lang::Tuk => "tk",
+        Lang::Tur => "tr",
+        Lang::Ukr => "uk",
+        Lang::Urd => "ur",
+        Lang::Uzb => "uz",
+        Lang::Vie => "vi",
+        Lang::Yid => "yi",
+        Lang::Zul => "zu",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_the_language_of_a_sentence() {
+        assert_eq!(
+            detect_language("Le renard brun rapide saute par-dessus le chien paresseux"),
+            Some("fr")
+        );
+        assert_eq!(
+            detect_language("Ich habe heute keine Zeit, weil ich noch arbeiten muss"),
+            Some("de")
+        );
+        assert_eq!(
+            detect_language("こんにちは、今日はいい天気ですね"),
+            Some("ja")
+        );
+    }
+
+    #[test]
+    fn test_unsure_detection_gives_no_language() {
+        assert_eq!(detect_language("ok"), None);
+        assert_eq!(detect_language("123 456"), None);
+    }
+
+    #[test]
+    fn test_every_language_has_a_code() {
+        for &lang in Lang::all() {
+            let code = language_code(lang);
+            assert!((2..=3).contains(&code.len()), "{:?} -> {}", lang, code);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::models::{LexiconEntry, ValidationError, MAX_LEXICON_ENTRIES};
+
+/// File in the app data directory the lexicon is kept in
+const LEXICON_FILE: &str = "tts-lexicon.json";
+
+/// App-wide respellings applied to all text before it is spoken, kept on disk
+/// across launches
+#[derive(Debug, Default)]
+pub(crate) struct Lexicon {
+    entries: Vec<LexiconEntry>,
+    /// `None` when the app data directory can't be resolved, which keeps the
+    /// lexicon for this session only
+    path: Option<PathBuf>,
+}
+
+impl Lexicon {
+    /// Load the lexicon saved by an earlier launch. A missing or unreadable file
+    /// starts an empty one, so a damaged file can't keep the plugin from loading
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> Self {
+        let path = match app.path().app_data_dir() {
+            Ok(dir) => Some(dir.join(LEXICON_FILE)),
+            Err(e) => {
+                log::warn!("Pronunciation lexicon won't be saved: {}", e);
+                None
+            }
+        };
+        let entries = path
+            .as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| match read_entries(path) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    log::error!("Failed to load pronunciation lexicon: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self { entries, path }
+    }
+
+    pub fn entries(&self) -> &[LexiconEntry] {
+        &self.entries
+    }
+
+    /// Add an entry, replacing the one for the same word, and save the lexicon
+    pub fn add(&mut self, entry: LexiconEntry) -> crate::Result<()> {
+        entry.validate()?;
+        let entry = LexiconEntry {
+            word: entry.word.trim().to_string(),
+            spoken_as: entry.spoken_as.trim().to_string(),
+        };
+        match self.position(&entry.word) {
+            Some(index) => self.entries[index] = entry,
+            None if self.entries.len() >= MAX_LEXICON_ENTRIES => {
+                return Err(ValidationError::LexiconFull {
+                    max: MAX_LEXICON_ENTRIES,
+                }
+                .into());
+            }
+            None => self.entries.push(entry),
+        }
+        self.save()
+    }
+
+    /// Remove the entry for `word`, saving the lexicon if there was one
+    pub fn remove(&mut self, word: &str) -> crate::Result<bool> {
+        let Some(index) = self.position(word.trim()) else {
+            return Ok(false);
+        };
+        self.entries.remove(index);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Replace every whole-word, case-insensitive match of an entry's word with
+    /// its respelling. The longest match wins where entries overlap
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.entries.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        let mut replaced = String::new();
+        let mut plain_start = 0;
+        let mut index = 0;
+        let mut previous: Option<char> = None;
+        while let Some(c) = text[index..].chars().next() {
+            let rest = &text[index..];
+            let found = if previous.is_some_and(char::is_alphanumeric) {
+                None
+            } else {
+                self.entries
+                    .iter()
+                    .filter_map(|entry| {
+                        crate::ssml::whole_word_len(rest, &entry.word).map(|len| (entry, len))
+                    })
+                    .max_by_key(|&(_, len)| len)
+            };
+            let Some((entry, len)) = found else {
+                previous = Some(c);
+                index += c.len_utf8();
+                continue;
+            };
+
+            replaced.push_str(&text[plain_start..index]);
+            replaced.push_str(&entry.spoken_as);
+            previous = rest[..len].chars().last();
+            index += len;
+            plain_start = index;
+        }
+        if plain_start == 0 {
+            return Cow::Borrowed(text);
+        }
+        replaced.push_str(&text[plain_start..]);
+        Cow::Owned(replaced)
+    }
+
+    fn position(&self, word: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.word.to_lowercase() == word.to_lowercase())
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(&self.entries).map_err(std::io::Error::from)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+fn read_entries(path: &Path) -> std::io::Result<Vec<LexiconEntry>> {
+    let json = std::fs::read(path)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, spoken_as: &str) -> LexiconEntry {
+        LexiconEntry {
+            word: word.to_string(),
+            spoken_as: spoken_as.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_replaces_whole_words_only() {
+        let mut lexicon = Lexicon::default();
+        lexicon.add(entry("SQL", "sequel")).unwrap();
+        lexicon.add(entry("nginx", "engine x")).unwrap();
+
+        assert_eq!(
+            lexicon.apply("Run sql behind Nginx."),
+            "Run sequel behind engine x."
+        );
+        assert_eq!(lexicon.apply("SQLite and MySQL"), "SQLite and MySQL");
+        assert!(matches!(lexicon.apply("nothing here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_longest_entry_wins() {
+        let mut lexicon = Lexicon::default();
+        lexicon.add(entry("New", "knew")).unwrap();
+        lexicon.add(entry("New York", "the big apple")).unwrap();
+
+        assert_eq!(lexicon.apply("New York is new"), "the big apple is knew");
+    }
+
+    #[test]
+    fn test_add_replaces_and_remove_ignores_case() {
+        let mut lexicon = Lexicon::default();
+        lexicon.add(entry("gif", "jif")).unwrap();
+        lexicon.add(entry("GIF", "gift")).unwrap();
+        assert_eq!(lexicon.entries(), &[entry("GIF", "gift")]);
+
+        assert!(lexicon.remove("Gif").unwrap());
+        assert!(!lexicon.remove("gif").unwrap());
+        assert!(lexicon.entries().is_empty());
+    }
+
+    #[test]
+    fn test_add_rejects_empty_entries() {
+        let mut lexicon = Lexicon::default();
+        assert!(lexicon.add(entry(" ", "something")).is_err());
+        assert!(lexicon.add(entry("word", "")).is_err());
+        assert!(lexicon.entries().is_empty());
+    }
+}
@@ -1,20 +1,57 @@
 use tauri::{
-    plugin::{Builder, TauriPlugin},
+    plugin::{Builder as PluginBuilder, TauriPlugin},
     Manager, Runtime,
 };
 
 pub use models::*;
 
+#[cfg(desktop)]
+mod audio_cache;
+#[cfg(desktop)]
+mod backend;
 #[cfg(desktop)]
 mod desktop;
 #[cfg(mobile)]
 mod mobile;
+#[cfg(all(desktop, feature = "mock"))]
+mod mock;
+#[cfg(desktop)]
+mod queue;
+#[cfg(desktop)]
+mod rate;
+#[cfg(any(
+    target_os = "macos",
+    feature = "polly",
+    feature = "elevenlabs",
+    feature = "openai",
+    feature = "piper",
+    test
+))]
+mod wav;
 
 mod commands;
+mod cooldown;
+mod default_voices;
+mod emoji;
 mod error;
+mod filter;
+mod language_detect;
+mod lexicon;
+mod markup;
 mod models;
+mod preprocess;
+mod speak_list;
+mod ssml;
+mod usage;
+mod word_offsets;
+mod wpm;
 
+#[cfg(desktop)]
+pub use backend::{SpeechMark, TtsBackend};
 pub use error::{Error, Result};
+pub use filter::TextFilter;
+#[cfg(all(desktop, feature = "mock"))]
+pub use mock::MockCall;
 
 #[cfg(desktop)]
 use desktop::Tts;
@@ -32,25 +69,148 @@ impl<R: Runtime, T: Manager<R>> crate::TtsExt<R> for T {
     }
 }
 
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("tts")
-        .invoke_handler(tauri::generate_handler![
-            commands::speak,
-            commands::stop,
-            commands::get_voices,
-            commands::is_speaking,
-            commands::is_initialized,
-            commands::pause_speaking,
-            commands::resume_speaking,
-            commands::preview_voice
-        ])
-        .setup(|app, api| {
-            #[cfg(mobile)]
-            let tts = mobile::init(app, api)?;
-            #[cfg(desktop)]
-            let tts = desktop::init(app, api)?;
-            app.manage(tts);
-            Ok(())
-        })
-        .build()
+/// Initializes the plugin, reading its configuration from `plugins.tts` in `tauri.conf.json`.
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<TtsConfig>> {
+    Builder::new().build()
+}
+
+/// Initializes the plugin with an explicit configuration, which takes precedence
+/// over any `plugins.tts` section in `tauri.conf.json`.
+pub fn init_with_config<R: Runtime>(config: TtsConfig) -> TauriPlugin<R, Option<TtsConfig>> {
+    Builder::new().config(config).build()
+}
+
+/// Initializes the plugin like [`init`], running the text of every utterance
+/// through `filters` in order before it is spoken.
+pub fn init_with_filters<R: Runtime>(
+    filters: Vec<Box<dyn TextFilter>>,
+) -> TauriPlugin<R, Option<TtsConfig>> {
+    Builder::new().filters(filters).build()
+}
+
+/// Builds the plugin with any combination of an explicit configuration and text
+/// filters, which [`init_with_config`] and [`init_with_filters`] can't combine.
+///
+/// ```rust,ignore
+/// tauri_plugin_tts::Builder::new()
+///     .config(config)
+///     .filter(|text: &str| text.replace("ETA", "estimated arrival"))
+///     .build()
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    config: Option<TtsConfig>,
+    filters: Vec<Box<dyn TextFilter>>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `config`, which takes precedence over any `plugins.tts` section in
+    /// `tauri.conf.json`
+    pub fn config(mut self, config: TtsConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Run the text of every utterance through `filter`, after the filters
+    /// added before it
+    pub fn filter(mut self, filter: impl TextFilter) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Add several filters at once, see [`Builder::filter`]
+    pub fn filters(mut self, filters: Vec<Box<dyn TextFilter>>) -> Self {
+        self.filters.extend(filters);
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R, Option<TtsConfig>> {
+        let Self { config, filters } = self;
+        PluginBuilder::<R, Option<TtsConfig>>::new("tts")
+            .invoke_handler(tauri::generate_handler![
+                commands::speak,
+                commands::speak_and_wait,
+                commands::stop,
+                commands::stop_if_matches,
+                commands::stop_gentle,
+                commands::get_queue_length,
+                commands::get_queue,
+                commands::clear_queue,
+                commands::remove_from_queue,
+                commands::skip_to_next,
+                commands::get_voices,
+                commands::refresh_voices,
+                commands::get_languages,
+                commands::is_speaking,
+                commands::is_paused,
+                commands::is_initialized,
+                commands::reinitialize,
+                commands::get_capabilities,
+                commands::get_rate_info,
+                commands::get_features,
+                commands::get_backends,
+                commands::get_cache_stats,
+                commands::clear_tts_cache,
+                commands::list_piper_voices,
+                commands::download_piper_voice,
+                commands::get_audio_route,
+                commands::request_audio_focus,
+                commands::abandon_audio_focus,
+                commands::get_engine_params,
+                commands::get_current_voice,
+                commands::get_default_voice,
+                commands::resolve_voice,
+                commands::get_best_voice,
+                commands::speak_to_file,
+                commands::synthesize,
+                commands::synthesize_stream,
+                commands::pause_speaking,
+                commands::resume_speaking,
+                commands::preview_voice,
+                commands::preview_all,
+                commands::calibration_samples,
+                commands::get_pronunciation,
+                commands::count_words,
+                commands::set_affixes,
+                commands::add_pronunciation,
+                commands::remove_pronunciation,
+                commands::list_pronunciations,
+                commands::set_voice_gain,
+                commands::export_profile,
+                commands::import_profile,
+                commands::set_voice,
+                commands::set_default_voice,
+                commands::set_rate,
+                commands::set_pitch,
+                commands::set_volume,
+                commands::reset_session_usage,
+                commands::speak_sequence,
+                commands::speak_segments,
+                commands::speak_from_offset,
+                commands::speak_outline,
+                commands::speak_section,
+                commands::speak_list_begin,
+                commands::speak_list_next,
+                commands::speak_list_reset,
+                commands::set_enabled,
+                commands::is_enabled,
+                commands::speak_words_begin,
+                commands::speak_word_next,
+                commands::benchmark_voice
+            ])
+            .setup(move |app, api| {
+                let config = config.or_else(|| api.config().clone()).unwrap_or_default();
+                #[cfg(mobile)]
+                let tts = mobile::init(app, api, config, filters)?;
+                #[cfg(desktop)]
+                let tts = desktop::init(app, api, config, filters)?;
+                app.manage(tts);
+                Ok(())
+            })
+            .build()
+    }
 }
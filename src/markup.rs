@@ -0,0 +1,418 @@
+use crate::models::TextFormat;
+
+/// Elements whose content is never spoken
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style", "pre", "template", "head"];
+/// Elements that end the sentence before and after them
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "br",
+    "hr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "li",
+    "blockquote",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "table",
+    "tr",
+    "dt",
+    "dd",
+];
+
+/// Strip markdown or HTML markup from `text`, leaving what a reader would say.
+/// Headings, paragraphs and list items become sentences of their own, ordered
+/// list items are read with their number and code blocks are left out
+pub(crate) fn strip(text: &str, format: TextFormat) -> String {
+    match format {
+        TextFormat::Plain => text.to_string(),
+        TextFormat::Markdown => strip_markdown(text),
+        TextFormat::Html => strip_html(text),
+    }
+}
+
+fn strip_markdown(text: &str) -> String {
+    let mut spoken = String::new();
+    // Character and length of the fence of the code block being skipped
+    let mut fence: Option<(char, usize)> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some((c, len)) = fence {
+            let run = line.chars().take_while(|&next| next == c).count();
+            if run >= len && line[run..].trim().is_empty() {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(opened) = code_fence(line) {
+            fence = Some(opened);
+            end_sentence(&mut spoken);
+            continue;
+        }
+
+        let mut content = line;
+        while let Some(quoted) = content.strip_prefix('>') {
+            content = quoted.trim_start();
+        }
+        if content.is_empty() || is_rule(content) {
+            end_sentence(&mut spoken);
+        } else if let Some(heading) = heading(content) {
+            end_sentence(&mut spoken);
+            spoken.push_str(&strip_inline(heading));
+            end_sentence(&mut spoken);
+        } else if let Some((number, item)) = list_item(content) {
+            end_sentence(&mut spoken);
+            if let Some(number) = number {
+                spoken.push_str(number);
+                spoken.push_str(", ");
+            }
+            spoken.push_str(&strip_inline(item));
+            end_sentence(&mut spoken);
+        } else if content.starts_with('|') {
+            if let Some(row) = table_row(content) {
+                end_sentence(&mut spoken);
+                spoken.push_str(&row);
+                end_sentence(&mut spoken);
+            }
+        } else {
+            spoken.push_str(&strip_inline(content));
+            spoken.push('\n');
+        }
+    }
+    spoken.trim_end().to_string()
+}
+
+/// Fence character and length of a line opening a fenced code block
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let c = line.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = line.chars().take_while(|&next| next == c).count();
+    (len >= 3).then_some((c, len))
+}
+
+/// Whether a line is a thematic break (`---`, `* * *`) or a setext heading underline
+fn is_rule(line: &str) -> bool {
+    let mut marks = line.chars().filter(|c| !c.is_whitespace());
+    let Some(first) = marks.next().filter(|c| "-*_=".contains(*c)) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in marks {
+        if c != first {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim_end())
+}
+
+/// The number of an ordered list item and the item's text
+fn list_item(line: &str) -> Option<(Option<&str>, &str)> {
+    let (number, rest) = if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+        (None, rest)
+    } else {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        let rest = line[digits..].strip_prefix(['.', ')'])?;
+        (Some(&line[..digits]), rest)
+    };
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let item = rest.trim_start();
+    // Task list checkboxes
+    let item = ["[ ]", "[x]", "[X]"]
+        .iter()
+        .find_map(|checkbox| item.strip_prefix(checkbox))
+        .map_or(item, str::trim_start);
+    Some((number, item))
+}
+
+/// Cells of a table row joined into one sentence, `None` for the header separator
+fn table_row(line: &str) -> Option<String> {
+    let cells: Vec<String> = line
+        .split('|')
+        .map(str::trim)
+        .filter(|cell| !cell.is_empty())
+        .map(strip_inline)
+        .collect();
+    let separator = cells
+        .iter()
+        .all(|cell| cell.chars().all(|c| c == '-' || c == ':'));
+    (!cells.is_empty() && !separator).then(|| cells.join(", "))
+}
+
+/// Remove emphasis, code spans and link targets from one line of markdown
+fn strip_inline(line: &str) -> String {
+    let mut spoken = String::with_capacity(line.len());
+    let mut previous: Option<char> = None;
+    let mut index = 0;
+    while let Some(c) = line[index..].chars().next() {
+        let rest = &line[index..];
+        let next = rest[c.len_utf8()..].chars().next();
+        let (text, consumed) = match c {
+            '\\' if next.is_some_and(|next| next.is_ascii_punctuation()) => (&rest[1..2], 2),
+            '!' if next == Some('[') => match link(&rest[1..]) {
+                Some((label, len)) => (label, 1 + len),
+                None => ("!", 1),
+            },
+            '[' => match link(rest) {
+                Some((label, len)) => (label, len),
+                None => ("[", 1),
+            },
+            '`' => {
+                let ticks = rest.chars().take_while(|&t| t == '`').count();
+                let fence = &rest[..ticks];
+                match rest[ticks..].find(fence) {
+                    Some(end) => (rest[ticks..ticks + end].trim(), ticks * 2 + end),
+                    None => (fence, ticks),
+                }
+            }
+            '<' => match rest.find('>') {
+                Some(end) if rest[1..end].contains("://") => (&rest[1..end], end + 1),
+                _ => ("<", 1),
+            },
+            '*' | '_' | '~' => {
+                let run = rest.chars().take_while(|&m| m == c).count();
+                let after = rest[run..].chars().next();
+                let flanking = after.is_some_and(|a| !a.is_whitespace())
+                    || previous.is_some_and(|p| !p.is_whitespace());
+                let intraword = previous.is_some_and(char::is_alphanumeric)
+                    && after.is_some_and(char::is_alphanumeric);
+                let emphasis = flanking && !intraword && (c != '~' || run >= 2);
+                (if emphasis { "" } else { &rest[..run] }, run)
+            }
+            _ => (&rest[..c.len_utf8()], c.len_utf8()),
+        };
+        // Nested emphasis and code inside link labels
+        if matches!(c, '[' | '!') && consumed > 1 {
+            spoken.push_str(&strip_inline(text));
+        } else {
+            spoken.push_str(text);
+        }
+        previous = rest[..consumed].chars().last();
+        index += consumed;
+    }
+    spoken
+}
+
+/// Label of a `[label](target)` or `[label][ref]` link at the start of `text`,
+/// and the link's length
+fn link(text: &str) -> Option<(&str, usize)> {
+    let close = text.find(']')?;
+    let label = &text[1..close];
+    let after = &text[close + 1..];
+    let target_end = match after.chars().next() {
+        Some('(') => after.find(')')?,
+        Some('[') => after.find(']')?,
+        _ => return None,
+    };
+    Some((label, close + 1 + target_end + 1))
+}
+
+fn strip_html(text: &str) -> String {
+    let mut spoken = String::new();
+    // The next number of each open `<ol>`, `None` for `<ul>`
+    let mut lists: Vec<Option<u32>> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        push_decoded(&mut spoken, &rest[..start]);
+        let markup = &rest[start..];
+        if let Some(comment) = markup.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let starts_tag = markup[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!');
+        let Some(end) = markup.find('>').filter(|_| starts_tag) else {
+            spoken.push('<');
+            rest = &markup[1..];
+            continue;
+        };
+        let tag = &markup[1..end];
+        rest = &markup[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !closing && SKIPPED_ELEMENTS.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(at) => rest[at..].find('>').map_or("", |end| &rest[at + end + 1..]),
+                None => "",
+            };
+            end_sentence(&mut spoken);
+            continue;
+        }
+        match name.as_str() {
+            "ol" | "ul" if !closing => {
+                end_sentence(&mut spoken);
+                lists.push((name == "ol").then_some(1));
+            }
+            "ol" | "ul" => {
+                end_sentence(&mut spoken);
+                lists.pop();
+            }
+            "li" if !closing => {
+                end_sentence(&mut spoken);
+                if let Some(Some(number)) = lists.last_mut() {
+                    spoken.push_str(&format!("{}, ", number));
+                    *number += 1;
+                }
+            }
+            "td" | "th" if closing => spoken.push_str(", "),
+            name if BLOCK_ELEMENTS.contains(&name) => end_sentence(&mut spoken),
+            _ => {}
+        }
+    }
+    push_decoded(&mut spoken, rest);
+    spoken.trim_end().to_string()
+}
+
+/// Append HTML text with its character references decoded
+fn push_decoded(spoken: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        spoken.push_str(&rest[..start]);
+        let reference = &rest[start..];
+        let decoded = reference
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode(&reference[1..end]).map(|c| (c, end + 1)));
+        match decoded {
+            Some((c, len)) => {
+                spoken.push(c);
+                rest = &reference[len..];
+            }
+            None => {
+                spoken.push('&');
+                rest = &reference[1..];
+            }
+        }
+    }
+    spoken.push_str(rest);
+}
+
+fn decode(entity: &str) -> Option<char> {
+    let code = if let Some(hex) = entity
+        .strip_prefix("#x")
+        .or_else(|| entity.strip_prefix("#X"))
+    {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(decimal) = entity.strip_prefix('#') {
+        decimal.parse().ok()
+    } else {
+        return match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "hellip" => Some('…'),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            _ => None,
+        };
+    };
+    code.and_then(char::from_u32)
+}
+
+/// End the text so far with a sentence break, so the engine pauses before what follows
+fn end_sentence(spoken: &mut String) {
+    let len = spoken.trim_end().len();
+    spoken.truncate(len);
+    let Some(last) = spoken.chars().last() else {
+        return;
+    };
+    if !".!?:;,".contains(last) {
+        spoken.push('.');
+    }
+    spoken.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_is_read_as_sentences() {
+        let note = "# Groceries\n\nBuy **fresh** _basil_ from [the market](https://example.com).\n\n- apples\n- [x] pears\n\n1. Wash\n2. Chop\n\n---\nSee `notes_v2`, done";
+        assert_eq!(
+            strip(note, TextFormat::Markdown),
+            "Groceries.\nBuy fresh basil from the market.\napples.\npears.\n1, Wash.\n2, Chop.\nSee notes_v2, done"
+        );
+    }
+
+    #[test]
+    fn test_markdown_skips_code_blocks() {
+        let note = "Run this:\n```sh\n# not a heading\nrm -rf build\n```\nThen rebuild.";
+        assert_eq!(
+            strip(note, TextFormat::Markdown),
+            "Run this:\nThen rebuild."
+        );
+    }
+
+    #[test]
+    fn test_markdown_keeps_literal_symbols() {
+        assert_eq!(
+            strip("snake_case and 2 * 3 and a_b_c", TextFormat::Markdown),
+            "snake_case and 2 * 3 and a_b_c"
+        );
+        assert_eq!(
+            strip(
+                "| Name | Qty |\n|---|:-:|\n| Tea | 2 |",
+                TextFormat::Markdown
+            ),
+            "Name, Qty.\nTea, 2."
+        );
+    }
+
+    #[test]
+    fn test_html_is_read_as_sentences() {
+        let page = "<h1>Menu</h1><p>Soup &amp; salad<br>Bread</p><ol><li>Starter</li><li>Main</li></ol><ul><li>Tea</li></ul>";
+        assert_eq!(
+            strip(page, TextFormat::Html),
+            "Menu.\nSoup & salad.\nBread.\n1, Starter.\n2, Main.\nTea."
+        );
+    }
+
+    #[test]
+    fn test_html_skips_code_and_scripts() {
+        let page = "<p>Try it:</p><pre><code>let x = 1 < 2;</code></pre><script>alert('hi')</script><!-- hidden -->Done, 3 < 4 &#x263A;";
+        assert_eq!(strip(page, TextFormat::Html), "Try it:\nDone, 3 < 4 ☺");
+    }
+
+    #[test]
+    fn test_plain_is_unchanged() {
+        assert_eq!(
+            strip("**not** <b>markup</b>", TextFormat::Plain),
+            "**not** <b>markup</b>"
+        );
+    }
+}
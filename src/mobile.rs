@@ -1,71 +1,1257 @@
 use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tauri::{
     plugin::{PluginApi, PluginHandle},
-    AppHandle, Runtime,
+    AppHandle, Emitter, Manager, Runtime,
 };
 
+use crate::cooldown::InterruptCooldown;
+use crate::default_voices::DefaultVoices;
+use crate::filter::TextFilter;
+use crate::lexicon::Lexicon;
 use crate::models::*;
+use crate::speak_list::SpeakLists;
+use crate::usage::{spoken_chars, SessionUsage};
+use crate::word_offsets::{sentence_offsets, WordAligner};
 
 #[cfg(target_os = "ios")]
 tauri::ios_plugin_binding!(init_plugin_tts);
 
+/// Longest a benchmark may take before it is abandoned
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(120);
+/// How long to wait for the native engine to report speaking before trusting `isSpeaking`
+const SPEECH_START_GRACE: Duration = Duration::from_millis(300);
+const SPEECH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Fail for any backend but the native engine, apps can only register them on desktop
+fn check_backend(backend: Option<&str>) -> crate::Result<()> {
+    match backend {
+        Some(name) if name != SYSTEM_BACKEND => {
+            Err(crate::Error::BackendNotFound(name.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
 pub fn init<R: Runtime, C: DeserializeOwned>(
-    _app: &AppHandle<R>,
+    app: &AppHandle<R>,
     api: PluginApi<R, C>,
+    config: TtsConfig,
+    filters: Vec<Box<dyn TextFilter>>,
 ) -> crate::Result<Tts<R>> {
     #[cfg(target_os = "android")]
     let handle = api.register_android_plugin("io.affex.tts", "TtsPlugin")?;
     #[cfg(target_os = "ios")]
     let handle = api.register_ios_plugin(init_plugin_tts)?;
-    Ok(Tts(handle))
+
+    #[cfg(target_os = "ios")]
+    if let Err(e) =
+        handle.run_mobile_plugin::<()>("configureAudioSession", config.audio_session.clone())
+    {
+        log::warn!("Failed to configure iOS audio session: {}", e);
+    }
+
+    config.affixes.validate()?;
+    config.validate_voice_gains()?;
+    let profile = config.default_profile()?;
+
+    Ok(Tts {
+        app: app.clone(),
+        handle,
+        affixes: RwLock::new(config.affixes.clone()),
+        filters,
+        lexicon: RwLock::new(Lexicon::load(app)),
+        default_voices: RwLock::new(DefaultVoices::load(app)),
+        voice_gains: RwLock::new(config.voice_gains.clone()),
+        cooldown: Mutex::new(InterruptCooldown::new(&config)),
+        usage: Mutex::new(SessionUsage::new(&config)),
+        lists: Mutex::new(SpeakLists::default()),
+        words: Mutex::new(SpeakLists::default()),
+        enabled: AtomicBool::new(true),
+        sequence_sent: Mutex::new(false),
+        known_voices: Mutex::new(None),
+        profile: RwLock::new(profile),
+        config,
+    })
 }
 
-pub struct Tts<R: Runtime>(PluginHandle<R>);
+pub struct Tts<R: Runtime> {
+    app: AppHandle<R>,
+    handle: PluginHandle<R>,
+    config: TtsConfig,
+    affixes: RwLock<Affixes>,
+    /// Host app rewrites from `init_with_filters`, run ahead of the lexicon
+    filters: Vec<Box<dyn TextFilter>>,
+    lexicon: RwLock<Lexicon>,
+    /// Voices saved with `set_default_voice`, applied after the profile's
+    default_voices: RwLock<DefaultVoices>,
+    voice_gains: RwLock<HashMap<String, f32>>,
+    cooldown: Mutex<InterruptCooldown>,
+    usage: Mutex<SessionUsage>,
+    lists: Mutex<SpeakLists>,
+    /// Word-by-word sessions, kept apart from lists so their ids don't mix
+    words: Mutex<SpeakLists>,
+    /// Cleared by `set_enabled(false)`: speaking calls succeed without speaking
+    enabled: AtomicBool,
+    /// Whether a sequence was sent to the native queue
+    sequence_sent: Mutex<bool>,
+    /// Last unfiltered voice list, which `refresh_voices` reports changes against
+    known_voices: Mutex<Option<Vec<Voice>>>,
+    profile: RwLock<VoiceProfile>,
+}
 
 impl<R: Runtime> Tts<R> {
-    pub fn speak(&self, payload: SpeakRequest) -> crate::Result<SpeakResponse> {
-        self.0
-            .run_mobile_plugin("speak", payload)
+    /// Resolve omitted fields and produce the final text, so the native plugins
+    /// receive explicit values. Returns one request per segment when the text is
+    /// split for structural pauses
+    fn prepare(&self, mut payload: SpeakRequest) -> crate::Result<Vec<SpeakRequest>> {
+        check_backend(payload.backend.as_deref())?;
+        // Ahead of the profile, whose rate it overrides. Only `speak` reports clamping
+        payload.apply_rate_wpm(crate::wpm::NORMAL_WPM);
+        // A detected language picks its own voice instead of the profile's
+        payload.apply_detected_language();
+        payload.apply_profile(
+            &*self
+                .profile
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?,
+        );
+        self.default_voices
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .apply(&mut payload);
+        // Callers may name a voice by its display name, the native engines only take ids
+        if payload.voice_id.is_some() || (payload.strict_voice && payload.language.is_some()) {
+            let voices = self.voices_or_none()?;
+            payload.match_voice_name(&voices);
+            payload.require_voice(&voices)?;
+        }
+        payload.apply_defaults(&self.config);
+        payload.apply_voice_gain(
+            &*self
+                .voice_gains
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?,
+        );
+        // Validate the whole text before splitting it up
+        payload.validate()?;
+
+        // Plain text around the markup would break the SSML document
+        let affixes = if payload.use_affixes && !payload.ssml {
+            self.affixes
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?
+                .clone()
+        } else {
+            Affixes::default()
+        };
+
+        let lexicon = self
+            .lexicon
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        let original = payload.text.clone();
+        let mut words = WordAligner::new(&original);
+        let mut sentence_index = 0;
+        let mut segments = payload.into_segments();
+        let last = segments.len() - 1;
+        for (index, segment) in segments.iter_mut().enumerate() {
+            let mut spoken = segment.spoken_text();
+            // Host filters and respellings are left out of raw text and SSML like every
+            // other rewrite. Rewritten words match nothing in the original, so report no
+            // boundaries
+            if !segment.preprocess_options().raw {
+                let filtered = crate::filter::apply(&self.filters, spoken);
+                spoken = lexicon.apply(&filtered).into_owned();
+            }
+            // Affixes go on after preprocessing so normalization can't mangle them
+            let text = affixes.apply_at(&spoken, index == 0, index == last);
+            // Engines that speak SSML report boundaries in text only they can see
+            if !segment.ssml {
+                let start = affixes.text_start_at(index == 0);
+                segment.word_offsets = words.align(&text, start, spoken.trim());
+                segment.sentence_offsets =
+                    sentence_offsets(&text, &segment.word_offsets, &mut sentence_index)
+                        .into_iter()
+                        .flatten()
+                        .collect();
+            }
+            segment.text = text;
+            // Overrides need SSML, engines that can't read it speak the plain text and warn
+            if segment.uses_pronunciations() {
+                if let Some(markup) =
+                    crate::ssml::with_phonemes(&segment.text, &segment.pronunciations)
+                {
+                    segment.text = markup;
+                    segment.ssml = true;
+                    segment.word_offsets.clear();
+                    segment.sentence_offsets.clear();
+                }
+            }
+        }
+        Ok(segments)
+    }
+
+    pub fn export_profile(&self) -> crate::Result<VoiceProfile> {
+        Ok(self
+            .profile
+            .read()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clone())
+    }
+
+    pub fn import_profile(&self, payload: VoiceProfile) -> crate::Result<ImportProfileResponse> {
+        let voices = self.voices_or_none()?;
+        let (profile, warnings) =
+            payload.sanitize(|voice_id| voices.iter().any(|v| v.id == voice_id))?;
+        for warning in &warnings {
+            log::warn!("Importing voice profile: {}", warning);
+        }
+
+        *self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)? = profile.clone();
+        Ok(ImportProfileResponse { profile, warnings })
+    }
+
+    pub fn set_voice(&self, payload: SetVoiceRequest) -> crate::Result<SetVoiceResponse> {
+        payload.validate()?;
+        let voices = self.voices_or_none()?;
+        Ok(self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .set_voice(payload.voice_id, |voice_id| {
+                voices.iter().any(|v| v.id == voice_id)
+            }))
+    }
+
+    pub fn set_default_voice(
+        &self,
+        payload: SetDefaultVoiceRequest,
+    ) -> crate::Result<SetDefaultVoiceResponse> {
+        payload.validate()?;
+        let voices = self.voices_or_none()?;
+        self.default_voices
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .set(payload.voice_id, payload.language, |voice_id| {
+                voices.iter().any(|v| v.id == voice_id)
+            })
+    }
+
+    pub fn set_rate(&self, payload: SetProsodyRequest) -> crate::Result<ProsodyResponse> {
+        let mut profile = self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(ProsodyResponse {
+            value: profile.set_rate(payload.value)?,
+        })
+    }
+
+    pub fn set_pitch(&self, payload: SetProsodyRequest) -> crate::Result<ProsodyResponse> {
+        let mut profile = self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(ProsodyResponse {
+            value: profile.set_pitch(payload.value)?,
+        })
+    }
+
+    pub fn set_volume(&self, payload: SetProsodyRequest) -> crate::Result<ProsodyResponse> {
+        let mut profile = self
+            .profile
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        Ok(ProsodyResponse {
+            value: profile.set_volume(payload.value)?,
+        })
+    }
+
+    pub fn set_affixes(&self, payload: Affixes) -> crate::Result<()> {
+        payload.validate()?;
+        *self
+            .affixes
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)? = payload;
+        Ok(())
+    }
+
+    pub fn add_pronunciation(&self, payload: LexiconEntry) -> crate::Result<()> {
+        self.lexicon
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .add(payload)
+    }
+
+    pub fn remove_pronunciation(
+        &self,
+        payload: RemovePronunciationRequest,
+    ) -> crate::Result<RemovePronunciationResponse> {
+        let removed = self
+            .lexicon
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .remove(&payload.word)?;
+        Ok(RemovePronunciationResponse { removed })
+    }
+
+    pub fn list_pronunciations(&self) -> crate::Result<ListPronunciationsResponse> {
+        Ok(ListPronunciationsResponse {
+            entries: self
+                .lexicon
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?
+                .entries()
+                .to_vec(),
+        })
+    }
+
+    pub fn set_enabled(&self, payload: SetEnabledRequest) -> crate::Result<()> {
+        self.enabled.store(payload.enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> crate::Result<IsEnabledResponse> {
+        Ok(IsEnabledResponse {
+            enabled: self.enabled.load(Ordering::Relaxed),
+        })
+    }
+
+    pub fn set_voice_gain(&self, payload: SetVoiceGainRequest) -> crate::Result<()> {
+        payload.validate()?;
+        let mut gains = self
+            .voice_gains
+            .write()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        match payload.gain {
+            Some(gain) => gains.insert(payload.voice_id, gain),
+            None => gains.remove(&payload.voice_id),
+        };
+        Ok(())
+    }
+
+    pub fn speak(&self, mut payload: SpeakRequest) -> crate::Result<SpeakResponse> {
+        let rate_warning = payload.apply_rate_wpm(crate::wpm::NORMAL_WPM);
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakResponse::disabled());
+        }
+        let mut segments = self.prepare(payload)?;
+
+        // Held until the speech is sent so concurrent requests can't overshoot the limit
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let chars = spoken_chars(segments.iter().map(|segment| segment.text.as_str()));
+        usage.check(chars)?;
+
+        let resolved = self
+            .cooldown
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .resolve(segments[0].queue_mode.unwrap_or_default(), Instant::now());
+        let Some(queue_mode) = resolved else {
+            return Ok(SpeakResponse {
+                success: false,
+                warning: Some(
+                    "Dropped: interrupted too soon after the last interruption".to_string(),
+                ),
+                remaining_chars: usage.remaining(),
+                ..Default::default()
+            });
+        };
+        segments[0].queue_mode = Some(queue_mode);
+        let scoped = segments[0].event_scope.is_some();
+        let capped = segments[0].max_duration_ms.is_some();
+        let normalized = segments[0].normalize_loudness;
+        let voice_warning = segments[0].voice_warning.clone();
+
+        // The native queue plays later segments after the first, keeping their pauses
+        let mut response: Option<SpeakResponse> = None;
+        for segment in segments {
+            let result: SpeakResponse = self.handle.run_mobile_plugin("speak", segment)?;
+            match response {
+                None => response = Some(result),
+                // The last segment's id is the one whose speech:finish ends the request
+                Some(ref mut first) => first.utterance_id = result.utterance_id,
+            }
+        }
+        let mut response = response.unwrap_or_default();
+        if response.warning.is_none() {
+            response.warning = voice_warning;
+        }
+        if scoped && response.warning.is_none() {
+            // Native events are delivered through the plugin channel, which has no namespaces
+            response.warning =
+                Some("eventScope is not supported on mobile, events use tts://".to_string());
+        }
+        if capped && response.warning.is_none() {
+            // The native queues give no way to stop one utterance without the rest
+            response.warning = Some("maxDurationMs is not supported on mobile".to_string());
+        }
+        if normalized && response.warning.is_none() {
+            // The native engines play their own audio, which the plugin never sees
+            response.warning = Some("normalizeLoudness is not supported on mobile".to_string());
+        }
+        if response.warning.is_none() {
+            response.warning = rate_warning;
+        }
+        if response.success {
+            response.remaining_chars = usage.charge(chars);
+        } else {
+            response.remaining_chars = usage.remaining();
+        }
+        Ok(response)
+    }
+
+    /// Speak like `speak`, then block until the native engine reports how the
+    /// utterance ended
+    pub fn speak_and_wait(&self, payload: SpeakRequest) -> crate::Result<SpeakAndWaitResponse> {
+        let spoken = self.speak(payload)?;
+        if !spoken.success {
+            return Ok(SpeakAndWaitResponse::new(SpeechOutcome::Stopped, spoken));
+        }
+        // Nothing was sent while the plugin is disabled
+        if spoken.utterance_id.is_none() {
+            return Ok(SpeakAndWaitResponse::new(SpeechOutcome::Skipped, spoken));
+        }
+
+        // Speech queued after this one doesn't hold up the answer
+        let utterance_id = spoken.utterance_id.clone().unwrap_or_default();
+        let end: UtteranceEnd = self
+            .handle
+            .run_mobile_plugin("waitForUtterance", WaitForUtteranceRequest { utterance_id })?;
+        Ok(match end.error {
+            Some(error) => SpeakAndWaitResponse::failed(error, spoken),
+            None => SpeakAndWaitResponse::new(end.outcome, spoken),
+        })
+    }
+
+    /// The native engines don't report completion to Rust, so poll until speech
+    /// started at `started` ends. Returns `false` if it is still going after `timeout`
+    fn wait_until_silent(
+        &self,
+        started: Instant,
+        timeout: Option<Duration>,
+    ) -> crate::Result<bool> {
+        let mut seen_speaking = false;
+        loop {
+            let speaking = self.is_speaking()?.speaking;
+            seen_speaking |= speaking;
+            if !speaking && (seen_speaking || started.elapsed() >= SPEECH_START_GRACE) {
+                return Ok(true);
+            }
+            if timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+                return Ok(false);
+            }
+            std::thread::sleep(SPEECH_POLL_INTERVAL);
+        }
+    }
+
+    pub fn get_queue_length(&self) -> crate::Result<QueueLengthResponse> {
+        self.handle
+            .run_mobile_plugin("getQueueLength", ())
             .map_err(Into::into)
     }
 
+    pub fn get_queue(&self) -> crate::Result<GetQueueResponse> {
+        let native: NativeQueueResponse = self.handle.run_mobile_plugin("getQueue", ())?;
+        Ok(native.into())
+    }
+
+    pub fn clear_queue(&self) -> crate::Result<ClearQueueResponse> {
+        // Neither TextToSpeech nor AVSpeechSynthesizer can drop queued utterances
+        // without also stopping the current one
+        Err(crate::Error::OperationFailed(
+            "Clearing the queue without stopping is not supported on mobile, use stop()"
+                .to_string(),
+        ))
+    }
+
+    pub fn remove_from_queue(
+        &self,
+        _payload: RemoveFromQueueRequest,
+    ) -> crate::Result<RemoveFromQueueResponse> {
+        // Same limitation as clear_queue: the native queues can only be flushed whole
+        Err(crate::Error::OperationFailed(
+            "Removing queued utterances is not supported on mobile, use stop()".to_string(),
+        ))
+    }
+
+    pub fn skip_to_next(&self) -> crate::Result<SkipToNextResponse> {
+        // Same limitation as clear_queue: stopping the current utterance flushes the rest
+        Err(crate::Error::OperationFailed(
+            "Skipping to the next utterance is not supported on mobile, use stop()".to_string(),
+        ))
+    }
+
+    pub fn stop_if_matches(
+        &self,
+        payload: StopIfMatchesRequest,
+    ) -> crate::Result<StopIfMatchesResponse> {
+        // Still reject invalid patterns so callers find out on every platform
+        payload.compile()?;
+        Ok(StopIfMatchesResponse {
+            stopped: false,
+            note: Some(
+                "Mobile engines don't expose the text being spoken, nothing was checked"
+                    .to_string(),
+            ),
+        })
+    }
+
+    pub fn stop_gentle(&self, payload: StopGentleRequest) -> crate::Result<StopGentleResponse> {
+        // Every backend renders audio inside the OS engine, out of reach for a fade
+        log::debug!(
+            "Gentle stop over {}ms requested, stopping immediately",
+            payload.duration_ms
+        );
+        self.stop()?;
+        Ok(StopGentleResponse::stopped_immediately())
+    }
+
+    /// Resolve a request into the one utterance the native engine renders.
+    /// Rendering makes no sound, so it isn't muted by set_enabled and doesn't
+    /// count against the session limit
+    fn prepare_render(&self, payload: SpeakRequest) -> crate::Result<SpeakRequest> {
+        let segments = self.prepare(payload)?;
+        // One recording holds one utterance, so the text is joined without structural pauses
+        let text = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(SpeakRequest {
+            text,
+            pause_after_ms: None,
+            word_offsets: Vec::new(),
+            sentence_offsets: Vec::new(),
+            ..segments[0].clone()
+        })
+    }
+
+    /// Render a prepared request to WAV bytes, plus any voice warning. The native
+    /// engines only render to files, so this goes through one in the cache
+    fn render_wav(&self, speak: SpeakRequest) -> crate::Result<(Vec<u8>, Option<String>)> {
+        let dir = self
+            .app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| crate::Error::OperationFailed(e.to_string()))?;
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("synthesize-{}.wav", uuid::Uuid::new_v4()));
+        let request = SpeakToFileRequest {
+            speak,
+            output_path: path.to_string_lossy().into_owned(),
+        };
+        let response: SpeakToFileResponse =
+            self.handle.run_mobile_plugin("speakToFile", request)?;
+        let wav = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        Ok((wav?, response.warning))
+    }
+
+    pub fn speak_to_file(&self, payload: SpeakToFileRequest) -> crate::Result<SpeakToFileResponse> {
+        payload.validate()?;
+        let SpeakToFileRequest { speak, output_path } = payload;
+        let request = SpeakToFileRequest {
+            speak: self.prepare_render(speak)?,
+            output_path,
+        };
+        self.handle
+            .run_mobile_plugin("speakToFile", request)
+            .map_err(Into::into)
+    }
+
+    pub fn synthesize(&self, payload: SpeakRequest) -> crate::Result<Vec<u8>> {
+        payload.validate()?;
+        let (wav, warning) = self.render_wav(self.prepare_render(payload)?)?;
+        if let Some(warning) = warning {
+            log::warn!("{}", warning);
+        }
+        Ok(wav)
+    }
+
+    pub fn synthesize_stream(
+        &self,
+        payload: SpeakRequest,
+        mut on_chunk: impl FnMut(Vec<u8>) -> crate::Result<()>,
+    ) -> crate::Result<SynthesizeStreamResponse> {
+        payload.validate()?;
+        let request = self.prepare_render(payload)?;
+        let mut response = SynthesizeStreamResponse::default();
+        for text in crate::preprocess::stream_pieces(&request.text, request.ssml) {
+            let (wav, warning) = self.render_wav(SpeakRequest {
+                text: text.to_string(),
+                ..request.clone()
+            })?;
+            on_chunk(wav)?;
+            response.chunks += 1;
+            response.warning = response.warning.or(warning);
+        }
+        Ok(response)
+    }
+
+    pub fn count_words(&self, payload: SpeakRequest) -> crate::Result<WordCountResponse> {
+        payload.validate()?;
+        let text = self
+            .prepare(payload)?
+            .into_iter()
+            // Only the words of SSML are spoken, not its tags
+            .map(|segment| {
+                if segment.ssml {
+                    crate::ssml::to_plain_text(&segment.text)
+                } else {
+                    segment.text
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(WordCountResponse::for_spoken_text(&text))
+    }
+
+    pub fn reset_session_usage(&self) -> crate::Result<SessionUsageResponse> {
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        usage.reset();
+        Ok(usage.snapshot())
+    }
+
+    pub fn speak_sequence(
+        &self,
+        payload: SpeakSequenceRequest,
+    ) -> crate::Result<SpeakSequenceResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakSequenceResponse::disabled());
+        }
+        let mut segments = Vec::new();
+        for item in payload.items {
+            segments.extend(self.prepare(item)?);
+        }
+
+        // Held until the speech is sent, which also keeps overlapping sequences in order
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let chars = spoken_chars(segments.iter().map(|segment| segment.text.as_str()));
+        usage.check(chars)?;
+
+        // The native queue can't tell utterances apart, so a sequence counts as
+        // playing for as long as anything is spoken after it was sent
+        let mut sequence_sent = self
+            .sequence_sent
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?;
+        let overlapping = *sequence_sent && self.is_speaking()?.speaking;
+        let (queue_mode, interrupted) = if overlapping {
+            match self.config.sequence_overlap {
+                SequenceOverlap::Reject => return Err(crate::Error::SequenceActive),
+                SequenceOverlap::Append => (QueueMode::Add, false),
+                // Flushes other pending speech too, which can't be told apart here
+                SequenceOverlap::Interrupt => (QueueMode::Flush, true),
+            }
+        } else {
+            (segments[0].queue_mode.unwrap_or_default(), false)
+        };
+
+        for (index, mut segment) in segments.into_iter().enumerate() {
+            segment.queue_mode = Some(if index == 0 {
+                queue_mode
+            } else {
+                QueueMode::Add
+            });
+            let _: SpeakResponse = self.handle.run_mobile_plugin("speak", segment)?;
+        }
+        *sequence_sent = true;
+
+        Ok(SpeakSequenceResponse {
+            success: true,
+            sequence_id: uuid::Uuid::new_v4().to_string(),
+            interrupted,
+            warning: None,
+            remaining_chars: usage.charge(chars),
+        })
+    }
+
+    pub fn speak_segments(
+        &self,
+        payload: SpeakSegmentsRequest,
+    ) -> crate::Result<SpeakSegmentsResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakSegmentsResponse::new(
+                Vec::new(),
+                SpeakResponse::disabled(),
+            ));
+        }
+        let segments = payload
+            .segments
+            .into_iter()
+            .map(|segment| self.prepare(segment))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        // Held until the speech is sent so concurrent requests can't overshoot the limit
+        let mut usage = self.usage.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let chars = spoken_chars(segments.iter().flatten().map(|part| part.text.as_str()));
+        usage.check(chars)?;
+
+        let resolved = self
+            .cooldown
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .resolve(
+                segments[0][0].queue_mode.unwrap_or_default(),
+                Instant::now(),
+            );
+        let Some(queue_mode) = resolved else {
+            return Ok(SpeakSegmentsResponse::new(
+                Vec::new(),
+                SpeakResponse {
+                    success: false,
+                    warning: Some(
+                        "Dropped: interrupted too soon after the last interruption".to_string(),
+                    ),
+                    remaining_chars: usage.remaining(),
+                    ..Default::default()
+                },
+            ));
+        };
+
+        // Only the first part may flush, everything after it queues behind
+        let mut utterance_ids = Vec::with_capacity(segments.len());
+        let mut warning = None;
+        let mut first = true;
+        for parts in segments {
+            let mut utterance_id = None;
+            for mut part in parts {
+                part.queue_mode = Some(if first { queue_mode } else { QueueMode::Add });
+                first = false;
+                let result: SpeakResponse = self.handle.run_mobile_plugin("speak", part)?;
+                warning = warning.or(result.warning);
+                utterance_id = result.utterance_id;
+            }
+            utterance_ids.push(utterance_id.unwrap_or_default());
+        }
+
+        Ok(SpeakSegmentsResponse::new(
+            utterance_ids,
+            SpeakResponse {
+                success: true,
+                warning,
+                remaining_chars: usage.charge(chars),
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn speak_from_offset(
+        &self,
+        payload: SpeakFromOffsetRequest,
+    ) -> crate::Result<SpeakFromOffsetResponse> {
+        let (request, offset) = payload.remainder()?;
+        Ok(SpeakFromOffsetResponse::new(offset, self.speak(request)?))
+    }
+
+    pub fn speak_outline(
+        &self,
+        payload: SpeakOutlineRequest,
+    ) -> crate::Result<SpeakOutlineResponse> {
+        payload.speak.validate()?;
+        let outline = crate::preprocess::outline(&payload.speak.text);
+        if outline.is_empty() {
+            let remaining_chars = self
+                .usage
+                .lock()
+                .map_err(|_| crate::Error::MutexPoisoned)?
+                .remaining();
+            return Ok(SpeakOutlineResponse {
+                outline,
+                remaining_chars,
+            });
+        }
+
+        let spoken = self.speak_sequence(SpeakSequenceRequest {
+            items: payload.items(&outline),
+        })?;
+        Ok(SpeakOutlineResponse {
+            outline,
+            remaining_chars: spoken.remaining_chars,
+        })
+    }
+
+    pub fn speak_section(&self, payload: SpeakSectionRequest) -> crate::Result<SpeakResponse> {
+        self.speak(payload.section_request()?)
+    }
+
+    pub fn speak_list_begin(
+        &self,
+        payload: SpeakListBeginRequest,
+    ) -> crate::Result<SpeakListBeginResponse> {
+        payload.validate()?;
+        let total = payload.items.len() as u32;
+        let session_id = self
+            .lists
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .begin(payload.items);
+        Ok(SpeakListBeginResponse { session_id, total })
+    }
+
+    pub fn speak_list_next(
+        &self,
+        payload: SpeakListSessionRequest,
+    ) -> crate::Result<SpeakListNextResponse> {
+        // Held while speaking so concurrent calls can't speak the same item twice
+        let mut lists = self.lists.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let (index, item) = lists.peek(&payload.session_id)?;
+        let spoken = self.speak(item)?;
+        // Dropped items stay current so the next call retries them
+        let has_more = !spoken.success || lists.advance(&payload.session_id);
+        Ok(SpeakListNextResponse::new(index, has_more, spoken))
+    }
+
+    pub fn speak_list_reset(
+        &self,
+        payload: SpeakListSessionRequest,
+    ) -> crate::Result<SpeakListResetResponse> {
+        let removed = self
+            .lists
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .reset(&payload.session_id);
+        Ok(SpeakListResetResponse { removed })
+    }
+
+    pub fn speak_words_begin(
+        &self,
+        payload: SpeakWordsBeginRequest,
+    ) -> crate::Result<SpeakWordsBeginResponse> {
+        let items = payload.items();
+        if items.is_empty() {
+            // Nothing to step through, so no session is kept
+            return Ok(SpeakWordsBeginResponse::default());
+        }
+        payload.speak.validate()?;
+        let total = items.len() as u32;
+        let session_id = self
+            .words
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .begin(items);
+        Ok(SpeakWordsBeginResponse {
+            session_id: Some(session_id),
+            total,
+        })
+    }
+
+    pub fn speak_word_next(
+        &self,
+        payload: SpeakListSessionRequest,
+    ) -> crate::Result<SpeakWordNextResponse> {
+        // Held while speaking so a bouncing switch can't speak the same word twice
+        let mut words = self.words.lock().map_err(|_| crate::Error::MutexPoisoned)?;
+        let (_, item) = words.peek(&payload.session_id)?;
+        let word = item.text.clone();
+        let spoken = self.speak(item)?;
+        // Finished sessions are removed by advance. A dropped word is still left,
+        // as the next call retries it
+        if spoken.success {
+            words.advance(&payload.session_id);
+        }
+        let remaining = words.remaining(&payload.session_id);
+        Ok(SpeakWordNextResponse {
+            success: spoken.success,
+            word,
+            remaining: remaining as u32,
+            warning: spoken.warning,
+            remaining_chars: spoken.remaining_chars,
+        })
+    }
+
     pub fn stop(&self) -> crate::Result<StopResponse> {
-        self.0.run_mobile_plugin("stop", ()).map_err(Into::into)
+        self.handle
+            .run_mobile_plugin("stop", ())
+            .map_err(Into::into)
     }
 
     pub fn get_voices(&self, payload: GetVoicesRequest) -> crate::Result<GetVoicesResponse> {
-        self.0
-            .run_mobile_plugin("getVoices", payload)
-            .map_err(Into::into)
+        check_backend(payload.backend.as_deref())?;
+        // The native plugins only filter by language; gender and quality are checked here
+        let mut response: GetVoicesResponse =
+            self.handle.run_mobile_plugin("getVoices", &payload)?;
+        // Android also lists no voices while its engine starts up
+        if response.voices.is_empty()
+            && payload.language.is_none()
+            && self.is_initialized()?.initialized
+        {
+            return Err(crate::Error::NoVoicesAvailable(PLATFORM));
+        }
+        response.voices.retain(|voice| payload.matches(voice));
+        for voice in &mut response.voices {
+            voice.platform = PLATFORM.to_string();
+        }
+        if payload.language.is_none() && payload.gender.is_none() && !payload.enhanced_only {
+            *self
+                .known_voices
+                .lock()
+                .map_err(|_| crate::Error::MutexPoisoned)? = Some(response.voices.clone());
+        }
+        Ok(response)
+    }
+
+    pub fn get_languages(&self) -> crate::Result<GetLanguagesResponse> {
+        // Cheap on iOS, which keeps its voice list for a minute
+        let voices = self.get_voices(GetVoicesRequest::default())?.voices;
+        Ok(GetLanguagesResponse::from_voices(&voices))
+    }
+
+    pub fn refresh_voices(&self) -> crate::Result<GetVoicesResponse> {
+        // Android enumerates voices on every call, iOS keeps them for a minute
+        #[cfg(target_os = "ios")]
+        self.handle.run_mobile_plugin::<()>("refreshVoices", ())?;
+        let known = self
+            .known_voices
+            .lock()
+            .map_err(|_| crate::Error::MutexPoisoned)?
+            .clone();
+        let response = self.get_voices(GetVoicesRequest::default())?;
+        if let Some(event) = VoicesChangedEvent::between(known.as_deref(), &response.voices) {
+            if let Err(e) = self.app.emit("tts://voices-changed", event) {
+                log::warn!("Failed to emit TTS event 'tts://voices-changed': {}", e);
+            }
+        }
+        Ok(response)
     }
 
     pub fn is_speaking(&self) -> crate::Result<IsSpeakingResponse> {
-        self.0
+        self.handle
             .run_mobile_plugin("isSpeaking", ())
             .map_err(Into::into)
     }
 
+    pub fn is_paused(&self) -> crate::Result<IsPausedResponse> {
+        self.handle
+            .run_mobile_plugin("isPaused", ())
+            .map_err(Into::into)
+    }
+
+    pub fn get_engine_params(&self) -> crate::Result<EngineParamsResponse> {
+        // Android only exposes the current voice. AVSpeechSynthesizer keeps no
+        // engine-wide parameters, everything is set per utterance
+        #[cfg(target_os = "android")]
+        return self
+            .handle
+            .run_mobile_plugin("getEngineParams", ())
+            .map_err(Into::into);
+        #[cfg(target_os = "ios")]
+        Ok(EngineParamsResponse::default())
+    }
+
+    /// All voices, or none when the engine lists none, for lookups that accept an
+    /// unavailable voice with a warning
+    fn voices_or_none(&self) -> crate::Result<Vec<Voice>> {
+        match self.get_voices(GetVoicesRequest::default()) {
+            Ok(response) => Ok(response.voices),
+            Err(crate::Error::NoVoicesAvailable(_)) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn resolve_voice(&self, payload: ResolveVoiceRequest) -> crate::Result<Option<Voice>> {
+        payload.validate()?;
+        let voices = self.get_voices(GetVoicesRequest::default())?.voices;
+        Ok(payload.resolve(&voices).cloned())
+    }
+
+    pub fn get_current_voice(&self) -> crate::Result<Option<Voice>> {
+        // Android reports only the voice name, so look it up among the voices.
+        // iOS picks the voice per utterance and has no current one
+        #[cfg(target_os = "android")]
+        if let Some(voice_id) = self.get_engine_params()?.voice_id {
+            let voices = self.get_voices(GetVoicesRequest::default())?;
+            return Ok(voices.voices.into_iter().find(|voice| voice.id == voice_id));
+        }
+        Ok(None)
+    }
+
+    pub fn get_best_voice(&self, payload: GetBestVoiceRequest) -> crate::Result<Option<Voice>> {
+        payload.validate()?;
+        let voices = self
+            .get_voices(GetVoicesRequest {
+                backend: payload.backend.clone(),
+                ..Default::default()
+            })?
+            .voices;
+        // Only breaks ties, so a platform that can't report it still gets a voice
+        let default_voice = self.get_default_voice().ok().and_then(|found| found.voice);
+        let default_voice_id = default_voice.as_ref().map(|voice| voice.id.as_str());
+        Ok(
+            voice_for_language(&voices, &payload.language, default_voice_id)
+                .map(|found| found.voice().clone()),
+        )
+    }
+
+    pub fn get_default_voice(&self) -> crate::Result<DefaultVoiceResponse> {
+        let mut response: DefaultVoiceResponse =
+            self.handle.run_mobile_plugin("getDefaultVoice", ())?;
+        if let Some(ref mut voice) = response.voice {
+            voice.platform = PLATFORM.to_string();
+        }
+        Ok(response)
+    }
+
     pub fn is_initialized(&self) -> crate::Result<IsInitializedResponse> {
-        self.0
+        self.handle
             .run_mobile_plugin("isInitialized", ())
             .map_err(Into::into)
     }
 
+    /// Native engines initialize themselves, so this only reports their state
+    pub fn reinitialize(&self) -> crate::Result<IsInitializedResponse> {
+        self.is_initialized()
+    }
+
+    pub fn get_capabilities(&self) -> crate::Result<Capabilities> {
+        // AVSpeechSynthesizer and TextToSpeech play outside the webview,
+        // so no user interaction is needed before the first utterance
+        Ok(Capabilities {
+            requires_user_gesture: false,
+        })
+    }
+
+    pub fn get_rate_info(&self) -> crate::Result<RateInfoResponse> {
+        Ok(crate::wpm::rate_info())
+    }
+
+    pub fn get_features(&self) -> crate::Result<TtsFeatures> {
+        // Fixed per platform: both native engines are used the same way on every device
+        #[cfg(target_os = "android")]
+        return Ok(TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: true,
+            // TextToSpeech.speak() plays at the stream volume, `volume` isn't passed on
+            volume: false,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: true,
+            // Android has no real pause, pauseSpeaking() only queues silence
+            pause_resume: false,
+        });
+        #[cfg(target_os = "ios")]
+        Ok(TtsFeatures {
+            stop: true,
+            rate: true,
+            pitch: true,
+            volume: true,
+            voice: true,
+            is_speaking: true,
+            utterance_callbacks: true,
+            pause_resume: true,
+        })
+    }
+
+    pub fn get_backends(&self) -> crate::Result<GetBackendsResponse> {
+        Ok(GetBackendsResponse::default())
+    }
+
+    pub fn get_cache_stats(&self) -> crate::Result<CacheStatsResponse> {
+        Err(crate::Error::OperationFailed(
+            "The synthesized audio cache is not supported on mobile".to_string(),
+        ))
+    }
+
+    pub fn clear_tts_cache(&self) -> crate::Result<ClearCacheResponse> {
+        Err(crate::Error::OperationFailed(
+            "The synthesized audio cache is not supported on mobile".to_string(),
+        ))
+    }
+
+    pub fn list_piper_voices(&self) -> crate::Result<PiperVoicesResponse> {
+        Err(crate::Error::OperationFailed(
+            "Piper voices are not supported on mobile".to_string(),
+        ))
+    }
+
+    pub fn download_piper_voice(
+        &self,
+        _payload: DownloadPiperVoiceRequest,
+    ) -> crate::Result<Voice> {
+        Err(crate::Error::OperationFailed(
+            "Piper voices are not supported on mobile".to_string(),
+        ))
+    }
+
+    pub fn get_audio_route(&self) -> crate::Result<AudioRouteResponse> {
+        // Native plugins only report the route; privacy is derived here so it's uniform
+        let native: AudioRouteResponse = self.handle.run_mobile_plugin("getAudioRoute", ())?;
+        Ok(native.route.into())
+    }
+
+    pub fn request_audio_focus(&self) -> crate::Result<AudioFocusResponse> {
+        self.handle
+            .run_mobile_plugin("requestAudioFocus", ())
+            .map_err(Into::into)
+    }
+
+    pub fn abandon_audio_focus(&self) -> crate::Result<AudioFocusResponse> {
+        self.handle
+            .run_mobile_plugin("abandonAudioFocus", ())
+            .map_err(Into::into)
+    }
+
     pub fn pause_speaking(&self) -> crate::Result<PauseResumeResponse> {
-        self.0
+        self.handle
             .run_mobile_plugin("pauseSpeaking", ())
             .map_err(Into::into)
     }
 
     pub fn resume_speaking(&self) -> crate::Result<PauseResumeResponse> {
-        self.0
+        self.handle
             .run_mobile_plugin("resumeSpeaking", ())
             .map_err(Into::into)
     }
 
-    pub fn preview_voice(&self, payload: PreviewVoiceRequest) -> crate::Result<SpeakResponse> {
-        self.0
+    pub fn preview_voice(&self, mut payload: PreviewVoiceRequest) -> crate::Result<SpeakResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(SpeakResponse::disabled());
+        }
+        // Native previews use whatever they are sent, so resolve the profile here
+        payload.apply_profile(
+            &*self
+                .profile
+                .read()
+                .map_err(|_| crate::Error::MutexPoisoned)?,
+        );
+        self.handle
             .run_mobile_plugin("previewVoice", payload)
             .map_err(Into::into)
     }
+
+    pub fn benchmark_voice(
+        &self,
+        payload: BenchmarkVoiceRequest,
+    ) -> crate::Result<BenchmarkVoiceResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(BenchmarkVoiceResponse::disabled());
+        }
+
+        let voice = self
+            .get_voices(GetVoicesRequest::default())?
+            .voices
+            .into_iter()
+            .find(|voice| voice.id == payload.voice_id)
+            .ok_or_else(|| {
+                crate::Error::OperationFailed(format!("Voice not found: {}", payload.voice_id))
+            })?;
+        let (phrase, warning) = self.config.benchmark_phrase(&voice.language);
+        let mut warnings: Vec<String> = warning.into_iter().collect();
+        let request = payload.speak_request(phrase);
+        request.validate()?;
+
+        // Benchmarks bypass the interrupt cooldown and session limit
+        let started = Instant::now();
+        let response: SpeakResponse = self.handle.run_mobile_plugin("speak", request)?;
+        if !response.success {
+            return Err(crate::Error::OperationFailed(
+                response
+                    .warning
+                    .unwrap_or_else(|| "Benchmark failed to start".to_string()),
+            ));
+        }
+        warnings.extend(response.warning);
+
+        if !self.wait_until_silent(started, Some(BENCHMARK_TIMEOUT))? {
+            self.stop()?;
+            return Err(crate::Error::OperationFailed(
+                "Benchmark timed out".to_string(),
+            ));
+        }
+
+        Ok(BenchmarkVoiceResponse::new(
+            voice,
+            phrase,
+            started.elapsed(),
+            warnings,
+        ))
+    }
+
+    pub fn calibration_samples(
+        &self,
+        payload: CalibrationRequest,
+    ) -> crate::Result<CalibrationResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(CalibrationResponse::disabled());
+        }
+
+        // The native queues play these back to back; the first one flushes current speech
+        let rates = payload.rates();
+        let last = rates.len() - 1;
+        for (index, &rate) in rates.iter().enumerate() {
+            for segment in self.prepare(payload.sample(index, rate, index == last))? {
+                self.handle
+                    .run_mobile_plugin::<SpeakResponse>("speak", segment)?;
+            }
+        }
+
+        Ok(CalibrationResponse::new(rates))
+    }
+
+    pub fn preview_all(&self, payload: PreviewAllRequest) -> crate::Result<PreviewAllResponse> {
+        payload.validate()?;
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(PreviewAllResponse::disabled());
+        }
+
+        let voices = self
+            .get_voices(GetVoicesRequest {
+                language: Some(payload.language.clone()),
+                ..Default::default()
+            })?
+            .voices;
+
+        // The native queues play these back to back; the first one flushes current speech
+        let last = voices.len().saturating_sub(1);
+        for (index, voice) in voices.iter().enumerate() {
+            let request = SpeakRequest {
+                text: payload.announcement(voice),
+                voice_id: Some(voice.id.clone()),
+                queue_mode: Some(if index == 0 {
+                    QueueMode::Flush
+                } else {
+                    QueueMode::Add
+                }),
+                pause_after_ms: (index < last).then_some(payload.gap_ms()),
+                use_affixes: false,
+                ..Default::default()
+            };
+            for segment in self.prepare(request)? {
+                self.handle
+                    .run_mobile_plugin::<SpeakResponse>("speak", segment)?;
+            }
+        }
+
+        if voices.is_empty() {
+            self.stop()?;
+        }
+
+        Ok(PreviewAllResponse::new(voices))
+    }
+
+    pub fn get_pronunciation(
+        &self,
+        payload: GetPronunciationRequest,
+    ) -> crate::Result<PronunciationResponse> {
+        payload.validate()?;
+        // Neither AVSpeechSynthesizer nor Android TextToSpeech expose phonemes
+        Err(crate::Error::OperationFailed(
+            "Pronunciation lookup is not supported on mobile platforms".to_string(),
+        ))
+    }
 }
@@ -0,0 +1,265 @@
+//! In-memory stand-in for the tts-rs engine, enabled by the `mock` feature so the
+//! desktop plugin runs without an audio subsystem. It mirrors the parts of the
+//! `tts::Tts` API the plugin uses, finishes every utterance as soon as it is
+//! spoken and records each call for tests to inspect.
+
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+type UtteranceCallback = Box<dyn FnMut(UtteranceId) + Send>;
+
+/// A call the plugin made on the mock engine
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockCall {
+    Speak(String),
+    Stop,
+    SetVoice(String),
+    SetRate(f32),
+    SetPitch(f32),
+    SetVolume(f32),
+}
+
+/// Utterance handle, numbered in the order utterances were spoken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtteranceId(u64);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Features {
+    pub is_speaking: bool,
+    pub pitch: bool,
+    pub rate: bool,
+    pub stop: bool,
+    pub utterance_callbacks: bool,
+    pub voice: bool,
+    pub volume: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voice {
+    id: &'static str,
+    name: &'static str,
+    language: &'static str,
+    gender: Option<tts::Gender>,
+}
+
+impl Voice {
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    pub fn language(&self) -> impl fmt::Display {
+        self.language
+    }
+
+    pub fn gender(&self) -> Option<tts::Gender> {
+        self.gender
+    }
+}
+
+/// Voices every mock engine offers, the first one being its initial voice
+const VOICES: [Voice; 2] = [
+    Voice {
+        id: "mock-en-US",
+        name: "Mock English",
+        language: "en-US",
+        gender: Some(tts::Gender::Female),
+    },
+    Voice {
+        id: "mock-pt-BR",
+        name: "Mock Portuguese",
+        language: "pt-BR",
+        gender: None,
+    },
+];
+
+pub struct MockEngine {
+    calls: Vec<MockCall>,
+    voice: Voice,
+    rate: f32,
+    pitch: f32,
+    volume: f32,
+    next_utterance: u64,
+    on_end: Mutex<Option<UtteranceCallback>>,
+    on_stop: Mutex<Option<UtteranceCallback>>,
+}
+
+impl MockEngine {
+    const NORMAL_RATE: f32 = 1.0;
+
+    /// Never fails, the result matches `tts::Tts::default`
+    #[allow(clippy::should_implement_trait)]
+    pub fn default() -> Result<Self, tts::Error> {
+        Ok(Self {
+            calls: Vec::new(),
+            voice: VOICES[0].clone(),
+            rate: Self::NORMAL_RATE,
+            pitch: 1.0,
+            volume: 1.0,
+            next_utterance: 0,
+            on_end: Mutex::new(None),
+            on_stop: Mutex::new(None),
+        })
+    }
+
+    /// Calls recorded since the engine was created or last cleared
+    pub fn calls(&self) -> &[MockCall] {
+        &self.calls
+    }
+
+    pub fn clear_calls(&mut self) {
+        self.calls.clear();
+    }
+
+    pub fn supported_features(&self) -> Features {
+        Features {
+            is_speaking: true,
+            pitch: true,
+            rate: true,
+            stop: true,
+            utterance_callbacks: true,
+            voice: true,
+            volume: true,
+        }
+    }
+
+    /// Record the text and finish the utterance right away, before returning
+    pub fn speak<S: Into<String>>(
+        &mut self,
+        text: S,
+        _interrupt: bool,
+    ) -> Result<Option<UtteranceId>, tts::Error> {
+        self.calls.push(MockCall::Speak(text.into()));
+        let id = UtteranceId(self.next_utterance);
+        self.next_utterance += 1;
+        if let Some(on_end) = self.on_end.get_mut().ok().and_then(Option::as_mut) {
+            on_end(id);
+        }
+        Ok(Some(id))
+    }
+
+    /// Utterances finish while being spoken, so there is never anything to cancel
+    pub fn stop(&mut self) -> Result<(), tts::Error> {
+        self.calls.push(MockCall::Stop);
+        Ok(())
+    }
+
+    pub fn is_speaking(&self) -> Result<bool, tts::Error> {
+        Ok(false)
+    }
+
+    pub fn min_rate(&self) -> f32 {
+        0.1
+    }
+
+    pub fn normal_rate(&self) -> f32 {
+        Self::NORMAL_RATE
+    }
+
+    pub fn max_rate(&self) -> f32 {
+        10.0
+    }
+
+    pub fn get_rate(&self) -> Result<f32, tts::Error> {
+        Ok(self.rate)
+    }
+
+    pub fn set_rate(&mut self, rate: f32) -> Result<(), tts::Error> {
+        self.calls.push(MockCall::SetRate(rate));
+        self.rate = rate;
+        Ok(())
+    }
+
+    pub fn get_pitch(&self) -> Result<f32, tts::Error> {
+        Ok(self.pitch)
+    }
+
+    pub fn set_pitch(&mut self, pitch: f32) -> Result<(), tts::Error> {
+        self.calls.push(MockCall::SetPitch(pitch));
+        self.pitch = pitch;
+        Ok(())
+    }
+
+    pub fn get_volume(&self) -> Result<f32, tts::Error> {
+        Ok(self.volume)
+    }
+
+    pub fn set_volume(&mut self, volume: f32) -> Result<(), tts::Error> {
+        self.calls.push(MockCall::SetVolume(volume));
+        self.volume = volume;
+        Ok(())
+    }
+
+    pub fn voices(&self) -> Result<Vec<Voice>, tts::Error> {
+        Ok(VOICES.to_vec())
+    }
+
+    pub fn voice(&self) -> Result<Option<Voice>, tts::Error> {
+        Ok(Some(self.voice.clone()))
+    }
+
+    pub fn set_voice(&mut self, voice: &Voice) -> Result<(), tts::Error> {
+        self.calls.push(MockCall::SetVoice(voice.id()));
+        self.voice = voice.clone();
+        Ok(())
+    }
+
+    pub fn on_utterance_end(&self, callback: Option<UtteranceCallback>) -> Result<(), tts::Error> {
+        Self::set_callback(&self.on_end, callback)
+    }
+
+    pub fn on_utterance_stop(&self, callback: Option<UtteranceCallback>) -> Result<(), tts::Error> {
+        Self::set_callback(&self.on_stop, callback)
+    }
+
+    fn set_callback(
+        slot: &Mutex<Option<UtteranceCallback>>,
+        callback: Option<UtteranceCallback>,
+    ) -> Result<(), tts::Error> {
+        *slot.lock().unwrap_or_else(PoisonError::into_inner) = callback;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_records_calls_and_finishes_utterances() {
+        let mut engine = MockEngine::default().unwrap();
+        let ended = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&ended);
+        engine
+            .on_utterance_end(Some(Box::new(move |id| recorder.lock().unwrap().push(id))))
+            .unwrap();
+
+        let voice = engine.voices().unwrap().pop().unwrap();
+        engine.set_voice(&voice).unwrap();
+        engine.set_rate(2.0).unwrap();
+        let first = engine.speak("Hello", false).unwrap();
+        let second = engine.speak("world", false).unwrap();
+        engine.stop().unwrap();
+
+        assert_eq!(
+            engine.calls(),
+            [
+                MockCall::SetVoice("mock-pt-BR".to_string()),
+                MockCall::SetRate(2.0),
+                MockCall::Speak("Hello".to_string()),
+                MockCall::Speak("world".to_string()),
+                MockCall::Stop,
+            ]
+        );
+        assert_eq!(*ended.lock().unwrap(), [first.unwrap(), second.unwrap()]);
+        assert_eq!(engine.voice().unwrap(), Some(voice));
+        assert!(!engine.is_speaking().unwrap());
+
+        engine.clear_calls();
+        assert!(engine.calls().is_empty());
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 use ts_rs::TS;
 
 /// Maximum text length in bytes (10KB)
@@ -8,6 +11,43 @@ pub const MAX_TEXT_LENGTH: usize = 10_000;
 pub const MAX_VOICE_ID_LENGTH: usize = 256;
 /// Maximum language code length
 pub const MAX_LANGUAGE_LENGTH: usize = 35;
+/// Maximum pause between queued utterances in milliseconds
+pub const MAX_PAUSE_MS: u32 = 10_000;
+/// Maximum length of the global prefix or suffix in bytes
+pub const MAX_AFFIX_LENGTH: usize = 500;
+/// Maximum length of a per-request event scope
+pub const MAX_EVENT_SCOPE_LENGTH: usize = 64;
+/// Maximum number of items in a user-paced speak list
+pub const MAX_LIST_ITEMS: usize = 1_000;
+/// Maximum per-voice volume gain
+pub const MAX_VOICE_GAIN: f32 = 2.0;
+/// Accepted speech rate range (1.0 = normal)
+pub const MIN_RATE: f32 = 0.1;
+pub const MAX_RATE: f32 = 3.0;
+/// Accepted pitch range (1.0 = normal)
+pub const MIN_PITCH: f32 = 0.5;
+pub const MAX_PITCH: f32 = 2.0;
+/// Platform this build reports voices for, as in `Voice::platform`
+pub const PLATFORM: &str = std::env::consts::OS;
+/// Maximum length of a `stop_if_matches` pattern in bytes
+pub const MAX_PATTERN_LENGTH: usize = 1_000;
+/// Longest text handed to a desktop engine in one utterance, in bytes, unless
+/// `maxChunkLength` is configured
+pub const DEFAULT_MAX_CHUNK_LENGTH: usize = 1_000;
+/// Most bytes of synthesized audio kept on disk unless `cacheMaxBytes` is configured
+pub const DEFAULT_CACHE_MAX_BYTES: u64 = 50 * 1024 * 1024;
+/// Smallest accepted `maxChunkLength`
+pub const MIN_CHUNK_LENGTH: usize = 50;
+/// Characters of text kept in `QueuedItem::text_preview`
+pub const QUEUE_PREVIEW_CHARS: usize = 60;
+/// Maximum number of entries in the pronunciation lexicon
+pub const MAX_LEXICON_ENTRIES: usize = 1_000;
+/// Maximum length of a lexicon word or its replacement in bytes
+pub const MAX_LEXICON_ENTRY_LENGTH: usize = 200;
+/// Warning of speaking calls skipped while the plugin is disabled by `set_enabled`
+pub const DISABLED_WARNING: &str = "tts disabled";
+/// Backend name of the OS engine, which requests get when they name no backend
+pub const SYSTEM_BACKEND: &str = "system";
 
 #[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
 #[ts(export, export_to = "../guest-js/bindings/")]
@@ -18,481 +58,5352 @@ pub enum QueueMode {
     Flush,
     /// Add to queue and speak after current speech finishes
     Add,
+    /// Drop pending speech but let the current utterance finish, then speak
+    #[serde(rename = "replacePending")]
+    ReplacePending,
+    /// Interrupt the current utterance, speak right away, then resume what was
+    /// interrupted and the rest of the queue
+    Urgent,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+/// How emoji are handled when preprocessing text
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
 #[ts(export, export_to = "../guest-js/bindings/")]
-#[serde(rename_all = "camelCase")]
-pub struct SpeakOptions {
-    /// The text to speak (max 10,000 characters)
-    pub text: String,
-    /// The language/locale code (e.g., "en-US", "pt-BR", "ja-JP")
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub language: Option<String>,
-    /// Specific voice ID to use (from getVoices). Takes priority over language
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub voice_id: Option<String>,
-    /// Speech rate (0.1 to 4.0, where 1.0 = normal)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub rate: Option<f32>,
-    /// Pitch (0.5 to 2.0, where 1.0 = normal)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pitch: Option<f32>,
-    /// Volume (0.0 to 1.0, where 1.0 = full volume)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub volume: Option<f32>,
-    /// Queue mode: "flush" (default) or "add"
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub queue_mode: Option<QueueMode>,
+#[serde(rename_all = "lowercase")]
+pub enum EmojiMode {
+    /// Remove emoji from the text
+    Skip,
+    /// Replace emoji with their CLDR short names ("😀" → "grinning face")
+    Describe,
+    /// Pass emoji to the engine unchanged (default)
+    #[default]
+    Keep,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+/// Markup the text is written in, stripped before speaking
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
 #[ts(export, export_to = "../guest-js/bindings/")]
-#[serde(rename_all = "camelCase")]
-pub struct PreviewVoiceOptions {
-    /// Voice ID to preview
-    pub voice_id: String,
-    /// Optional custom sample text (uses default if not provided)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub text: Option<String>,
+#[serde(rename_all = "lowercase")]
+pub enum TextFormat {
+    /// Speak the text as written (default)
+    #[default]
+    Plain,
+    /// Strip markdown syntax, read list items as sentences and skip code blocks
+    Markdown,
+    /// Strip HTML tags, decode entities and skip `<pre>`, `<script>` and `<style>`
+    Html,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SpeakRequest {
-    /// The text to speak
-    pub text: String,
-    /// The language/locale code (e.g., "en-US", "pt-BR", "ja-JP")
-    #[serde(default)]
-    pub language: Option<String>,
-    /// Voice ID to use (from getVoices)
-    #[serde(default)]
-    pub voice_id: Option<String>,
-    /// Speech rate (0.1 to 4.0, where 1.0 = normal, 2.0 = double, 0.5 = half)
-    #[serde(default = "default_rate")]
-    pub rate: f32,
-    /// Pitch (0.5 = low, 1.0 = normal, 2.0 = high)
-    #[serde(default = "default_pitch")]
-    pub pitch: f32,
-    /// Volume (0.0 = silent, 1.0 = full volume)
-    #[serde(default = "default_volume")]
-    pub volume: f32,
-    /// Queue mode: "flush" (default) or "add"
-    #[serde(default)]
-    pub queue_mode: QueueMode,
+/// What to do with a flush request that arrives during the interrupt cooldown
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InterruptCooldownAction {
+    /// Speak it after the current speech instead of interrupting (default)
+    #[default]
+    Queue,
+    /// Discard it
+    Drop,
 }
 
-fn default_rate() -> f32 {
-    1.0
+/// What `speak_sequence` does while an earlier sequence is still playing
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SequenceOverlap {
+    /// Drop the rest of the earlier sequence and start the new one (default)
+    #[default]
+    Interrupt,
+    /// Play the new sequence after the earlier one
+    Append,
+    /// Fail with `SEQUENCE_ACTIVE`
+    Reject,
 }
-fn default_pitch() -> f32 {
-    1.0
+
+/// `AVAudioSession` category used while speaking (iOS only)
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioSessionCategory {
+    /// Plays even with the silent switch on; interrupts or ducks other audio (default)
+    #[default]
+    Playback,
+    /// Silenced by the silent switch; mixes with other audio
+    Ambient,
+    /// Silenced by the silent switch; interrupts other audio
+    SoloAmbient,
+    /// For apps that also record (e.g. voice assistants)
+    PlayAndRecord,
 }
-fn default_volume() -> f32 {
-    1.0
+
+/// `AVAudioSession` mode used while speaking (iOS only)
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioSessionMode {
+    /// Mode for continuous spoken audio such as narration (default)
+    #[default]
+    SpokenAudio,
+    /// Mode for short spoken prompts such as navigation instructions
+    VoicePrompt,
+    /// The system default mode
+    Default,
 }
 
-#[derive(Debug, Clone, thiserror::Error)]
-pub enum ValidationError {
-    #[error("Text cannot be empty")]
-    EmptyText,
-    #[error("Text too long: {len} bytes (max: {max})")]
-    TextTooLong { len: usize, max: usize },
-    #[error("Voice ID too long: {len} chars (max: {max})")]
-    VoiceIdTooLong { len: usize, max: usize },
-    #[error("Language code too long: {len} chars (max: {max})")]
-    LanguageTooLong { len: usize, max: usize },
+/// `AVAudioSession` category option (iOS only)
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioSessionOption {
+    /// Lower the volume of other audio while speaking
+    DuckOthers,
+    /// Play alongside other audio at full volume
+    MixWithOthers,
+    /// Pause other spoken audio (podcasts, audiobooks) and mix with music
+    InterruptSpokenAudioAndMixWithOthers,
+    /// Allow Bluetooth hands-free devices (requires `playAndRecord`)
+    AllowBluetooth,
+    /// Allow Bluetooth A2DP output devices
+    #[serde(rename = "allowBluetoothA2DP")]
+    AllowBluetoothA2dp,
+    /// Route to the speaker instead of the receiver (requires `playAndRecord`)
+    DefaultToSpeaker,
 }
 
-#[derive(Debug, Clone)]
-pub struct ValidatedSpeakRequest {
-    pub text: String,
-    pub language: Option<String>,
-    pub voice_id: Option<String>,
-    pub rate: f32,
-    pub pitch: f32,
-    pub volume: f32,
-    pub queue_mode: QueueMode,
+/// iOS audio session setup applied before speaking. Ignored on other platforms.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioSessionConfig {
+    #[serde(default)]
+    pub category: AudioSessionCategory,
+    #[serde(default)]
+    pub mode: AudioSessionMode,
+    #[serde(default = "default_audio_session_options")]
+    pub options: Vec<AudioSessionOption>,
 }
 
-impl SpeakRequest {
-    pub fn validate(&self) -> Result<ValidatedSpeakRequest, ValidationError> {
-        // Text validation
-        if self.text.is_empty() {
-            return Err(ValidationError::EmptyText);
+impl Default for AudioSessionConfig {
+    fn default() -> Self {
+        Self {
+            category: AudioSessionCategory::default(),
+            mode: AudioSessionMode::default(),
+            options: default_audio_session_options(),
         }
-        if self.text.len() > MAX_TEXT_LENGTH {
-            return Err(ValidationError::TextTooLong {
-                len: self.text.len(),
-                max: MAX_TEXT_LENGTH,
-            });
-        }
-
-        // Language validation (if provided)
-        let sanitized_language = self
-            .language
-            .as_ref()
-            .map(|lang| Self::validate_language(lang))
-            .transpose()?;
-
-        Ok(ValidatedSpeakRequest {
-            text: self.text.clone(),
-            language: sanitized_language,
-            voice_id: self.voice_id.clone(),
-            rate: self.rate.clamp(0.1, 4.0),
-            pitch: self.pitch.clamp(0.5, 2.0),
-            volume: self.volume.clamp(0.0, 1.0),
-            queue_mode: self.queue_mode,
-        })
     }
+}
 
-    fn validate_language(lang: &str) -> Result<String, ValidationError> {
-        if lang.len() > MAX_LANGUAGE_LENGTH {
-            return Err(ValidationError::LanguageTooLong {
-                len: lang.len(),
-                max: MAX_LANGUAGE_LENGTH,
-            });
-        }
-        Ok(lang.to_string())
-    }
+fn default_audio_session_options() -> Vec<AudioSessionOption> {
+    vec![AudioSessionOption::DuckOthers]
 }
 
+/// Plugin configuration, read from `plugins.tts` in `tauri.conf.json`
+/// or passed to `init_with_config`
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SpeakResponse {
-    /// Whether speech was successfully initiated
-    pub success: bool,
-    /// Optional warning message (e.g., voice not found, using fallback)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub warning: Option<String>,
+pub struct TtsConfig {
+    /// Queue mode used when a request omits `queueMode` (default: "flush")
+    #[serde(default)]
+    pub default_queue_mode: QueueMode,
+    /// iOS audio session category/mode/options (default: playback, spokenAudio, duckOthers)
+    #[serde(default)]
+    pub audio_session: AudioSessionConfig,
+    /// Prefix and suffix added to all spoken text (default: none)
+    #[serde(default)]
+    pub affixes: Affixes,
+    /// Minimum time between two interrupting (flush) requests in milliseconds (default: 0, disabled)
+    #[serde(default)]
+    pub interrupt_cooldown_ms: u32,
+    /// How a flush request within the cooldown is handled (default: "queue")
+    #[serde(default)]
+    pub interrupt_cooldown_action: InterruptCooldownAction,
+    /// Maximum characters spoken per session before `speak` is rejected (default: unlimited)
+    #[serde(default)]
+    pub session_char_limit: Option<u32>,
+    /// Standard phrases for `benchmark_voice`, keyed by language code (e.g. "pt" or "pt-BR")
+    #[serde(default)]
+    pub benchmark_phrases: HashMap<String, String>,
+    /// Volume multiplier per voice ID (0.0 to 2.0), evening out loudness between voices
+    #[serde(default)]
+    pub voice_gains: HashMap<String, f32>,
+    /// Measure the audio desktop backends play through the plugin and even out its
+    /// loudness between voices. The OS engines play audio themselves, so it
+    /// doesn't apply to them
+    #[serde(default)]
+    pub normalize_loudness: bool,
+    /// How `speak_sequence` handles a call while another sequence plays (default: "interrupt")
+    #[serde(default)]
+    pub sequence_overlap: SequenceOverlap,
+    /// Longest text in bytes handed to a desktop engine at once; longer text is
+    /// spoken in chunks split at sentence ends (default: 1000)
+    #[serde(default)]
+    pub max_chunk_length: Option<usize>,
+    /// Speech rate used when neither the request nor an imported profile sets one (default: 1.0)
+    #[serde(default)]
+    pub default_rate: Option<f32>,
+    /// Pitch used when neither the request nor an imported profile sets one (default: 1.0)
+    #[serde(default)]
+    pub default_pitch: Option<f32>,
+    /// Volume used when neither the request nor an imported profile sets one (default: 1.0)
+    #[serde(default)]
+    pub default_volume: Option<f32>,
+    /// Voice ID used when a request sets neither `voiceId` nor `language`
+    #[serde(default)]
+    pub default_voice_id: Option<String>,
+    /// Language used when a request sets neither `voiceId` nor `language`
+    #[serde(default)]
+    pub default_language: Option<String>,
+    /// Backends tried in order when one fails or isn't registered, e.g.
+    /// `["elevenlabs", "system"]`. Requests naming no backend start with the
+    /// first, those naming one in the list continue with the ones after it
+    /// (desktop only, default: none)
+    #[serde(default)]
+    pub backend_fallback: Vec<String>,
+    /// Most bytes of synthesized audio kept in the app cache directory, so
+    /// repeated phrases aren't synthesized again; 0 turns caching off (desktop
+    /// only, default: 50 MiB)
+    #[serde(default)]
+    pub cache_max_bytes: Option<u64>,
+    /// Azure AI Speech resource to register the `azure` backend with (desktop only)
+    #[cfg(feature = "azure")]
+    #[serde(default)]
+    pub azure: Option<AzureConfig>,
+    /// Google Cloud project to register the `gcp` backend with (desktop only)
+    #[cfg(feature = "gcp-tts")]
+    #[serde(default)]
+    pub gcp: Option<GcpConfig>,
+    /// AWS account to register the `polly` backend with (desktop only)
+    #[cfg(feature = "polly")]
+    #[serde(default)]
+    pub polly: Option<PollyConfig>,
+    /// ElevenLabs account to register the `elevenlabs` backend with (desktop only)
+    #[cfg(feature = "elevenlabs")]
+    #[serde(default)]
+    pub elevenlabs: Option<ElevenLabsConfig>,
+    /// OpenAI account to register the `openai` backend with (desktop only)
+    #[cfg(feature = "openai")]
+    #[serde(default)]
+    pub openai: Option<OpenAiConfig>,
+    /// Piper executable and default voice of the `piper` backend (desktop only)
+    #[cfg(feature = "piper")]
+    #[serde(default)]
+    pub piper: Option<PiperConfig>,
+    /// Self-hosted server to register the `http` backend with (desktop only)
+    #[cfg(feature = "http-tts")]
+    #[serde(default)]
+    pub http: Option<HttpTtsConfig>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+/// Azure AI Speech resource requests naming the `azure` backend are spoken with
+#[cfg(feature = "azure")]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct StopResponse {
-    pub success: bool,
+pub struct AzureConfig {
+    /// Key of the Speech resource
+    pub key: String,
+    /// Region of the Speech resource, e.g. "westeurope"
+    pub region: String,
+    /// Voice used when a request names neither `voiceId` nor a language one
+    /// speaks (default: "en-US-AvaMultilingualNeural")
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Speaking style, e.g. "cheerful" or "whispering", for voices that have it.
+    /// Other voices speak normally (default: none)
+    #[serde(default)]
+    pub style: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, TS)]
-#[ts(export, export_to = "../guest-js/bindings/")]
+#[cfg(feature = "azure")]
+impl std::fmt::Debug for AzureConfig {
+    // Keeps the key out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureConfig")
+            .field("key", &"<redacted>")
+            .field("region", &self.region)
+            .field("voice", &self.voice)
+            .field("style", &self.style)
+            .finish()
+    }
+}
+
+/// Google Cloud project requests naming the `gcp` backend are spoken with
+#[cfg(feature = "gcp-tts")]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Voice {
-    /// Unique identifier for the voice
-    pub id: String,
-    /// Display name of the voice
-    pub name: String,
-    /// Language code (e.g., "en-US")
-    pub language: String,
+pub struct GcpConfig {
+    /// API key of a project with the Text-to-Speech API enabled
+    pub api_key: String,
+    /// Voice used when a request names neither `voiceId` nor a language one
+    /// speaks (default: "en-US-Neural2-F")
+    #[serde(default)]
+    pub voice: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[cfg(feature = "gcp-tts")]
+impl std::fmt::Debug for GcpConfig {
+    // Keeps the key out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GcpConfig")
+            .field("api_key", &"<redacted>")
+            .field("voice", &self.voice)
+            .finish()
+    }
+}
+
+/// AWS account and region requests naming the `polly` backend are spoken with
+#[cfg(feature = "polly")]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetVoicesRequest {
-    /// Optional language filter
+pub struct PollyConfig {
+    /// Access key ID of credentials allowed `polly:SynthesizeSpeech` and
+    /// `polly:DescribeVoices`
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Session token of temporary credentials
     #[serde(default)]
-    pub language: Option<String>,
+    pub session_token: Option<String>,
+    /// AWS region, e.g. "eu-west-1"
+    pub region: String,
+    /// Voice used when a request names neither `voiceId` nor a language one
+    /// speaks (default: "Joanna")
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Polly engine: "standard", "neural", "long-form" or "generative" (default:
+    /// "neural"). Only voices of this engine are listed
+    #[serde(default)]
+    pub engine: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg(feature = "polly")]
+impl std::fmt::Debug for PollyConfig {
+    // Keeps the credentials out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PollyConfig")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("region", &self.region)
+            .field("voice", &self.voice)
+            .field("engine", &self.engine)
+            .finish()
+    }
+}
+
+/// ElevenLabs account requests naming the `elevenlabs` backend are spoken with
+#[cfg(feature = "elevenlabs")]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetVoicesResponse {
-    pub voices: Vec<Voice>,
+pub struct ElevenLabsConfig {
+    pub api_key: String,
+    /// Model speaking every request, e.g. "eleven_flash_v2_5" for lower latency
+    /// (default: "eleven_multilingual_v2")
+    #[serde(default)]
+    pub model: Option<String>,
+    /// ID of the voice used when a request names neither `voiceId` nor a
+    /// language one speaks (default: "21m00Tcm4TlvDq8ikWAM", Rachel)
+    #[serde(default)]
+    pub voice: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg(feature = "elevenlabs")]
+impl std::fmt::Debug for ElevenLabsConfig {
+    // Keeps the key out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElevenLabsConfig")
+            .field("api_key", &"<redacted>")
+            .field("model", &self.model)
+            .field("voice", &self.voice)
+            .finish()
+    }
+}
+
+/// OpenAI account requests naming the `openai` backend are spoken with
+#[cfg(feature = "openai")]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct IsSpeakingResponse {
-    pub speaking: bool,
+pub struct OpenAiConfig {
+    pub api_key: String,
+    /// "tts-1", "tts-1-hd" or "gpt-4o-mini-tts" (default: "tts-1")
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Voice used when a request names no `voiceId`, e.g. "nova" (default: "alloy")
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Root of an OpenAI-compatible API to use instead (default:
+    /// "https://api.openai.com/v1")
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+#[cfg(feature = "openai")]
+impl std::fmt::Debug for OpenAiConfig {
+    // Keeps the key out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAiConfig")
+            .field("api_key", &"<redacted>")
+            .field("model", &self.model)
+            .field("voice", &self.voice)
+            .field("base_url", &self.base_url)
+            .finish()
+    }
 }
 
+/// How the `piper` backend runs Piper, whose voices are kept in the app data
+/// directory under "piper-voices"
+#[cfg(feature = "piper")]
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct IsInitializedResponse {
-    /// Whether the TTS engine is initialized and ready
-    pub initialized: bool,
-    /// Number of available voices (0 if not initialized)
-    pub voice_count: u32,
+pub struct PiperConfig {
+    /// Piper executable to run (default: "piper" on the `PATH`)
+    #[serde(default)]
+    pub binary: Option<std::path::PathBuf>,
+    /// Installed voice used when a request names no `voiceId` (default:
+    /// "en_US-lessac-medium")
+    #[serde(default)]
+    pub voice: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
-#[ts(export, export_to = "../guest-js/bindings/")]
+/// Self-hosted speech server, like MaryTTS or Coqui, requests naming the `http`
+/// backend are spoken with. Each sentence is POSTed to `url`, which must answer
+/// with WAV, MP3 or Ogg Vorbis audio
+#[cfg(feature = "http-tts")]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct PauseResumeResponse {
-    pub success: bool,
-    /// Reason for failure (if success is false)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reason: Option<String>,
+pub struct HttpTtsConfig {
+    /// Endpoint, e.g. "http://localhost:59125/process"
+    pub url: String,
+    /// How the fields are sent (default: "json")
+    #[serde(default)]
+    pub format: HttpBodyFormat,
+    /// Field the text is sent in (default: "text")
+    #[serde(default)]
+    pub text_field: Option<String>,
+    /// Field the request's `voiceId` is sent in, when it names one (default: "voice")
+    #[serde(default)]
+    pub voice_field: Option<String>,
+    /// Field the request's `language` is sent in, when it names one (default:
+    /// "language")
+    #[serde(default)]
+    pub language_field: Option<String>,
+    /// Fields sent with every request, like MaryTTS's `"OUTPUT_TYPE": "AUDIO"`
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// Headers sent with every request, like an API key
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Sent as `Authorization: Bearer <token>`
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// Voices the server offers, which `getVoices()` lists for the backend
+    #[serde(default)]
+    pub voices: Vec<Voice>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PreviewVoiceRequest {
-    /// Voice ID to preview
-    pub voice_id: String,
-    /// Optional custom sample text (uses default if not provided)
-    #[serde(default)]
-    pub text: Option<String>,
-}
+#[cfg(feature = "http-tts")]
+impl std::fmt::Debug for HttpTtsConfig {
+    // Keeps credentials in headers out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpTtsConfig")
+            .field("url", &self.url)
+            .field("format", &self.format)
+            .field("text_field", &self.text_field)
+            .field("voice_field", &self.voice_field)
+            .field("language_field", &self.language_field)
+            .field("params", &self.params)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("voices", &self.voices)
+            .finish()
+    }
+}
+
+/// How the `http` backend sends its fields
+#[cfg(feature = "http-tts")]
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpBodyFormat {
+    /// A JSON object (default)
+    #[default]
+    Json,
+    /// `application/x-www-form-urlencoded`, as MaryTTS takes
+    Form,
+    /// The text as a plain text body, the other fields in the query string
+    Text,
+}
+
+impl TtsConfig {
+    pub const DEFAULT_BENCHMARK_PHRASE: &'static str =
+        "The quick brown fox jumps over the lazy dog.";
+
+    /// Benchmark phrase for `language`: an exact match, then its primary language,
+    /// then the English default along with a warning when `language` isn't English
+    pub fn benchmark_phrase(&self, language: &str) -> (&str, Option<String>) {
+        let normalize = |code: &str| code.to_lowercase().replace('_', "-");
+        let wanted = normalize(language);
+        let primary = wanted.split('-').next().unwrap_or_default().to_string();
+
+        let find = |code: &str| {
+            self.benchmark_phrases
+                .iter()
+                .find(|(key, _)| normalize(key) == code)
+                .map(|(_, phrase)| phrase.as_str())
+        };
+        if let Some(phrase) = find(&wanted).or_else(|| find(&primary)) {
+            return (phrase, None);
+        }
+
+        let warning = (primary != "en").then(|| {
+            format!(
+                "No benchmark phrase configured for '{}', using the English default",
+                language
+            )
+        });
+        (Self::DEFAULT_BENCHMARK_PHRASE, warning)
+    }
+
+    pub fn validate_voice_gains(&self) -> Result<(), ValidationError> {
+        for &gain in self.voice_gains.values() {
+            validate_voice_gain(gain)?;
+        }
+        Ok(())
+    }
+
+    /// Backends a request naming `backend` is tried with, in order. Never empty:
+    /// without a fallback list it is just the named backend, or "system"
+    pub fn backend_chain(&self, backend: Option<&str>) -> Vec<String> {
+        let start = match backend {
+            None => 0,
+            Some(name) => match self.backend_fallback.iter().position(|b| b == name) {
+                Some(start) => start,
+                None => return vec![name.to_string()],
+            },
+        };
+        match self.backend_fallback.get(start..) {
+            Some(chain) if !chain.is_empty() => chain.to_vec(),
+            _ => vec![SYSTEM_BACKEND.to_string()],
+        }
+    }
+
+    pub fn cache_max_bytes(&self) -> u64 {
+        self.cache_max_bytes.unwrap_or(DEFAULT_CACHE_MAX_BYTES)
+    }
+
+    pub fn max_chunk_length(&self) -> usize {
+        self.max_chunk_length.unwrap_or(DEFAULT_MAX_CHUNK_LENGTH)
+    }
+
+    pub fn validate_max_chunk_length(&self) -> Result<(), ValidationError> {
+        let len = self.max_chunk_length();
+        if len < MIN_CHUNK_LENGTH {
+            return Err(ValidationError::ChunkLength {
+                len,
+                min: MIN_CHUNK_LENGTH,
+            });
+        }
+        Ok(())
+    }
+
+    /// The voice profile the plugin starts with, built from the `default*` options
+    pub fn default_profile(&self) -> Result<VoiceProfile, ValidationError> {
+        if let Some(ref voice_id) = self.default_voice_id {
+            if voice_id.len() > MAX_VOICE_ID_LENGTH {
+                return Err(ValidationError::VoiceIdTooLong {
+                    len: voice_id.len(),
+                    max: MAX_VOICE_ID_LENGTH,
+                });
+            }
+        }
+        Ok(VoiceProfile {
+            voice_id: self.default_voice_id.clone(),
+            language: self
+                .default_language
+                .as_deref()
+                .map(SpeakRequest::validate_language)
+                .transpose()?,
+            rate: SpeakRequest::validate_range(
+                "defaultRate",
+                self.default_rate,
+                MIN_RATE,
+                MAX_RATE,
+            )?,
+            pitch: SpeakRequest::validate_range(
+                "defaultPitch",
+                self.default_pitch,
+                MIN_PITCH,
+                MAX_PITCH,
+            )?,
+            volume: SpeakRequest::validate_range("defaultVolume", self.default_volume, 0.0, 1.0)?,
+            ..Default::default()
+        })
+    }
+}
+
+fn validate_voice_gain(gain: f32) -> Result<(), ValidationError> {
+    if !(0.0..=MAX_VOICE_GAIN).contains(&gain) {
+        return Err(ValidationError::VoiceGain {
+            gain,
+            max: MAX_VOICE_GAIN,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetEnabledRequest {
+    /// `false` turns every speaking call into a no-op until re-enabled
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsEnabledResponse {
+    pub enabled: bool,
+}
+
+/// Set or clear the voice of the active profile
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetVoiceRequest {
+    /// `None` falls back to the profile's language, or the default voice
+    #[serde(default)]
+    pub voice_id: Option<String>,
+}
+
+impl SetVoiceRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(ref voice_id) = self.voice_id {
+            if voice_id.len() > MAX_VOICE_ID_LENGTH {
+                return Err(ValidationError::VoiceIdTooLong {
+                    len: voice_id.len(),
+                    max: MAX_VOICE_ID_LENGTH,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SetVoiceResponse {
+    /// The voice `speak()` now defaults to
+    pub voice_id: Option<String>,
+    /// Set when the voice isn't available and the previous one was kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+}
+
+/// Save the voice `speak()` uses across launches, for all requests or one language
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDefaultVoiceRequest {
+    /// `None` removes the saved voice
+    #[serde(default)]
+    pub voice_id: Option<String>,
+    /// Only use the voice for requests in this language (e.g. "en" or "pt-BR"),
+    /// otherwise for requests that set neither a voice nor a language
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl SetDefaultVoiceRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        SetVoiceRequest {
+            voice_id: self.voice_id.clone(),
+        }
+        .validate()?;
+        if let Some(ref language) = self.language {
+            SpeakRequest::validate_language(language)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SetDefaultVoiceResponse {
+    /// The voice now saved for `language`
+    pub voice_id: Option<String>,
+    /// Language the voice is saved for, `null` for requests without one
+    pub language: Option<String>,
+    /// Set when the voice isn't available and the saved one was kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+}
+
+/// New default for one of the active profile's `rate`, `pitch` or `volume`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProsodyRequest {
+    pub value: f32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProsodyResponse {
+    /// The value `speak()` now defaults to
+    pub value: f32,
+}
+
+/// Set or clear the volume gain of one voice
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetVoiceGainRequest {
+    pub voice_id: String,
+    /// `None` removes the voice's gain
+    #[serde(default)]
+    pub gain: Option<f32>,
+}
+
+impl SetVoiceGainRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.voice_id.len() > MAX_VOICE_ID_LENGTH {
+            return Err(ValidationError::VoiceIdTooLong {
+                len: self.voice_id.len(),
+                max: MAX_VOICE_ID_LENGTH,
+            });
+        }
+        self.gain.map(validate_voice_gain).transpose()?;
+        Ok(())
+    }
+}
+
+/// Text framing every utterance, e.g. a brand name announced first
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct Affixes {
+    /// Text spoken before every utterance
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Text spoken after every utterance
+    #[serde(default)]
+    pub suffix: Option<String>,
+}
+
+impl Affixes {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for affix in [&self.prefix, &self.suffix].into_iter().flatten() {
+            if affix.len() > MAX_AFFIX_LENGTH {
+                return Err(ValidationError::AffixTooLong {
+                    len: affix.len(),
+                    max: MAX_AFFIX_LENGTH,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Wrap already preprocessed text in the prefix and suffix
+    pub fn apply(&self, text: &str) -> String {
+        self.apply_at(text, true, true)
+    }
+
+    /// Like [`Affixes::apply`], for one part of an utterance split into several
+    pub fn apply_at(&self, text: &str, first: bool, last: bool) -> String {
+        let prefix = self.prefix.as_deref().filter(|_| first);
+        let suffix = self.suffix.as_deref().filter(|_| last);
+        [prefix, Some(text), suffix]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Byte offset of the (trimmed) text in what [`Affixes::apply_at`] returns
+    pub fn text_start_at(&self, first: bool) -> usize {
+        self.prefix
+            .as_deref()
+            .filter(|_| first)
+            .map(str::trim)
+            .filter(|prefix| !prefix.is_empty())
+            .map_or(0, |prefix| prefix.len() + 1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakOptions {
+    /// The text to speak (max 10,000 characters)
+    pub text: String,
+    /// The language/locale code (e.g., "en-US", "pt-BR", "ja-JP")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Specific voice ID to use (from getVoices). Takes priority over language.
+    /// A display name like "Samantha" or "Microsoft Zira" also works, matched
+    /// loosely with a warning when it isn't the whole name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice_id: Option<String>,
+    /// Detect the language of the text and speak it with a voice for that language.
+    /// `language` is kept when detection is unsure, and `voiceId` takes priority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_detect_language: Option<bool>,
+    /// Speech rate (0.1 to 3.0, where 1.0 = normal). Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f32>,
+    /// Speech rate in words per minute, taking precedence over `rate`. Converted
+    /// with the engine's estimated normal pace (see `getRateInfo`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_wpm: Option<u32>,
+    /// Pitch (0.5 to 2.0, where 1.0 = normal). Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<f32>,
+    /// Volume (0.0 to 1.0, where 1.0 = full volume). Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>,
+    /// Queue mode: "flush", "add", "replacePending" or "urgent" (defaults to the configured default queue mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_mode: Option<QueueMode>,
+    /// Silence to insert after this utterance, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause_after_ms: Option<u32>,
+    /// Send the text to the engine verbatim, bypassing all preprocessing.
+    /// Overrides every other preprocessing option
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<bool>,
+    /// Whether to add the global prefix/suffix to this utterance (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_affixes: Option<bool>,
+    /// Pause before and after heading-like lines (markdown `#`, short ALL CAPS or
+    /// Title Case lines) when reading multi-line text. Ignored in raw mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structural_pauses: Option<bool>,
+    /// How to handle emoji: "skip", "describe" or "keep" (default). Ignored in raw mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji_mode: Option<EmojiMode>,
+    /// Markup to strip from the text: "plain" (default), "markdown" or "html".
+    /// List items are read as sentences and code blocks skipped. Ignored in raw mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<TextFormat>,
+    /// Emit this utterance's events as `<eventScope>://speech:*` instead of
+    /// `tts://speech:*` (letters, digits, `-` and `_`). Desktop only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_scope: Option<String>,
+    /// Treat the text as an SSML `<speak>` document. Skips preprocessing,
+    /// structural pauses and affixes; engines without SSML support speak the
+    /// text without its markup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssml: Option<bool>,
+    /// Stop the utterance if it is still playing this many milliseconds after it
+    /// started, emitting `tts://utterance-timeout`. Desktop only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration_ms: Option<u32>,
+    /// Words to speak with the given IPA pronunciation, matched whole-word and
+    /// case-insensitively. Needs an engine that reads SSML; others speak the
+    /// text as written and warn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pronunciations: Option<Vec<Pronunciation>>,
+    /// Speak with this backend instead of the OS engine, one of `getBackends`
+    /// (default: "system"). Desktop only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// Reject with `VOICE_NOT_FOUND` instead of speaking with a fallback voice when
+    /// no voice has `voiceId` or speaks `language` (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict_voice: Option<bool>,
+}
+
+/// Pronunciation override for one word or phrase
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct Pronunciation {
+    /// Word as it appears in the text, matched case-insensitively
+    pub word: String,
+    /// Pronunciation in the International Phonetic Alphabet, e.g. "təˈmɑːtəʊ"
+    pub ipa: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewVoiceOptions {
+    /// Voice ID to preview
+    pub voice_id: String,
+    /// Optional custom sample text (uses default if not provided)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Speech rate (0.1 to 3.0, where 1.0 = normal). Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f32>,
+    /// Pitch (0.5 to 2.0, where 1.0 = normal). Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<f32>,
+    /// Volume (0.0 to 1.0, where 1.0 = full volume). Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakRequest {
+    /// The text to speak
+    pub text: String,
+    /// The language/locale code (e.g., "en-US", "pt-BR", "ja-JP")
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Voice ID to use (from getVoices), or a voice's display name
+    #[serde(default)]
+    pub voice_id: Option<String>,
+    /// Replace `language` with the one detected in the text, unless a voice is named
+    #[serde(default)]
+    pub auto_detect_language: bool,
+    /// Speech rate (0.1 to 3.0, where 1.0 = normal, 2.0 = double, 0.5 = half)
+    /// (falls back to the active profile)
+    #[serde(default)]
+    pub rate: Option<f32>,
+    /// Speech rate in words per minute, replacing `rate` once converted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_wpm: Option<u32>,
+    /// Pitch (0.5 = low, 1.0 = normal, 2.0 = high) (falls back to the active profile)
+    #[serde(default)]
+    pub pitch: Option<f32>,
+    /// Volume (0.0 = silent, 1.0 = full volume) (falls back to the active profile)
+    #[serde(default)]
+    pub volume: Option<f32>,
+    /// Queue mode: "flush", "add", "replacePending" or "urgent" (falls back to the configured default)
+    #[serde(default)]
+    pub queue_mode: Option<QueueMode>,
+    /// Silence to insert after this utterance, in milliseconds
+    #[serde(default)]
+    pub pause_after_ms: Option<u32>,
+    /// Bypass all preprocessing and speak the text verbatim (falls back to the active profile)
+    #[serde(default)]
+    pub raw: Option<bool>,
+    /// Whether to add the global prefix/suffix (applied after preprocessing)
+    #[serde(default = "default_use_affixes")]
+    pub use_affixes: bool,
+    /// Insert pauses around heading-like lines of multi-line text
+    #[serde(default)]
+    pub structural_pauses: bool,
+    /// How to handle emoji (default: keep)
+    #[serde(default)]
+    pub emoji_mode: Option<EmojiMode>,
+    /// Markup stripped from the text before speaking (default: plain)
+    #[serde(default)]
+    pub format: Option<TextFormat>,
+    /// Event namespace for this utterance's events, replacing `tts`
+    #[serde(default)]
+    pub event_scope: Option<String>,
+    /// The text is an SSML document rather than plain text
+    #[serde(default)]
+    pub ssml: bool,
+    /// Longest the utterance may play before it is stopped, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_ms: Option<u32>,
+    /// IPA overrides, rewritten into SSML `<phoneme>` markup for engines that read it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pronunciations: Vec<Pronunciation>,
+    /// Registered backend to speak with instead of the OS engine (desktop only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// Fail with `VoiceNotFound` instead of speaking with a fallback voice
+    #[serde(default, skip_serializing)]
+    pub strict_voice: bool,
+    /// Filled in by the plugin for mobile engines, which report word boundaries
+    /// in the text they were given
+    #[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub word_offsets: Vec<WordOffset>,
+    /// Filled in by the plugin for mobile engines, which report sentence starts
+    /// when a word boundary enters a new sentence
+    #[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub sentence_offsets: Vec<SentenceOffset>,
+    /// Set by the plugin when it had to guess the voice: one matched by name, or
+    /// one of another variant of `language`
+    #[serde(skip)]
+    pub voice_warning: Option<String>,
+    /// Set by the plugin from the `normalize_loudness` config
+    #[serde(skip)]
+    pub normalize_loudness: bool,
+}
+
+impl Default for SpeakRequest {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            language: None,
+            voice_id: None,
+            auto_detect_language: false,
+            rate: None,
+            rate_wpm: None,
+            pitch: None,
+            volume: None,
+            queue_mode: None,
+            pause_after_ms: None,
+            raw: None,
+            use_affixes: default_use_affixes(),
+            structural_pauses: false,
+            emoji_mode: None,
+            format: None,
+            event_scope: None,
+            ssml: false,
+            max_duration_ms: None,
+            pronunciations: Vec::new(),
+            backend: None,
+            strict_voice: false,
+            word_offsets: Vec::new(),
+            sentence_offsets: Vec::new(),
+            voice_warning: None,
+            normalize_loudness: false,
+        }
+    }
+}
+
+fn default_rate() -> f32 {
+    1.0
+}
+fn default_pitch() -> f32 {
+    1.0
+}
+fn default_volume() -> f32 {
+    1.0
+}
+fn default_use_affixes() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ValidationError {
+    #[error("Text cannot be empty")]
+    EmptyText,
+    #[error("Text too long: {len} bytes (max: {max})")]
+    TextTooLong { len: usize, max: usize },
+    #[error("Voice ID too long: {len} chars (max: {max})")]
+    VoiceIdTooLong { len: usize, max: usize },
+    #[error("Language code too long: {len} chars (max: {max})")]
+    LanguageTooLong { len: usize, max: usize },
+    #[error("Prefix/suffix too long: {len} bytes (max: {max})")]
+    AffixTooLong { len: usize, max: usize },
+    #[error("Calibration needs between 1 and {max} rates, got {count}")]
+    CalibrationRates { count: usize, max: usize },
+    #[error("Invalid event scope '{0}': use 1 to 64 letters, digits, '-' or '_'")]
+    InvalidEventScope(String),
+    #[error("Lists need between 1 and {max} items, got {count}")]
+    ListItems { count: usize, max: usize },
+    #[error("Voice gain must be between 0 and {max}, got {gain}")]
+    VoiceGain { gain: f32, max: f32 },
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Section {index} out of range, the text has {count} sections")]
+    SectionIndex { index: u32, count: usize },
+    #[error("Offset {offset} out of range, the text has {len} characters")]
+    OffsetOutOfBounds { offset: u32, len: usize },
+    #[error("maxChunkLength must be at least {min} bytes, got {len}")]
+    ChunkLength { len: usize, min: usize },
+    #[error("maxDurationMs must be greater than 0")]
+    ZeroMaxDuration,
+    #[error("Output path cannot be empty")]
+    EmptyOutputPath,
+    #[error("Invalid SSML: {0}")]
+    InvalidSsml(String),
+    #[error("Pronunciations need a non-empty word and IPA")]
+    EmptyPronunciation,
+    #[error("Lexicon entries need a non-empty word and replacement")]
+    EmptyLexiconEntry,
+    #[error("Lexicon entry too long: {len} bytes (max: {max})")]
+    LexiconEntryTooLong { len: usize, max: usize },
+    #[error("Lexicon is full: {max} entries")]
+    LexiconFull { max: usize },
+    #[error("{field} must be between {min} and {max}, got {value}")]
+    OutOfRange {
+        field: &'static str,
+        value: f32,
+        min: f32,
+        max: f32,
+    },
+}
+
+/// Options controlling the text preprocessing pipeline
+#[derive(Debug, Clone, Default)]
+pub struct PreprocessOptions {
+    /// Skip every preprocessing step, regardless of the other options
+    pub raw: bool,
+    /// How emoji are handled
+    pub emoji_mode: EmojiMode,
+    /// Markup stripped ahead of every other step
+    pub format: TextFormat,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatedSpeakRequest {
+    pub text: String,
+    pub language: Option<String>,
+    pub voice_id: Option<String>,
+    pub rate: f32,
+    pub pitch: f32,
+    pub volume: f32,
+    pub queue_mode: QueueMode,
+    pub pause_after_ms: Option<u32>,
+    pub event_scope: Option<String>,
+    pub max_duration_ms: Option<u32>,
+    /// Registered backend speaking the request, `None` for the OS engine
+    pub backend: Option<String>,
+    /// Whether this starts the utterance, false for chunks after the first of a
+    /// long text
+    pub first_chunk: bool,
+    /// Whether this ends the utterance, false for chunks before the last
+    pub last_chunk: bool,
+    /// Where each sentence of `text` came from in the caller's text, in the order
+    /// the desktop worker speaks them. `None` for sentences the caller didn't
+    /// write, like a prefix, and empty when not known at all
+    pub sentences: Vec<Option<SentenceOffset>>,
+    /// Where the words of each sentence came from in the caller's text, alongside
+    /// `sentences`. Spoken offsets count from the start of the sentence
+    pub words: Vec<Vec<WordOffset>>,
+    /// Set when the voice was guessed, see [`SpeakRequest::voice_warning`]
+    pub voice_warning: Option<String>,
+    pub strict_voice: bool,
+    /// Whether audio the plugin plays for a backend has its loudness evened out
+    pub normalize_loudness: bool,
+    /// Backends to try in order when `backend` fails, from `backendFallback`
+    pub fallback: Vec<String>,
+}
+
+impl ValidatedSpeakRequest {
+    /// Split text longer than `max_len` bytes into several requests spoken back
+    /// to back as one utterance. Only the first may interrupt other speech and
+    /// only the last keeps `pause_after_ms`
+    pub fn into_chunks(self, max_len: usize) -> Vec<ValidatedSpeakRequest> {
+        let chunks = crate::preprocess::chunks(&self.text, max_len);
+        let last = chunks.len() - 1;
+        let mut starts = self.chunk_sentences(&chunks).into_iter();
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| {
+                let starts = starts.next().unwrap_or_default();
+                ValidatedSpeakRequest {
+                    sentences: starts
+                        .iter()
+                        .map(|start| start.and_then(|i| self.sentences[i]))
+                        .collect(),
+                    words: starts
+                        .iter()
+                        .map(|start| {
+                            start
+                                .and_then(|i| self.words.get(i).cloned())
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                    text,
+                    queue_mode: if index == 0 {
+                        self.queue_mode
+                    } else {
+                        QueueMode::Add
+                    },
+                    pause_after_ms: self.pause_after_ms.filter(|_| index == last),
+                    first_chunk: index == 0,
+                    last_chunk: index == last,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// For each sentence of each chunk of the text, the sentence of the whole text
+    /// it starts. A sentence too long for one chunk is spread over several, and
+    /// only its first piece keeps its offsets
+    fn chunk_sentences(&self, chunks: &[String]) -> Vec<Vec<Option<usize>>> {
+        if self.sentences.is_empty() {
+            return Vec::new();
+        }
+        let whole = crate::preprocess::sentences(&self.text);
+        // The sentence of the whole text the next piece continues, and how many of
+        // its bytes earlier pieces took
+        let mut next = 0;
+        let mut taken = 0;
+        let mut chunk_sentences = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let mut sentences = Vec::new();
+            for piece in crate::preprocess::sentences(chunk) {
+                let Some(source) = whole.get(next) else {
+                    sentences.push(None);
+                    continue;
+                };
+                let rest = source[taken..].trim_start();
+                if !rest.starts_with(piece) {
+                    sentences.push(None);
+                    continue;
+                }
+                let first_piece = taken == 0;
+                sentences
+                    .push(Some(next).filter(|&next| first_piece && next < self.sentences.len()));
+                taken = source.len() - rest.len() + piece.len();
+                if source[taken..].trim().is_empty() {
+                    next += 1;
+                    taken = 0;
+                }
+            }
+            chunk_sentences.push(sentences);
+        }
+        chunk_sentences
+    }
+}
+
+impl SpeakRequest {
+    /// Speak in the language detected in the text when the request asks for
+    /// `auto_detect_language` and names no voice. A requested language that is a
+    /// regional variant of the detected one is kept, as is any when detection is unsure
+    pub fn apply_detected_language(&mut self) {
+        if !self.auto_detect_language || self.voice_id.is_some() {
+            return;
+        }
+        let detected = if self.ssml {
+            crate::language_detect::detect_language(&crate::ssml::to_plain_text(&self.text))
+        } else {
+            crate::language_detect::detect_language(&self.text)
+        };
+        let Some(detected) = detected else {
+            return;
+        };
+        let requested_variant = self.language.as_deref().is_some_and(|language| {
+            language
+                .split(['-', '_'])
+                .next()
+                .is_some_and(|primary| primary.eq_ignore_ascii_case(detected))
+        });
+        if !requested_variant {
+            log::debug!("Detected language '{}' in the text", detected);
+            self.language = Some(detected.to_string());
+        }
+    }
+
+    /// Fill in fields the caller omitted from the plugin configuration
+    pub fn apply_defaults(&mut self, config: &TtsConfig) {
+        if self.queue_mode.is_none() {
+            self.queue_mode = Some(config.default_queue_mode);
+        }
+        self.normalize_loudness = config.normalize_loudness;
+    }
+
+    /// Fill in voice, prosody and preprocessing settings the caller omitted from
+    /// the active profile
+    pub fn apply_profile(&mut self, profile: &VoiceProfile) {
+        // An explicit language picks its own voice, so only inherit when neither is set
+        if self.voice_id.is_none() && self.language.is_none() {
+            self.voice_id = profile.voice_id.clone();
+            self.language = profile.language.clone();
+        }
+        self.rate.get_or_insert(profile.rate);
+        self.pitch.get_or_insert(profile.pitch);
+        self.volume.get_or_insert(profile.volume);
+        self.raw.get_or_insert(profile.raw);
+    }
+
+    /// Convert `rate_wpm` into `rate`, which it takes precedence over, for an engine
+    /// speaking `normal_wpm` at 1.0. Returns a warning when the pace had to be
+    /// clamped to the supported rates
+    pub fn apply_rate_wpm(&mut self, normal_wpm: u32) -> Option<String> {
+        let wpm = self.rate_wpm.take()?;
+        let (rate, clamped) = crate::wpm::rate_from_wpm(wpm, normal_wpm);
+        self.rate = Some(rate);
+        clamped.then(|| {
+            format!(
+                "rateWpm {} is outside the supported {}-{} words per minute and was clamped",
+                wpm,
+                crate::wpm::wpm_from_rate(MIN_RATE, normal_wpm),
+                crate::wpm::wpm_from_rate(MAX_RATE, normal_wpm),
+            )
+        })
+    }
+
+    /// Scale the volume by the gain configured for the chosen voice. Engines cap
+    /// volume at 1.0, so gains above 1 only make a difference below full volume
+    pub fn apply_voice_gain(&mut self, gains: &HashMap<String, f32>) {
+        let Some(gain) = self.voice_id.as_ref().and_then(|id| gains.get(id)) else {
+            return;
+        };
+        let volume = self.volume.unwrap_or(1.0);
+        // Out-of-range volumes are left as they are for validation to reject
+        if (0.0..=1.0).contains(&volume) {
+            self.volume = Some((volume * gain).min(1.0));
+        }
+    }
+
+    /// Replace a `voice_id` that isn't one of `voices` with the voice it names, for
+    /// callers who only know a voice's display name. A fuzzy match sets `voice_warning`
+    pub fn match_voice_name(&mut self, voices: &[Voice]) {
+        let Some(ref query) = self.voice_id else {
+            return;
+        };
+        if voices.iter().any(|voice| voice.id == *query) {
+            return;
+        }
+        let Some(found) = voice_by_name(voices, query) else {
+            return;
+        };
+        if !found.name.eq_ignore_ascii_case(query.trim()) {
+            self.voice_warning = Some(format!(
+                "No voice with id '{}', using '{}' ({}) whose name matches it",
+                query, found.name, found.id
+            ));
+        }
+        self.voice_id = Some(found.id.clone());
+    }
+
+    /// For a `strict_voice` request, fail when its voice isn't one of `voices` or,
+    /// without a voice, none of them speaks its language
+    pub fn require_voice(&self, voices: &[Voice]) -> crate::Result<()> {
+        if !self.strict_voice {
+            return Ok(());
+        }
+        if let Some(ref voice_id) = self.voice_id {
+            if !voices.iter().any(|voice| voice.id == *voice_id) {
+                return Err(crate::Error::VoiceNotFound(format!(
+                    "Voice '{}' not found",
+                    voice_id
+                )));
+            }
+        } else if let Some(ref language) = self.language {
+            if !matches!(
+                voice_for_language(voices, language, None),
+                Some(LanguageMatch::Speaks(_))
+            ) {
+                return Err(crate::Error::VoiceNotFound(format!(
+                    "No voice found for language '{}'",
+                    language
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Split into one request per structural block when `structural_pauses` is on,
+    /// leaving pauses between them. Otherwise the request is returned as-is
+    pub fn into_segments(self) -> Vec<SpeakRequest> {
+        if !self.structural_pauses || self.preprocess_options().raw {
+            return vec![self];
+        }
+        let blocks = crate::preprocess::split_structure(&self.text);
+        if blocks.len() <= 1 {
+            return vec![self];
+        }
+        self.split_into(blocks)
+    }
+
+    /// Turn SSML into plain-text requests for engines that can't read markup, split
+    /// wherever a `<break>` or a paragraph end asks for silence so it is kept as a pause
+    pub fn into_plain_segments(self) -> Vec<SpeakRequest> {
+        let blocks = crate::ssml::to_plain_blocks(&self.text);
+        SpeakRequest {
+            ssml: false,
+            raw: Some(true),
+            ..self
+        }
+        .split_into(blocks)
+    }
+
+    fn split_into(self, blocks: Vec<crate::preprocess::StructuralBlock>) -> Vec<SpeakRequest> {
+        let last = blocks.len() - 1;
+        blocks
+            .into_iter()
+            .enumerate()
+            .map(|(index, block)| SpeakRequest {
+                text: block.text,
+                // Later segments must follow the first instead of interrupting it
+                queue_mode: if index == 0 {
+                    self.queue_mode
+                } else {
+                    Some(QueueMode::Add)
+                },
+                pause_after_ms: if index == last {
+                    self.pause_after_ms
+                } else {
+                    block.pause_after_ms
+                },
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    pub fn preprocess_options(&self) -> PreprocessOptions {
+        PreprocessOptions {
+            // Preprocessing would rewrite the markup along with the text
+            raw: self.raw.unwrap_or(false) || self.ssml,
+            emoji_mode: self.emoji_mode.unwrap_or_default(),
+            format: self.format.unwrap_or_default(),
+        }
+    }
+
+    /// Whether pronunciation overrides should be applied, which like every other
+    /// rewrite of the text is skipped for raw text and SSML
+    pub fn uses_pronunciations(&self) -> bool {
+        !self.pronunciations.is_empty() && !self.preprocess_options().raw
+    }
+
+    /// The text as it will be handed to the engine, after preprocessing
+    pub fn spoken_text(&self) -> String {
+        crate::preprocess::preprocess(&self.text, &self.preprocess_options())
+    }
+
+    pub fn validate(&self) -> Result<ValidatedSpeakRequest, ValidationError> {
+        // Text validation
+        if self.text.trim().is_empty() {
+            return Err(ValidationError::EmptyText);
+        }
+        if self.text.len() > MAX_TEXT_LENGTH {
+            return Err(ValidationError::TextTooLong {
+                len: self.text.len(),
+                max: MAX_TEXT_LENGTH,
+            });
+        }
+        if self.ssml {
+            crate::ssml::check(&self.text).map_err(ValidationError::InvalidSsml)?;
+            if crate::ssml::to_plain_text(&self.text).is_empty() {
+                return Err(ValidationError::EmptyText);
+            }
+        }
+        let text = self.spoken_text();
+        if text.is_empty() {
+            return Err(ValidationError::EmptyText);
+        }
+
+        if let Some(ref voice_id) = self.voice_id {
+            if voice_id.len() > MAX_VOICE_ID_LENGTH {
+                return Err(ValidationError::VoiceIdTooLong {
+                    len: voice_id.len(),
+                    max: MAX_VOICE_ID_LENGTH,
+                });
+            }
+        }
+        let rate = Self::validate_range("rate", self.rate, MIN_RATE, MAX_RATE)?;
+        let pitch = Self::validate_range("pitch", self.pitch, MIN_PITCH, MAX_PITCH)?;
+        let volume = Self::validate_range("volume", self.volume, 0.0, 1.0)?;
+
+        // Language validation (if provided)
+        let sanitized_language = self
+            .language
+            .as_ref()
+            .map(|lang| Self::validate_language(lang))
+            .transpose()?;
+        let event_scope = self
+            .event_scope
+            .as_ref()
+            .map(|scope| Self::validate_event_scope(scope))
+            .transpose()?;
+        if self.max_duration_ms == Some(0) {
+            return Err(ValidationError::ZeroMaxDuration);
+        }
+        let empty = |s: &str| s.trim().is_empty();
+        if self
+            .pronunciations
+            .iter()
+            .any(|p| empty(&p.word) || empty(&p.ipa))
+        {
+            return Err(ValidationError::EmptyPronunciation);
+        }
+
+        Ok(ValidatedSpeakRequest {
+            text,
+            language: sanitized_language,
+            voice_id: self.voice_id.clone(),
+            rate,
+            pitch,
+            volume,
+            queue_mode: self.queue_mode.unwrap_or_default(),
+            pause_after_ms: self.pause_after_ms.map(|ms| ms.min(MAX_PAUSE_MS)),
+            event_scope,
+            max_duration_ms: self.max_duration_ms,
+            backend: self.backend.clone(),
+            first_chunk: true,
+            last_chunk: true,
+            sentences: Vec::new(),
+            words: Vec::new(),
+            voice_warning: self.voice_warning.clone(),
+            strict_voice: self.strict_voice,
+            normalize_loudness: self.normalize_loudness,
+            fallback: Vec::new(),
+        })
+    }
+
+    /// Omitted values mean normal (1.0). NaN is rejected along with out-of-range values
+    fn validate_range(
+        field: &'static str,
+        value: Option<f32>,
+        min: f32,
+        max: f32,
+    ) -> Result<f32, ValidationError> {
+        let value = value.unwrap_or(1.0);
+        if !(min..=max).contains(&value) {
+            return Err(ValidationError::OutOfRange {
+                field,
+                value,
+                min,
+                max,
+            });
+        }
+        Ok(value)
+    }
+
+    /// Event scopes become part of the event name, so only allow characters that
+    /// are valid there and can't be confused with the `://` separator
+    fn validate_event_scope(scope: &str) -> Result<String, ValidationError> {
+        let valid = !scope.is_empty()
+            && scope.len() <= MAX_EVENT_SCOPE_LENGTH
+            && scope
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ValidationError::InvalidEventScope(scope.to_string()));
+        }
+        Ok(scope.to_string())
+    }
+
+    fn validate_language(lang: &str) -> Result<String, ValidationError> {
+        if lang.len() > MAX_LANGUAGE_LENGTH {
+            return Err(ValidationError::LanguageTooLong {
+                len: lang.len(),
+                max: MAX_LANGUAGE_LENGTH,
+            });
+        }
+        Ok(lang.to_string())
+    }
+}
+
+/// Shareable voice setup, applied to every request that doesn't override it
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceProfile {
+    /// Profile format version, bumped when the meaning of a field changes
+    #[serde(default = "VoiceProfile::current_version")]
+    pub version: u32,
+    /// Voice ID (from getVoices)
+    #[serde(default)]
+    pub voice_id: Option<String>,
+    /// Language/locale code, used when no voice is set
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Speech rate (0.1 to 3.0, where 1.0 = normal)
+    #[serde(default = "default_rate")]
+    pub rate: f32,
+    /// Pitch (0.5 to 2.0, where 1.0 = normal)
+    #[serde(default = "default_pitch")]
+    pub pitch: f32,
+    /// Volume (0.0 to 1.0, where 1.0 = full volume)
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// Bypass all preprocessing by default
+    #[serde(default)]
+    pub raw: bool,
+}
+
+impl Default for VoiceProfile {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            voice_id: None,
+            language: None,
+            rate: default_rate(),
+            pitch: default_pitch(),
+            volume: default_volume(),
+            raw: false,
+        }
+    }
+}
+
+impl VoiceProfile {
+    /// Current profile format version
+    pub const VERSION: u32 = 1;
+
+    fn current_version() -> u32 {
+        Self::VERSION
+    }
+
+    /// Make `voice_id` the default voice, keeping the current one when `voice_exists`
+    /// says it isn't available here
+    pub fn set_voice(
+        &mut self,
+        voice_id: Option<String>,
+        voice_exists: impl FnOnce(&str) -> bool,
+    ) -> SetVoiceResponse {
+        let warning = match voice_id {
+            Some(voice_id) if !voice_exists(&voice_id) => Some(format!(
+                "Voice '{}' not available, keeping the current voice",
+                voice_id
+            )),
+            voice_id => {
+                self.voice_id = voice_id;
+                None
+            }
+        };
+        SetVoiceResponse {
+            voice_id: self.voice_id.clone(),
+            warning,
+        }
+    }
+
+    /// Validate `rate` like the `SpeakRequest` field and make it the default
+    pub fn set_rate(&mut self, rate: f32) -> Result<f32, ValidationError> {
+        self.rate = SpeakRequest::validate_range("rate", Some(rate), MIN_RATE, MAX_RATE)?;
+        Ok(self.rate)
+    }
+
+    /// Validate `pitch` like the `SpeakRequest` field and make it the default
+    pub fn set_pitch(&mut self, pitch: f32) -> Result<f32, ValidationError> {
+        self.pitch = SpeakRequest::validate_range("pitch", Some(pitch), MIN_PITCH, MAX_PITCH)?;
+        Ok(self.pitch)
+    }
+
+    /// Validate `volume` like the `SpeakRequest` field and make it the default
+    pub fn set_volume(&mut self, volume: f32) -> Result<f32, ValidationError> {
+        self.volume = SpeakRequest::validate_range("volume", Some(volume), 0.0, 1.0)?;
+        Ok(self.volume)
+    }
+
+    /// Validate and normalize a profile for import, collecting non-fatal issues as
+    /// warnings. `voice_exists` decides whether the referenced voice is available here.
+    pub fn sanitize(
+        mut self,
+        voice_exists: impl FnOnce(&str) -> bool,
+    ) -> Result<(Self, Vec<String>), ValidationError> {
+        let mut warnings = Vec::new();
+
+        if self.version > Self::VERSION {
+            warnings.push(format!(
+                "Profile version {} is newer than supported version {}; unknown settings were ignored",
+                self.version,
+                Self::VERSION
+            ));
+        }
+        self.version = Self::VERSION;
+
+        if let Some(ref language) = self.language {
+            SpeakRequest::validate_language(language)?;
+        }
+        if let Some(voice_id) = self.voice_id.take() {
+            if voice_exists(&voice_id) {
+                self.voice_id = Some(voice_id);
+            } else {
+                warnings.push(match self.language {
+                    Some(ref language) => format!(
+                        "Voice '{}' not available, falling back to language '{}'",
+                        voice_id, language
+                    ),
+                    None => format!(
+                        "Voice '{}' not available, falling back to the default voice",
+                        voice_id
+                    ),
+                });
+            }
+        }
+
+        self.rate = self.rate.clamp(MIN_RATE, MAX_RATE);
+        self.pitch = self.pitch.clamp(0.5, 2.0);
+        self.volume = self.volume.clamp(0.0, 1.0);
+
+        Ok((self, warnings))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProfileResponse {
+    /// The profile as applied, after fallbacks
+    pub profile: VoiceProfile,
+    /// Settings that could not be applied as-is (e.g. missing voice)
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakResponse {
+    /// Whether speech was successfully initiated
+    pub success: bool,
+    /// Optional warning message (e.g., voice not found, using fallback)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+    /// Id carried by the `speech:*` events of this utterance. When the text is split
+    /// for structural pauses, the id of the last part, whose `speech:finish` ends it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub utterance_id: Option<String>,
+    /// Backend the speech starts with, "system" for the OS engine, after skipping
+    /// `backendFallback` entries that aren't registered (desktop only). Falling
+    /// back while speaking is reported by `tts://backend-fallback`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub backend: Option<String>,
+    /// Voice the speech starts with, the engine's initial voice when the requested
+    /// one wasn't found. Left out when the engine keeps whichever voice it has
+    /// (desktop only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub used_voice_id: Option<String>,
+}
+
+impl SpeakResponse {
+    /// Reported instead of speaking while the plugin is disabled
+    pub fn disabled() -> Self {
+        Self {
+            success: true,
+            warning: Some(DISABLED_WARNING.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Synthesize speech into an audio file instead of playing it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakToFileRequest {
+    /// Text plus the voice and prosody it is synthesized with
+    #[serde(flatten)]
+    pub speak: SpeakRequest,
+    /// Absolute path of the file to write, replaced if it exists
+    pub output_path: String,
+}
+
+impl SpeakToFileRequest {
+    pub fn validate(&self) -> Result<ValidatedSpeakRequest, ValidationError> {
+        if self.output_path.trim().is_empty() {
+            return Err(ValidationError::EmptyOutputPath);
+        }
+        self.speak.validate()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakToFileResponse {
+    /// Path of the written file
+    pub path: String,
+    /// Optional warning message (e.g., voice not found, using fallback)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SynthesizeStreamResponse {
+    /// Number of WAV chunks sent, one per sentence
+    pub chunks: u32,
+    /// Optional warning message (e.g., voice not found, using fallback)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsageResponse {
+    /// Characters spoken so far this session
+    pub used: u32,
+    /// Configured `sessionCharLimit`, if any
+    pub limit: Option<u32>,
+    /// Characters left before `speak` is rejected, if limited
+    pub remaining: Option<u32>,
+}
+
+/// Size and use of the synthesized audio cache
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStatsResponse {
+    /// Cached clips, including those of earlier launches
+    pub entries: u32,
+    /// Bytes the cached clips take up
+    #[ts(type = "number")]
+    pub bytes: u64,
+    /// Configured `cacheMaxBytes`, 0 when caching is off
+    #[ts(type = "number")]
+    pub max_bytes: u64,
+    /// Clips played or rendered from the cache this session
+    #[ts(type = "number")]
+    pub hits: u64,
+    /// Clips looked up but synthesized this session
+    #[ts(type = "number")]
+    pub misses: u64,
+    /// Where clips are kept, `null` when the app cache directory can't be resolved
+    pub directory: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClearCacheResponse {
+    pub removed_entries: u32,
+    #[ts(type = "number")]
+    pub freed_bytes: u64,
+}
+
+/// Items for a user-paced list, spoken one at a time by `speak_list_next`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakListBeginRequest {
+    pub items: Vec<SpeakRequest>,
+}
+
+impl SpeakListBeginRequest {
+    /// Check every item up front so a bad one doesn't surface halfway through
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let count = self.items.len();
+        if count == 0 || count > MAX_LIST_ITEMS {
+            return Err(ValidationError::ListItems {
+                count,
+                max: MAX_LIST_ITEMS,
+            });
+        }
+        for item in &self.items {
+            item.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Utterances queued back to back by `speak_sequence`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakSequenceRequest {
+    pub items: Vec<SpeakRequest>,
+}
+
+impl SpeakSequenceRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let count = self.items.len();
+        if count == 0 || count > MAX_LIST_ITEMS {
+            return Err(ValidationError::ListItems {
+                count,
+                max: MAX_LIST_ITEMS,
+            });
+        }
+        for item in &self.items {
+            item.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakSequenceResponse {
+    pub success: bool,
+    /// Identifies this sequence's utterances in the queue
+    pub sequence_id: String,
+    /// Whether the rest of an earlier sequence was dropped
+    pub interrupted: bool,
+    /// Set when nothing was queued because the plugin is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+}
+
+impl SpeakSequenceResponse {
+    /// Reported instead of queueing while the plugin is disabled. The sequence
+    /// id is empty since nothing was queued
+    pub fn disabled() -> Self {
+        Self {
+            success: true,
+            warning: Some(DISABLED_WARNING.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Segments spoken back to back by `speak_segments`, each with its own voice,
+/// language and prosody. The first segment's queue mode applies to the whole batch
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakSegmentsRequest {
+    pub segments: Vec<SpeakRequest>,
+}
+
+impl SpeakSegmentsRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let count = self.segments.len();
+        if count == 0 || count > MAX_LIST_ITEMS {
+            return Err(ValidationError::ListItems {
+                count,
+                max: MAX_LIST_ITEMS,
+            });
+        }
+        for segment in &self.segments {
+            segment.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakSegmentsResponse {
+    pub success: bool,
+    /// Utterance id of each segment, in order: the `id` of its `speech:*` events.
+    /// Empty when nothing was queued
+    pub utterance_ids: Vec<String>,
+    /// Set when the request was adjusted or dropped (e.g. by the interrupt cooldown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+}
+
+impl SpeakSegmentsResponse {
+    pub fn new(utterance_ids: Vec<String>, spoken: SpeakResponse) -> Self {
+        Self {
+            success: spoken.success,
+            utterance_ids,
+            warning: spoken.warning,
+            remaining_chars: spoken.remaining_chars,
+        }
+    }
+}
+
+/// Payload of the `tts://segment:start` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentStartEvent {
+    /// Position of the segment in the request (0-based)
+    pub index: u32,
+    /// Total number of segments
+    pub total: u32,
+    /// Utterance id of the segment, as in the response's `utteranceIds`
+    pub utterance_id: String,
+}
+
+/// Pace of the platform engine in words per minute, to relate rates to
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct RateInfoResponse {
+    /// Estimated words per minute at rate 1.0. The real pace varies with the
+    /// voice and the text
+    pub normal_wpm: u32,
+    /// Words per minute at the slowest rate (0.1)
+    pub min_wpm: u32,
+    /// Words per minute at the fastest rate (3.0)
+    pub max_wpm: u32,
+}
+
+/// Payload of the `tts://utterance-timeout` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct UtteranceTimeoutEvent {
+    /// Id of the utterance that was stopped, as in its `speech:*` events
+    pub id: String,
+    /// The `maxDurationMs` it ran into
+    pub max_duration_ms: u32,
+}
+
+/// Payload of the `tts://error` event for failures after a command returned,
+/// shaped like the error a command rejects with
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorEvent {
+    pub code: &'static str,
+    pub message: String,
+    /// Utterance the failure concerns, as in its `speech:*` events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl ErrorEvent {
+    pub fn new(error: &crate::Error, id: Option<&str>) -> Self {
+        Self {
+            code: error.code(),
+            message: error.to_string(),
+            id: id.map(str::to_string),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakListBeginResponse {
+    /// Pass to `speakListNext` and `speakListReset`
+    pub session_id: String,
+    /// Number of items in the list
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakListSessionRequest {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakListNextResponse {
+    /// Whether the item was spoken. Dropped items are retried by the next call
+    pub success: bool,
+    /// Position of the item in the list, starting at 0
+    pub index: u32,
+    /// Whether items remain. The session is gone once this is false
+    pub has_more: bool,
+    /// Set when the item was dropped (e.g. by the interrupt cooldown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+}
+
+impl SpeakListNextResponse {
+    pub fn new(index: usize, has_more: bool, spoken: SpeakResponse) -> Self {
+        Self {
+            success: spoken.success,
+            index: index as u32,
+            has_more,
+            warning: spoken.warning,
+            remaining_chars: spoken.remaining_chars,
+        }
+    }
+}
+
+/// Text spoken one word per `speak_word_next`, e.g. advanced by an AAC switch
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakWordsBeginRequest {
+    /// Text plus the voice and prosody every word is spoken with
+    #[serde(flatten)]
+    pub speak: SpeakRequest,
+}
+
+impl SpeakWordsBeginRequest {
+    /// One utterance per word, with punctuation attached to its word. The global
+    /// prefix/suffix would be repeated around every word, so it is left out
+    pub fn items(&self) -> Vec<SpeakRequest> {
+        crate::preprocess::words(&self.speak.text)
+            .into_iter()
+            .map(|word| SpeakRequest {
+                text: word,
+                use_affixes: false,
+                structural_pauses: false,
+                ..self.speak.clone()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakWordsBeginResponse {
+    /// Pass to `speakWordNext`. `null` when the text has no words to speak
+    pub session_id: Option<String>,
+    /// Number of words
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakWordNextResponse {
+    /// Whether the word was spoken. Dropped words are retried by the next call
+    pub success: bool,
+    /// The word, with any punctuation attached to it
+    pub word: String,
+    /// Words left to speak, including a dropped one. The session is gone once this is 0
+    pub remaining: u32,
+    /// Set when the word was dropped (e.g. by the interrupt cooldown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakListResetResponse {
+    /// Whether the session existed. Finished sessions are already gone
+    pub removed: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct WordCountResponse {
+    /// Number of words that will be spoken
+    pub words: u32,
+    /// Number of characters that will be spoken
+    pub characters: u32,
+}
+
+impl WordCountResponse {
+    /// Count the words and characters of already preprocessed text
+    pub fn for_spoken_text(text: &str) -> Self {
+        Self {
+            words: text.split_whitespace().count() as u32,
+            characters: text.chars().count() as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopResponse {
+    pub success: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopGentleRequest {
+    /// How long to fade out before stopping, in milliseconds
+    pub duration_ms: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopIfMatchesRequest {
+    /// Regular expression tested against the text being spoken
+    pub pattern: String,
+}
+
+impl StopIfMatchesRequest {
+    /// Compile the pattern, rejecting overly long or invalid ones
+    pub fn compile(&self) -> Result<regex::Regex, ValidationError> {
+        if self.pattern.len() > MAX_PATTERN_LENGTH {
+            return Err(ValidationError::InvalidPattern(format!(
+                "{} bytes (max: {})",
+                self.pattern.len(),
+                MAX_PATTERN_LENGTH
+            )));
+        }
+        regex::Regex::new(&self.pattern).map_err(|e| ValidationError::InvalidPattern(e.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct StopIfMatchesResponse {
+    /// Whether the current utterance matched and speech was stopped
+    pub stopped: bool,
+    /// Why nothing could be checked, if so
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SkipToNextResponse {
+    /// Whether an utterance was being spoken and was cut short
+    pub skipped: bool,
+    /// Id of the utterance spoken next, when one is waiting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub next_utterance_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct StopGentleResponse {
+    pub success: bool,
+    /// Whether speech was faded out rather than stopped immediately
+    pub faded: bool,
+    /// Why the fade wasn't applied, if it wasn't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub note: Option<String>,
+}
+
+impl StopGentleResponse {
+    /// Response for engines that play audio themselves, where the plugin can't
+    /// ramp volume or rate mid-utterance
+    pub fn stopped_immediately() -> Self {
+        Self {
+            success: true,
+            faded: false,
+            note: Some(
+                "The system speech engine can't fade out, so speech was stopped immediately"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Response for a backend that faded its audio out before stopping
+    pub fn faded() -> Self {
+        Self {
+            success: true,
+            faded: true,
+            note: None,
+        }
+    }
+
+    /// Response for a registered backend without a fade, `backend` being its name
+    pub fn backend_stopped_immediately(backend: &str) -> Self {
+        Self {
+            success: true,
+            faded: false,
+            note: Some(format!(
+                "The {} backend can't fade out, so speech was stopped immediately",
+                backend
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct Voice {
+    /// Unique identifier for the voice
+    pub id: String,
+    /// Display name of the voice
+    pub name: String,
+    /// Language code (e.g., "en-US")
+    pub language: String,
+    /// `null` when the engine doesn't report it
+    #[serde(default)]
+    pub gender: Option<VoiceGender>,
+    /// `null` when the engine doesn't report it
+    #[serde(default)]
+    pub quality: Option<VoiceQuality>,
+    /// Whether speaking needs a network connection. `null` when the engine doesn't report it
+    #[serde(default)]
+    pub requires_network: Option<bool>,
+    /// Whether the voice's data is on the device, `false` when it still has to be
+    /// downloaded. `null` when the engine doesn't report it
+    #[serde(default)]
+    pub installed: Option<bool>,
+    /// Engine that speaks the voice, e.g. "com.google.android.tts", "AVSpeechSynthesizer",
+    /// "WinRT" or the name of a cloud backend. `null` when unknown
+    #[serde(default)]
+    pub engine: Option<String>,
+    /// Platform that reported the voice: "windows", "macos", "linux", "ios" or "android"
+    #[serde(default)]
+    pub platform: String,
+}
+
+impl Voice {
+    /// Whether the voice speaks `language`, exactly or as a regional variant of it
+    /// ("pt" matches "pt-BR"). Case-insensitive, with `_` read as `-`
+    pub fn speaks(&self, language: &str) -> bool {
+        let normalize = |tag: &str| tag.replace('_', "-").to_lowercase();
+        let (own, wanted) = (normalize(&self.language), normalize(language));
+        own.strip_prefix(&wanted)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+    }
+}
+
+/// Voice picked for a requested language by [`voice_for_language`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LanguageMatch<'a> {
+    /// Speaks the requested locale, or a regional variant of a bare language
+    Speaks(&'a Voice),
+    /// Only speaks another variant of the language, like "pt-PT" for "pt-BR"
+    OtherVariant(&'a Voice),
+}
+
+impl<'a> LanguageMatch<'a> {
+    pub fn voice(self) -> &'a Voice {
+        match self {
+            Self::Speaks(voice) | Self::OtherVariant(voice) => voice,
+        }
+    }
+}
+
+/// Best voice among `voices` for `language`. Voices rank by locale first: exactly
+/// that locale, then a regional variant of it ("en" finds "en-US"), then the same
+/// language in another variant ("pt-BR" finds "pt-PT"). Ties go to the higher
+/// quality, then to `default_voice_id`, then to the earlier voice. Voices that
+/// still have to be downloaded only win when no other voice speaks the language
+pub(crate) fn voice_for_language<'a>(
+    voices: &'a [Voice],
+    language: &str,
+    default_voice_id: Option<&str>,
+) -> Option<LanguageMatch<'a>> {
+    let normalize = |tag: &str| tag.replace('_', "-").to_lowercase();
+    let wanted = normalize(language);
+    let base_language = language.split(['-', '_']).next().unwrap_or_default();
+    let locale_rank = |voice: &Voice| {
+        if normalize(&voice.language) == wanted {
+            Some(2)
+        } else if voice.speaks(language) {
+            Some(1)
+        } else if voice.speaks(base_language) {
+            Some(0)
+        } else {
+            None
+        }
+    };
+    let (rank, voice) = voices
+        .iter()
+        .filter_map(|voice| locale_rank(voice).map(|rank| (rank, voice)))
+        .min_by_key(|&(rank, voice)| {
+            (
+                voice.installed == Some(false),
+                Reverse(rank),
+                Reverse(voice.quality),
+                Some(voice.id.as_str()) != default_voice_id,
+            )
+        })?;
+    Some(if rank > 0 {
+        LanguageMatch::Speaks(voice)
+    } else {
+        LanguageMatch::OtherVariant(voice)
+    })
+}
+
+/// Voice whose name best matches `query`, like "Samantha" or "Microsoft Zira".
+/// A case-insensitive match of the whole name wins. Otherwise every word of the
+/// query has to match a word of the voice's name or id: exactly, then as the
+/// start of a longer word, then with one typo in words of four letters or more.
+/// Ties go to the shorter name, then the higher quality, then the earlier voice
+pub(crate) fn voice_by_name<'a>(voices: &'a [Voice], query: &str) -> Option<&'a Voice> {
+    let query = query.trim();
+    if let Some(voice) = voices
+        .iter()
+        .find(|voice| voice.name.eq_ignore_ascii_case(query))
+    {
+        return Some(voice);
+    }
+    let query_words = name_words(query);
+    if query_words.is_empty() {
+        return None;
+    }
+    voices
+        .iter()
+        .filter_map(|voice| {
+            let (name, id) = (name_words(&voice.name), name_words(&voice.id));
+            let words: Vec<&String> = name.iter().chain(&id).collect();
+            let cost = query_words
+                .iter()
+                .map(|wanted| {
+                    words
+                        .iter()
+                        .filter_map(|word| word_match_cost(wanted, word))
+                        .min()
+                })
+                .sum::<Option<usize>>()?;
+            Some((cost, name.len(), Reverse(voice.quality), voice))
+        })
+        .min_by_key(|&(cost, name_len, quality, _)| (cost, name_len, quality))
+        .map(|(_, _, _, voice)| voice)
+}
+
+/// Lowercase alphanumeric words of a voice name or id
+fn name_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// How loosely `word` matches the `wanted` query word, `None` if it doesn't
+fn word_match_cost(wanted: &str, word: &str) -> Option<usize> {
+    if word == wanted {
+        Some(0)
+    } else if wanted.chars().count() >= 3 && word.starts_with(wanted) {
+        Some(1)
+    } else if wanted.chars().count() >= 4 && within_one_edit(wanted, word) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Whether `a` turns into `b` by inserting, removing or replacing at most one character
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short.iter().zip(&long).take_while(|(x, y)| x == y).count();
+    // Past the first difference the rest lines up once the extra or replaced
+    // character is skipped
+    let rest = if short.len() == long.len() {
+        prefix + 1
+    } else {
+        prefix
+    };
+    short.get(rest..).unwrap_or_default() == long.get(prefix + 1..).unwrap_or_default()
+}
+
+/// A voice saved earlier, possibly on another device or OS version, to find
+/// among the current voices
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveVoiceRequest {
+    /// Id of the saved voice
+    pub id: String,
+    /// Display name of the saved voice
+    pub name: String,
+    /// Language code of the saved voice
+    pub language: String,
+    /// Platform the voice was saved on
+    #[serde(default)]
+    pub platform: String,
+}
+
+impl ResolveVoiceRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.id.len() > MAX_VOICE_ID_LENGTH {
+            return Err(ValidationError::VoiceIdTooLong {
+                len: self.id.len(),
+                max: MAX_VOICE_ID_LENGTH,
+            });
+        }
+        if self.language.len() > MAX_LANGUAGE_LENGTH {
+            return Err(ValidationError::LanguageTooLong {
+                len: self.language.len(),
+                max: MAX_LANGUAGE_LENGTH,
+            });
+        }
+        Ok(())
+    }
+
+    /// Best match among `voices`: the same id, then the same name in the same
+    /// language, then the best voice of the language, falling back to its base
+    /// language ("en" for "en-US"), see [`voice_for_language`]
+    pub fn resolve<'a>(&self, voices: &'a [Voice]) -> Option<&'a Voice> {
+        let same_language = |voice: &Voice| {
+            let language = |tag: &str| tag.replace('_', "-").to_lowercase();
+            language(&voice.language) == language(&self.language)
+        };
+        voices
+            .iter()
+            .find(|voice| voice.id == self.id)
+            .or_else(|| {
+                voices.iter().find(|voice| {
+                    voice.name.eq_ignore_ascii_case(&self.name) && same_language(voice)
+                })
+            })
+            .or_else(|| voice_for_language(voices, &self.language, None).map(LanguageMatch::voice))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "lowercase")]
+pub enum VoiceGender {
+    Male,
+    Female,
+}
+
+/// Quality tier of a voice, lowest first
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "lowercase")]
+pub enum VoiceQuality {
+    Low,
+    Normal,
+    /// Android's high quality, iOS enhanced voices
+    Enhanced,
+    /// Android's very high quality, iOS 16+ premium voices
+    Premium,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVoicesRequest {
+    /// Optional language filter
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Only voices of this gender; voices of unknown gender are left out
+    #[serde(default)]
+    pub gender: Option<VoiceGender>,
+    /// Only enhanced or premium voices; voices of unknown quality are left out
+    #[serde(default)]
+    pub enhanced_only: bool,
+    /// Voices of this registered backend instead of the OS engine's
+    #[serde(default)]
+    pub backend: Option<String>,
+}
+
+impl GetVoicesRequest {
+    /// Whether `voice` passes every filter of the request
+    pub fn matches(&self, voice: &Voice) -> bool {
+        let language = self.language.as_ref().map_or(true, |language| {
+            voice
+                .language
+                .to_lowercase()
+                .contains(&language.to_lowercase())
+        });
+        let gender = self
+            .gender
+            .map_or(true, |gender| voice.gender == Some(gender));
+        let quality = !self.enhanced_only || voice.quality >= Some(VoiceQuality::Enhanced);
+        language && gender && quality
+    }
+}
+
+/// Language to find the best voice for, see [`voice_for_language`]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBestVoiceRequest {
+    /// Language code (e.g. "en" or "pt-BR")
+    pub language: String,
+    /// Pick among the voices of this registered backend instead of the OS engine's
+    #[serde(default)]
+    pub backend: Option<String>,
+}
+
+impl GetBestVoiceRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        SpeakRequest::validate_language(&self.language)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVoicesResponse {
+    pub voices: Vec<Voice>,
+}
+
+/// Payload of the `tts://voices-changed` event: the voice list differs from the
+/// one seen before, e.g. after a voice was installed in the system settings
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct VoicesChangedEvent {
+    /// Every voice now available
+    pub voices: Vec<Voice>,
+}
+
+impl VoicesChangedEvent {
+    /// The event for a list going from `before` to `after`, `None` when no list
+    /// was seen before or it holds the same voices, in any order
+    pub fn between(before: Option<&[Voice]>, after: &[Voice]) -> Option<Self> {
+        let before = before?;
+        let unchanged =
+            before.len() == after.len() && before.iter().all(|voice| after.contains(voice));
+        (!unchanged).then(|| Self {
+            voices: after.to_vec(),
+        })
+    }
+}
+
+/// Languages the available voices speak
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct GetLanguagesResponse {
+    /// Language codes of the voices, sorted and without duplicates
+    pub languages: Vec<String>,
+    /// Number of voices per language code
+    pub voice_counts: BTreeMap<String, u32>,
+}
+
+impl GetLanguagesResponse {
+    pub fn from_voices(voices: &[Voice]) -> Self {
+        let mut voice_counts = BTreeMap::new();
+        for voice in voices {
+            *voice_counts.entry(voice.language.clone()).or_insert(0) += 1;
+        }
+        Self {
+            languages: voice_counts.keys().cloned().collect(),
+            voice_counts,
+        }
+    }
+}
+
+/// Parameters as read back from the live engine. `None` means the engine can't report it
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct EngineParamsResponse {
+    /// Speech rate converted to the plugin's scale (1.0 = normal)
+    #[serde(default)]
+    pub rate: Option<f32>,
+    /// Speech rate in the engine's own units
+    #[serde(default)]
+    pub native_rate: Option<f32>,
+    /// Pitch (1.0 = normal)
+    #[serde(default)]
+    pub pitch: Option<f32>,
+    /// Volume (0.0 to 1.0)
+    #[serde(default)]
+    pub volume: Option<f32>,
+    /// ID of the voice the engine currently uses
+    #[serde(default)]
+    pub voice_id: Option<String>,
+}
+
+/// The system's own voice and speech language, before the app picks any
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultVoiceResponse {
+    /// Voice the system speaks with by default, `null` when the engine can't report it
+    #[serde(default)]
+    pub voice: Option<Voice>,
+    /// Language the system speaks by default (e.g. "en-US"), `null` when unknown
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct IsSpeakingResponse {
+    /// Whether speech is in progress or queued
+    pub speaking: bool,
+    /// Id of the utterance being spoken, as in its `speech:*` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub current_utterance_id: Option<String>,
+    /// Text of that utterance as handed to the engine, after preprocessing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub current_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsPausedResponse {
+    /// Whether speech was paused with `pause_speaking` and not resumed or stopped
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueLengthResponse {
+    /// Utterances waiting behind the one being spoken
+    pub length: u32,
+}
+
+/// An utterance waiting to be spoken
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedItem {
+    /// Id carried by the `speech:*` events of this utterance
+    pub id: String,
+    /// Start of its text as handed to the engine, ending in "…" when cut
+    pub text_preview: String,
+    /// Voice requested for it, when not the engine default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub voice_id: Option<String>,
+    /// Place in the queue, 0 being the next one spoken
+    pub position: u32,
+}
+
+impl QueuedItem {
+    pub fn new(id: String, text: &str, voice_id: Option<String>, position: usize) -> Self {
+        let mut text_preview: String = text.chars().take(QUEUE_PREVIEW_CHARS).collect();
+        if text_preview.len() < text.len() {
+            text_preview.push('…');
+        }
+        Self {
+            id,
+            text_preview,
+            voice_id,
+            position: position as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct GetQueueResponse {
+    /// Utterances waiting behind the one being spoken, in the order they'll be spoken
+    pub items: Vec<QueuedItem>,
+}
+
+/// An utterance waiting in a native engine queue, as reported by `getQueue`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeQueuedUtterance {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub voice_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NativeQueueResponse {
+    pub utterances: Vec<NativeQueuedUtterance>,
+}
+
+impl From<NativeQueueResponse> for GetQueueResponse {
+    fn from(native: NativeQueueResponse) -> Self {
+        let items = native
+            .utterances
+            .into_iter()
+            .enumerate()
+            .map(|(position, u)| QueuedItem::new(u.id, &u.text, u.voice_id, position))
+            .collect();
+        Self { items }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveFromQueueRequest {
+    /// Id of the pending utterance, as in `get_queue` and its `speech:*` events
+    pub utterance_id: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveFromQueueResponse {
+    /// False when no pending utterance had that id, e.g. it already started
+    pub removed: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearQueueResponse {
+    /// Pending utterances that were dropped
+    pub cleared: u32,
+}
+
+/// Where audio output is currently going
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub enum AudioRoute {
+    /// Built-in loudspeaker
+    Speaker,
+    /// Built-in earpiece (phone held to the ear)
+    Earpiece,
+    /// Wired or USB headphones/headset
+    Headphones,
+    /// Bluetooth headset or speaker
+    Bluetooth,
+    /// Other outputs such as AirPlay, HDMI or car audio
+    Other,
+    /// The platform can't tell (desktop)
+    #[default]
+    Unknown,
+}
+
+impl AudioRoute {
+    /// Whether only the user is likely to hear the output. Bluetooth counts as
+    /// private, though it may also be a car or a loudspeaker
+    pub fn is_private(self) -> bool {
+        matches!(self, Self::Earpiece | Self::Headphones | Self::Bluetooth)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct AudioRouteResponse {
+    /// Current output route
+    pub route: AudioRoute,
+    /// Whether the route is a private output (headphones, Bluetooth or earpiece)
+    #[serde(default)]
+    pub is_private: bool,
+}
+
+impl From<AudioRoute> for AudioRouteResponse {
+    fn from(route: AudioRoute) -> Self {
+        Self {
+            route,
+            is_private: route.is_private(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFocusResponse {
+    /// Whether the app now holds audio focus (always `false` after abandoning)
+    pub granted: bool,
+    /// Why focus wasn't granted, or why the call had no effect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub note: Option<String>,
+}
+
+/// Payload of the `tts://audio:focusLost` event (mobile only)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFocusLostEvent {
+    /// Whether focus is expected back, e.g. after a phone call ends
+    pub transient: bool,
+    /// Whether the other app only asked for ducking (Android)
+    pub can_duck: bool,
+}
+
+/// Payload of the `tts://word-boundary` event (mobile, and desktop backends that
+/// report words): the word about to be spoken, as UTF-16 offsets into the `text` of the request (JavaScript string
+/// indices, so `text.slice(charStart, charEnd)` is the word)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct WordBoundaryEvent {
+    /// The `utteranceId` of the utterance being spoken
+    pub utterance_id: String,
+    /// Start of the word in the request's text
+    pub char_start: u32,
+    /// End of the word in the request's text, exclusive
+    pub char_end: u32,
+    /// Length of the word, `charEnd - charStart`
+    #[serde(default)]
+    pub char_length: u32,
+}
+
+/// Payload of the `tts://viseme` event (desktop backends that report visemes):
+/// the mouth shape of the sound about to be heard, e.g. to animate an avatar
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct VisemeEvent {
+    /// The `utteranceId` of the utterance being spoken
+    pub utterance_id: String,
+    /// Viseme in the backend's alphabet, e.g. Amazon Polly's "p", "t" or "@"
+    pub viseme: String,
+}
+
+/// Payload of the `tts://backend-fallback` event (desktop): the backend speaking
+/// an utterance failed, and the rest of it is spoken with the next one of
+/// `backendFallback`
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct BackendFallbackEvent {
+    /// The `utteranceId` of the utterance being spoken
+    pub utterance_id: String,
+    /// Backend that failed, "system" for the OS engine
+    pub from: String,
+    /// Backend speaking the rest of the utterance
+    pub to: String,
+    /// Why `from` failed
+    pub error: String,
+}
+
+/// Payload of the `tts://sentence-start` event: the sentence about to be spoken,
+/// as UTF-16 offsets into the `text` of the request like [`WordBoundaryEvent`]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SentenceStartEvent {
+    /// The `utteranceId` of the utterance being spoken
+    pub utterance_id: String,
+    /// Position of the sentence in the utterance, from 0
+    pub index: u32,
+    /// Start of the sentence in the request's text
+    pub char_start: u32,
+    /// End of the sentence in the request's text, exclusive
+    pub char_end: u32,
+}
+
+/// Where a word of the text sent to a mobile engine came from in the request's
+/// text, in UTF-16 offsets, used to translate the engine's word boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordOffset {
+    pub spoken_start: u32,
+    pub spoken_end: u32,
+    pub text_start: u32,
+    pub text_end: u32,
+}
+
+/// Where a sentence of the text sent to an engine came from in the request's
+/// text, in UTF-16 offsets like [`WordOffset`]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentenceOffset {
+    /// Position among the sentences of the utterance that have offsets
+    pub index: u32,
+    pub spoken_start: u32,
+    pub spoken_end: u32,
+    pub text_start: u32,
+    pub text_end: u32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// Whether audio can only start after a user interaction (e.g. a click)
+    pub requires_user_gesture: bool,
+}
+
+/// What the current engine can honor, e.g. to disable controls it would ignore
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct TtsFeatures {
+    /// `stop()` interrupts speech
+    pub stop: bool,
+    /// `rate` changes the speaking speed
+    pub rate: bool,
+    /// `pitch` changes the voice pitch
+    pub pitch: bool,
+    /// `volume` changes the loudness
+    pub volume: bool,
+    /// `voiceId` selects a voice
+    pub voice: bool,
+    /// `isSpeaking()` reports the engine state
+    pub is_speaking: bool,
+    /// The engine reports when utterances start and end, so speech events are exact
+    /// rather than detected by polling
+    pub utterance_callbacks: bool,
+    /// `pauseSpeaking()`/`resumeSpeaking()` work
+    pub pause_resume: bool,
+}
+
+/// A backend requests can name in `backend`, the OS engine being `system`
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct BackendInfo {
+    pub name: String,
+    /// What the backend can honor, like `getFeatures` for the OS engine
+    pub features: TtsFeatures,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct GetBackendsResponse {
+    /// Backends by name, the OS engine among them as "system"
+    pub backends: Vec<BackendInfo>,
+}
+
+/// Piper voices installed for the `piper` backend
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PiperVoicesResponse {
+    /// Where voices are kept, `null` when the app data directory can't be resolved
+    pub directory: Option<String>,
+    pub voices: Vec<Voice>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadPiperVoiceRequest {
+    /// Voice from the Piper voice repository, e.g. "en_US-lessac-medium"
+    pub voice_id: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsInitializedResponse {
+    /// Whether the TTS engine is initialized and ready
+    pub initialized: bool,
+    /// Number of available voices (0 if not initialized)
+    pub voice_count: u32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PauseResumeResponse {
+    pub success: bool,
+    /// Reason for failure, or how desktop approximated the pause on success
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewVoiceRequest {
+    /// Voice ID to preview
+    pub voice_id: String,
+    /// Optional custom sample text (uses default if not provided)
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Speech rate, falls back to the active profile
+    #[serde(default)]
+    pub rate: Option<f32>,
+    /// Pitch, falls back to the active profile
+    #[serde(default)]
+    pub pitch: Option<f32>,
+    /// Volume, falls back to the active profile
+    #[serde(default)]
+    pub volume: Option<f32>,
+}
+
+impl PreviewVoiceRequest {
+    pub const DEFAULT_SAMPLE_TEXT: &'static str =
+        "Hello! This is a sample of how this voice sounds.";
+
+    pub fn sample_text(&self) -> Cow<'_, str> {
+        match &self.text {
+            Some(text) => Cow::Borrowed(text.as_str()),
+            None => Cow::Borrowed(Self::DEFAULT_SAMPLE_TEXT),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        // Validate custom text if provided
+        if let Some(ref text) = self.text {
+            if text.is_empty() {
+                return Err(ValidationError::EmptyText);
+            }
+            if text.len() > MAX_TEXT_LENGTH {
+                return Err(ValidationError::TextTooLong {
+                    len: text.len(),
+                    max: MAX_TEXT_LENGTH,
+                });
+            }
+        }
+        SpeakRequest::validate_range("rate", self.rate, MIN_RATE, MAX_RATE)?;
+        SpeakRequest::validate_range("pitch", self.pitch, MIN_PITCH, MAX_PITCH)?;
+        SpeakRequest::validate_range("volume", self.volume, 0.0, 1.0)?;
+
+        Ok(())
+    }
+
+    /// Fill in the prosody the caller omitted from the active profile
+    pub fn apply_profile(&mut self, profile: &VoiceProfile) {
+        self.rate.get_or_insert(profile.rate);
+        self.pitch.get_or_insert(profile.pitch);
+        self.volume.get_or_insert(profile.volume);
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewAllOptions {
+    /// Language whose voices should be previewed (e.g., "en" or "pt-BR")
+    pub language: String,
+    /// Optional custom sample text (uses default if not provided)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Silence between previews in milliseconds (default: 700)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_ms: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewAllRequest {
+    /// Language whose voices should be previewed (matched like the getVoices filter)
+    pub language: String,
+    /// Optional custom sample text (uses default if not provided)
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Silence between previews in milliseconds
+    #[serde(default)]
+    pub gap_ms: Option<u32>,
+}
+
+impl PreviewAllRequest {
+    pub const DEFAULT_GAP_MS: u32 = 700;
+
+    pub fn gap_ms(&self) -> u32 {
+        self.gap_ms.unwrap_or(Self::DEFAULT_GAP_MS)
+    }
+
+    pub fn sample_text(&self) -> &str {
+        self.text
+            .as_deref()
+            .unwrap_or(PreviewVoiceRequest::DEFAULT_SAMPLE_TEXT)
+    }
+
+    /// Text spoken for one voice: its name announced first, then the sample
+    pub fn announcement(&self, voice: &Voice) -> String {
+        format!("{}: {}", voice.name, self.sample_text())
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        SpeakRequest::validate_language(&self.language)?;
+        if let Some(ref text) = self.text {
+            if text.is_empty() {
+                return Err(ValidationError::EmptyText);
+            }
+            if text.len() > MAX_TEXT_LENGTH {
+                return Err(ValidationError::TextTooLong {
+                    len: text.len(),
+                    max: MAX_TEXT_LENGTH,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewAllResponse {
+    pub success: bool,
+    /// Voices queued for preview, in playback order
+    pub voices: Vec<Voice>,
+    /// Set when nothing was queued because the plugin is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+}
+
+impl PreviewAllResponse {
+    pub fn new(voices: Vec<Voice>) -> Self {
+        Self {
+            success: true,
+            voices,
+            warning: None,
+        }
+    }
+
+    /// Reported instead of queueing while the plugin is disabled
+    pub fn disabled() -> Self {
+        Self {
+            warning: Some(DISABLED_WARNING.to_string()),
+            ..Self::new(Vec::new())
+        }
+    }
+}
+
+/// Payload of the `tts://preview:start` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewStartEvent {
+    /// Position of this voice in the preview sequence (0-based)
+    pub index: u32,
+    /// Total number of voices being previewed
+    pub total: u32,
+    /// The voice whose preview is starting
+    pub voice: Voice,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationOptions {
+    /// Phrase spoken at every rate (uses default if not provided)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Rates to sample, in order (default: 0.75, 1.0, 1.25, 1.5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rates: Option<Vec<f32>>,
+    /// Voice to calibrate. Defaults to the active profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice_id: Option<String>,
+    /// Language, used when no voice is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Silence between samples in milliseconds (default: 700)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap_ms: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationRequest {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub rates: Option<Vec<f32>>,
+    #[serde(default)]
+    pub voice_id: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub gap_ms: Option<u32>,
+}
+
+impl CalibrationRequest {
+    pub const DEFAULT_RATES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+    pub const DEFAULT_TEXT: &'static str = "This is how I sound at this speed.";
+    pub const MAX_SAMPLES: usize = 10;
+
+    /// Rates that will be sampled, clamped to the supported range
+    pub fn rates(&self) -> Vec<f32> {
+        self.rates
+            .as_deref()
+            .unwrap_or(&Self::DEFAULT_RATES)
+            .iter()
+            .map(|rate| rate.clamp(MIN_RATE, MAX_RATE))
+            .collect()
+    }
+
+    /// One sample: the rate announced first, then the phrase, both spoken at `rate`.
+    /// The first sample interrupts current speech, the others queue behind it
+    pub fn sample(&self, index: usize, rate: f32, last: bool) -> SpeakRequest {
+        let text = self.text.as_deref().unwrap_or(Self::DEFAULT_TEXT);
+        SpeakRequest {
+            text: format!("Speed {}. {}", rate, text),
+            language: self.language.clone(),
+            voice_id: self.voice_id.clone(),
+            rate: Some(rate),
+            queue_mode: Some(if index == 0 {
+                QueueMode::Flush
+            } else {
+                QueueMode::Add
+            }),
+            pause_after_ms: (!last).then(|| {
+                self.gap_ms
+                    .unwrap_or(PreviewAllRequest::DEFAULT_GAP_MS)
+                    .min(MAX_PAUSE_MS)
+            }),
+            use_affixes: false,
+            ..Default::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let count = self.rates().len();
+        if count == 0 || count > Self::MAX_SAMPLES {
+            return Err(ValidationError::CalibrationRates {
+                count,
+                max: Self::MAX_SAMPLES,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationResponse {
+    pub success: bool,
+    /// Rates queued for playback, in order
+    pub rates: Vec<f32>,
+    /// Set when nothing was queued because the plugin is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+}
+
+impl CalibrationResponse {
+    pub fn new(rates: Vec<f32>) -> Self {
+        Self {
+            success: true,
+            rates,
+            warning: None,
+        }
+    }
+
+    /// Reported instead of queueing while the plugin is disabled
+    pub fn disabled() -> Self {
+        Self {
+            warning: Some(DISABLED_WARNING.to_string()),
+            ..Self::new(Vec::new())
+        }
+    }
+}
+
+/// Payload of the `tts://calibration:start` event
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationStartEvent {
+    /// Position of this sample in the sequence (0-based)
+    pub index: u32,
+    /// Total number of samples
+    pub total: u32,
+    /// Rate of the sample that is starting
+    pub rate: f32,
+}
+
+/// A heading found by `speak_outline`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineEntry {
+    /// Heading text, without markdown `#`
+    pub title: String,
+    /// Markdown heading depth (1 for `#`), 1 for headings detected by their casing
+    pub level: u8,
+    /// Line of the heading in the text (0-based)
+    pub line: u32,
+}
+
+/// Speak the headings of a document as a numbered list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakOutlineRequest {
+    /// Document text plus the voice and prosody to read the outline with
+    #[serde(flatten)]
+    pub speak: SpeakRequest,
+    /// Silence between headings in milliseconds (default: 500)
+    #[serde(default)]
+    pub gap_ms: Option<u32>,
+}
+
+impl SpeakOutlineRequest {
+    pub const DEFAULT_GAP_MS: u32 = 500;
+
+    /// One utterance per heading, e.g. "Section 2: Install"
+    pub fn items(&self, outline: &[OutlineEntry]) -> Vec<SpeakRequest> {
+        let gap = self
+            .gap_ms
+            .unwrap_or(Self::DEFAULT_GAP_MS)
+            .min(MAX_PAUSE_MS);
+        outline
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| SpeakRequest {
+                text: format!("Section {}: {}", index + 1, entry.title),
+                pause_after_ms: Some(gap),
+                structural_pauses: false,
+                ..self.speak.clone()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakOutlineResponse {
+    /// Headings in document order; nothing is spoken when empty
+    pub outline: Vec<OutlineEntry>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+}
+
+/// Read a document from one of its `speak_outline` sections to the end
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakSectionRequest {
+    /// Document text (the same as given to `speak_outline`) plus voice and prosody
+    #[serde(flatten)]
+    pub speak: SpeakRequest,
+    /// Position of the section in the outline (0-based)
+    pub index: u32,
+}
+
+impl SpeakSectionRequest {
+    /// The request reading on from the section, with pauses around later headings
+    pub fn section_request(&self) -> Result<SpeakRequest, ValidationError> {
+        let outline = crate::preprocess::outline(&self.speak.text);
+        let entry = outline
+            .get(self.index as usize)
+            .ok_or(ValidationError::SectionIndex {
+                index: self.index,
+                count: outline.len(),
+            })?;
+        Ok(SpeakRequest {
+            text: crate::preprocess::from_line(&self.speak.text, entry.line),
+            structural_pauses: true,
+            ..self.speak.clone()
+        })
+    }
+}
+
+/// Start reading a document part-way through, e.g. where the user stopped last time
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakFromOffsetRequest {
+    /// The full document plus voice and prosody
+    #[serde(flatten)]
+    pub speak: SpeakRequest,
+    /// Where to start, in user-perceived characters (grapheme clusters) of `text`
+    pub offset: u32,
+}
+
+impl SpeakFromOffsetRequest {
+    /// The request for the rest of the text and the offset reading really starts
+    /// at: the start of the word containing `offset`, so no word is cut in half
+    pub fn remainder(&self) -> Result<(SpeakRequest, u32), ValidationError> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let text = &self.speak.text;
+        let graphemes: Vec<(usize, &str)> = text.grapheme_indices(true).collect();
+        let offset = self.offset as usize;
+        if offset >= graphemes.len() {
+            return Err(ValidationError::OffsetOutOfBounds {
+                offset: self.offset,
+                len: graphemes.len(),
+            });
+        }
+
+        let start = graphemes[..=offset]
+            .iter()
+            .rposition(|(_, grapheme)| grapheme.chars().all(char::is_whitespace))
+            .map_or(0, |space| (space + 1).min(offset));
+        Ok((
+            SpeakRequest {
+                text: text[graphemes[start].0..].to_string(),
+                ..self.speak.clone()
+            },
+            start as u32,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakFromOffsetResponse {
+    pub success: bool,
+    /// Offset reading started at, in grapheme clusters of the full text. Add it to
+    /// positions within the spoken remainder to get positions in the full text
+    pub offset: u32,
+    /// Set when the request was adjusted or dropped (e.g. by the interrupt cooldown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+}
+
+impl SpeakFromOffsetResponse {
+    pub fn new(offset: u32, spoken: SpeakResponse) -> Self {
+        Self {
+            success: spoken.success,
+            offset,
+            warning: spoken.warning,
+            remaining_chars: spoken.remaining_chars,
+        }
+    }
+}
+
+/// How speech waited on by `speak_and_wait` ended
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "lowercase")]
+pub enum SpeechOutcome {
+    /// Spoken to the end
+    Completed,
+    /// Not spoken because the plugin is disabled
+    Skipped,
+    /// Cut short by `stop` or by later speech that flushed the queue, or dropped by
+    /// the interrupt cooldown before it started
+    Stopped,
+    /// The engine failed to speak it
+    Error,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakAndWaitResponse {
+    pub outcome: SpeechOutcome,
+    /// What the engine reported, when `outcome` is `error`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+    /// Optional warning message (e.g., voice not found, using fallback)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub warning: Option<String>,
+    /// Characters left in the session budget, when `sessionCharLimit` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub remaining_chars: Option<u32>,
+    /// Id carried by the `speech:*` events of this utterance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub utterance_id: Option<String>,
+}
+
+impl SpeakAndWaitResponse {
+    pub fn new(outcome: SpeechOutcome, spoken: SpeakResponse) -> Self {
+        Self {
+            outcome,
+            error: None,
+            warning: spoken.warning,
+            remaining_chars: spoken.remaining_chars,
+            utterance_id: spoken.utterance_id,
+        }
+    }
+
+    pub fn failed(error: String, spoken: SpeakResponse) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(SpeechOutcome::Error, spoken)
+        }
+    }
+}
+
+/// Sent to the native `waitForUtterance` by `speak_and_wait`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitForUtteranceRequest {
+    pub utterance_id: String,
+}
+
+/// How the native engine reported the end of an utterance
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtteranceEnd {
+    pub outcome: SpeechOutcome,
+    /// What the engine reported, when `outcome` is `error`
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkVoiceRequest {
+    /// Voice ID to benchmark
+    pub voice_id: String,
+}
+
+impl BenchmarkVoiceRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.voice_id.len() > MAX_VOICE_ID_LENGTH {
+            return Err(ValidationError::VoiceIdTooLong {
+                len: self.voice_id.len(),
+                max: MAX_VOICE_ID_LENGTH,
+            });
+        }
+        Ok(())
+    }
+
+    /// The benchmark request: fixed prosody, no profile, affixes or preprocessing,
+    /// so runs are comparable across devices
+    pub fn speak_request(&self, phrase: &str) -> SpeakRequest {
+        SpeakRequest {
+            text: phrase.to_string(),
+            voice_id: Some(self.voice_id.clone()),
+            rate: Some(1.0),
+            pitch: Some(1.0),
+            volume: Some(1.0),
+            queue_mode: Some(QueueMode::Flush),
+            raw: Some(true),
+            use_affixes: false,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkVoiceResponse {
+    pub success: bool,
+    /// The benchmarked voice. Left out, like `text` and `durationMs`, when nothing
+    /// was spoken because the plugin is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub voice: Option<Voice>,
+    /// The standard phrase that was spoken
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub text: Option<String>,
+    /// Measured time from submitting the phrase until speech ended, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub duration_ms: Option<u32>,
+    /// Anything that makes the measurement less comparable (e.g. fallback phrase),
+    /// or `"tts disabled"` when there was none
+    pub warnings: Vec<String>,
+}
+
+impl BenchmarkVoiceResponse {
+    pub fn new(voice: Voice, text: &str, duration: Duration, warnings: Vec<String>) -> Self {
+        Self {
+            success: true,
+            voice: Some(voice),
+            text: Some(text.to_string()),
+            duration_ms: Some(duration.as_millis().min(u128::from(u32::MAX)) as u32),
+            warnings,
+        }
+    }
+
+    /// Reported instead of speaking while the plugin is disabled
+    pub fn disabled() -> Self {
+        Self {
+            success: true,
+            voice: None,
+            text: None,
+            duration_ms: None,
+            warnings: vec![DISABLED_WARNING.to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPronunciationRequest {
+    /// The text to transcribe into phonemes
+    pub text: String,
+    /// Voice ID whose pronunciation rules should be used (from getVoices)
+    #[serde(default)]
+    pub voice_id: Option<String>,
+}
+
+impl GetPronunciationRequest {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.text.is_empty() {
+            return Err(ValidationError::EmptyText);
+        }
+        if self.text.len() > MAX_TEXT_LENGTH {
+            return Err(ValidationError::TextTooLong {
+                len: self.text.len(),
+                max: MAX_TEXT_LENGTH,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PronunciationResponse {
+    /// Phonetic representation of the text as the engine will pronounce it
+    pub phonemes: String,
+    /// Phoneme alphabet used by the engine (e.g., "apple", "ipa")
+    pub alphabet: String,
+}
+
+/// Respelling applied to every utterance's text before it is spoken
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct LexiconEntry {
+    /// Word or phrase as it appears in the text, matched whole-word and case-insensitively
+    pub word: String,
+    /// Text spoken in its place, e.g. "sequel" for "SQL"
+    pub spoken_as: String,
+}
+
+impl LexiconEntry {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.word.trim().is_empty() || self.spoken_as.trim().is_empty() {
+            return Err(ValidationError::EmptyLexiconEntry);
+        }
+        for text in [&self.word, &self.spoken_as] {
+            if text.len() > MAX_LEXICON_ENTRY_LENGTH {
+                return Err(ValidationError::LexiconEntryTooLong {
+                    len: text.len(),
+                    max: MAX_LEXICON_ENTRY_LENGTH,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovePronunciationRequest {
+    /// Word of the lexicon entry to remove, matched case-insensitively
+    pub word: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovePronunciationResponse {
+    /// Whether the lexicon had an entry for the word
+    pub removed: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[ts(export, export_to = "../guest-js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ListPronunciationsResponse {
+    /// Lexicon entries in the order they were added
+    pub entries: Vec<LexiconEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speak_request_defaults() {
+        let json = r#"{"text": "Hello world"}"#;
+        let request: SpeakRequest = serde_json::from_str(json).unwrap();
+
+        assert_eq!(request.text, "Hello world");
+        assert!(request.language.is_none());
+        assert!(request.voice_id.is_none());
+        // Left unset so the voice profile fills them in
+        assert!(request.rate.is_none());
+        assert!(request.pitch.is_none());
+        assert!(request.volume.is_none());
+    }
+
+    #[test]
+    fn test_speak_request_full() {
+        let json = r#"{
+            "text": "Olá",
+            "language": "pt-BR",
+            "voiceId": "com.apple.voice.enhanced.pt-BR",
+            "rate": 0.8,
+            "pitch": 1.2,
+            "volume": 0.9
+        }"#;
+
+        let request: SpeakRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.text, "Olá");
+        assert_eq!(request.language, Some("pt-BR".to_string()));
+        assert_eq!(
+            request.voice_id,
+            Some("com.apple.voice.enhanced.pt-BR".to_string())
+        );
+        assert_eq!(request.rate, Some(0.8));
+        assert_eq!(request.pitch, Some(1.2));
+        assert_eq!(request.volume, Some(0.9));
+    }
+
+    #[test]
+    fn test_voice_serialization() {
+        let voice = Voice {
+            id: "test-voice".to_string(),
+            name: "Test Voice".to_string(),
+            language: "en-US".to_string(),
+            gender: Some(VoiceGender::Female),
+            quality: None,
+            requires_network: Some(false),
+            installed: Some(true),
+            engine: Some("AVFoundation".to_string()),
+            platform: "macos".to_string(),
+        };
+
+        let json = serde_json::to_string(&voice).unwrap();
+        assert!(json.contains("\"id\":\"test-voice\""));
+        assert!(json.contains("\"name\":\"Test Voice\""));
+        assert!(json.contains("\"language\":\"en-US\""));
+        assert!(json.contains("\"gender\":\"female\""));
+        assert!(json.contains("\"quality\":null"));
+        assert!(json.contains("\"requiresNetwork\":false"));
+        assert!(json.contains("\"installed\":true"));
+        assert!(json.contains("\"engine\":\"AVFoundation\""));
+        assert!(json.contains("\"platform\":\"macos\""));
+
+        // Native plugins leave out what their engine doesn't report
+        let native: Voice =
+            serde_json::from_str(r#"{"id": "a", "name": "A", "language": "en"}"#).unwrap();
+        assert_eq!(native.gender, None);
+        assert_eq!(native.quality, None);
+        assert_eq!(native.requires_network, None);
+        assert_eq!(native.installed, None);
+        assert_eq!(native.engine, None);
+        assert_eq!(native.platform, "");
+    }
+
+    #[test]
+    fn test_default_voice_response_deserialization() {
+        // iOS has no voice for some device languages
+        let response: DefaultVoiceResponse =
+            serde_json::from_str(r#"{"language": "gsw-CH"}"#).unwrap();
+        assert!(response.voice.is_none());
+        assert_eq!(response.language.as_deref(), Some("gsw-CH"));
+
+        let response: DefaultVoiceResponse = serde_json::from_str(
+            r#"{"voice": {"id": "en-us-x-iol-local", "name": "English", "language": "en-US",
+                "quality": "enhanced", "requiresNetwork": false, "installed": true,
+                "engine": "com.google.android.tts"}, "language": "en-US"}"#,
+        )
+        .unwrap();
+        let voice = response.voice.unwrap();
+        assert_eq!(voice.quality, Some(VoiceQuality::Enhanced));
+        assert_eq!(voice.requires_network, Some(false));
+        assert_eq!(voice.engine.as_deref(), Some("com.google.android.tts"));
+    }
+
+    #[test]
+    fn test_config_default_queue_mode() {
+        let config: TtsConfig = serde_json::from_str(r#"{}"#).unwrap();
+        assert_eq!(config.default_queue_mode, QueueMode::Flush);
+
+        let config: TtsConfig = serde_json::from_str(r#"{"defaultQueueMode": "add"}"#).unwrap();
+        assert_eq!(config.default_queue_mode, QueueMode::Add);
+    }
+
+    #[test]
+    fn test_queue_mode_replace_pending_is_camel_case() {
+        let mode: QueueMode = serde_json::from_str(r#""replacePending""#).unwrap();
+        assert_eq!(mode, QueueMode::ReplacePending);
+        assert_eq!(
+            serde_json::to_string(&QueueMode::ReplacePending).unwrap(),
+            r#""replacePending""#
+        );
+        // The other modes keep their lowercase names
+        assert_eq!(serde_json::to_string(&QueueMode::Add).unwrap(), r#""add""#);
+        assert_eq!(
+            serde_json::from_str::<QueueMode>(r#""urgent""#).unwrap(),
+            QueueMode::Urgent
+        );
+    }
+
+    #[test]
+    fn test_config_audio_session() {
+        let config: TtsConfig = serde_json::from_str(r#"{}"#).unwrap();
+        assert_eq!(config.audio_session, AudioSessionConfig::default());
+        assert_eq!(
+            config.audio_session.options,
+            vec![AudioSessionOption::DuckOthers]
+        );
+
+        let json = r#"{
+            "audioSession": {
+                "category": "ambient",
+                "options": ["mixWithOthers", "allowBluetoothA2DP"]
+            }
+        }"#;
+        let config: TtsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.audio_session.category, AudioSessionCategory::Ambient);
+        assert_eq!(config.audio_session.mode, AudioSessionMode::SpokenAudio);
+        assert_eq!(
+            config.audio_session.options,
+            vec![
+                AudioSessionOption::MixWithOthers,
+                AudioSessionOption::AllowBluetoothA2dp
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queue_mode_uses_configured_default() {
+        let config = TtsConfig {
+            default_queue_mode: QueueMode::Add,
+            ..Default::default()
+        };
+        let mut request: SpeakRequest = serde_json::from_str(r#"{"text": "Hello"}"#).unwrap();
+        assert!(request.queue_mode.is_none());
+
+        request.apply_defaults(&config);
+        assert_eq!(request.validate().unwrap().queue_mode, QueueMode::Add);
+    }
+
+    #[test]
+    fn test_explicit_queue_mode_overrides_configured_default() {
+        let config = TtsConfig {
+            default_queue_mode: QueueMode::Add,
+            ..Default::default()
+        };
+        let mut request: SpeakRequest =
+            serde_json::from_str(r#"{"text": "Hello", "queueMode": "flush"}"#).unwrap();
+
+        request.apply_defaults(&config);
+        assert_eq!(request.validate().unwrap().queue_mode, QueueMode::Flush);
+    }
+
+    #[test]
+    fn test_configured_voice_defaults_fill_missing_fields() {
+        let config: TtsConfig = serde_json::from_str(
+            r#"{"defaultRate": 1.5, "defaultVolume": 0.5, "defaultLanguage": "pt-BR"}"#,
+        )
+        .unwrap();
+        let profile = config.default_profile().unwrap();
+
+        let mut request: SpeakRequest =
+            serde_json::from_str(r#"{"text": "Olá", "volume": 0.8}"#).unwrap();
+        request.apply_profile(&profile);
+        let validated = request.validate().unwrap();
+        assert_eq!(validated.rate, 1.5);
+        assert_eq!(validated.pitch, 1.0);
+        assert_eq!(validated.volume, 0.8);
+        assert_eq!(validated.language.as_deref(), Some("pt-BR"));
+
+        let config: TtsConfig = serde_json::from_str(r#"{"defaultPitch": 3.0}"#).unwrap();
+        assert!(matches!(
+            config.default_profile().unwrap_err(),
+            ValidationError::OutOfRange {
+                field: "defaultPitch",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_get_voices_request_optional_language() {
+        let json1 = r#"{}"#;
+        let request1: GetVoicesRequest = serde_json::from_str(json1).unwrap();
+        assert!(request1.language.is_none());
+
+        let json2 = r#"{"language": "en"}"#;
+        let request2: GetVoicesRequest = serde_json::from_str(json2).unwrap();
+        assert_eq!(request2.language, Some("en".to_string()));
+    }
+
+    #[test]
+    fn test_voice_speaks_language() {
+        let voice = |language: &str| Voice {
+            id: language.to_string(),
+            name: language.to_string(),
+            language: language.to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        };
+
+        assert!(voice("pt-BR").speaks("pt"));
+        assert!(voice("pt-BR").speaks("PT-br"));
+        assert!(voice("pt_BR").speaks("pt-BR"));
+        assert!(voice("ja").speaks("ja"));
+        assert!(!voice("pt-BR").speaks("pt-PT"));
+        assert!(!voice("en-US").speaks("e"));
+        assert!(!voice("en").speaks("en-US"));
+    }
+
+    #[test]
+    fn test_voice_for_language() {
+        let voice = |language: &str| Voice {
+            id: language.to_string(),
+            name: language.to_string(),
+            language: language.to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        };
+        let voices = [voice("en-US"), voice("en-GB"), voice("pt-PT"), voice("de")];
+        let pick = |language: &str| voice_for_language(&voices, language, None);
+
+        assert_eq!(pick("en-gb"), Some(LanguageMatch::Speaks(&voices[1])));
+        assert_eq!(pick("en"), Some(LanguageMatch::Speaks(&voices[0])));
+        assert_eq!(pick("pt_BR"), Some(LanguageMatch::OtherVariant(&voices[2])));
+        assert_eq!(pick("de-AT"), Some(LanguageMatch::OtherVariant(&voices[3])));
+        assert_eq!(pick("ja-JP"), None);
+    }
+
+    #[test]
+    fn test_voice_for_language_ranking() {
+        let voice = |id: &str, language: &str, quality, installed| Voice {
+            id: id.to_string(),
+            name: id.to_string(),
+            language: language.to_string(),
+            gender: None,
+            quality,
+            requires_network: None,
+            installed,
+            engine: None,
+            platform: String::new(),
+        };
+        let voices = [
+            voice("us-normal", "en-US", Some(VoiceQuality::Normal), None),
+            voice("us-default", "en-US", None, None),
+            voice("gb-premium", "en-GB", Some(VoiceQuality::Premium), None),
+            voice("us-enhanced", "en-US", Some(VoiceQuality::Enhanced), None),
+            voice(
+                "us-premium",
+                "en-US",
+                Some(VoiceQuality::Premium),
+                Some(false),
+            ),
+        ];
+        let pick = |language: &str, default_voice_id| {
+            voice_for_language(&voices, language, default_voice_id).map(|found| &found.voice().id)
+        };
+
+        // The exact locale beats a better voice of another variant, and voices
+        // that aren't downloaded yet can't win
+        assert_eq!(pick("en-US", None).unwrap(), "us-enhanced");
+        assert_eq!(pick("en", None).unwrap(), "gb-premium");
+        assert_eq!(pick("en-AU", None).unwrap(), "gb-premium");
+
+        // Without quality to go by, the system default wins over list order
+        let voices = [
+            voice("samantha", "en-US", None, None),
+            voice("alex", "en-US", None, None),
+        ];
+        let pick = |default_voice_id| {
+            voice_for_language(&voices, "en-US", default_voice_id).map(|found| &found.voice().id)
+        };
+        assert_eq!(pick(None).unwrap(), "samantha");
+        assert_eq!(pick(Some("alex")).unwrap(), "alex");
+    }
+
+    #[test]
+    fn test_match_voice_name() {
+        let voice = |id: &str, name: &str| Voice {
+            id: id.to_string(),
+            name: name.to_string(),
+            language: "en-US".to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        };
+        let voices = [
+            voice(
+                "HKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Speech\\Voices\\Tokens\\TTS_MS_EN-US_ZIRA_11.0",
+                "Microsoft Zira Desktop - English (United States)",
+            ),
+            voice("com.apple.voice.compact.en-US.Samantha", "Samantha"),
+            voice("com.apple.voice.enhanced.en-US.Samantha", "Samantha (Enhanced)"),
+        ];
+        let matched = |voice_id: &str| {
+            let mut request = SpeakRequest {
+                text: "Hello".to_string(),
+                voice_id: Some(voice_id.to_string()),
+                ..Default::default()
+            };
+            request.match_voice_name(&voices);
+            (request.voice_id.unwrap(), request.voice_warning)
+        };
+
+        // Ids and whole names match without a warning
+        assert_eq!(matched(&voices[1].id), (voices[1].id.clone(), None));
+        assert_eq!(matched("samantha"), (voices[1].id.clone(), None));
+
+        let (id, warning) = matched("Microsoft Zira");
+        assert_eq!(id, voices[0].id);
+        assert!(warning.unwrap().contains("Microsoft Zira Desktop"));
+        assert_eq!(matched("Zirra").0, voices[0].id);
+        assert_eq!(matched("samantha enhanced").0, voices[2].id);
+
+        // Nothing close keeps the id, for the engine to report
+        assert_eq!(matched("Daniel"), ("Daniel".to_string(), None));
+    }
+
+    #[test]
+    fn test_voices_changed_event() {
+        let voice = |id: &str| Voice {
+            id: id.to_string(),
+            name: id.to_string(),
+            language: "en-US".to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        };
+        let before = [voice("samantha"), voice("daniel")];
+
+        assert!(VoicesChangedEvent::between(None, &before).is_none());
+        let reordered = [voice("daniel"), voice("samantha")];
+        assert!(VoicesChangedEvent::between(Some(&before), &reordered).is_none());
+
+        let installed = [voice("samantha"), voice("daniel"), voice("karen")];
+        let event = VoicesChangedEvent::between(Some(&before), &installed).unwrap();
+        assert_eq!(event.voices, installed);
+        assert!(VoicesChangedEvent::between(Some(&before), &before[..1]).is_some());
+    }
+
+    #[test]
+    fn test_require_voice() {
+        let voices = [Voice {
+            id: "com.apple.voice.compact.en-US.Samantha".to_string(),
+            name: "Samantha".to_string(),
+            language: "en-US".to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        }];
+        let request = |value: serde_json::Value| -> SpeakRequest {
+            let mut value = value;
+            value["text"] = "Hello".into();
+            serde_json::from_value(value).unwrap()
+        };
+
+        let missing = request(serde_json::json!({ "voiceId": "Daniel", "strictVoice": true }));
+        assert!(matches!(
+            missing.require_voice(&voices),
+            Err(crate::Error::VoiceNotFound(_))
+        ));
+        let german = request(serde_json::json!({ "language": "de-DE", "strictVoice": true }));
+        assert!(german.require_voice(&voices).is_err());
+        // A speaker of another variant isn't enough either
+        let british = request(serde_json::json!({ "language": "en-GB", "strictVoice": true }));
+        assert!(british.require_voice(&voices).is_err());
+
+        let english = request(serde_json::json!({ "language": "en", "strictVoice": true }));
+        assert!(english.require_voice(&voices).is_ok());
+        let lenient = request(serde_json::json!({ "voiceId": "Daniel" }));
+        assert!(lenient.require_voice(&voices).is_ok());
+    }
+
+    #[test]
+    fn test_within_one_edit() {
+        assert!(within_one_edit("zira", "zira"));
+        assert!(within_one_edit("zira", "zara"));
+        assert!(within_one_edit("zra", "zira"));
+        assert!(within_one_edit("zira", "zir"));
+        assert!(!within_one_edit("zira", "azir"));
+        assert!(!within_one_edit("zira", "ziraaa"));
+    }
+
+    #[test]
+    fn test_resolve_voice() {
+        let voice = |id: &str, name: &str, language: &str| Voice {
+            id: id.to_string(),
+            name: name.to_string(),
+            language: language.to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: "ios".to_string(),
+        };
+        let voices = [
+            voice("com.apple.voice.compact.en-GB.Daniel", "Daniel", "en-GB"),
+            voice(
+                "com.apple.voice.compact.en-US.Samantha",
+                "Samantha",
+                "en-US",
+            ),
+            voice("com.apple.eloquence.en-US.Reed", "Reed", "en-US"),
+        ];
+        let saved = |id: &str, name: &str, language: &str| ResolveVoiceRequest {
+            id: id.to_string(),
+            name: name.to_string(),
+            language: language.to_string(),
+            platform: "macos".to_string(),
+        };
+        let resolved = |request: ResolveVoiceRequest| request.resolve(&voices).map(|v| &v.name);
+
+        assert_eq!(
+            resolved(saved("com.apple.eloquence.en-US.Reed", "Other", "fr")),
+            Some(&"Reed".to_string())
+        );
+        // An id from another platform falls back to the name in the same language
+        assert_eq!(
+            resolved(saved("HKEY_LOCAL_MACHINE\\Samantha", "samantha", "en_US")),
+            Some(&"Samantha".to_string())
+        );
+        // The same name in another language isn't the same voice
+        assert_eq!(
+            resolved(saved("x", "Daniel", "en-US")),
+            Some(&"Samantha".to_string())
+        );
+        assert_eq!(
+            resolved(saved("x", "Karen", "en-AU")),
+            Some(&"Daniel".to_string())
+        );
+        assert_eq!(resolved(saved("x", "Amélie", "fr-CA")), None);
+    }
+
+    #[test]
+    fn test_languages_from_voices() {
+        let voice = |id: &str, language: &str| Voice {
+            id: id.to_string(),
+            name: id.to_string(),
+            language: language.to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        };
+        let voices = [
+            voice("a", "pt-BR"),
+            voice("b", "en-US"),
+            voice("c", "pt-BR"),
+            voice("d", "de-DE"),
+        ];
+
+        let response = GetLanguagesResponse::from_voices(&voices);
+        assert_eq!(response.languages, ["de-DE", "en-US", "pt-BR"]);
+        assert_eq!(response.voice_counts["pt-BR"], 2);
+        assert_eq!(response.voice_counts["en-US"], 1);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["voiceCounts"]["de-DE"], 1);
+    }
+
+    #[test]
+    fn test_queued_item_preview() {
+        let short = QueuedItem::new("a".to_string(), "Olá mundo", None, 0);
+        assert_eq!(short.text_preview, "Olá mundo");
+        let long_text = "é".repeat(QUEUE_PREVIEW_CHARS + 1);
+        let long = QueuedItem::new("b".to_string(), &long_text, None, 1);
+        assert_eq!(long.text_preview.chars().count(), QUEUE_PREVIEW_CHARS + 1);
+        assert!(long.text_preview.ends_with('…'));
+
+        let native: NativeQueueResponse = serde_json::from_str(
+            r#"{"utterances": [{"id": "x", "text": "First"}, {"id": "y", "text": "Second", "voiceId": "v"}]}"#,
+        )
+        .unwrap();
+        let response = GetQueueResponse::from(native);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["items"][1]["position"], 1);
+        assert_eq!(json["items"][1]["voiceId"], "v");
+        assert_eq!(json["items"][0]["textPreview"], "First");
+        assert!(json["items"][0].get("voiceId").is_none());
+    }
+
+    #[test]
+    fn test_get_voices_request_filters() {
+        let voice = |language: &str, gender, quality| Voice {
+            id: language.to_string(),
+            name: language.to_string(),
+            language: language.to_string(),
+            gender,
+            quality,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
+        };
+        let voices = [
+            voice(
+                "en-US",
+                Some(VoiceGender::Female),
+                Some(VoiceQuality::Premium),
+            ),
+            voice("en-GB", Some(VoiceGender::Male), Some(VoiceQuality::Normal)),
+            voice("en-AU", None, None),
+            voice(
+                "pt-BR",
+                Some(VoiceGender::Female),
+                Some(VoiceQuality::Enhanced),
+            ),
+        ];
+        let matching = |json: &str| {
+            let request: GetVoicesRequest = serde_json::from_str(json).unwrap();
+            voices
+                .iter()
+                .filter(|voice| request.matches(voice))
+                .map(|voice| voice.language.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching("{}"), ["en-US", "en-GB", "en-AU", "pt-BR"]);
+        assert_eq!(
+            matching(r#"{"language": "EN"}"#),
+            ["en-US", "en-GB", "en-AU"]
+        );
+        assert_eq!(matching(r#"{"gender": "female"}"#), ["en-US", "pt-BR"]);
+        assert_eq!(matching(r#"{"enhancedOnly": true}"#), ["en-US", "pt-BR"]);
+        assert_eq!(
+            matching(r#"{"language": "en", "gender": "male", "enhancedOnly": true}"#),
+            Vec::<&str>::new()
+        );
+        assert!(serde_json::from_str::<GetVoicesRequest>(r#"{"gender": "other"}"#).is_err());
+    }
+
+    #[test]
+    fn test_validation_empty_text() {
+        let request = SpeakRequest {
+            text: "".to_string(),
+            queue_mode: Some(QueueMode::Flush),
+            ..Default::default()
+        };
+
+        let result = request.validate();
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), ValidationError::EmptyText));
+    }
+
+    #[test]
+    fn test_validation_text_too_long() {
+        let long_text = "x".repeat(MAX_TEXT_LENGTH + 1);
+        let request = SpeakRequest {
+            text: long_text,
+            queue_mode: Some(QueueMode::Flush),
+            ..Default::default()
+        };
+
+        let result = request.validate();
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            ValidationError::TextTooLong { .. }
+        ));
+    }
+
+    #[test]
+    fn test_validation_valid_voice_id() {
+        let request = SpeakRequest {
+            text: "Hello".to_string(),
+            voice_id: Some("com.apple.voice.enhanced.en-US".to_string()),
+            queue_mode: Some(QueueMode::Flush),
+            ..Default::default()
+        };
+
+        let result = request.validate();
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().voice_id,
+            Some("com.apple.voice.enhanced.en-US".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validation_voice_id_too_long() {
+        let long_voice_id = "x".repeat(MAX_VOICE_ID_LENGTH + 1);
+        let request = SpeakRequest {
+            text: "Hello".to_string(),
+            voice_id: Some(long_voice_id),
+            queue_mode: Some(QueueMode::Flush),
+            ..Default::default()
+        };
+
+        let result = request.validate();
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            ValidationError::VoiceIdTooLong { .. }
+        ));
+    }
+
+    #[test]
+    fn test_validation_event_scope() {
+        let scoped = |scope: &str| SpeakRequest {
+            text: "Hello".to_string(),
+            event_scope: Some(scope.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            scoped("reader_panel-2").validate().unwrap().event_scope,
+            Some("reader_panel-2".to_string())
+        );
+        for invalid in [
+            "",
+            "a://b",
+            "with space",
+            &"x".repeat(MAX_EVENT_SCOPE_LENGTH + 1),
+        ] {
+            assert!(matches!(
+                scoped(invalid).validate().unwrap_err(),
+                ValidationError::InvalidEventScope(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_speak_list_validation() {
+        let list = |texts: &[&str]| SpeakListBeginRequest {
+            items: texts
+                .iter()
+                .map(|text| SpeakRequest {
+                    text: text.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+
+        assert!(list(&["one", "two"]).validate().is_ok());
+        assert!(matches!(
+            list(&[]).validate().unwrap_err(),
+            ValidationError::ListItems { count: 0, .. }
+        ));
+        // A bad item fails the whole list before anything is spoken
+        assert!(matches!(
+            list(&["one", ""]).validate().unwrap_err(),
+            ValidationError::EmptyText
+        ));
+    }
+
+    #[test]
+    fn test_disabled_responses_succeed_with_warning() {
+        let json = serde_json::to_value(SpeakResponse::disabled()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"success": true, "warning": "tts disabled"})
+        );
+
+        let sequence = SpeakSequenceResponse::disabled();
+        assert!(sequence.success);
+        assert_eq!(sequence.warning.as_deref(), Some(DISABLED_WARNING));
+        assert!(sequence.sequence_id.is_empty());
+
+        let request: SetEnabledRequest = serde_json::from_str(r#"{"enabled": false}"#).unwrap();
+        assert!(!request.enabled);
+    }
+
+    #[test]
+    fn test_speak_words_items_keep_options() {
+        let json = r#"{"text": "Hello , world", "rate": 0.5, "structuralPauses": true}"#;
+        let request: SpeakWordsBeginRequest = serde_json::from_str(json).unwrap();
+        let items = request.items();
+
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(texts, ["Hello,", "world"]);
+        assert_eq!(items[1].rate, Some(0.5));
+        assert!(!items[1].structural_pauses);
+        assert!(!items[1].use_affixes);
+    }
+
+    #[test]
+    fn test_speak_to_file_request() {
+        let json = r#"{"text": "Hello", "rate": 1.5, "outputPath": "/tmp/hello.wav"}"#;
+        let request: SpeakToFileRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.speak.rate, Some(1.5));
+        assert_eq!(request.validate().unwrap().rate, 1.5);
+
+        let blank = SpeakToFileRequest {
+            output_path: " ".to_string(),
+            ..request.clone()
+        };
+        assert!(matches!(
+            blank.validate().unwrap_err(),
+            ValidationError::EmptyOutputPath
+        ));
+        let invalid_speech = SpeakToFileRequest {
+            speak: SpeakRequest {
+                rate: Some(MAX_RATE + 1.0),
+                ..request.speak
+            },
+            ..request
+        };
+        assert!(invalid_speech.validate().is_err());
+    }
+
+    #[test]
+    fn test_features_serialize_camel_case() {
+        let features = TtsFeatures {
+            is_speaking: true,
+            pause_resume: true,
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&features).unwrap();
+        assert_eq!(json["isSpeaking"], true);
+        assert_eq!(json["pauseResume"], true);
+        assert_eq!(json["utteranceCallbacks"], false);
+    }
+
+    #[test]
+    fn test_voice_gain() {
+        let gains = HashMap::from([("loud".to_string(), 0.5), ("quiet".to_string(), 1.5)]);
+        let gained = |voice_id: &str, volume: Option<f32>| {
+            let mut request = SpeakRequest {
+                text: "Hello".to_string(),
+                voice_id: Some(voice_id.to_string()),
+                volume,
+                ..Default::default()
+            };
+            request.apply_voice_gain(&gains);
+            request.validate().unwrap().volume
+        };
+
+        assert_eq!(gained("loud", None), 0.5);
+        assert_eq!(gained("quiet", Some(0.5)), 0.75);
+        // Still capped at full volume
+        assert_eq!(gained("quiet", Some(1.0)), 1.0);
+        assert_eq!(gained("other", Some(0.8)), 0.8);
+
+        let request = |gain| SetVoiceGainRequest {
+            voice_id: "loud".to_string(),
+            gain,
+        };
+        assert!(request(None).validate().is_ok());
+        assert!(request(Some(MAX_VOICE_GAIN)).validate().is_ok());
+        for invalid in [-0.1, MAX_VOICE_GAIN + 0.1, f32::NAN] {
+            assert!(matches!(
+                request(Some(invalid)).validate().unwrap_err(),
+                ValidationError::VoiceGain { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stop_pattern_compile() {
+        let request = |pattern: &str| StopIfMatchesRequest {
+            pattern: pattern.to_string(),
+        };
+
+        assert!(request(r"(?i)\bspoiler\b")
+            .compile()
+            .unwrap()
+            .is_match("Spoiler ahead"));
+        assert!(matches!(
+            request("(unclosed").compile().unwrap_err(),
+            ValidationError::InvalidPattern(_)
+        ));
+        assert!(matches!(
+            request(&"a".repeat(MAX_PATTERN_LENGTH + 1))
+                .compile()
+                .unwrap_err(),
+            ValidationError::InvalidPattern(_)
+        ));
+    }
+
+    #[test]
+    fn test_outline_and_sections() {
+        let doc = "# Introduction\nHello.\n# Install\nRun the installer.";
+        let speak = SpeakRequest {
+            text: doc.to_string(),
+            rate: Some(1.2),
+            ..Default::default()
+        };
+
+        let outline_request = SpeakOutlineRequest {
+            speak: speak.clone(),
+            gap_ms: None,
+        };
+        let items = outline_request.items(&crate::preprocess::outline(doc));
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(texts, ["Section 1: Introduction", "Section 2: Install"]);
+        assert_eq!(items[0].rate, Some(1.2));
+        assert_eq!(
+            items[0].pause_after_ms,
+            Some(SpeakOutlineRequest::DEFAULT_GAP_MS)
+        );
+
+        let section = SpeakSectionRequest { speak, index: 1 }
+            .section_request()
+            .unwrap();
+        assert_eq!(section.text, "# Install\nRun the installer.");
+        assert!(section.structural_pauses);
+
+        let request: SpeakSectionRequest =
+            serde_json::from_str(r##"{"text": "# Only", "index": 3}"##).unwrap();
+        assert!(matches!(
+            request.section_request().unwrap_err(),
+            ValidationError::SectionIndex { index: 3, count: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_speak_from_offset() {
+        let request = |text: &str, offset| SpeakFromOffsetRequest {
+            speak: SpeakRequest {
+                text: text.to_string(),
+                ..Default::default()
+            },
+            offset,
+        };
+        let remainder = |text: &str, offset| {
+            let (speak, start) = request(text, offset).remainder().unwrap();
+            (speak.text, start)
+        };
+
+        assert_eq!(
+            remainder("One two three", 0),
+            ("One two three".to_string(), 0)
+        );
+        assert_eq!(remainder("One two three", 4), ("two three".to_string(), 4));
+        // Mid-word offsets back up to the start of the word
+        assert_eq!(remainder("One two three", 10), ("three".to_string(), 8));
+        // Offsets count graphemes, so a flag or an accented letter is one character
+        assert_eq!(
+            remainder("🇧🇷 e\u{301}t\u{e9} x", 2),
+            ("e\u{301}t\u{e9} x".to_string(), 2)
+        );
+        assert_eq!(remainder("🇧🇷 e\u{301}t\u{e9} x", 6), ("x".to_string(), 6));
+
+        assert!(matches!(
+            request("abc", 3).remainder().unwrap_err(),
+            ValidationError::OffsetOutOfBounds { offset: 3, len: 3 }
+        ));
+    }
+
+    #[test]
+    fn test_validation_prosody_ranges() {
+        let request = |rate, pitch, volume| SpeakRequest {
+            text: "Hello".to_string(),
+            rate: Some(rate),
+            pitch: Some(pitch),
+            volume: Some(volume),
+            ..Default::default()
+        };
+
+        for (rate, pitch, volume) in [(MIN_RATE, MIN_PITCH, 0.0), (MAX_RATE, MAX_PITCH, 1.0)] {
+            let validated = request(rate, pitch, volume).validate().unwrap();
+            assert_eq!(
+                (validated.rate, validated.pitch, validated.volume),
+                (rate, pitch, volume)
+            );
+        }
+
+        let rejected = |request: SpeakRequest| match request.validate().unwrap_err() {
+            ValidationError::OutOfRange { field, .. } => field,
+            other => panic!("unexpected error: {other}"),
+        };
+        assert_eq!(rejected(request(0.09, 1.0, 1.0)), "rate");
+        assert_eq!(rejected(request(50.0, 1.0, 1.0)), "rate");
+        assert_eq!(rejected(request(f32::NAN, 1.0, 1.0)), "rate");
+        assert_eq!(rejected(request(1.0, 0.49, 1.0)), "pitch");
+        assert_eq!(rejected(request(1.0, 2.01, 1.0)), "pitch");
+        assert_eq!(rejected(request(1.0, 1.0, -0.1)), "volume");
+        assert_eq!(rejected(request(1.0, 1.0, 1.01)), "volume");
+
+        let message = request(1.0, 1.0, 5.0).validate().unwrap_err().to_string();
+        assert_eq!(message, "volume must be between 0 and 1, got 5");
+    }
+
+    #[test]
+    fn test_validation_whitespace_text() {
+        for text in ["   ", "\n\t"] {
+            let request = SpeakRequest {
+                text: text.to_string(),
+                raw: Some(true),
+                ..Default::default()
+            };
+            assert!(matches!(
+                request.validate().unwrap_err(),
+                ValidationError::EmptyText
+            ));
+        }
+    }
+
+    #[test]
+    fn test_preview_voice_validation() {
+        // Valid preview
+        let valid = PreviewVoiceRequest {
+            voice_id: "valid-voice_123".to_string(),
+            text: None,
+            rate: None,
+            pitch: None,
+            volume: None,
+        };
+        assert!(valid.validate().is_ok());
+
+        // Invalid voice_id
+        let invalid = PreviewVoiceRequest {
+            voice_id: "invalid<script>".to_string(),
+            text: None,
+            rate: None,
+            pitch: None,
+            volume: None,
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_preview_voice_sample_text() {
+        let without_text = PreviewVoiceRequest {
+            voice_id: "voice".to_string(),
+            text: None,
+            rate: None,
+            pitch: None,
+            volume: None,
+        };
+        assert_eq!(
+            without_text.sample_text(),
+            PreviewVoiceRequest::DEFAULT_SAMPLE_TEXT
+        );
+
+        let with_text = PreviewVoiceRequest {
+            voice_id: "voice".to_string(),
+            text: Some("Custom sample".to_string()),
+            rate: None,
+            pitch: None,
+            volume: None,
+        };
+        assert_eq!(with_text.sample_text(), "Custom sample");
+    }
+
+    #[test]
+    fn test_preview_voice_prosody() {
+        let json = r#"{"voiceId": "voice", "rate": 0.5}"#;
+        let mut request: PreviewVoiceRequest = serde_json::from_str(json).unwrap();
+        assert!(request.validate().is_ok());
+
+        request.apply_profile(&VoiceProfile {
+            rate: 2.0,
+            pitch: 1.5,
+            ..Default::default()
+        });
+        // Explicit values win over the profile
+        assert_eq!(request.rate, Some(0.5));
+        assert_eq!(request.pitch, Some(1.5));
+
+        request.volume = Some(1.5);
+        assert!(matches!(
+            request.validate().unwrap_err(),
+            ValidationError::OutOfRange {
+                field: "volume",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_ssml_is_checked_and_passed_through() {
+        let request = SpeakRequest {
+            text: "<speak>Wait<break time=\"1s\"/>  now 😀</speak>".to_string(),
+            ssml: true,
+            emoji_mode: Some(EmojiMode::Skip),
+            ..Default::default()
+        };
+        // Preprocessing would rewrite the markup, so it is skipped
+        assert_eq!(request.validate().unwrap().text, request.text);
+
+        let malformed = SpeakRequest {
+            text: "<speak>Wait<break time=1s/></speak>".to_string(),
+            ..request.clone()
+        };
+        assert!(matches!(
+            malformed.validate().unwrap_err(),
+            ValidationError::InvalidSsml(_)
+        ));
+
+        let silent = SpeakRequest {
+            text: "<speak><break time=\"1s\"/></speak>".to_string(),
+            ..request
+        };
+        assert!(matches!(
+            silent.validate().unwrap_err(),
+            ValidationError::EmptyText
+        ));
+    }
+
+    #[test]
+    fn test_ssml_breaks_become_pauses_between_plain_segments() {
+        let request = SpeakRequest {
+            text: "<speak>Wait<break time=\"1s\"/>now</speak>".to_string(),
+            ssml: true,
+            queue_mode: Some(QueueMode::Flush),
+            pause_after_ms: Some(200),
+            ..Default::default()
+        };
+        let segments = request.into_plain_segments();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "Wait");
+        assert_eq!(segments[0].pause_after_ms, Some(1000));
+        assert_eq!(segments[1].text, "now");
+        assert_eq!(segments[1].queue_mode, Some(QueueMode::Add));
+        assert_eq!(segments[1].pause_after_ms, Some(200));
+        assert!(segments.iter().all(|s| !s.ssml && s.raw == Some(true)));
+    }
+
+    #[test]
+    fn test_long_text_is_chunked_in_order() {
+        let sentence = "This sentence is exactly forty bytes ok. ";
+        let request = SpeakRequest {
+            text: sentence.repeat(5),
+            pause_after_ms: Some(300),
+            ..Default::default()
+        };
+        let chunks = request.validate().unwrap().into_chunks(100);
+
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|chunk| chunk.text.len() <= 100));
+        // Later chunks queue behind the first instead of interrupting it
+        assert_eq!(chunks[0].queue_mode, QueueMode::Flush);
+        assert_eq!(chunks[1].queue_mode, QueueMode::Add);
+        assert_eq!(chunks[2].queue_mode, QueueMode::Add);
+        assert_eq!(chunks[0].pause_after_ms, None);
+        assert_eq!(chunks[2].pause_after_ms, Some(300));
+        // Together the chunks make up a single utterance
+        assert!(chunks[0].first_chunk && !chunks[0].last_chunk);
+        assert!(!chunks[1].first_chunk && !chunks[1].last_chunk);
+        assert!(!chunks[2].first_chunk && chunks[2].last_chunk);
+    }
+
+    #[test]
+    fn test_detected_language_replaces_the_requested_one() {
+        let text = "Le renard brun rapide saute par-dessus le chien paresseux";
+        let detect = |language: Option<&str>, voice_id: Option<&str>| {
+            let mut request = SpeakRequest {
+                text: text.to_string(),
+                language: language.map(str::to_string),
+                voice_id: voice_id.map(str::to_string),
+                auto_detect_language: true,
+                ..Default::default()
+            };
+            request.apply_detected_language();
+            request.language
+        };
+
+        assert_eq!(detect(None, None).as_deref(), Some("fr"));
+        assert_eq!(detect(Some("en-US"), None).as_deref(), Some("fr"));
+        // A regional variant of the detected language says more than detection can
+        assert_eq!(detect(Some("fr-CA"), None).as_deref(), Some("fr-CA"));
+        // A named voice already decides the language
+        assert_eq!(
+            detect(Some("en-US"), Some("voice")).as_deref(),
+            Some("en-US")
+        );
+
+        let mut unsure = SpeakRequest {
+            text: "ok".to_string(),
+            language: Some("en-US".to_string()),
+            auto_detect_language: true,
+            ..Default::default()
+        };
+        unsure.apply_detected_language();
+        assert_eq!(unsure.language.as_deref(), Some("en-US"));
+    }
+
+    #[test]
+    fn test_chunks_keep_their_sentence_offsets() {
+        let sentence_offset = |index: u32| SentenceOffset {
+            index,
+            spoken_start: 0,
+            spoken_end: 0,
+            text_start: index * 41,
+            text_end: index * 41 + 40,
+        };
+        let sentence = "This sentence is exactly forty bytes ok. ";
+        let mut validated = SpeakRequest {
+            text: sentence.repeat(5),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        validated.sentences = (0..5).map(|index| Some(sentence_offset(index))).collect();
+
+        let indices: Vec<Vec<u32>> = validated
+            .into_chunks(100)
+            .iter()
+            .map(|chunk| chunk.sentences.iter().map(|s| s.unwrap().index).collect())
+            .collect();
+        assert_eq!(indices, [vec![0, 1], vec![2, 3], vec![4]]);
+
+        // Only the first piece of a sentence cut across chunks starts it
+        let mut validated = SpeakRequest {
+            text: format!("Short one. {}end.", "word ".repeat(30)),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+        validated.sentences = vec![Some(sentence_offset(0)), Some(sentence_offset(1))];
+        let sentences: Vec<Vec<Option<SentenceOffset>>> = validated
+            .into_chunks(60)
+            .into_iter()
+            .map(|chunk| chunk.sentences)
+            .collect();
+        assert_eq!(
+            sentences,
+            [
+                vec![Some(sentence_offset(0)), Some(sentence_offset(1))],
+                vec![None],
+                vec![None]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backend_chain_follows_fallback_list() {
+        let config: TtsConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.backend_chain(None), vec!["system"]);
+        assert_eq!(config.backend_chain(Some("azure")), vec!["azure"]);
+
+        let config: TtsConfig =
+            serde_json::from_str(r#"{"backendFallback": ["elevenlabs", "azure", "system"]}"#)
+                .unwrap();
+        assert_eq!(
+            config.backend_chain(None),
+            vec!["elevenlabs", "azure", "system"]
+        );
+        assert_eq!(config.backend_chain(Some("azure")), vec!["azure", "system"]);
+        assert_eq!(config.backend_chain(Some("polly")), vec!["polly"]);
+    }
+
+    #[test]
+    fn test_max_chunk_length_config() {
+        let config: TtsConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.max_chunk_length(), DEFAULT_MAX_CHUNK_LENGTH);
+
+        let config: TtsConfig = serde_json::from_str(r#"{"maxChunkLength": 10}"#).unwrap();
+        assert!(matches!(
+            config.validate_max_chunk_length().unwrap_err(),
+            ValidationError::ChunkLength { len: 10, .. }
+        ));
+    }
 
-impl PreviewVoiceRequest {
-    pub const DEFAULT_SAMPLE_TEXT: &'static str =
-        "Hello! This is a sample of how this voice sounds.";
+    #[test]
+    fn test_raw_request_skips_preprocessing() {
+        let text = "if x {\n    return 1;\n}";
+        let processed = SpeakRequest {
+            text: text.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(processed.validate().unwrap().text, "if x { return 1; }");
 
-    pub fn sample_text(&self) -> Cow<'_, str> {
-        match &self.text {
-            Some(text) => Cow::Borrowed(text.as_str()),
-            None => Cow::Borrowed(Self::DEFAULT_SAMPLE_TEXT),
-        }
+        let raw = SpeakRequest {
+            text: text.to_string(),
+            raw: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(raw.validate().unwrap().text, text);
     }
 
-    pub fn validate(&self) -> Result<(), ValidationError> {
-        // Validate custom text if provided
-        if let Some(ref text) = self.text {
-            if text.is_empty() {
-                return Err(ValidationError::EmptyText);
-            }
-            if text.len() > MAX_TEXT_LENGTH {
-                return Err(ValidationError::TextTooLong {
-                    len: text.len(),
-                    max: MAX_TEXT_LENGTH,
-                });
-            }
-        }
+    #[test]
+    fn test_whitespace_only_text_is_empty() {
+        let request = SpeakRequest {
+            text: " \n\t ".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            request.validate(),
+            Err(ValidationError::EmptyText)
+        ));
+    }
 
-        Ok(())
+    #[test]
+    fn test_word_count_uses_preprocessed_text() {
+        let request = SpeakRequest {
+            text: "  Olá,\n\n  mundo  ".to_string(),
+            ..Default::default()
+        };
+        let count = WordCountResponse::for_spoken_text(&request.validate().unwrap().text);
+        assert_eq!(count.words, 2);
+        assert_eq!(count.characters, "Olá, mundo".chars().count() as u32);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_affixes_wrap_text() {
+        let affixes = Affixes {
+            prefix: Some("Acme says:".to_string()),
+            suffix: None,
+        };
+        assert_eq!(affixes.apply("Hello"), "Acme says: Hello");
+        assert_eq!(Affixes::default().apply("Hello"), "Hello");
+
+        let too_long = Affixes {
+            prefix: None,
+            suffix: Some("x".repeat(MAX_AFFIX_LENGTH + 1)),
+        };
+        assert!(too_long.validate().is_err());
+    }
 
     #[test]
-    fn test_speak_request_defaults() {
-        let json = r#"{"text": "Hello world"}"#;
-        let request: SpeakRequest = serde_json::from_str(json).unwrap();
+    fn test_use_affixes_defaults_to_true() {
+        let request: SpeakRequest = serde_json::from_str(r#"{"text": "Hi"}"#).unwrap();
+        assert!(request.use_affixes);
+    }
 
-        assert_eq!(request.text, "Hello world");
-        assert!(request.language.is_none());
+    #[test]
+    fn test_profile_fills_omitted_fields() {
+        let profile = VoiceProfile {
+            voice_id: Some("samantha".to_string()),
+            rate: 1.5,
+            raw: true,
+            ..Default::default()
+        };
+
+        let mut request = SpeakRequest {
+            text: "Hello".to_string(),
+            pitch: Some(0.8),
+            ..Default::default()
+        };
+        request.apply_profile(&profile);
+        assert_eq!(request.voice_id.as_deref(), Some("samantha"));
+        assert_eq!(request.rate, Some(1.5));
+        assert_eq!(request.pitch, Some(0.8));
+        assert_eq!(request.raw, Some(true));
+
+        // An explicit language wins over the profile's voice
+        let mut request = SpeakRequest {
+            text: "Olá".to_string(),
+            language: Some("pt-BR".to_string()),
+            ..Default::default()
+        };
+        request.apply_profile(&profile);
         assert!(request.voice_id.is_none());
-        assert_eq!(request.rate, 1.0);
-        assert_eq!(request.pitch, 1.0);
-        assert_eq!(request.volume, 1.0);
     }
 
     #[test]
-    fn test_speak_request_full() {
-        let json = r#"{
-            "text": "Olá",
-            "language": "pt-BR",
-            "voiceId": "com.apple.voice.enhanced.pt-BR",
-            "rate": 0.8,
-            "pitch": 1.2,
-            "volume": 0.9
-        }"#;
+    fn test_profile_import_falls_back_when_voice_missing() {
+        let json = r#"{"version": 1, "voiceId": "gone", "language": "en-US", "rate": 9.0}"#;
+        let profile: VoiceProfile = serde_json::from_str(json).unwrap();
+        let (profile, warnings) = profile.sanitize(|_| false).unwrap();
 
-        let request: SpeakRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(request.text, "Olá");
-        assert_eq!(request.language, Some("pt-BR".to_string()));
-        assert_eq!(
-            request.voice_id,
-            Some("com.apple.voice.enhanced.pt-BR".to_string())
-        );
-        assert_eq!(request.rate, 0.8);
-        assert_eq!(request.pitch, 1.2);
-        assert_eq!(request.volume, 0.9);
+        assert!(profile.voice_id.is_none());
+        assert_eq!(profile.language.as_deref(), Some("en-US"));
+        assert_eq!(profile.rate, MAX_RATE);
+        assert_eq!(profile.pitch, 1.0);
+        assert_eq!(warnings.len(), 1);
     }
 
     #[test]
-    fn test_voice_serialization() {
-        let voice = Voice {
-            id: "test-voice".to_string(),
-            name: "Test Voice".to_string(),
-            language: "en-US".to_string(),
-        };
+    fn test_profile_setters() {
+        let mut profile = VoiceProfile::default();
 
-        let json = serde_json::to_string(&voice).unwrap();
-        assert!(json.contains("\"id\":\"test-voice\""));
-        assert!(json.contains("\"name\":\"Test Voice\""));
-        assert!(json.contains("\"language\":\"en-US\""));
+        assert_eq!(profile.set_rate(1.5).unwrap(), 1.5);
+        assert!(profile.set_rate(9.0).is_err());
+        assert!(profile.set_pitch(f32::NAN).is_err());
+        assert!(profile.set_volume(-0.1).is_err());
+        assert_eq!(profile.set_volume(0.4).unwrap(), 0.4);
+        assert_eq!(
+            (profile.rate, profile.pitch, profile.volume),
+            (1.5, 1.0, 0.4)
+        );
+
+        let set = profile.set_voice(Some("alex".to_string()), |id| id == "alex");
+        assert_eq!(set.voice_id.as_deref(), Some("alex"));
+        assert!(set.warning.is_none());
+        let kept = profile.set_voice(Some("gone".to_string()), |id| id == "alex");
+        assert_eq!(kept.voice_id.as_deref(), Some("alex"));
+        assert!(kept.warning.is_some());
+        assert!(profile.set_voice(None, |_| false).voice_id.is_none());
     }
 
     #[test]
-    fn test_get_voices_request_optional_language() {
-        let json1 = r#"{}"#;
-        let request1: GetVoicesRequest = serde_json::from_str(json1).unwrap();
-        assert!(request1.language.is_none());
+    fn test_profile_from_newer_version_is_accepted_with_warning() {
+        let json = r#"{"version": 99, "voiceId": "alex", "futureSetting": true}"#;
+        let profile: VoiceProfile = serde_json::from_str(json).unwrap();
+        let (profile, warnings) = profile.sanitize(|id| id == "alex").unwrap();
 
-        let json2 = r#"{"language": "en"}"#;
-        let request2: GetVoicesRequest = serde_json::from_str(json2).unwrap();
-        assert_eq!(request2.language, Some("en".to_string()));
+        assert_eq!(profile.version, VoiceProfile::VERSION);
+        assert_eq!(profile.voice_id.as_deref(), Some("alex"));
+        assert_eq!(warnings.len(), 1);
     }
 
     #[test]
-    fn test_validation_empty_text() {
+    fn test_structural_pauses_split_request() {
         let request = SpeakRequest {
-            text: "".to_string(),
-            language: None,
-            voice_id: None,
-            rate: 1.0,
-            pitch: 1.0,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+            text: "# Intro\nHello there.\n# Next\nMore text.".to_string(),
+            queue_mode: Some(QueueMode::Flush),
+            pause_after_ms: Some(100),
+            structural_pauses: true,
+            ..Default::default()
         };
-
-        let result = request.validate();
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), ValidationError::EmptyText));
+        let segments = request.into_segments();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0].text, "Intro");
+        assert_eq!(segments[0].queue_mode, Some(QueueMode::Flush));
+        assert!(segments[1..]
+            .iter()
+            .all(|s| s.queue_mode == Some(QueueMode::Add)));
+        assert_eq!(segments[3].pause_after_ms, Some(100));
     }
 
     #[test]
-    fn test_validation_text_too_long() {
-        let long_text = "x".repeat(MAX_TEXT_LENGTH + 1);
+    fn test_raw_disables_structural_pauses() {
         let request = SpeakRequest {
-            text: long_text,
-            language: None,
-            voice_id: None,
-            rate: 1.0,
-            pitch: 1.0,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+            text: "# Intro\nHello there.".to_string(),
+            raw: Some(true),
+            structural_pauses: true,
+            ..Default::default()
         };
+        assert_eq!(request.into_segments().len(), 1);
+    }
 
-        let result = request.validate();
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            ValidationError::TextTooLong { .. }
-        ));
+    #[test]
+    fn test_affixes_on_split_utterance() {
+        let affixes = Affixes {
+            prefix: Some("Acme says:".to_string()),
+            suffix: Some("Over.".to_string()),
+        };
+        assert_eq!(affixes.apply_at("One", true, false), "Acme says: One");
+        assert_eq!(affixes.apply_at("Two", false, false), "Two");
+        assert_eq!(affixes.apply_at("Three", false, true), "Three Over.");
     }
 
     #[test]
-    fn test_validation_valid_voice_id() {
-        let request = SpeakRequest {
-            text: "Hello".to_string(),
-            language: None,
-            voice_id: Some("com.apple.voice.enhanced.en-US".to_string()),
-            rate: 1.0,
-            pitch: 1.0,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+    fn test_benchmark_phrase_per_language() {
+        let config = TtsConfig {
+            benchmark_phrases: HashMap::from([
+                (
+                    "pt".to_string(),
+                    "Um pequeno jabuti xereta viu dez cegonhas felizes.".to_string(),
+                ),
+                ("pt_PT".to_string(), "Frase de Portugal.".to_string()),
+            ]),
+            ..Default::default()
         };
 
-        let result = request.validate();
-        assert!(result.is_ok());
         assert_eq!(
-            result.unwrap().voice_id,
-            Some("com.apple.voice.enhanced.en-US".to_string())
+            config.benchmark_phrase("pt-PT"),
+            ("Frase de Portugal.", None)
+        );
+        assert_eq!(
+            config.benchmark_phrase("pt-BR"),
+            ("Um pequeno jabuti xereta viu dez cegonhas felizes.", None)
+        );
+        assert_eq!(
+            config.benchmark_phrase("en-GB"),
+            (TtsConfig::DEFAULT_BENCHMARK_PHRASE, None)
         );
+
+        let (phrase, warning) = config.benchmark_phrase("ja-JP");
+        assert_eq!(phrase, TtsConfig::DEFAULT_BENCHMARK_PHRASE);
+        assert!(warning.unwrap().contains("ja-JP"));
     }
-    
+
     #[test]
-    fn test_validation_voice_id_too_long() {
-        let long_voice_id = "x".repeat(MAX_VOICE_ID_LENGTH + 1);
-        let request = SpeakRequest {
-            text: "Hello".to_string(),
-            language: None,
-            voice_id: Some(long_voice_id),
-            rate: 1.0,
-            pitch: 1.0,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+    fn test_benchmark_request_ignores_profile_and_affixes() {
+        let request = BenchmarkVoiceRequest {
+            voice_id: "voice-1".to_string(),
+        }
+        .speak_request(TtsConfig::DEFAULT_BENCHMARK_PHRASE);
+        assert!(!request.use_affixes);
+
+        let validated = request.validate().unwrap();
+        assert_eq!(validated.text, TtsConfig::DEFAULT_BENCHMARK_PHRASE);
+        assert_eq!(validated.voice_id.as_deref(), Some("voice-1"));
+        assert_eq!(validated.rate, 1.0);
+        assert_eq!(validated.queue_mode, QueueMode::Flush);
+    }
+
+    #[test]
+    fn test_calibration_samples() {
+        let request = CalibrationRequest::default();
+        request.validate().unwrap();
+        assert_eq!(request.rates(), CalibrationRequest::DEFAULT_RATES);
+
+        let first = request.sample(0, 0.75, false);
+        assert_eq!(first.text, "Speed 0.75. This is how I sound at this speed.");
+        assert_eq!(first.rate, Some(0.75));
+        assert_eq!(first.queue_mode, Some(QueueMode::Flush));
+        assert_eq!(
+            first.pause_after_ms,
+            Some(PreviewAllRequest::DEFAULT_GAP_MS)
+        );
+        assert!(!first.use_affixes);
+
+        let last = request.sample(3, 1.0, true);
+        assert_eq!(last.text, "Speed 1. This is how I sound at this speed.");
+        assert_eq!(last.queue_mode, Some(QueueMode::Add));
+        assert_eq!(last.pause_after_ms, None);
+    }
+
+    #[test]
+    fn test_calibration_rates_are_clamped_and_bounded() {
+        let request = CalibrationRequest {
+            rates: Some(vec![0.01, 2.0, 9.0]),
+            ..Default::default()
         };
+        assert_eq!(request.rates(), [0.1, 2.0, 3.0]);
 
-        let result = request.validate();
-        assert!(result.is_err());
+        let empty = CalibrationRequest {
+            rates: Some(vec![]),
+            ..Default::default()
+        };
         assert!(matches!(
-            result.unwrap_err(),
-            ValidationError::VoiceIdTooLong { .. }
+            empty.validate(),
+            Err(ValidationError::CalibrationRates { count: 0, .. })
         ));
+
+        let too_many = CalibrationRequest {
+            rates: Some(vec![1.0; CalibrationRequest::MAX_SAMPLES + 1]),
+            ..Default::default()
+        };
+        assert!(too_many.validate().is_err());
     }
 
     #[test]
-    fn test_validation_rate_clamping() {
-        let request = SpeakRequest {
-            text: "Hello".to_string(),
-            language: None,
-            voice_id: None,
-            rate: 999.0,
-            pitch: 1.0,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+    fn test_audio_route_privacy() {
+        let native: AudioRouteResponse = serde_json::from_str(r#"{"route": "bluetooth"}"#).unwrap();
+        let response = AudioRouteResponse::from(native.route);
+        assert_eq!(response.route, AudioRoute::Bluetooth);
+        assert!(response.is_private);
+
+        assert!(!AudioRouteResponse::from(AudioRoute::Speaker).is_private);
+        assert!(!AudioRouteResponse::from(AudioRoute::Unknown).is_private);
+    }
+
+    #[test]
+    fn test_speak_response_utterance_id() {
+        let native: SpeakResponse =
+            serde_json::from_str(r#"{"success": true, "utteranceId": "tts_1"}"#).unwrap();
+        assert_eq!(native.utterance_id.as_deref(), Some("tts_1"));
+
+        let json = serde_json::to_value(SpeakResponse::default()).unwrap();
+        assert!(json.get("utteranceId").is_none());
+        assert!(json.get("usedVoiceId").is_none());
+    }
+
+    #[test]
+    fn test_speak_and_wait_response() {
+        let spoken = SpeakResponse {
+            success: true,
+            utterance_id: Some("tts_1".to_string()),
+            ..Default::default()
         };
+        let json = serde_json::to_value(SpeakAndWaitResponse::new(
+            SpeechOutcome::Completed,
+            spoken.clone(),
+        ))
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"outcome": "completed", "utteranceId": "tts_1"})
+        );
 
-        let result = request.validate();
-        assert!(result.is_ok());
-        let validated = result.unwrap();
-        assert_eq!(validated.rate, 4.0); // Clamped to max
+        let failed = SpeakAndWaitResponse::failed("engine error".to_string(), spoken);
+        assert_eq!(failed.outcome, SpeechOutcome::Error);
+        assert_eq!(failed.error.as_deref(), Some("engine error"));
     }
 
     #[test]
-    fn test_validation_pitch_clamping() {
-        let request = SpeakRequest {
+    fn test_rate_wpm_takes_precedence() {
+        let mut request = SpeakRequest {
             text: "Hello".to_string(),
-            language: None,
-            voice_id: None,
-            rate: 1.0,
-            pitch: 0.1,
-            volume: 1.0,
-            queue_mode: QueueMode::Flush,
+            rate: Some(2.0),
+            rate_wpm: Some(90),
+            ..Default::default()
         };
+        assert_eq!(request.apply_rate_wpm(180), None);
+        assert_eq!(request.rate, Some(0.5));
+        assert_eq!(request.rate_wpm, None);
+        // Applying again keeps the converted rate
+        assert_eq!(request.apply_rate_wpm(180), None);
+        assert_eq!(request.rate, Some(0.5));
 
-        let result = request.validate();
-        assert!(result.is_ok());
-        let validated = result.unwrap();
-        assert_eq!(validated.pitch, 0.5); // Clamped to min
+        let mut fast = SpeakRequest {
+            text: "Hello".to_string(),
+            rate_wpm: Some(2_000),
+            ..Default::default()
+        };
+        let warning = fast.apply_rate_wpm(180).unwrap();
+        assert!(warning.contains("18-540"), "{}", warning);
+        assert_eq!(fast.rate, Some(MAX_RATE));
     }
 
     #[test]
-    fn test_validation_volume_clamping() {
-        let request = SpeakRequest {
-            text: "Hello".to_string(),
-            language: None,
-            voice_id: None,
-            rate: 1.0,
-            pitch: 1.0,
-            volume: 5.0,
-            queue_mode: QueueMode::Flush,
+    fn test_error_event_matches_command_errors() {
+        let error = crate::Error::OperationFailed("engine error".to_string());
+        let event = serde_json::to_value(ErrorEvent::new(&error, Some("u1"))).unwrap();
+        let rejected = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(event["code"], rejected["code"]);
+        assert_eq!(event["message"], rejected["message"]);
+        assert_eq!(event["id"], "u1");
+        let event = serde_json::to_value(ErrorEvent::new(&crate::Error::MutexPoisoned, None));
+        assert!(event.unwrap().get("id").is_none());
+    }
+
+    #[test]
+    fn test_is_speaking_response_is_backward_compatible() {
+        let idle = serde_json::to_value(IsSpeakingResponse::default()).unwrap();
+        assert_eq!(idle, serde_json::json!({ "speaking": false }));
+
+        // What older native plugins answer
+        let native: IsSpeakingResponse = serde_json::from_str(r#"{"speaking": true}"#).unwrap();
+        assert!(native.speaking);
+        assert!(native.current_utterance_id.is_none() && native.current_text.is_none());
+    }
+
+    #[test]
+    fn test_no_voices_error_names_the_platform() {
+        let rejected = serde_json::to_value(crate::Error::NoVoicesAvailable("linux")).unwrap();
+        assert_eq!(rejected["code"], "NO_VOICES");
+        assert_eq!(
+            rejected["message"],
+            "No text-to-speech voices available on linux"
+        );
+    }
+
+    #[test]
+    fn test_pronunciations_validation() {
+        let request = |word: &str, ipa: &str| SpeakRequest {
+            text: "Tomato".to_string(),
+            pronunciations: vec![Pronunciation {
+                word: word.to_string(),
+                ipa: ipa.to_string(),
+            }],
+            ..Default::default()
         };
 
-        let result = request.validate();
-        assert!(result.is_ok());
-        let validated = result.unwrap();
-        assert_eq!(validated.volume, 1.0); // Clamped to max
+        assert!(request("tomato", "təˈmɑːtəʊ").validate().is_ok());
+        assert!(request(" ", "təˈmɑːtəʊ").validate().is_err());
+        assert!(request("tomato", "").validate().is_err());
+        assert!(request("tomato", "x").uses_pronunciations());
+        let raw = SpeakRequest {
+            raw: Some(true),
+            ..request("tomato", "x")
+        };
+        assert!(!raw.uses_pronunciations());
     }
 
     #[test]
-    fn test_preview_voice_validation() {
-        // Valid preview
-        let valid = PreviewVoiceRequest {
-            voice_id: "valid-voice_123".to_string(),
-            text: None,
+    fn test_max_duration_validation() {
+        let request = |max_duration_ms| SpeakRequest {
+            text: "Now boarding".to_string(),
+            max_duration_ms,
+            ..Default::default()
         };
-        assert!(valid.validate().is_ok());
+        assert_eq!(request(None).validate().unwrap().max_duration_ms, None);
+        assert_eq!(
+            request(Some(15_000)).validate().unwrap().max_duration_ms,
+            Some(15_000)
+        );
+        assert!(matches!(
+            request(Some(0)).validate().unwrap_err(),
+            ValidationError::ZeroMaxDuration
+        ));
+    }
 
-        // Invalid voice_id
-        let invalid = PreviewVoiceRequest {
-            voice_id: "invalid<script>".to_string(),
-            text: None,
+    #[test]
+    fn test_speak_segments() {
+        let request: SpeakSegmentsRequest = serde_json::from_value(serde_json::json!({
+            "segments": [
+                {"text": "The French word for cheese is", "language": "en-US"},
+                {"text": "fromage", "language": "fr-FR", "rate": 0.8},
+            ]
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.segments[1].language.as_deref(), Some("fr-FR"));
+        assert!(matches!(
+            SpeakSegmentsRequest { segments: vec![] }
+                .validate()
+                .unwrap_err(),
+            ValidationError::ListItems { count: 0, .. }
+        ));
+
+        let spoken = SpeakResponse {
+            success: true,
+            utterance_id: Some("b".to_string()),
+            ..Default::default()
         };
-        assert!(invalid.validate().is_err());
+        let response = SpeakSegmentsResponse::new(vec!["a".to_string(), "b".to_string()], spoken);
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            serde_json::json!({"success": true, "utteranceIds": ["a", "b"]})
+        );
     }
 
     #[test]
-    fn test_preview_voice_sample_text() {
-        let without_text = PreviewVoiceRequest {
-            voice_id: "voice".to_string(),
-            text: None,
+    fn test_audio_focus_response_from_native() {
+        let granted: AudioFocusResponse = serde_json::from_str(r#"{"granted": true}"#).unwrap();
+        assert!(granted.granted);
+        assert!(granted.note.is_none());
+
+        let json = serde_json::to_value(&granted).unwrap();
+        assert!(json.get("note").is_none());
+    }
+
+    #[test]
+    fn test_preview_all_announces_voice_name() {
+        let json = r#"{"language": "en"}"#;
+        let request: PreviewAllRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.gap_ms(), PreviewAllRequest::DEFAULT_GAP_MS);
+        assert!(request.validate().is_ok());
+
+        let voice = Voice {
+            id: "samantha".to_string(),
+            name: "Samantha".to_string(),
+            language: "en-US".to_string(),
+            gender: None,
+            quality: None,
+            requires_network: None,
+            installed: None,
+            engine: None,
+            platform: String::new(),
         };
         assert_eq!(
-            without_text.sample_text(),
-            PreviewVoiceRequest::DEFAULT_SAMPLE_TEXT
+            request.announcement(&voice),
+            format!("Samantha: {}", PreviewVoiceRequest::DEFAULT_SAMPLE_TEXT)
         );
+    }
 
-        let with_text = PreviewVoiceRequest {
-            voice_id: "voice".to_string(),
-            text: Some("Custom sample".to_string()),
+    #[test]
+    fn test_pronunciation_request_validation() {
+        let json = r#"{"text": "tomato"}"#;
+        let request: GetPronunciationRequest = serde_json::from_str(json).unwrap();
+        assert!(request.voice_id.is_none());
+        assert!(request.validate().is_ok());
+
+        let empty = GetPronunciationRequest {
+            text: "".to_string(),
+            voice_id: None,
         };
-        assert_eq!(with_text.sample_text(), "Custom sample");
+        assert!(matches!(
+            empty.validate().unwrap_err(),
+            ValidationError::EmptyText
+        ));
     }
 }
@@ -0,0 +1,417 @@
+use crate::models::{OutlineEntry, PreprocessOptions};
+
+/// Run the preprocessing pipeline over `text`, returning what the engine should speak.
+///
+/// `raw` short-circuits the whole pipeline so the text reaches the engine verbatim;
+/// it takes precedence over every other option.
+pub(crate) fn preprocess(text: &str, options: &PreprocessOptions) -> String {
+    if options.raw {
+        return text.to_string();
+    }
+
+    let text = crate::markup::strip(text, options.format);
+    let text = crate::emoji::apply(&text, options.emoji_mode);
+    normalize_whitespace(&text)
+}
+
+/// Pause before a heading-like line when `structural_pauses` is on
+pub(crate) const PAUSE_BEFORE_HEADING_MS: u32 = 700;
+/// Pause between a heading and the text that follows it
+pub(crate) const PAUSE_AFTER_HEADING_MS: u32 = 350;
+
+const MAX_HEADING_WORDS: usize = 8;
+const MAX_HEADING_CHARS: usize = 60;
+
+/// A block of multi-line text, spoken as its own utterance
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StructuralBlock {
+    pub text: String,
+    /// Silence after this block, `None` for the last one
+    pub pause_after_ms: Option<u32>,
+}
+
+/// Split multi-line text so every heading-like line is spoken on its own,
+/// with a pause before it and a shorter one after it
+pub(crate) fn split_structure(text: &str) -> Vec<StructuralBlock> {
+    let mut blocks: Vec<StructuralBlock> = Vec::new();
+    let mut body = String::new();
+
+    for line in text.lines() {
+        if !is_heading(line) {
+            body.push_str(line);
+            body.push('\n');
+            continue;
+        }
+        if !body.trim().is_empty() {
+            blocks.push(StructuralBlock {
+                text: std::mem::take(&mut body),
+                pause_after_ms: None,
+            });
+        }
+        body.clear();
+        if let Some(previous) = blocks.last_mut() {
+            previous.pause_after_ms = Some(PAUSE_BEFORE_HEADING_MS);
+        }
+        blocks.push(StructuralBlock {
+            text: line.trim().trim_start_matches('#').trim().to_string(),
+            pause_after_ms: Some(PAUSE_AFTER_HEADING_MS),
+        });
+    }
+    if !body.trim().is_empty() {
+        blocks.push(StructuralBlock {
+            text: body,
+            pause_after_ms: None,
+        });
+    }
+    if let Some(last) = blocks.last_mut() {
+        last.pause_after_ms = None;
+    }
+    blocks
+}
+
+/// Heading-like lines of `text`, in order, for a spoken table of contents
+pub(crate) fn outline(text: &str) -> Vec<OutlineEntry> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| is_heading(line))
+        .map(|(index, line)| {
+            let line = line.trim();
+            let hashes = line.chars().take_while(|&c| c == '#').count();
+            OutlineEntry {
+                title: line.trim_start_matches('#').trim().to_string(),
+                // Lines detected by their casing have no depth of their own
+                level: hashes.clamp(1, 6) as u8,
+                line: index as u32,
+            }
+        })
+        .collect()
+}
+
+/// The text from line `line` to the end, for reading on from a heading
+pub(crate) fn from_line(text: &str, line: u32) -> String {
+    text.lines()
+        .skip(line as usize)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Words of `text` for speaking one at a time. Punctuation standing apart from a
+/// word ("wait ... what") joins the word before it, opening quotes and brackets
+/// join the word after, so punctuation is never spoken on its own
+pub(crate) fn words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut opening = String::new();
+    for token in text.split_whitespace() {
+        if !token.chars().all(is_punctuation) {
+            words.push(std::mem::take(&mut opening) + token);
+            continue;
+        }
+        match words.last_mut() {
+            Some(last) if !token.chars().all(is_opening) => last.push_str(token),
+            _ => opening.push_str(token),
+        }
+    }
+    // Only punctuation left after the last word, e.g. a trailing "("
+    if let Some(last) = words.last_mut() {
+        last.push_str(&opening);
+    }
+    words
+}
+
+/// Split `text` into chunks of at most `max_len` bytes for engines that truncate
+/// long input. Chunks end at sentence ends (`.`, `!` or `?` before whitespace) or
+/// line breaks where possible, then between words, and never inside a character.
+/// Short sentences are packed together so the text isn't cut up more than needed
+pub(crate) fn chunks(text: &str, max_len: usize) -> Vec<String> {
+    if text.len() <= max_len {
+        return vec![text.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let units = sentences(text)
+        .into_iter()
+        .flat_map(|sentence| fit(sentence, max_len));
+    for unit in units {
+        if !current.is_empty() && current.len() + 1 + unit.len() > max_len {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(unit);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Sentences of `text`, trimmed, ending after `.`, `!` or `?` followed by
+/// whitespace, or at line breaks
+pub(crate) fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let ends = match c {
+            '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_some_and(|&(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends {
+            let end = index + c.len_utf8();
+            sentences.push(&text[start..end]);
+            start = end;
+        }
+    }
+    sentences.push(&text[start..]);
+    sentences
+        .into_iter()
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
+
+/// Pieces of `text` that `synthesize_stream` renders one after another: its
+/// sentences, or the whole text for SSML, which can't be cut between its tags
+#[cfg(any(mobile, test))]
+pub(crate) fn stream_pieces(text: &str, ssml: bool) -> Vec<&str> {
+    if ssml {
+        return vec![text];
+    }
+    sentences(text)
+}
+
+/// A sentence as pieces of at most `max_len` bytes: itself if short enough,
+/// otherwise its words, with words that are still too long cut between characters
+fn fit(sentence: &str, max_len: usize) -> Vec<&str> {
+    if sentence.len() <= max_len {
+        return vec![sentence];
+    }
+    let mut pieces = Vec::new();
+    for word in sentence.split_whitespace() {
+        let mut rest = word;
+        while rest.len() > max_len {
+            let mut end = max_len;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            pieces.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+        pieces.push(rest);
+    }
+    pieces
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || "“”‘’«»—–…¡¿".contains(c)
+}
+
+fn is_opening(c: char) -> bool {
+    "([{“‘«¡¿".contains(c)
+}
+
+/// Whether a line looks like a heading: a markdown `#` heading, or a short line
+/// without sentence punctuation that is ALL CAPS or Title Case
+pub(crate) fn is_heading(line: &str) -> bool {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return !line.trim_start_matches('#').trim().is_empty();
+    }
+
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() || words.len() > MAX_HEADING_WORDS {
+        return false;
+    }
+    if line.chars().count() > MAX_HEADING_CHARS {
+        return false;
+    }
+    if line.ends_with(['.', ',', ';', '!', '?']) {
+        return false;
+    }
+
+    let mut letters = line.chars().filter(|c| c.is_alphabetic()).peekable();
+    if letters.peek().is_none() {
+        return false;
+    }
+    let all_caps = letters.all(|c| !c.is_lowercase());
+    // Short connecting words ("of", "and", "the") may stay lowercase in Title Case
+    let title_case = words.iter().enumerate().all(|(i, word)| {
+        let starts_upper = word.chars().next().is_some_and(|c| !c.is_lowercase());
+        starts_upper || (i > 0 && word.chars().count() <= 3)
+    });
+    all_caps || title_case
+}
+
+/// Collapse runs of whitespace (including line breaks) into single spaces, so engines
+/// don't stall on indentation or blank lines
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{EmojiMode, TextFormat};
+
+    #[test]
+    fn test_short_text_is_one_chunk() {
+        assert_eq!(chunks("Hello. World.", 100), ["Hello. World."]);
+    }
+
+    #[test]
+    fn test_chunks_end_at_sentences() {
+        let text = "First sentence here. Second one! Third?\nFourth line";
+        let parts = chunks(text, 35);
+        assert_eq!(
+            parts,
+            ["First sentence here. Second one!", "Third? Fourth line"]
+        );
+        assert!(parts.iter().all(|part| part.len() <= 35));
+        // Decimal points aren't sentence ends
+        assert_eq!(
+            chunks("Pi is 3.14 or so. Done", 20),
+            ["Pi is 3.14 or so.", "Done"]
+        );
+    }
+
+    #[test]
+    fn test_long_sentences_split_between_words() {
+        let text = "one two three four five six seven eight nine ten";
+        let parts = chunks(text, 15);
+        assert_eq!(parts.join(" "), text);
+        assert!(parts.iter().all(|part| part.len() <= 15));
+    }
+
+    #[test]
+    fn test_chunks_never_split_characters() {
+        // 3-byte and 4-byte characters with no sentence ends or spaces
+        let text = "日本語のテキスト".repeat(10) + &"😀".repeat(20);
+        let parts = chunks(&text, 10);
+        assert_eq!(parts.concat(), text);
+        for part in &parts {
+            assert!(!part.is_empty() && part.len() <= 10);
+        }
+
+        let accented = "ação é útil. ".repeat(20);
+        let parts = chunks(&accented, 10);
+        assert_eq!(parts.join(" "), accented.trim());
+        assert!(parts.iter().all(|part| part.len() <= 10));
+    }
+
+    #[test]
+    fn test_stream_pieces_are_sentences_unless_ssml() {
+        assert_eq!(
+            stream_pieces("One. Two!\nThree", false),
+            ["One.", "Two!", "Three"]
+        );
+        let ssml = "<speak>One. <break time=\"1s\"/> Two.</speak>";
+        assert_eq!(stream_pieces(ssml, true), [ssml]);
+    }
+
+    #[test]
+    fn test_whitespace_is_normalized() {
+        let options = PreprocessOptions::default();
+        assert_eq!(
+            preprocess("  Hello,\n\n    world!\t", &options),
+            "Hello, world!"
+        );
+    }
+
+    #[test]
+    fn test_heading_detection() {
+        assert!(is_heading("# Introduction"));
+        assert!(is_heading("## Getting Started"));
+        assert!(is_heading("CHAPTER ONE"));
+        assert!(is_heading("Terms of Service"));
+        assert!(!is_heading("This is a regular sentence."));
+        assert!(!is_heading("the quick brown fox"));
+        assert!(!is_heading("#"));
+        assert!(!is_heading("42"));
+        assert!(!is_heading(
+            "A Very Long Line That Has Far Too Many Words To Be A Heading"
+        ));
+    }
+
+    #[test]
+    fn test_split_structure_markdown_document() {
+        let doc = "# Introduction\nThis guide covers setup.\nIt is short.\n\n## Install\nRun the installer.";
+        let blocks = split_structure(doc);
+        let texts: Vec<&str> = blocks.iter().map(|b| b.text.trim()).collect();
+        assert_eq!(
+            texts,
+            [
+                "Introduction",
+                "This guide covers setup.\nIt is short.",
+                "Install",
+                "Run the installer."
+            ]
+        );
+        let pauses: Vec<Option<u32>> = blocks.iter().map(|b| b.pause_after_ms).collect();
+        assert_eq!(
+            pauses,
+            [
+                Some(PAUSE_AFTER_HEADING_MS),
+                Some(PAUSE_BEFORE_HEADING_MS),
+                Some(PAUSE_AFTER_HEADING_MS),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_structure_all_caps_headings() {
+        let doc = "Welcome to the report.\nSUMMARY\nSales grew this quarter.";
+        let blocks = split_structure(doc);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].pause_after_ms, Some(PAUSE_BEFORE_HEADING_MS));
+        assert_eq!(blocks[1].text, "SUMMARY");
+    }
+
+    #[test]
+    fn test_outline() {
+        let doc = "# Guide\nIntro text.\n## Install\nRun it.\n\nUSAGE\nCall it.";
+        let entries = outline(doc);
+        let summary: Vec<(&str, u8, u32)> = entries
+            .iter()
+            .map(|e| (e.title.as_str(), e.level, e.line))
+            .collect();
+        assert_eq!(
+            summary,
+            [("Guide", 1, 0), ("Install", 2, 2), ("USAGE", 1, 5)]
+        );
+
+        assert_eq!(from_line(doc, entries[2].line), "USAGE\nCall it.");
+        assert!(outline("Just one sentence.\nAnd another one.").is_empty());
+    }
+
+    #[test]
+    fn test_words_attach_punctuation() {
+        assert_eq!(
+            words("Wait ... what ?  « Really » ( yes )"),
+            ["Wait...", "what?", "«Really»", "(yes)"]
+        );
+        assert_eq!(words("Hello, world!"), ["Hello,", "world!"]);
+        assert!(words("  ").is_empty());
+        assert!(words("... !").is_empty());
+    }
+
+    #[test]
+    fn test_split_structure_plain_text_is_single_block() {
+        let blocks = split_structure("Just one sentence.\nAnd another one.");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].pause_after_ms, None);
+    }
+
+    #[test]
+    fn test_raw_bypasses_preprocessing() {
+        let code = "fn main() {\n    println!(\"https://example.com 🚀\");\n}";
+        let options = PreprocessOptions {
+            raw: true,
+            emoji_mode: EmojiMode::Describe,
+            format: TextFormat::Markdown,
+        };
+        assert_eq!(preprocess(code, &options), code);
+    }
+}
@@ -0,0 +1,695 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::models::{SequenceOverlap, ValidatedSpeakRequest};
+
+/// Event emitted when a queued utterance starts speaking
+#[derive(Debug, Clone)]
+pub(crate) struct QueueEvent {
+    pub name: &'static str,
+    pub payload: serde_json::Value,
+}
+
+/// How a queued utterance ended, reported to whoever waits on it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum UtteranceOutcome {
+    /// Spoken to the end, taking this long from submission
+    Finished(Duration),
+    /// Flushed before or while being spoken
+    Interrupted,
+    /// The engine refused the utterance
+    Failed(String),
+}
+
+/// An utterance waiting in the plugin-managed speech queue
+#[derive(Debug, Clone)]
+pub(crate) struct QueuedUtterance {
+    pub id: String,
+    pub request: ValidatedSpeakRequest,
+    pub on_start: Option<QueueEvent>,
+    pub on_finish: Option<Sender<UtteranceOutcome>>,
+    /// The `speak_sequence` call this utterance belongs to
+    pub sequence: Option<String>,
+    /// Put back by a pause after it started, so its start events were already emitted
+    pub resumed: bool,
+    /// Queued with `QueueMode::Urgent`, ahead of everything that isn't
+    pub urgent: bool,
+}
+
+impl QueuedUtterance {
+    pub fn new(id: String, request: ValidatedSpeakRequest) -> Self {
+        Self {
+            id,
+            request,
+            on_start: None,
+            on_finish: None,
+            sequence: None,
+            resumed: false,
+            urgent: false,
+        }
+    }
+
+    /// Queue items for prepared requests, each with a new id except the chunks
+    /// after the first of a long text, which share the id of the first
+    pub fn from_requests(requests: Vec<ValidatedSpeakRequest>) -> Vec<Self> {
+        let mut id = String::new();
+        requests
+            .into_iter()
+            .map(|request| {
+                if request.first_chunk || id.is_empty() {
+                    id = uuid::Uuid::new_v4().to_string();
+                }
+                Self::new(id.clone(), request)
+            })
+            .collect()
+    }
+
+    pub fn with_sequence(mut self, sequence_id: &str) -> Self {
+        self.sequence = Some(sequence_id.to_string());
+        self
+    }
+
+    pub fn with_start_event(mut self, name: &'static str, payload: serde_json::Value) -> Self {
+        self.on_start = Some(QueueEvent { name, payload });
+        self
+    }
+
+    pub fn with_finish_notifier(mut self, sender: Sender<UtteranceOutcome>) -> Self {
+        self.on_finish = Some(sender);
+        self
+    }
+
+    /// Report how the utterance ended; a dropped receiver is ignored
+    pub fn notify_finished(&self, outcome: UtteranceOutcome) {
+        if let Some(ref sender) = self.on_finish {
+            let _ = sender.send(outcome);
+        }
+    }
+
+    /// Whether this item starts an utterance in the queue, rather than going on
+    /// with the text of the item before it
+    pub fn starts_utterance(&self) -> bool {
+        self.request.first_chunk || self.resumed
+    }
+
+    /// Silence to leave after this utterance before the next one starts
+    pub fn pause_after(&self) -> Duration {
+        Duration::from_millis(u64::from(self.request.pause_after_ms.unwrap_or(0)))
+    }
+
+    /// Longest this utterance may play before the worker stops it
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.request
+            .max_duration_ms
+            .map(|ms| Duration::from_millis(u64::from(ms)))
+    }
+}
+
+/// How a new sequence was let into the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SequenceAdmission {
+    /// No earlier sequence was playing
+    Idle,
+    /// The new sequence goes after the earlier one
+    Appended,
+    /// The earlier sequence was dropped. When it was speaking, the engine must be
+    /// stopped too
+    Interrupted { stop_current: bool },
+}
+
+/// Speech queue owned by the plugin rather than the platform engine.
+///
+/// The engine only ever sees one utterance at a time, which lets the plugin switch
+/// voices between items, insert pauses and flush pending speech reliably.
+#[derive(Debug, Default)]
+pub(crate) struct SpeechQueue {
+    pending: VecDeque<QueuedUtterance>,
+    current: Option<String>,
+    /// Text of the current utterance, as handed to the engine
+    current_text: Option<String>,
+    /// Sequence of the current utterance, if any
+    current_sequence: Option<String>,
+    /// Whether the current utterance was queued as urgent
+    current_urgent: bool,
+    /// Bumped on every flush so in-flight work can tell it was cancelled
+    generation: u64,
+    /// Generation the pause interrupted, while paused
+    paused: Option<u64>,
+    /// Generation the last urgent utterance interrupted, to be resumed after it
+    preempted: Option<u64>,
+}
+
+impl SpeechQueue {
+    pub fn push(&mut self, item: QueuedUtterance) {
+        self.pending.push_back(item);
+    }
+
+    /// Take the next pending utterance and mark it as the one being spoken.
+    /// Nothing is taken while paused
+    pub fn start_next(&mut self) -> Option<QueuedUtterance> {
+        if self.paused.is_some() {
+            return None;
+        }
+        let item = self.pending.pop_front()?;
+        self.current = Some(item.id.clone());
+        self.current_text = Some(item.request.text.clone());
+        self.current_sequence = item.sequence.clone();
+        self.current_urgent = item.urgent;
+        Some(item)
+    }
+
+    /// Mark the utterance as done, if it is still the current one
+    pub fn finish(&mut self, id: &str) {
+        if self.current.as_deref() == Some(id) {
+            self.clear_current();
+        }
+    }
+
+    /// Drop the current and all pending utterances, returning how many were pending
+    pub fn flush(&mut self) -> usize {
+        let dropped = self.drop_pending();
+        self.clear_current();
+        self.paused = None;
+        self.preempted = None;
+        self.generation = self.generation.wrapping_add(1);
+        dropped
+    }
+
+    /// Drop the pending utterances but let the current one finish, returning how
+    /// many were dropped
+    pub fn clear_pending(&mut self) -> usize {
+        // The chunks left of a long text belong to the current utterance
+        let current = self.current.clone();
+        self.drop_pending_where(|item| current.as_ref() != Some(&item.id))
+    }
+
+    /// Drop one pending utterance, telling anyone waiting on it. The current one
+    /// is left alone, returning false like an unknown id
+    pub fn remove_pending(&mut self, id: &str) -> bool {
+        if self.current.as_deref() == Some(id) {
+            return false;
+        }
+        let before = self.pending.len();
+        self.drop_pending_where(|item| item.id == id);
+        self.pending.len() < before
+    }
+
+    /// Abandon the current utterance like a flush but keep the pending ones, so the
+    /// worker moves on to the next. Returns false when nothing is being spoken
+    pub fn skip_current(&mut self) -> bool {
+        let Some(current) = self.current.clone() else {
+            return false;
+        };
+        if self.paused.is_some() {
+            return false;
+        }
+        self.drop_pending_where(|item| item.id == current);
+        self.clear_current();
+        self.generation = self.generation.wrapping_add(1);
+        true
+    }
+
+    /// Queue an urgent utterance behind the other urgent ones but ahead of the rest
+    pub fn push_urgent(&mut self, mut item: QueuedUtterance) {
+        item.urgent = true;
+        let index = self.first_regular();
+        self.pending.insert(index, item);
+    }
+
+    /// Abandon the current utterance so urgent speech can start, to be resumed
+    /// after it like after a pause. Returns false when there is nothing to interrupt
+    pub fn preempt(&mut self) -> bool {
+        if self.current.is_none() || self.current_urgent || self.paused.is_some() {
+            return false;
+        }
+        self.preempted = Some(self.generation);
+        self.generation = self.generation.wrapping_add(1);
+        true
+    }
+
+    /// Hold the queue and abandon the current utterance like a flush, but keep
+    /// everything pending. Returns false when there is nothing to pause
+    pub fn pause(&mut self) -> bool {
+        if self.paused.is_some() {
+            return true;
+        }
+        if self.is_idle() {
+            return false;
+        }
+        self.paused = Some(self.generation);
+        self.generation = self.generation.wrapping_add(1);
+        true
+    }
+
+    /// Let the worker take utterances again. Returns false when not paused
+    pub fn resume(&mut self) -> bool {
+        self.paused.take().is_some()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Whether work started at `generation` was abandoned by the current pause or
+    /// by urgent speech rather than by a flush, and should be resumed
+    pub fn interrupted_to_resume(&self, generation: u64) -> bool {
+        self.paused == Some(generation) || self.preempted == Some(generation)
+    }
+
+    /// Put the rest of an interrupted utterance back at the front, behind urgent
+    /// speech, to be spoken first on resume
+    pub fn requeue(&mut self, mut item: QueuedUtterance, remaining_text: String) {
+        item.request.text = remaining_text;
+        item.resumed = true;
+        self.finish(&item.id);
+        let index = if item.urgent { 0 } else { self.first_regular() };
+        self.pending.insert(index, item);
+    }
+
+    /// Index of the first pending utterance that isn't urgent
+    fn first_regular(&self) -> usize {
+        self.pending
+            .iter()
+            .position(|item| !item.urgent)
+            .unwrap_or(self.pending.len())
+    }
+
+    /// Empty the pending queue, telling anyone waiting on those utterances
+    fn drop_pending(&mut self) -> usize {
+        self.drop_pending_where(|_| true)
+    }
+
+    /// Drop the pending items `dropped` picks, telling anyone waiting on them.
+    /// Returns how many utterances were dropped, counting a chunked text once
+    fn drop_pending_where(&mut self, dropped: impl Fn(&QueuedUtterance) -> bool) -> usize {
+        let mut count = 0;
+        let mut kept = VecDeque::with_capacity(self.pending.len());
+        for item in self.pending.drain(..) {
+            if !dropped(&item) {
+                kept.push_back(item);
+                continue;
+            }
+            if item.starts_utterance() {
+                count += 1;
+            }
+            item.notify_finished(UtteranceOutcome::Interrupted);
+        }
+        self.pending = kept;
+        count
+    }
+
+    fn clear_current(&mut self) {
+        self.current = None;
+        self.current_text = None;
+        self.current_sequence = None;
+        self.current_urgent = false;
+    }
+
+    /// Whether an utterance of a `speak_sequence` call is speaking or waiting
+    pub fn has_sequence(&self) -> bool {
+        self.current_sequence.is_some() || self.pending.iter().any(|item| item.sequence.is_some())
+    }
+
+    /// Apply `policy` before a new sequence is queued. Other speech is left alone
+    pub fn admit_sequence(&mut self, policy: SequenceOverlap) -> crate::Result<SequenceAdmission> {
+        if !self.has_sequence() {
+            return Ok(SequenceAdmission::Idle);
+        }
+        match policy {
+            SequenceOverlap::Reject => Err(crate::Error::SequenceActive),
+            SequenceOverlap::Append => Ok(SequenceAdmission::Appended),
+            SequenceOverlap::Interrupt => {
+                self.pending.retain(|item| {
+                    if item.sequence.is_some() {
+                        item.notify_finished(UtteranceOutcome::Interrupted);
+                    }
+                    item.sequence.is_none()
+                });
+                let stop_current = self.current_sequence.is_some();
+                if stop_current {
+                    self.clear_current();
+                    // Makes the worker abandon the utterance like a flush would
+                    self.generation = self.generation.wrapping_add(1);
+                }
+                Ok(SequenceAdmission::Interrupted { stop_current })
+            }
+        }
+    }
+
+    /// Utterances waiting behind the current one
+    pub fn pending_len(&self) -> usize {
+        self.pending().count()
+    }
+
+    /// Utterances waiting behind the current one, next first. A long text split
+    /// into chunks is listed once, by its first chunk
+    pub fn pending(&self) -> impl Iterator<Item = &QueuedUtterance> {
+        self.pending.iter().filter(|item| item.starts_utterance())
+    }
+
+    pub fn current_text(&self) -> Option<&str> {
+        self.current_text.as_deref()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.current.is_none() && self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{QueueMode, SpeakRequest};
+
+    fn utterance(id: &str) -> QueuedUtterance {
+        let json = format!(r#"{{"text": "Utterance {}", "queueMode": "add"}}"#, id);
+        let request: SpeakRequest = serde_json::from_str(&json).unwrap();
+        QueuedUtterance::new(id.to_string(), request.validate().unwrap())
+    }
+
+    #[test]
+    fn test_queue_is_fifo() {
+        let mut queue = SpeechQueue::default();
+        assert!(queue.is_idle());
+
+        queue.push(utterance("a"));
+        queue.push(utterance("b"));
+        assert!(!queue.is_idle());
+
+        assert_eq!(queue.start_next().unwrap().id, "a");
+        assert_eq!(queue.start_next().unwrap().id, "b");
+        assert!(queue.start_next().is_none());
+    }
+
+    #[test]
+    fn test_finish_only_clears_current() {
+        let mut queue = SpeechQueue::default();
+        queue.push(utterance("a"));
+        queue.start_next();
+
+        queue.finish("other");
+        assert!(!queue.is_idle());
+        assert_eq!(queue.current_text(), Some("Utterance a"));
+
+        queue.finish("a");
+        assert!(queue.is_idle());
+        assert_eq!(queue.current_text(), None);
+    }
+
+    #[test]
+    fn test_flush_drops_everything_and_bumps_generation() {
+        let mut queue = SpeechQueue::default();
+        queue.push(utterance("a"));
+        queue.push(utterance("b"));
+        queue.push(utterance("c"));
+        queue.start_next();
+
+        let generation = queue.generation();
+        assert_eq!(queue.flush(), 2);
+        assert!(queue.is_idle());
+        assert_ne!(queue.generation(), generation);
+    }
+
+    #[test]
+    fn test_clear_pending_keeps_current() {
+        let mut queue = SpeechQueue::default();
+        queue.push(utterance("a"));
+        queue.push(utterance("b"));
+        queue.push(utterance("c"));
+        queue.start_next();
+        assert_eq!(queue.pending_len(), 2);
+
+        let generation = queue.generation();
+        assert_eq!(queue.clear_pending(), 2);
+        assert_eq!(queue.pending_len(), 0);
+        assert_eq!(queue.current_text(), Some("Utterance a"));
+        // The worker must not abandon the current utterance
+        assert_eq!(queue.generation(), generation);
+        assert!(queue.start_next().is_none());
+    }
+
+    #[test]
+    fn test_remove_pending_drops_only_that_utterance() {
+        let mut queue = SpeechQueue::default();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        queue.push(utterance("a"));
+        queue.push(utterance("b").with_finish_notifier(sender));
+        queue.push(utterance("c"));
+        queue.start_next();
+
+        let generation = queue.generation();
+        assert!(!queue.remove_pending("a"));
+        assert!(!queue.remove_pending("unknown"));
+        assert!(queue.remove_pending("b"));
+        assert_eq!(receiver.try_recv(), Ok(UtteranceOutcome::Interrupted));
+        assert_eq!(queue.generation(), generation);
+        assert_eq!(queue.current_text(), Some("Utterance a"));
+        queue.finish("a");
+        assert_eq!(queue.start_next().unwrap().id, "c");
+    }
+
+    #[test]
+    fn test_skip_current_keeps_pending() {
+        let mut queue = SpeechQueue::default();
+        assert!(!queue.skip_current());
+        queue.push(utterance("a"));
+        queue.push(utterance("b"));
+        queue.start_next();
+
+        let generation = queue.generation();
+        assert!(queue.skip_current());
+        assert_ne!(queue.generation(), generation);
+        assert_eq!(queue.current_text(), None);
+        assert_eq!(queue.pending_len(), 1);
+        // The worker finishing the skipped utterance must not clear the next one
+        let next = queue.start_next().unwrap();
+        queue.finish("a");
+        assert_eq!(queue.current_text(), Some("Utterance b"));
+        assert_eq!(next.id, "b");
+
+        assert!(queue.pause());
+        assert!(!queue.skip_current());
+    }
+
+    #[test]
+    fn test_chunks_of_one_utterance_share_its_id() {
+        let sentence = "This sentence is exactly forty bytes ok. ";
+        let request = SpeakRequest {
+            text: sentence.repeat(5),
+            queue_mode: Some(QueueMode::Add),
+            ..Default::default()
+        };
+        let chunks = request.validate().unwrap().into_chunks(100);
+        let parts = QueuedUtterance::from_requests(chunks);
+        assert_eq!(parts.len(), 3);
+        let id = parts[0].id.clone();
+        assert!(parts.iter().all(|part| part.id == id));
+
+        let mut queue = SpeechQueue::default();
+        for part in parts {
+            queue.push(part);
+        }
+        queue.push(utterance("b"));
+        // The chunks count as the one utterance they make up
+        assert_eq!(queue.pending_len(), 2);
+        queue.start_next();
+        assert_eq!(queue.pending_len(), 1);
+
+        // Skipping drops the rest of the utterance, not just the chunk
+        assert!(queue.skip_current());
+        assert_eq!(queue.start_next().unwrap().id, "b");
+    }
+
+    #[test]
+    fn test_pause_keeps_pending_until_resumed() {
+        let mut queue = SpeechQueue::default();
+        assert!(!queue.pause());
+
+        queue.push(utterance("a"));
+        queue.push(utterance("b"));
+        let item = queue.start_next().unwrap();
+        let generation = queue.generation();
+
+        assert!(queue.pause());
+        assert!(queue.is_paused());
+        assert_ne!(queue.generation(), generation);
+        assert!(queue.interrupted_to_resume(generation));
+        queue.requeue(item, "rest of a".to_string());
+        assert!(queue.start_next().is_none());
+        assert_eq!(queue.pending_len(), 2);
+
+        assert!(queue.resume());
+        assert!(!queue.resume());
+        let resumed = queue.start_next().unwrap();
+        assert_eq!(resumed.id, "a");
+        assert_eq!(resumed.request.text, "rest of a");
+        assert!(resumed.resumed);
+    }
+
+    #[test]
+    fn test_flush_cancels_pause() {
+        let mut queue = SpeechQueue::default();
+        queue.push(utterance("a"));
+        queue.start_next();
+        let generation = queue.generation();
+
+        assert!(queue.pause());
+        queue.flush();
+        assert!(!queue.is_paused());
+        // The worker must drop the utterance rather than put it back
+        assert!(!queue.interrupted_to_resume(generation));
+    }
+
+    #[test]
+    fn test_urgent_preempts_and_resumes() {
+        let mut queue = SpeechQueue::default();
+        queue.push(utterance("a"));
+        queue.push(utterance("b"));
+        let item = queue.start_next().unwrap();
+        let generation = queue.generation();
+
+        queue.push_urgent(utterance("u1"));
+        assert!(queue.preempt());
+        assert!(queue.interrupted_to_resume(generation));
+        queue.requeue(item, "rest of a".to_string());
+        queue.push_urgent(utterance("u2"));
+
+        let order: Vec<_> = queue.pending().map(|item| item.id.as_str()).collect();
+        assert_eq!(order, ["u1", "u2", "a", "b"]);
+        // Urgent speech isn't interrupted by more urgent speech
+        queue.start_next();
+        assert!(!queue.preempt());
+        assert!(queue.pending().nth(1).unwrap().resumed);
+    }
+
+    fn sequence(queue: &mut SpeechQueue, id: &str, len: usize) {
+        for index in 0..len {
+            queue.push(utterance(&format!("{}{}", id, index)).with_sequence(id));
+        }
+    }
+
+    #[test]
+    fn test_first_sequence_is_admitted_under_any_policy() {
+        for policy in [
+            SequenceOverlap::Interrupt,
+            SequenceOverlap::Append,
+            SequenceOverlap::Reject,
+        ] {
+            let mut queue = SpeechQueue::default();
+            queue.push(utterance("plain"));
+            assert_eq!(
+                queue.admit_sequence(policy).unwrap(),
+                SequenceAdmission::Idle
+            );
+        }
+    }
+
+    #[test]
+    fn test_overlapping_sequence_interrupts() {
+        let mut queue = SpeechQueue::default();
+        sequence(&mut queue, "a", 3);
+        queue.push(utterance("plain"));
+        queue.start_next();
+        let generation = queue.generation();
+
+        assert_eq!(
+            queue.admit_sequence(SequenceOverlap::Interrupt).unwrap(),
+            SequenceAdmission::Interrupted { stop_current: true }
+        );
+        assert_ne!(queue.generation(), generation);
+        assert!(!queue.has_sequence());
+        // Speech that isn't part of a sequence survives
+        assert_eq!(queue.start_next().unwrap().id, "plain");
+    }
+
+    #[test]
+    fn test_overlapping_sequence_waiting_behind_other_speech_is_dropped() {
+        let mut queue = SpeechQueue::default();
+        queue.push(utterance("plain"));
+        sequence(&mut queue, "a", 2);
+        queue.start_next();
+        let generation = queue.generation();
+
+        assert_eq!(
+            queue.admit_sequence(SequenceOverlap::Interrupt).unwrap(),
+            SequenceAdmission::Interrupted {
+                stop_current: false
+            }
+        );
+        assert_eq!(queue.generation(), generation);
+        assert_eq!(queue.current_text(), Some("Utterance plain"));
+        assert!(queue.start_next().is_none());
+    }
+
+    #[test]
+    fn test_overlapping_sequence_appends() {
+        let mut queue = SpeechQueue::default();
+        sequence(&mut queue, "a", 2);
+        queue.start_next();
+
+        assert_eq!(
+            queue.admit_sequence(SequenceOverlap::Append).unwrap(),
+            SequenceAdmission::Appended
+        );
+        sequence(&mut queue, "b", 1);
+        let order: Vec<_> = std::iter::from_fn(|| queue.start_next().map(|item| item.id)).collect();
+        assert_eq!(order, ["a1", "b0"]);
+    }
+
+    #[test]
+    fn test_overlapping_sequence_is_rejected() {
+        let mut queue = SpeechQueue::default();
+        sequence(&mut queue, "a", 1);
+        queue.start_next();
+
+        assert!(matches!(
+            queue.admit_sequence(SequenceOverlap::Reject),
+            Err(crate::Error::SequenceActive)
+        ));
+        assert!(queue.has_sequence());
+
+        // Once the sequence is done, a new one is welcome again
+        queue.finish("a0");
+        assert_eq!(
+            queue.admit_sequence(SequenceOverlap::Reject).unwrap(),
+            SequenceAdmission::Idle
+        );
+    }
+
+    #[test]
+    fn test_finish_notifier() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let item = utterance("a").with_finish_notifier(sender);
+        item.notify_finished(UtteranceOutcome::Finished(Duration::from_millis(1200)));
+        assert_eq!(
+            receiver.recv().unwrap(),
+            UtteranceOutcome::Finished(Duration::from_millis(1200))
+        );
+
+        // Flushed and cleared items are reported as interrupted before being dropped
+        let mut queue = SpeechQueue::default();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        queue.push(utterance("b").with_finish_notifier(sender.clone()));
+        queue.flush();
+        queue.push(utterance("c").with_finish_notifier(sender));
+        queue.clear_pending();
+        assert_eq!(
+            receiver.iter().collect::<Vec<_>>(),
+            [UtteranceOutcome::Interrupted, UtteranceOutcome::Interrupted]
+        );
+    }
+
+    #[test]
+    fn test_pause_after() {
+        let mut item = utterance("a");
+        assert_eq!(item.pause_after(), Duration::ZERO);
+
+        item.request.pause_after_ms = Some(250);
+        assert_eq!(item.pause_after(), Duration::from_millis(250));
+    }
+}
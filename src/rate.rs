@@ -0,0 +1,80 @@
+use crate::models::{MAX_RATE, MIN_RATE};
+
+/// Map a user rate (1.0 = normal, `MIN_RATE` to `MAX_RATE`) onto an engine's rate
+/// range. Each half of the user scale is stretched linearly over its side of
+/// `normal`, since engines rarely center `normal` between `min` and `max`
+pub(crate) fn map_rate(user: f32, min: f32, normal: f32, max: f32) -> f32 {
+    let user = user.clamp(MIN_RATE, MAX_RATE);
+    if user <= 1.0 {
+        let t = (user - MIN_RATE) / (1.0 - MIN_RATE);
+        min + t * (normal - min)
+    } else {
+        let t = (user - 1.0) / (MAX_RATE - 1.0);
+        normal + t * (max - normal)
+    }
+}
+
+/// Inverse of [`map_rate`]: the user rate an engine rate corresponds to
+pub(crate) fn unmap_rate(engine: f32, min: f32, normal: f32, max: f32) -> f32 {
+    if engine <= normal {
+        let span = normal - min;
+        let t = if span > 0.0 {
+            ((engine - min) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        MIN_RATE + t * (1.0 - MIN_RATE)
+    } else {
+        let span = max - normal;
+        let t = if span > 0.0 {
+            ((engine - normal) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        1.0 + t * (MAX_RATE - 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (min, normal, max) as reported by the `tts` backends
+    const AV_FOUNDATION: (f32, f32, f32) = (0.1, 0.5, 2.0);
+    const WIN_RT: (f32, f32, f32) = (0.5, 1.0, 6.0);
+    const SPEECH_DISPATCHER: (f32, f32, f32) = (-100.0, 0.0, 100.0);
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_anchors_map_onto_engine_range() {
+        for (min, normal, max) in [AV_FOUNDATION, WIN_RT, SPEECH_DISPATCHER] {
+            assert!(close(map_rate(MIN_RATE, min, normal, max), min));
+            assert!(close(map_rate(1.0, min, normal, max), normal));
+            assert!(close(map_rate(MAX_RATE, min, normal, max), max));
+        }
+    }
+
+    #[test]
+    fn test_linear_on_each_side_of_normal() {
+        let (min, normal, max) = SPEECH_DISPATCHER;
+        let halfway_down = (MIN_RATE + 1.0) / 2.0;
+        let halfway_up = (1.0 + MAX_RATE) / 2.0;
+        assert!(close(map_rate(halfway_down, min, normal, max), -50.0));
+        assert!(close(map_rate(halfway_up, min, normal, max), 50.0));
+        // Out-of-range input stays within the engine's range
+        assert!(close(map_rate(10.0, min, normal, max), max));
+    }
+
+    #[test]
+    fn test_unmap_round_trips() {
+        for (min, normal, max) in [AV_FOUNDATION, WIN_RT, SPEECH_DISPATCHER] {
+            for user in [MIN_RATE, 0.5, 1.0, 1.5, MAX_RATE] {
+                let engine = map_rate(user, min, normal, max);
+                assert!(close(unmap_rate(engine, min, normal, max), user));
+            }
+        }
+    }
+}
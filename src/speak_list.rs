@@ -0,0 +1,139 @@
+use crate::models::SpeakRequest;
+
+/// A list started by `speak_list_begin`, advanced one item per `speak_list_next`
+#[derive(Debug)]
+struct SpeakList {
+    id: String,
+    items: Vec<SpeakRequest>,
+    next: usize,
+}
+
+/// User-paced speak lists, forgotten once their last item was spoken
+#[derive(Debug, Default)]
+pub(crate) struct SpeakLists {
+    lists: Vec<SpeakList>,
+}
+
+impl SpeakLists {
+    /// Lists kept at once; beginning another forgets the oldest
+    const MAX_LISTS: usize = 32;
+
+    /// Start a list, returning its session id
+    pub fn begin(&mut self, items: Vec<SpeakRequest>) -> String {
+        if self.lists.len() >= Self::MAX_LISTS {
+            let dropped = self.lists.remove(0);
+            log::debug!("Forgetting abandoned speak list {}", dropped.id);
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        self.lists.push(SpeakList {
+            id: id.clone(),
+            items,
+            next: 0,
+        });
+        id
+    }
+
+    /// The item to speak next and its index, without moving past it
+    pub fn peek(&self, id: &str) -> crate::Result<(usize, SpeakRequest)> {
+        let list = self
+            .lists
+            .iter()
+            .find(|list| list.id == id)
+            .ok_or_else(|| crate::Error::SpeakListNotFound(id.to_string()))?;
+        Ok((list.next, list.items[list.next].clone()))
+    }
+
+    /// Move past the current item, returning whether items remain. Finished lists
+    /// are removed
+    pub fn advance(&mut self, id: &str) -> bool {
+        let Some(pos) = self.lists.iter().position(|list| list.id == id) else {
+            return false;
+        };
+        let list = &mut self.lists[pos];
+        list.next += 1;
+        if list.next < list.items.len() {
+            return true;
+        }
+        self.lists.remove(pos);
+        false
+    }
+
+    /// Items not spoken yet, including the current one; 0 for unknown lists
+    pub fn remaining(&self, id: &str) -> usize {
+        self.lists
+            .iter()
+            .find(|list| list.id == id)
+            .map_or(0, |list| list.items.len() - list.next)
+    }
+
+    /// Forget a list, returning whether it existed
+    pub fn reset(&mut self, id: &str) -> bool {
+        let before = self.lists.len();
+        self.lists.retain(|list| list.id != id);
+        self.lists.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(texts: &[&str]) -> Vec<SpeakRequest> {
+        texts
+            .iter()
+            .map(|text| SpeakRequest {
+                text: text.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_walks_items_in_order_then_forgets_the_list() {
+        let mut lists = SpeakLists::default();
+        let id = lists.begin(items(&["one", "two"]));
+
+        let (index, item) = lists.peek(&id).unwrap();
+        assert_eq!((index, item.text.as_str()), (0, "one"));
+        // Peeking again doesn't skip anything
+        assert_eq!(lists.peek(&id).unwrap().0, 0);
+        assert_eq!(lists.remaining(&id), 2);
+        assert!(lists.advance(&id));
+
+        let (index, item) = lists.peek(&id).unwrap();
+        assert_eq!((index, item.text.as_str()), (1, "two"));
+        assert_eq!(lists.remaining(&id), 1);
+        assert!(!lists.advance(&id));
+        assert_eq!(lists.remaining(&id), 0);
+
+        assert!(matches!(
+            lists.peek(&id),
+            Err(crate::Error::SpeakListNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_reset_forgets_only_that_list() {
+        let mut lists = SpeakLists::default();
+        let first = lists.begin(items(&["a", "b"]));
+        let second = lists.begin(items(&["c"]));
+
+        assert!(lists.reset(&first));
+        assert!(!lists.reset(&first));
+        assert!(lists.peek(&first).is_err());
+        assert_eq!(lists.peek(&second).unwrap().1.text, "c");
+    }
+
+    #[test]
+    fn test_oldest_list_is_evicted() {
+        let mut lists = SpeakLists::default();
+        let oldest = lists.begin(items(&["a"]));
+        for _ in 1..SpeakLists::MAX_LISTS {
+            lists.begin(items(&["b"]));
+        }
+        assert!(lists.peek(&oldest).is_ok());
+
+        lists.begin(items(&["c"]));
+        assert!(lists.peek(&oldest).is_err());
+    }
+}
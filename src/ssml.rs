@@ -0,0 +1,456 @@
+#[cfg(any(mobile, test))]
+use crate::models::Pronunciation;
+use crate::models::MAX_PAUSE_MS;
+use crate::preprocess::StructuralBlock;
+
+/// Warning returned when SSML is spoken as plain text because the engine can't read markup
+pub(crate) const PLAIN_TEXT_WARNING: &str =
+    "SSML is not supported by this engine, speaking the text without markup";
+
+/// Check that `markup` is a single well-formed `<speak>` element: tags nested
+/// and closed, attribute values quoted and every `&` starting a known entity.
+/// Returns a description of the first problem found
+pub(crate) fn check(markup: &str) -> Result<(), String> {
+    let mut open: Vec<&str> = Vec::new();
+    let mut root_closed = false;
+    let mut rest = markup.trim();
+    if let Some(declaration) = rest.strip_prefix("<?xml") {
+        let end = declaration
+            .find("?>")
+            .ok_or("unterminated XML declaration")?;
+        rest = &declaration[end + 2..];
+    }
+
+    loop {
+        let (content, tail) = rest.split_at(rest.find('<').unwrap_or(rest.len()));
+        if open.is_empty() && !content.trim().is_empty() {
+            return Err("text outside the <speak> element".to_string());
+        }
+        check_entities(content)?;
+        if tail.is_empty() {
+            break;
+        }
+        if let Some(comment) = tail.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or("unterminated comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+
+        let end = tail.find('>').ok_or("unterminated tag")?;
+        let tag = &tail[1..end];
+        rest = &tail[end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim_end();
+            match open.pop() {
+                Some(opened) if opened == name => {}
+                Some(opened) => return Err(format!("</{name}> closes <{opened}>")),
+                None => return Err(format!("</{name}> has no opening tag")),
+            }
+            root_closed = open.is_empty();
+            continue;
+        }
+
+        let (tag, self_closing) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let name = tag_name(tag)?;
+        if open.is_empty() && (root_closed || name != "speak") {
+            return Err("the markup must be a single <speak> element".to_string());
+        }
+        if self_closing {
+            root_closed = open.is_empty();
+        } else {
+            open.push(name);
+        }
+    }
+
+    if let Some(name) = open.last() {
+        return Err(format!("<{name}> is never closed"));
+    }
+    if !root_closed {
+        return Err("missing <speak> element".to_string());
+    }
+    Ok(())
+}
+
+/// Pause of a `<break>` without `time` or `strength`, and of `strength="medium"`
+const MEDIUM_BREAK_MS: u32 = 500;
+/// Pause at the end of a `<p>` paragraph followed by more text
+const PARAGRAPH_PAUSE_MS: u32 = 750;
+
+/// Text for engines that can't read SSML: tags dropped, with a space where a
+/// `<break>`, sentence or paragraph separated words, and entities decoded
+pub(crate) fn to_plain_text(markup: &str) -> String {
+    to_plain_blocks(markup)
+        .into_iter()
+        .map(|block| block.text)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Like `to_plain_text`, but split wherever a `<break>` or the end of a paragraph
+/// asks for silence, so it can be kept as a pause between utterances
+pub(crate) fn to_plain_blocks(markup: &str) -> Vec<StructuralBlock> {
+    let mut blocks: Vec<StructuralBlock> = Vec::new();
+    let mut plain = String::with_capacity(markup.len());
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        push_decoded(&mut plain, &rest[..start]);
+        let tail = &rest[start..];
+        let end = if tail.starts_with("<!--") {
+            tail.find("-->").map(|end| end + 3)
+        } else {
+            tail.find('>').map(|end| end + 1)
+        }
+        .unwrap_or(tail.len());
+
+        let tag = &tail[1..end];
+        let name = tag
+            .trim_start_matches(['/', '?', '!'])
+            .split(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .next()
+            .unwrap_or_default();
+        if matches!(name, "speak" | "break" | "p" | "s") {
+            plain.push(' ');
+        }
+        let pause = match name {
+            "break" => break_ms(tag),
+            "p" if tag.starts_with('/') => PARAGRAPH_PAUSE_MS,
+            _ => 0,
+        };
+        if pause > 0 {
+            let text = normalize(&std::mem::take(&mut plain));
+            if !text.is_empty() {
+                blocks.push(StructuralBlock {
+                    text,
+                    pause_after_ms: Some(pause),
+                });
+            } else if let Some(previous) = blocks.last_mut() {
+                let total = previous.pause_after_ms.unwrap_or(0) + pause;
+                previous.pause_after_ms = Some(total.min(MAX_PAUSE_MS));
+            }
+            // Silence before any text is dropped, the engine starts right away
+        }
+        rest = &tail[end..];
+    }
+    push_decoded(&mut plain, rest);
+
+    let text = normalize(&plain);
+    if !text.is_empty() || blocks.is_empty() {
+        blocks.push(StructuralBlock {
+            text,
+            pause_after_ms: None,
+        });
+    } else if let Some(last) = blocks.last_mut() {
+        // Trailing silence is left to the request's own pause_after_ms
+        last.pause_after_ms = None;
+    }
+    blocks
+}
+
+/// Silence asked for by a `<break>` tag, from its `time` ("500ms", "1.5s") or
+/// else its `strength`, capped like `pause_after_ms`
+fn break_ms(tag: &str) -> u32 {
+    if let Some(time) = attribute(tag, "time") {
+        let ms = match time.strip_suffix("ms") {
+            Some(ms) => ms.trim().parse::<f64>().ok(),
+            None => time
+                .strip_suffix('s')
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .map(|s| s * 1000.0),
+        };
+        if let Some(ms) = ms.filter(|ms| ms.is_finite() && *ms >= 0.0) {
+            return ms.min(f64::from(MAX_PAUSE_MS)) as u32;
+        }
+    }
+    match attribute(tag, "strength") {
+        Some("none") => 0,
+        Some("x-weak") => 100,
+        Some("weak") => 250,
+        Some("strong") => 1000,
+        Some("x-strong") => 1500,
+        _ => MEDIUM_BREAK_MS,
+    }
+}
+
+/// Value of the attribute `name` in a tag that passed `check`
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let before = rest[..index].chars().next_back();
+        let after = rest[index + name.len()..].trim_start();
+        rest = &rest[index + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=').map(str::trim_start) else {
+            continue;
+        };
+        let quote = value.chars().next()?;
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// SSML document speaking `text` with every whole-word, case-insensitive match of
+/// a pronunciation's word wrapped in an IPA `<phoneme>`. The first matching
+/// pronunciation wins. `None` when no word matches, so the text can stay plain
+#[cfg(any(mobile, test))]
+pub(crate) fn with_phonemes(text: &str, pronunciations: &[Pronunciation]) -> Option<String> {
+    let mut markup = String::from("<speak>");
+    let mut plain_start = 0;
+    let mut index = 0;
+    let mut previous: Option<char> = None;
+    while let Some(c) = text[index..].chars().next() {
+        let rest = &text[index..];
+        let found = if previous.is_some_and(char::is_alphanumeric) {
+            None
+        } else {
+            pronunciations.iter().find_map(|pronunciation| {
+                whole_word_len(rest, pronunciation.word.trim())
+                    .filter(|&len| len > 0)
+                    .map(|len| (pronunciation, len))
+            })
+        };
+        let Some((pronunciation, len)) = found else {
+            previous = Some(c);
+            index += c.len_utf8();
+            continue;
+        };
+
+        push_escaped(&mut markup, &text[plain_start..index]);
+        markup.push_str("<phoneme alphabet=\"ipa\" ph=\"");
+        push_escaped(&mut markup, pronunciation.ipa.trim());
+        markup.push_str("\">");
+        push_escaped(&mut markup, &rest[..len]);
+        markup.push_str("</phoneme>");
+        previous = rest[..len].chars().last();
+        index += len;
+        plain_start = index;
+    }
+    // Nothing was wrapped
+    if plain_start == 0 {
+        return None;
+    }
+    push_escaped(&mut markup, &text[plain_start..]);
+    markup.push_str("</speak>");
+    Some(markup)
+}
+
+/// Byte length of `word` at the start of `text`, compared case-insensitively,
+/// unless the text goes on with more letters or digits of a longer word
+pub(crate) fn whole_word_len(text: &str, word: &str) -> Option<usize> {
+    let mut chars = text.chars();
+    let mut len = 0;
+    for expected in word.chars() {
+        let c = chars.next()?;
+        if !c.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+        len += c.len_utf8();
+    }
+    match chars.next() {
+        Some(next) if next.is_alphanumeric() => None,
+        _ => Some(len),
+    }
+}
+
+#[cfg(any(mobile, test, feature = "azure", feature = "polly"))]
+pub(crate) fn push_escaped(markup: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => markup.push_str("&amp;"),
+            '<' => markup.push_str("&lt;"),
+            '>' => markup.push_str("&gt;"),
+            '"' => markup.push_str("&quot;"),
+            '\'' => markup.push_str("&apos;"),
+            c => markup.push(c),
+        }
+    }
+}
+
+/// Name of an opening tag, after checking its attributes are `name="value"` pairs
+fn tag_name(tag: &str) -> Result<&str, String> {
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = &tag[..name_end];
+    if !is_name(name) {
+        return Err(format!("invalid tag <{tag}>"));
+    }
+
+    let mut attributes = tag[name_end..].trim_start();
+    while !attributes.is_empty() {
+        let (attribute, value) = attributes
+            .split_once('=')
+            .ok_or_else(|| format!("attribute without a value in <{name}>"))?;
+        if !is_name(attribute.trim_end()) {
+            return Err(format!("invalid attribute in <{name}>"));
+        }
+        let value = value.trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+            .ok_or_else(|| format!("unquoted attribute value in <{name}>"))?;
+        let value = &value[1..];
+        let end = value
+            .find(quote)
+            .ok_or_else(|| format!("unterminated attribute value in <{name}>"))?;
+        check_entities(&value[..end])?;
+
+        let after = &value[end + 1..];
+        if !after.is_empty() && !after.starts_with(char::is_whitespace) {
+            return Err(format!(
+                "attributes in <{name}> must be separated by spaces"
+            ));
+        }
+        attributes = after.trim_start();
+    }
+    Ok(name)
+}
+
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+fn check_entities(text: &str) -> Result<(), String> {
+    for (index, _) in text.match_indices('&') {
+        let known = text[index + 1..]
+            .split_once(';')
+            .is_some_and(|(entity, _)| decode_entity(entity).is_some());
+        if !known {
+            return Err("'&' must start an entity such as &amp;".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn push_decoded(plain: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        plain.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let decoded = rest
+            .split_once(';')
+            .and_then(|(entity, after)| Some((decode_entity(entity)?, after)));
+        match decoded {
+            Some((c, after)) => {
+                plain.push(c);
+                rest = after;
+            }
+            None => plain.push('&'),
+        }
+    }
+    plain.push_str(rest);
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match entity.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_well_formed_markup() {
+        let markup = r#"<?xml version="1.0"?>
+            <speak version="1.1" xml:lang="en-US">
+              <p>Wait<break time='500ms'/> for it &amp; <emphasis level="strong">now</emphasis>.</p>
+              <!-- ignored -->
+            </speak>"#;
+        assert_eq!(check(markup), Ok(()));
+        assert_eq!(check("<speak/>"), Ok(()));
+    }
+
+    #[test]
+    fn test_rejects_malformed_markup() {
+        for markup in [
+            "Hello",
+            "<speak>Hello",
+            "<speak>Hello</p></speak>",
+            "<speak><p>Hello</speak></p>",
+            "<speak>Hello</speak><speak>again</speak>",
+            "<speak>Hello</speak> world",
+            "<p>Hello</p>",
+            "<speak>Fish & chips</speak>",
+            "<speak><break time=500ms/></speak>",
+            "<speak><break time=\"500ms/></speak>",
+            "<speak><1st/></speak>",
+        ] {
+            assert!(check(markup).is_err(), "{markup}");
+        }
+    }
+
+    #[test]
+    fn test_phonemes_replace_whole_words() {
+        let pronunciations = [Pronunciation {
+            word: "tomato".to_string(),
+            ipa: "təˈmɑːtəʊ".to_string(),
+        }];
+        let markup = with_phonemes("Tomato, tomatoes & TOMATO", &pronunciations).unwrap();
+
+        assert_eq!(
+            markup,
+            "<speak><phoneme alphabet=\"ipa\" ph=\"təˈmɑːtəʊ\">Tomato</phoneme>, \
+             tomatoes &amp; <phoneme alphabet=\"ipa\" ph=\"təˈmɑːtəʊ\">TOMATO</phoneme></speak>"
+        );
+        assert_eq!(check(&markup), Ok(()));
+        assert_eq!(to_plain_text(&markup), "Tomato, tomatoes & TOMATO");
+        assert_eq!(with_phonemes("Green tomatoes", &pronunciations), None);
+        assert!(with_phonemes("Sun-dried-tomato", &pronunciations).is_some());
+    }
+
+    #[test]
+    fn test_plain_text_drops_tags_between_words() {
+        let markup = "<speak><s>Hello</s><s>world</s>Wait<break time=\"1s\"/>now, \
+                      <emphasis>really</emphasis>. Fish &amp; chips &#x263A;</speak>";
+        assert_eq!(
+            to_plain_text(markup),
+            "Hello world Wait now, really. Fish & chips \u{263A}"
+        );
+    }
+
+    #[test]
+    fn test_plain_blocks_keep_breaks_as_pauses() {
+        let block = |text: &str, pause_after_ms| StructuralBlock {
+            text: text.to_string(),
+            pause_after_ms,
+        };
+        let markup = "<speak><break/><p>Ready<break time=\"1.5s\"/>set</p>\
+                      <p>go<break strength='weak'/><break time=\"100ms\"/></p>\
+                      <p>Done<break strength=\"none\"/>now<break time=\"60s\"/></p></speak>";
+        assert_eq!(
+            to_plain_blocks(markup),
+            [
+                block("Ready", Some(1500)),
+                block("set", Some(750)),
+                block("go", Some(1100)),
+                block("Done now", None),
+            ]
+        );
+        assert_eq!(to_plain_text(markup), "Ready set go Done now");
+        assert_eq!(to_plain_blocks("<speak></speak>"), [block("", None)]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::models::{SessionUsageResponse, TtsConfig};
+
+/// Tracks how many characters were spoken this session against `session_char_limit`
+#[derive(Debug)]
+pub(crate) struct SessionUsage {
+    limit: Option<u32>,
+    used: u32,
+}
+
+impl SessionUsage {
+    pub fn new(config: &TtsConfig) -> Self {
+        Self {
+            limit: config.session_char_limit,
+            used: 0,
+        }
+    }
+
+    /// Characters left before the limit, `None` when unlimited
+    pub fn remaining(&self) -> Option<u32> {
+        self.limit.map(|limit| limit.saturating_sub(self.used))
+    }
+
+    /// Fail if speaking `chars` more characters would exceed the limit
+    pub fn check(&self, chars: u32) -> crate::Result<()> {
+        match self.remaining() {
+            Some(remaining) if chars > remaining => Err(crate::Error::SessionLimitExceeded {
+                requested: chars,
+                remaining,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record `chars` as spoken, returning the remaining budget
+    pub fn charge(&mut self, chars: u32) -> Option<u32> {
+        self.used = self.used.saturating_add(chars);
+        self.remaining()
+    }
+
+    pub fn reset(&mut self) {
+        self.used = 0;
+    }
+
+    pub fn snapshot(&self) -> SessionUsageResponse {
+        SessionUsageResponse {
+            used: self.used,
+            limit: self.limit,
+            remaining: self.remaining(),
+        }
+    }
+}
+
+/// Characters counted against the session limit for a batch of segment texts
+pub(crate) fn spoken_chars<'a>(texts: impl IntoIterator<Item = &'a str>) -> u32 {
+    texts
+        .into_iter()
+        .map(|text| text.chars().count() as u32)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(limit: Option<u32>) -> SessionUsage {
+        SessionUsage::new(&TtsConfig {
+            session_char_limit: limit,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_unlimited_by_default() {
+        let mut usage = usage(None);
+        assert!(usage.check(u32::MAX).is_ok());
+        assert_eq!(usage.charge(1_000), None);
+        assert_eq!(usage.snapshot().used, 1_000);
+    }
+
+    #[test]
+    fn test_limit_is_enforced_across_calls() {
+        let mut usage = usage(Some(10));
+        usage.check(6).unwrap();
+        assert_eq!(usage.charge(6), Some(4));
+
+        let err = usage.check(5).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::SessionLimitExceeded {
+                requested: 5,
+                remaining: 4
+            }
+        ));
+        assert!(usage.check(4).is_ok());
+    }
+
+    #[test]
+    fn test_reset_restores_budget() {
+        let mut usage = usage(Some(10));
+        usage.charge(10);
+        assert!(usage.check(1).is_err());
+
+        usage.reset();
+        assert_eq!(usage.remaining(), Some(10));
+        assert!(usage.check(10).is_ok());
+    }
+
+    #[test]
+    fn test_spoken_chars_counts_unicode_characters() {
+        assert_eq!(spoken_chars(["Olá", "mundo"]), 8);
+    }
+}
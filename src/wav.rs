@@ -0,0 +1,221 @@
+//! Conversion of the AIFF files macOS renders speech to, and the raw PCM some
+//! backends return, into WAV, the format `speak_to_file` and `synthesize`
+//! produce on every platform
+
+/// Uncompressed PCM audio read from an AIFF file, samples still big-endian
+/// unless `little_endian` is set (AIFF-C `sowt`)
+struct Pcm<'a> {
+    channels: u16,
+    sample_rate: u32,
+    bits: u16,
+    little_endian: bool,
+    data: &'a [u8],
+}
+
+/// Rewrite an AIFF or uncompressed AIFF-C file as a WAV file with the same samples
+#[cfg(any(target_os = "macos", test))]
+pub(crate) fn aiff_to_wav(aiff: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(wav(&read_aiff(aiff)?))
+}
+
+/// Wrap mono 16-bit little-endian PCM samples in a WAV header
+#[cfg(any(
+    feature = "polly",
+    feature = "elevenlabs",
+    feature = "openai",
+    feature = "piper"
+))]
+pub(crate) fn pcm16_to_wav(samples: &[u8], sample_rate: u32) -> Vec<u8> {
+    wav(&Pcm {
+        channels: 1,
+        sample_rate,
+        bits: 16,
+        little_endian: true,
+        data: samples,
+    })
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn read_aiff(aiff: &[u8]) -> Result<Pcm<'_>, String> {
+    if aiff.len() < 12 || &aiff[..4] != b"FORM" {
+        return Err("Not an AIFF file".to_string());
+    }
+    let aifc = match &aiff[8..12] {
+        b"AIFF" => false,
+        b"AIFC" => true,
+        _ => return Err("Not an AIFF file".to_string()),
+    };
+
+    let mut format = None;
+    let mut sound = None;
+    let mut offset = 12;
+    while offset + 8 <= aiff.len() {
+        let id = &aiff[offset..offset + 4];
+        let len = be_u32(&aiff[offset + 4..offset + 8]) as usize;
+        let body = aiff
+            .get(offset + 8..offset + 8 + len)
+            .ok_or("AIFF chunk runs past the end of the file")?;
+        match id {
+            b"COMM" => format = Some(body),
+            b"SSND" => sound = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        offset += 8 + len + len % 2;
+    }
+    let format = format
+        .filter(|c| c.len() >= 18)
+        .ok_or("AIFF file has no format")?;
+    let sound = sound
+        .filter(|c| c.len() >= 8)
+        .ok_or("AIFF file has no samples")?;
+
+    let channels = be_u16(&format[0..2]);
+    let frames = be_u32(&format[2..6]) as usize;
+    let bits = be_u16(&format[6..8]);
+    let sample_rate = extended_to_u32(&format[8..18]);
+    let little_endian = match format.get(18..22) {
+        Some(b"sowt") if aifc => true,
+        Some(b"NONE") | None => false,
+        Some(_) if !aifc => false,
+        Some(compression) => {
+            return Err(format!(
+                "Compressed AIFF-C audio ({}) is not supported",
+                String::from_utf8_lossy(compression)
+            ))
+        }
+    };
+    if channels == 0 || bits == 0 || bits > 32 || sample_rate == 0 {
+        return Err("AIFF file has an invalid format".to_string());
+    }
+
+    let start = 8 + be_u32(&sound[0..4]) as usize;
+    let len = frames * channels as usize * bits.div_ceil(8) as usize;
+    let data = sound
+        .get(start..start + len)
+        .ok_or("AIFF file has fewer samples than it declares")?;
+    Ok(Pcm {
+        channels,
+        sample_rate,
+        bits,
+        little_endian,
+        data,
+    })
+}
+
+/// The PCM samples as a WAV file: little-endian, and unsigned when 8 bits wide
+fn wav(pcm: &Pcm) -> Vec<u8> {
+    let sample_bytes = pcm.bits.div_ceil(8);
+    let block_align = pcm.channels * sample_bytes;
+    let data_len = pcm.data.len() as u32;
+
+    let mut wav = Vec::with_capacity(44 + pcm.data.len() + 1);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len + data_len % 2).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // Integer PCM
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&pcm.channels.to_le_bytes());
+    wav.extend_from_slice(&pcm.sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(pcm.sample_rate * block_align as u32).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&(sample_bytes * 8).to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in pcm.data.chunks(sample_bytes as usize) {
+        if sample_bytes == 1 {
+            // AIFF bytes are signed, WAV bytes unsigned
+            wav.push(sample[0] ^ 0x80);
+        } else if pcm.little_endian {
+            wav.extend_from_slice(sample);
+        } else {
+            wav.extend(sample.iter().rev());
+        }
+    }
+    if data_len % 2 == 1 {
+        wav.push(0);
+    }
+    wav
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn be_u16(bytes: &[u8]) -> u16 {
+    u16::from_be_bytes([bytes[0], bytes[1]])
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Whole part of the 80-bit IEEE extended float AIFF stores the sample rate in
+#[cfg(any(target_os = "macos", test))]
+fn extended_to_u32(bytes: &[u8]) -> u32 {
+    let exponent = (be_u16(&bytes[0..2]) & 0x7fff) as i32 - 16383;
+    let mantissa = u64::from_be_bytes(bytes[2..10].try_into().unwrap_or_default());
+    if bytes[0] & 0x80 != 0 || !(0..32).contains(&exponent) {
+        return 0;
+    }
+    (mantissa >> (63 - exponent)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mono AIFF file with the given 16-bit samples at 22050 Hz
+    fn aiff(samples: &[i16]) -> Vec<u8> {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+        let mut comm = Vec::new();
+        comm.extend_from_slice(&1u16.to_be_bytes());
+        comm.extend_from_slice(&(samples.len() as u32).to_be_bytes());
+        comm.extend_from_slice(&16u16.to_be_bytes());
+        // 22050 as an 80-bit extended float
+        comm.extend_from_slice(&[0x40, 0x0d, 0xac, 0x44, 0, 0, 0, 0, 0, 0]);
+
+        let mut aiff = b"FORM".to_vec();
+        aiff.extend_from_slice(&(4 + 8 + 18 + 8 + 8 + data.len() as u32).to_be_bytes());
+        aiff.extend_from_slice(b"AIFF");
+        aiff.extend_from_slice(b"COMM");
+        aiff.extend_from_slice(&18u32.to_be_bytes());
+        aiff.extend_from_slice(&comm);
+        aiff.extend_from_slice(b"SSND");
+        aiff.extend_from_slice(&(8 + data.len() as u32).to_be_bytes());
+        aiff.extend_from_slice(&[0; 8]);
+        aiff.extend_from_slice(&data);
+        aiff
+    }
+
+    #[test]
+    fn test_aiff_becomes_little_endian_wav() {
+        let wav = aiff_to_wav(&aiff(&[1, -2, 0x1234])).unwrap();
+
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 1);
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 22050);
+        assert_eq!(u16::from_le_bytes([wav[34], wav[35]]), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 6);
+        let samples: Vec<i16> = wav[44..]
+            .chunks(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]))
+            .collect();
+        assert_eq!(samples, [1, -2, 0x1234]);
+        assert_eq!(
+            u32::from_le_bytes(wav[4..8].try_into().unwrap()) as usize,
+            wav.len() - 8
+        );
+    }
+
+    #[test]
+    fn test_rejects_what_isnt_pcm_aiff() {
+        assert!(aiff_to_wav(b"RIFF\0\0\0\0WAVE").is_err());
+
+        let mut truncated = aiff(&[1, 2, 3]);
+        truncated.truncate(truncated.len() - 2);
+        assert!(aiff_to_wav(&truncated).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use std::ops::Range;
+
+use crate::models::{SentenceOffset, WordOffset};
+
+/// Pairs the words of text sent to an engine with the words of the caller's
+/// text they came from, so word boundaries can be reported against the latter.
+/// Words added by preprocessing, like emoji names, have no counterpart and are
+/// left out rather than guessed
+pub(crate) struct WordAligner<'a> {
+    original: &'a str,
+    words: Vec<Range<usize>>,
+    next: usize,
+    offsets: Utf16Offsets<'a>,
+}
+
+impl<'a> WordAligner<'a> {
+    /// Original words searched for a match, so an unmatched word can't make
+    /// the alignment skip far ahead
+    const LOOKAHEAD: usize = 4;
+
+    pub fn new(original: &'a str) -> Self {
+        Self {
+            original,
+            words: word_ranges(original).collect(),
+            next: 0,
+            offsets: Utf16Offsets::new(original),
+        }
+    }
+
+    /// Offsets of the words of `spoken`, which starts `start` bytes into
+    /// `engine_text`. Consecutive calls continue where the last one stopped
+    pub fn align(&mut self, engine_text: &str, start: usize, spoken: &str) -> Vec<WordOffset> {
+        let mut engine_offsets = Utf16Offsets::new(engine_text);
+        let mut aligned = Vec::new();
+        for range in word_ranges(spoken) {
+            let word = &spoken[range.clone()];
+            let end = (self.next + Self::LOOKAHEAD).min(self.words.len());
+            let Some(found) =
+                (self.next..end).find(|&index| &self.original[self.words[index].clone()] == word)
+            else {
+                continue;
+            };
+            self.next = found + 1;
+            let original = self.words[found].clone();
+            aligned.push(WordOffset {
+                spoken_start: engine_offsets.at(start + range.start),
+                spoken_end: engine_offsets.at(start + range.end),
+                text_start: self.offsets.at(original.start),
+                text_end: self.offsets.at(original.end),
+            });
+        }
+        aligned
+    }
+}
+
+/// Offsets of the sentences of `engine_text`, split the way the desktop worker
+/// speaks them, from the offsets [`WordAligner::align`] gave its words. Sentences
+/// are numbered from `next_index`, which is left after the last one so later
+/// segments continue the count. Sentences without any of the caller's words,
+/// like a prefix, get `None` and no number
+pub(crate) fn sentence_offsets(
+    engine_text: &str,
+    words: &[WordOffset],
+    next_index: &mut u32,
+) -> Vec<Option<SentenceOffset>> {
+    let mut engine_offsets = Utf16Offsets::new(engine_text);
+    crate::preprocess::sentences(engine_text)
+        .into_iter()
+        .map(|sentence| {
+            let start = sentence.as_ptr() as usize - engine_text.as_ptr() as usize;
+            let spoken_start = engine_offsets.at(start);
+            let spoken_end = engine_offsets.at(start + sentence.len());
+            let mut inside = words
+                .iter()
+                .filter(|word| word.spoken_start >= spoken_start && word.spoken_start < spoken_end);
+            let first = inside.next()?;
+            let last = inside.next_back().unwrap_or(first);
+            let offset = SentenceOffset {
+                index: *next_index,
+                spoken_start,
+                spoken_end,
+                text_start: first.text_start,
+                text_end: last.text_end,
+            };
+            *next_index += 1;
+            Some(offset)
+        })
+        .collect()
+}
+
+/// The words of each of `sentences`, with spoken offsets counted from the start
+/// of their sentence, for backends that report words within the sentence they
+/// were given. Sentences without offsets have no words
+#[cfg(desktop)]
+pub(crate) fn sentence_words(
+    sentences: &[Option<SentenceOffset>],
+    words: &[WordOffset],
+) -> Vec<Vec<WordOffset>> {
+    sentences
+        .iter()
+        .map(|sentence| {
+            let Some(sentence) = sentence else {
+                return Vec::new();
+            };
+            words
+                .iter()
+                .filter(|word| {
+                    word.spoken_start >= sentence.spoken_start
+                        && word.spoken_start < sentence.spoken_end
+                })
+                .map(|word| WordOffset {
+                    spoken_start: word.spoken_start - sentence.spoken_start,
+                    spoken_end: word.spoken_end - sentence.spoken_start,
+                    ..*word
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Converts increasing byte offsets into UTF-16 offsets, which is how JavaScript,
+/// Android and iOS index strings
+struct Utf16Offsets<'a> {
+    text: &'a str,
+    byte: usize,
+    unit: u32,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte: 0,
+            unit: 0,
+        }
+    }
+
+    fn at(&mut self, byte: usize) -> u32 {
+        let units: usize = self.text[self.byte..byte]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        self.unit += units as u32;
+        self.byte = byte;
+        self.unit
+    }
+}
+
+/// Byte ranges of the whitespace-separated words of `text`
+fn word_ranges(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    text.split_whitespace().map(move |word| {
+        let start = word.as_ptr() as usize - text.as_ptr() as usize;
+        start..start + word.len()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_point_into_the_original_text() {
+        let original = "Olá,   mundo\n日本語 text";
+        let spoken = "Olá, mundo 日本語 text";
+        let offsets = WordAligner::new(original).align(spoken, 0, spoken);
+
+        let utf16: Vec<u16> = original.encode_utf16().collect();
+        let words: Vec<String> = offsets
+            .iter()
+            .map(|o| {
+                String::from_utf16(&utf16[o.text_start as usize..o.text_end as usize]).unwrap()
+            })
+            .collect();
+        assert_eq!(words, ["Olá,", "mundo", "日本語", "text"]);
+        // "Olá, " is five UTF-16 units although "á" takes two bytes
+        assert_eq!((offsets[1].spoken_start, offsets[1].spoken_end), (5, 10));
+        assert_eq!((offsets[1].text_start, offsets[1].text_end), (7, 12));
+    }
+
+    #[test]
+    fn test_added_words_are_left_out() {
+        let original = "See you 👋 soon";
+        // A prefix, and the emoji read by its name
+        let engine_text = "Message: See you waving hand soon";
+        let offsets = WordAligner::new(original).align(engine_text, 9, &engine_text[9..]);
+
+        let spoken: Vec<&str> = offsets
+            .iter()
+            .map(|o| &engine_text[o.spoken_start as usize..o.spoken_end as usize])
+            .collect();
+        assert_eq!(spoken, ["See", "you", "soon"]);
+        assert_eq!((offsets[2].text_start, offsets[2].text_end), (11, 15));
+    }
+
+    #[test]
+    fn test_sentences_span_their_words() {
+        let original = "Hi there!  Bye 👋";
+        let engine_text = "Note. Hi there! Bye waving hand";
+        let words = WordAligner::new(original).align(engine_text, 6, &engine_text[6..]);
+        let mut next_index = 0;
+        let sentences = sentence_offsets(engine_text, &words, &mut next_index);
+
+        assert_eq!(sentences.len(), 3);
+        assert_eq!(next_index, 2);
+        // The prefix isn't the caller's, so it isn't numbered
+        assert_eq!(sentences[0], None);
+        let first = sentences[1].unwrap();
+        assert_eq!(
+            (first.index, first.spoken_start, first.spoken_end),
+            (0, 6, 15)
+        );
+        assert_eq!((first.text_start, first.text_end), (0, 9));
+        let second = sentences[2].unwrap();
+        assert_eq!(
+            (second.index, second.text_start, second.text_end),
+            (1, 11, 14)
+        );
+    }
+
+    #[test]
+    fn test_sentence_words_count_from_their_sentence() {
+        let original = "Hi there! Bye";
+        let engine_text = "Note. Hi there! Bye";
+        let words = WordAligner::new(original).align(engine_text, 6, &engine_text[6..]);
+        let sentences = sentence_offsets(engine_text, &words, &mut 0);
+        let per_sentence = sentence_words(&sentences, &words);
+
+        assert_eq!(per_sentence.len(), 3);
+        assert!(per_sentence[0].is_empty());
+        let spoken: Vec<_> = per_sentence[1]
+            .iter()
+            .map(|word| (word.spoken_start, word.spoken_end, word.text_start))
+            .collect();
+        assert_eq!(spoken, [(0, 2, 0), (3, 9, 3)]);
+        assert_eq!(
+            (
+                per_sentence[2][0].spoken_start,
+                per_sentence[2][0].text_start
+            ),
+            (0, 10)
+        );
+    }
+
+    #[test]
+    fn test_segments_continue_the_alignment() {
+        let original = "# Intro\nHello there";
+        let mut aligner = WordAligner::new(original);
+        assert_eq!(aligner.align("Intro", 0, "Intro")[0].text_start, 2);
+        let offsets = aligner.align("Hello there", 0, "Hello there");
+        assert_eq!((offsets[0].text_start, offsets[1].text_end), (8, 19));
+    }
+}
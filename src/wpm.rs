@@ -0,0 +1,79 @@
+use crate::models::{RateInfoResponse, MAX_RATE, MIN_RATE};
+
+/// Words per minute the platform engine speaks at the normal rate (1.0). Only an
+/// estimate, the real pace depends on the voice and the text
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub(crate) const NORMAL_WPM: u32 = 180;
+#[cfg(any(target_os = "windows", target_os = "android"))]
+pub(crate) const NORMAL_WPM: u32 = 160;
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "windows",
+    target_os = "android"
+)))]
+pub(crate) const NORMAL_WPM: u32 = 175;
+
+/// Rate (1.0 = normal) for `wpm` words per minute on an engine that speaks
+/// `normal_wpm` at 1.0, clamped to `MIN_RATE`..=`MAX_RATE`. The flag tells whether
+/// it had to be clamped
+pub(crate) fn rate_from_wpm(wpm: u32, normal_wpm: u32) -> (f32, bool) {
+    let rate = wpm as f32 / normal_wpm as f32;
+    let clamped = rate.clamp(MIN_RATE, MAX_RATE);
+    (clamped, clamped != rate)
+}
+
+/// Inverse of [`rate_from_wpm`], rounded to whole words
+pub(crate) fn wpm_from_rate(rate: f32, normal_wpm: u32) -> u32 {
+    (rate * normal_wpm as f32).round() as u32
+}
+
+/// The pace range of this platform's engine
+pub(crate) fn rate_info() -> RateInfoResponse {
+    RateInfoResponse {
+        normal_wpm: NORMAL_WPM,
+        min_wpm: wpm_from_rate(MIN_RATE, NORMAL_WPM),
+        max_wpm: wpm_from_rate(MAX_RATE, NORMAL_WPM),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_normal_pace_is_normal_rate() {
+        let (rate, clamped) = rate_from_wpm(180, 180);
+        assert!(close(rate, 1.0));
+        assert!(!clamped);
+
+        let (rate, clamped) = rate_from_wpm(270, 180);
+        assert!(close(rate, 1.5));
+        assert!(!clamped);
+    }
+
+    #[test]
+    fn test_pace_outside_range_is_clamped() {
+        let (rate, clamped) = rate_from_wpm(1_000, 175);
+        assert!(close(rate, MAX_RATE));
+        assert!(clamped);
+
+        let (rate, clamped) = rate_from_wpm(0, 175);
+        assert!(close(rate, MIN_RATE));
+        assert!(clamped);
+    }
+
+    #[test]
+    fn test_wpm_round_trips() {
+        for wpm in [90, 160, 175, 300] {
+            let (rate, _) = rate_from_wpm(wpm, 160);
+            assert_eq!(wpm_from_rate(rate, 160), wpm);
+        }
+        let info = rate_info();
+        assert!(info.min_wpm < info.normal_wpm && info.normal_wpm < info.max_wpm);
+    }
+}